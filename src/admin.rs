@@ -0,0 +1,660 @@
+//! Admin API for managing execution plugins at runtime
+//!
+//! Lets operators register, reconfigure, remove and promote plugins without a
+//! restart, hit the panic button (`POST /api/v1/panic`) and dump the config
+//! the service actually loaded (`GET /api/v1/diag`). Every endpoint requires
+//! `Authorization: Bearer <ADMIN_TOKEN>`; the API is disabled when
+//! `ADMIN_TOKEN` is not set.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::Json,
+    routing::{delete, get, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::in_flight::InFlightOrders;
+use crate::plugins::{
+    bybit::BybitPlugin, ccxt::CCXTPlugin, ibkr::IbkrPlugin, kucoin::KuCoinPlugin, mock::MockPlugin,
+    openalgo::OpenAlgoPlugin, registry::PluginRegistry, CancelOutcome, ExecutionPlugin, InitRetry, Order, OrderType,
+};
+
+type AdminError = (StatusCode, Json<Value>);
+
+/// Config keys whose values are credentials (matched case-insensitively, e.g. `api_key`, `X-Api-Key`)
+const SECRET_FRAGMENTS: &[&str] = &["key", "secret", "passphrase", "password", "token", "authorization", "cookie"];
+
+/// Shared state for the admin routes
+pub struct AdminState {
+    registry: Arc<PluginRegistry>,
+    token: Option<String>,
+    /// Optional behaviour switched on at startup, reported by `GET /api/v1/diag`
+    features: BTreeMap<String, bool>,
+}
+
+impl AdminState {
+    pub fn new(registry: Arc<PluginRegistry>, token: Option<String>) -> Self {
+        Self {
+            registry,
+            token: token.filter(|t| !t.is_empty()),
+            features: BTreeMap::new(),
+        }
+    }
+    
+    /// Build from `ADMIN_TOKEN` (or `ADMIN_TOKEN_FILE`), reporting `features` in diagnostics
+    pub fn from_env(registry: Arc<PluginRegistry>, features: BTreeMap<String, bool>) -> Self {
+        Self {
+            features,
+            ..Self::new(registry, crate::secrets::read_secret("ADMIN_TOKEN"))
+        }
+    }
+}
+
+/// Plugin registration request: POST /admin/plugins
+#[derive(Deserialize)]
+pub struct RegisterPluginRequest {
+    /// Registry name, also used as the `exchange` in order requests
+    name: String,
+    
+    /// Plugin implementation: mock, ccxt, bybit, kucoin, ibkr, openalgo
+    kind: String,
+    
+    /// Plugin-specific configuration passed to `init`
+    #[serde(default)]
+    config: Value,
+    
+    /// Make this plugin the default after registering
+    #[serde(default)]
+    default: bool,
+}
+
+pub fn admin_routes<S>(state: AdminState) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/admin/plugins", post(register_plugin_handler))
+        .route("/admin/plugins/{name}", delete(deregister_plugin_handler))
+        .route("/admin/plugins/{name}/default", post(set_default_handler))
+        .route("/admin/plugins/{name}/enable", post(enable_plugin_handler))
+        .route("/admin/plugins/{name}/disable", post(disable_plugin_handler))
+        .route("/admin/resume", post(resume_handler))
+        .route("/api/v1/panic", post(panic_handler))
+        .route("/api/v1/diag", get(diag_handler))
+        .with_state(Arc::new(state))
+}
+
+fn admin_error(status: StatusCode, message: impl Into<String>) -> AdminError {
+    (status, Json(json!({ "error": message.into() })))
+}
+
+/// Compare without short-circuiting on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Check the bearer token against `ADMIN_TOKEN`
+fn authorize(state: &AdminState, headers: &HeaderMap) -> Result<(), AdminError> {
+    let Some(expected) = state.token.as_deref() else {
+        return Err(admin_error(StatusCode::FORBIDDEN, "Admin API disabled (ADMIN_TOKEN not set)"));
+    };
+    
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    
+    if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        tracing::warn!("admin_request_unauthorized");
+        return Err(admin_error(StatusCode::UNAUTHORIZED, "Invalid or missing admin token"));
+    }
+    Ok(())
+}
+
+/// Construct and initialize a plugin of the given kind, retrying per `InitRetry::from_env`
+pub(crate) async fn create_plugin(
+    kind: &str,
+    name: &str,
+    config: Value,
+) -> Result<Arc<dyn ExecutionPlugin>, Box<dyn Error + Send + Sync>> {
+    // Plugins that take no settings still expect an object
+    let config = if config.is_null() { json!({}) } else { config };
+    let retry = InitRetry::from_env();
+    
+    let plugin: Arc<dyn ExecutionPlugin> = match kind {
+        "mock" => {
+            let mut plugin = MockPlugin::new(name);
+            retry.init(&mut plugin, config).await?;
+            Arc::new(plugin)
+        }
+        "ccxt" => {
+            let mut plugin = CCXTPlugin::new(name);
+            retry.init(&mut plugin, config).await?;
+            Arc::new(plugin)
+        }
+        "bybit" => {
+            let mut plugin = BybitPlugin::new(name);
+            retry.init(&mut plugin, config).await?;
+            Arc::new(plugin)
+        }
+        "kucoin" => {
+            let mut plugin = KuCoinPlugin::new(name);
+            retry.init(&mut plugin, config).await?;
+            Arc::new(plugin)
+        }
+        "ibkr" => {
+            let mut plugin = IbkrPlugin::new(name);
+            retry.init(&mut plugin, config).await?;
+            Arc::new(plugin)
+        }
+        "openalgo" => {
+            let mut plugin = OpenAlgoPlugin::new(name);
+            retry.init(&mut plugin, config).await?;
+            Arc::new(plugin)
+        }
+        other => return Err(format!("Unknown plugin kind: {}", other).into()),
+    };
+    Ok(plugin)
+}
+
+/// Register or reconfigure a plugin: POST /admin/plugins
+async fn register_plugin_handler(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Json(req): Json<RegisterPluginRequest>,
+) -> Result<Json<Value>, AdminError> {
+    authorize(&state, &headers)?;
+    
+    if req.name.trim().is_empty() {
+        return Err(admin_error(StatusCode::BAD_REQUEST, "Plugin name must not be empty"));
+    }
+    
+    let plugin = create_plugin(&req.kind, &req.name, req.config).await.map_err(|e| {
+        tracing::warn!(plugin = %req.name, kind = %req.kind, error = %e, "admin_plugin_init_failed");
+        admin_error(StatusCode::BAD_REQUEST, format!("Failed to initialize plugin: {}", e))
+    })?;
+    
+    // Registering under an existing name replaces (reconfigures) that plugin
+    let replaced = state.registry.get(&req.name).await.is_some();
+    state.registry.register(req.name.clone(), plugin).await;
+    if req.default {
+        state.registry.set_default(req.name.clone()).await
+            .map_err(|e| admin_error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    }
+    
+    tracing::info!(plugin = %req.name, kind = %req.kind, replaced, default = req.default, "admin_plugin_registered");
+    Ok(Json(json!({
+        "success": true,
+        "name": req.name,
+        "kind": req.kind,
+        "replaced": replaced
+    })))
+}
+
+/// Remove a plugin: DELETE /admin/plugins/{name}
+async fn deregister_plugin_handler(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Json<Value>, AdminError> {
+    authorize(&state, &headers)?;
+    
+    if !state.registry.deregister(&name).await {
+        return Err(admin_error(StatusCode::NOT_FOUND, format!("Plugin '{}' not found", name)));
+    }
+    
+    tracing::info!(plugin = %name, "admin_plugin_deregistered");
+    Ok(Json(json!({ "success": true, "name": name })))
+}
+
+/// Make a plugin the default: POST /admin/plugins/{name}/default
+async fn set_default_handler(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Json<Value>, AdminError> {
+    authorize(&state, &headers)?;
+    
+    state.registry.set_default(name.clone()).await
+        .map_err(|e| admin_error(StatusCode::NOT_FOUND, e))?;
+    
+    tracing::info!(plugin = %name, "admin_default_plugin_set");
+    Ok(Json(json!({ "success": true, "default": name })))
+}
+
+/// Resume routing to a plugin: POST /admin/plugins/{name}/enable
+async fn enable_plugin_handler(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Json<Value>, AdminError> {
+    set_enabled(&state, &headers, name, true).await
+}
+
+/// Stop routing to a plugin without deregistering it: POST /admin/plugins/{name}/disable
+async fn disable_plugin_handler(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Json<Value>, AdminError> {
+    set_enabled(&state, &headers, name, false).await
+}
+
+async fn set_enabled(state: &AdminState, headers: &HeaderMap, name: String, enabled: bool) -> Result<Json<Value>, AdminError> {
+    authorize(state, headers)?;
+    
+    let changed = state.registry.set_enabled(&name, enabled).await
+        .map_err(|e| admin_error(StatusCode::NOT_FOUND, e))?;
+    
+    tracing::info!(plugin = %name, enabled, changed, "admin_plugin_enabled_set");
+    Ok(Json(json!({ "success": true, "name": name, "enabled": enabled, "changed": changed })))
+}
+
+/// Panic button options: POST /api/v1/panic?flatten=true
+#[derive(Deserialize)]
+pub struct PanicQuery {
+    /// Also close every open position with reduce-only market orders
+    #[serde(default)]
+    flatten: bool,
+}
+
+/// What the panic button did on one exchange
+#[derive(Debug, Default, Serialize)]
+pub struct PanicActions {
+    /// Orders cancelled, or failed to cancel, one entry each
+    cancelled: Vec<CancelOutcome>,
+    /// Set when open orders couldn't be listed or cancelled at all
+    cancel_error: Option<String>,
+    /// Symbols whose positions were closed
+    flattened: Vec<String>,
+    /// Positions that couldn't be closed, or why positions couldn't be read
+    flatten_errors: Vec<String>,
+}
+
+/// Emergency stop: POST /api/v1/panic
+///
+/// Halts trading first, so no new order slips in, then cancels every open
+/// order on every registered plugin (disabled ones included) and, with
+/// `flatten=true`, closes every open position. Closing orders bypass the halt.
+/// Trading stays halted until `POST /admin/resume`.
+async fn panic_handler(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Query(query): Query<PanicQuery>,
+) -> Result<Json<Value>, AdminError> {
+    authorize(&state, &headers)?;
+    
+    state.registry.halt();
+    tracing::warn!(flatten = query.flatten, "panic_trading_halted");
+    
+    let mut names = state.registry.list_plugins().await;
+    names.sort();
+    let mut exchanges = BTreeMap::new();
+    for name in names {
+        let Some(plugin) = state.registry.get(&name).await else { continue };
+        let mut actions = PanicActions::default();
+        
+        match plugin.cancel_all_orders(None).await {
+            Ok(outcomes) => actions.cancelled = outcomes,
+            Err(e) => actions.cancel_error = Some(e.to_string()),
+        }
+        if query.flatten {
+            flatten_positions(plugin.as_ref(), state.registry.in_flight(), &mut actions).await;
+        }
+        
+        tracing::warn!(
+            plugin = %name,
+            cancelled = actions.cancelled.iter().filter(|o| o.success).count(),
+            cancel_failed = actions.cancelled.iter().filter(|o| !o.success).count() + actions.cancel_error.iter().count(),
+            flattened = actions.flattened.len(),
+            flatten_failed = actions.flatten_errors.len(),
+            "panic_exchange_done"
+        );
+        exchanges.insert(name, actions);
+    }
+    
+    Ok(Json(json!({ "halted": true, "flatten": query.flatten, "exchanges": exchanges })))
+}
+
+/// Close each open position with a reduce-only market order
+async fn flatten_positions(plugin: &dyn ExecutionPlugin, in_flight: &Arc<InFlightOrders>, actions: &mut PanicActions) {
+    let positions = match plugin.get_positions(None).await {
+        Ok(positions) => positions,
+        Err(e) => {
+            actions.flatten_errors.push(format!("positions unavailable: {}", e));
+            return;
+        }
+    };
+    for position in positions.into_iter().filter(|p| p.size > 0.0) {
+        let order = Order {
+            symbol: position.symbol.clone(),
+            side: position.side.closing_side(),
+            order_type: OrderType::Market,
+            quantity: position.size,
+            reduce_only: true,
+            strategy_tag: Some("panic".to_string()),
+            ..Default::default()
+        };
+        // Trading is halted by now, so this bypasses the registry; the guard keeps shutdown waiting
+        let _in_flight = in_flight.begin();
+        match plugin.execute_order(order).await {
+            Ok(result) if result.success => actions.flattened.push(position.symbol),
+            Ok(result) => actions.flatten_errors.push(format!("{}: {}", position.symbol, result.error.unwrap_or_default())),
+            Err(e) => actions.flatten_errors.push(format!("{}: {}", position.symbol, e)),
+        }
+    }
+}
+
+/// Lift the panic halt: POST /admin/resume
+async fn resume_handler(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, AdminError> {
+    authorize(&state, &headers)?;
+    
+    let was_halted = state.registry.resume();
+    tracing::warn!(was_halted, "trading_resumed");
+    Ok(Json(json!({ "success": true, "was_halted": was_halted })))
+}
+
+/// Whether a config key holds a credential; file paths (`client_key_path`) don't
+fn is_secret(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    !name.ends_with("_path") && SECRET_FRAGMENTS.iter().any(|f| name.contains(f))
+}
+
+/// Mask a credential, keeping only its last four characters
+fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    match chars.len() {
+        0 => String::new(),
+        len if len <= 8 => "****".to_string(),
+        len => format!("****{}", chars[len - 4..].iter().collect::<String>()),
+    }
+}
+
+/// Copy of `config` with every credential masked, at any depth
+fn redact_config(config: Value) -> Value {
+    match config {
+        Value::Object(fields) => Value::Object(fields.into_iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    Value::String(secret) if is_secret(&name) => Value::String(mask_secret(&secret)),
+                    other => redact_config(other),
+                };
+                (name, value)
+            })
+            .collect()),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact_config).collect()),
+        other => other,
+    }
+}
+
+/// What the service is running with: GET /api/v1/diag
+///
+/// Each plugin's effective config (credentials masked to their last four
+/// characters), the routing settings and which optional features are on, for
+/// telling which of env, config file and admin changes actually took effect.
+async fn diag_handler(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, AdminError> {
+    authorize(&state, &headers)?;
+    
+    let mut names = state.registry.list_plugins().await;
+    names.sort();
+    let mut plugins = BTreeMap::new();
+    for name in names {
+        let Some(plugin) = state.registry.get(&name).await else { continue };
+        plugins.insert(name.clone(), json!({
+            "enabled": state.registry.is_enabled(&name).await,
+            "paper": plugin.is_paper(),
+            "config": plugin.effective_config().await.map(redact_config),
+        }));
+    }
+    
+    Ok(Json(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "routing": state.registry.routing_config().await,
+        "features": state.features,
+        "plugins": plugins,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::{ExecutionError, HealthStatus, Order, OrderSide, OrderType};
+    
+    fn admin_state(registry: Arc<PluginRegistry>) -> Arc<AdminState> {
+        Arc::new(AdminState::new(registry, Some("secret".to_string())))
+    }
+    
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
+        headers
+    }
+    
+    fn register_request(name: &str, default: bool) -> RegisterPluginRequest {
+        RegisterPluginRequest {
+            name: name.to_string(),
+            kind: "mock".to_string(),
+            config: Value::Null,
+            default,
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_register_mock_at_runtime_and_route_order() {
+        let registry = Arc::new(PluginRegistry::new());
+        let state = admin_state(registry.clone());
+        
+        let Json(body) = register_plugin_handler(State(state), bearer("secret"), Json(register_request("runtime-mock", false)))
+            .await
+            .unwrap();
+        assert_eq!(body["success"], true);
+        assert_eq!(body["replaced"], false);
+        
+        let order = Order {
+            symbol: "BTC/USDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: 0.1,
+            confidence: 0.8,
+            ..Default::default()
+        };
+        let result = registry.execute_order(order, Some("runtime-mock")).await.unwrap();
+        assert!(result.success);
+    }
+    
+    #[tokio::test]
+    async fn test_deregister_and_set_default() {
+        let registry = Arc::new(PluginRegistry::new());
+        let state = admin_state(registry.clone());
+        
+        assert!(register_plugin_handler(State(state.clone()), bearer("secret"), Json(register_request("mock1", false))).await.is_ok());
+        assert!(register_plugin_handler(State(state.clone()), bearer("secret"), Json(register_request("mock2", false))).await.is_ok());
+        assert_eq!(registry.get_default().await.unwrap().name(), "mock1");
+        
+        assert!(set_default_handler(State(state.clone()), bearer("secret"), Path("mock2".to_string())).await.is_ok());
+        assert_eq!(registry.get_default().await.unwrap().name(), "mock2");
+        
+        assert!(deregister_plugin_handler(State(state.clone()), bearer("secret"), Path("mock1".to_string())).await.is_ok());
+        assert!(registry.get("mock1").await.is_none());
+        
+        let (status, _) = deregister_plugin_handler(State(state.clone()), bearer("secret"), Path("mock1".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        
+        let (status, _) = set_default_handler(State(state), bearer("secret"), Path("missing".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_disabled_plugin_rejects_orders_until_enabled() {
+        let registry = Arc::new(PluginRegistry::new());
+        let state = admin_state(registry.clone());
+        assert!(register_plugin_handler(State(state.clone()), bearer("secret"), Json(register_request("mock1", false))).await.is_ok());
+        
+        let order = Order {
+            symbol: "BTC/USDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: 0.1,
+            confidence: 0.8,
+            ..Default::default()
+        };
+        
+        let Json(body) = disable_plugin_handler(State(state.clone()), bearer("secret"), Path("mock1".to_string())).await.unwrap();
+        assert_eq!(body["enabled"], false);
+        assert_eq!(body["changed"], true);
+        
+        // Still registered, but neither explicit nor default routing reaches it
+        assert!(registry.get("mock1").await.is_some());
+        for target in [Some("mock1"), None] {
+            let err = registry.execute_order(order.clone(), target).await.unwrap_err();
+            assert_eq!(err.to_string(), "disabled: Plugin 'mock1' is disabled");
+            assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Disabled(_))));
+        }
+        assert_eq!(registry.health_status_all().await["mock1"], HealthStatus::Disabled);
+        
+        let Json(body) = enable_plugin_handler(State(state.clone()), bearer("secret"), Path("mock1".to_string())).await.unwrap();
+        assert_eq!(body["changed"], true);
+        assert!(registry.execute_order(order, Some("mock1")).await.unwrap().success);
+        assert_eq!(registry.health_status_all().await["mock1"], HealthStatus::Healthy);
+        
+        let (status, _) = disable_plugin_handler(State(state), bearer("secret"), Path("missing".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_panic_halts_and_cancels_on_every_plugin() {
+        let registry = Arc::new(PluginRegistry::new());
+        let state = admin_state(registry.clone());
+        assert!(register_plugin_handler(State(state.clone()), bearer("secret"), Json(register_request("mock1", false))).await.is_ok());
+        assert!(register_plugin_handler(State(state.clone()), bearer("secret"), Json(register_request("mock2", false))).await.is_ok());
+        
+        let resting = Order {
+            symbol: "BTC/USDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity: 0.1,
+            price: Some(60000.0),
+            confidence: 0.8,
+            ..Default::default()
+        };
+        let mut placed = Vec::new();
+        for name in ["mock1", "mock2"] {
+            let order_id = registry.execute_order(resting.clone(), Some(name)).await.unwrap().order_id.unwrap();
+            placed.push((name, order_id));
+        }
+        
+        let (status, _) = panic_handler(State(state.clone()), bearer("wrong"), Query(PanicQuery { flatten: true })).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(!registry.is_halted());
+        
+        let Json(body) = panic_handler(State(state.clone()), bearer("secret"), Query(PanicQuery { flatten: true })).await.unwrap();
+        assert_eq!(body["halted"], true);
+        for (name, order_id) in &placed {
+            let actions = &body["exchanges"][name];
+            assert_eq!(actions["cancelled"], json!([{ "order_id": order_id, "success": true, "error": null }]));
+            assert_eq!(actions["flattened"], json!(["BTC/USDT", "ETH/USDT"]));
+            
+            let plugin = registry.get(name).await.unwrap();
+            assert_eq!(plugin.get_order("BTC/USDT", order_id).await.unwrap().status, crate::plugins::OrderStatus::Cancelled);
+        }
+        
+        // Halted until resumed
+        let err = registry.execute_order(resting.clone(), Some("mock1")).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Disabled(_))));
+        let Json(body) = resume_handler(State(state), bearer("secret")).await.unwrap();
+        assert_eq!(body["was_halted"], true);
+        assert!(registry.execute_order(resting, Some("mock1")).await.unwrap().success);
+    }
+    
+    #[tokio::test]
+    async fn test_admin_requires_token() {
+        let registry = Arc::new(PluginRegistry::new());
+        
+        let (status, _) = register_plugin_handler(State(admin_state(registry.clone())), bearer("wrong"), Json(register_request("mock1", false)))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        
+        let (status, _) = register_plugin_handler(State(admin_state(registry.clone())), HeaderMap::new(), Json(register_request("mock1", false)))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        
+        // No ADMIN_TOKEN configured: the API is disabled entirely
+        let disabled = Arc::new(AdminState::new(registry.clone(), None));
+        let (status, _) = register_plugin_handler(State(disabled), bearer(""), Json(register_request("mock1", false)))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        
+        assert!(registry.list_plugins().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_diag_redacts_secrets() {
+        let mut plugin = OpenAlgoPlugin::new("nse");
+        plugin.init(json!({
+            "base_url": "http://127.0.0.1:9",
+            "api_key": "oa-live-7f3e9c2d5b1a4321",
+            "sandbox_mode": true,
+            "broker": "zerodha",
+            "timeout_secs": 1,
+            "headers": { "X-Api-Key": "hdr-4c8d2e6f9a5678", "X-Desk": "equities" }
+        })).await.unwrap();
+        let registry = Arc::new(PluginRegistry::new());
+        registry.register("nse".to_string(), Arc::new(plugin)).await;
+        
+        let features = BTreeMap::from([("strict_order_validation".to_string(), true)]);
+        let state = Arc::new(AdminState { features, ..AdminState::new(registry, Some("secret".to_string())) });
+        
+        let (status, _) = diag_handler(State(state.clone()), bearer("wrong")).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        
+        let Json(body) = diag_handler(State(state), bearer("secret")).await.unwrap();
+        let plugin = &body["plugins"]["nse"];
+        assert_eq!(plugin["paper"], true);
+        assert_eq!(plugin["config"]["api_key"], "****4321");
+        assert_eq!(plugin["config"]["headers"]["X-Api-Key"], "****5678");
+        assert_eq!(plugin["config"]["headers"]["X-Desk"], "equities");
+        assert_eq!(plugin["config"]["base_url"], "http://127.0.0.1:9");
+        assert_eq!(body["routing"]["default_plugin"], "nse");
+        assert_eq!(body["features"]["strict_order_validation"], true);
+        
+        let output = body.to_string();
+        for secret in ["oa-live-7f3e9c2d5b1a4321", "hdr-4c8d2e6f9a5678"] {
+            assert!(!output.contains(secret), "{} leaked into {}", secret, output);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_register_rejects_unknown_kind() {
+        let registry = Arc::new(PluginRegistry::new());
+        let mut req = register_request("x", false);
+        req.kind = "nope".to_string();
+        
+        let (status, Json(body)) = register_plugin_handler(State(admin_state(registry)), bearer("secret"), Json(req))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("Unknown plugin kind"));
+    }
+}
@@ -0,0 +1,61 @@
+//! Injectable wall clock
+//!
+//! Time-windowed state (replay nonces, maintenance windows) reads the time
+//! through a [`Clock`] so tests can drive it deterministically with
+//! [`TestClock`] instead of sleeping.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Source of the current time
+pub trait Clock: Send + Sync + Debug {
+    /// Current time in Unix millis
+    fn now_millis(&self) -> i64;
+}
+
+/// Shared handle to a clock
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The real wall clock
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> i64 {
+        crate::plugins::now_millis()
+    }
+}
+
+/// Shared handle to the real wall clock
+pub fn system() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// Manually advanced clock for tests
+#[cfg(test)]
+#[derive(Debug)]
+pub struct TestClock {
+    millis: std::sync::atomic::AtomicI64,
+}
+
+#[cfg(test)]
+impl TestClock {
+    /// Start at `millis` (Unix millis)
+    pub fn new(millis: i64) -> Arc<Self> {
+        Arc::new(Self {
+            millis: std::sync::atomic::AtomicI64::new(millis),
+        })
+    }
+    
+    /// Move the clock forward
+    pub fn advance(&self, by: std::time::Duration) {
+        self.millis.fetch_add(by.as_millis() as i64, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+impl Clock for TestClock {
+    fn now_millis(&self) -> i64 {
+        self.millis.load(std::sync::atomic::Ordering::SeqCst)
+    }
+}
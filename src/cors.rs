@@ -0,0 +1,102 @@
+//! CORS for browser-based dashboards
+//!
+//! Driven by `ALLOWED_ORIGINS`, a comma-separated list of origins (or `*` for
+//! development). When unset no CORS headers are sent, so browsers only allow
+//! same-origin calls.
+
+use axum::http::{header, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// CORS layer for an `ALLOWED_ORIGINS` value; `None` when nothing usable is listed
+pub fn layer(spec: &str) -> Option<CorsLayer> {
+    let origins: Vec<&str> = spec.split(',')
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+        .collect();
+    
+    let allow_origin = if origins.contains(&"*") {
+        AllowOrigin::any()
+    } else {
+        let values: Vec<HeaderValue> = origins.iter()
+            .filter_map(|origin| match origin.parse() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    tracing::warn!(origin = %origin, "cors_origin_invalid_ignored");
+                    None
+                }
+            })
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        AllowOrigin::list(values)
+    };
+    
+    Some(CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]))
+}
+
+/// Build from `ALLOWED_ORIGINS`
+pub fn from_env() -> Option<CorsLayer> {
+    let spec = std::env::var("ALLOWED_ORIGINS").ok()?;
+    let layer = layer(&spec);
+    if layer.is_some() {
+        tracing::info!(origins = %spec, "cors_enabled");
+    }
+    layer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+    
+    async fn allow_origin(cors: Option<CorsLayer>, method: Method, origin: &str) -> Option<String> {
+        let mut app = Router::new().route("/api/v1/ping", get(|| async { "pong" }));
+        if let Some(cors) = cors {
+            app = app.layer(cors);
+        }
+        
+        let preflight = method == Method::OPTIONS;
+        let mut request = Request::builder()
+            .method(method)
+            .uri("/api/v1/ping")
+            .header(header::ORIGIN, origin);
+        if preflight {
+            request = request.header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET");
+        }
+        
+        let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        response.headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+    
+    #[tokio::test]
+    async fn test_configured_origin_allowed_others_not() {
+        let spec = "https://dash.example.com, https://ops.example.com";
+        
+        assert_eq!(
+            allow_origin(layer(spec), Method::GET, "https://dash.example.com").await.as_deref(),
+            Some("https://dash.example.com")
+        );
+        assert_eq!(
+            allow_origin(layer(spec), Method::OPTIONS, "https://ops.example.com").await.as_deref(),
+            Some("https://ops.example.com")
+        );
+        assert_eq!(allow_origin(layer(spec), Method::GET, "https://evil.example.com").await, None);
+    }
+    
+    #[tokio::test]
+    async fn test_wildcard_and_default() {
+        assert_eq!(allow_origin(layer("*"), Method::GET, "http://localhost:3000").await.as_deref(), Some("*"));
+        
+        // Unset or empty: same-origin only, no CORS headers at all
+        assert!(layer("").is_none());
+        assert!(layer(" , ").is_none());
+        assert_eq!(allow_origin(None, Method::GET, "http://localhost:3000").await, None);
+    }
+}
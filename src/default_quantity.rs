@@ -0,0 +1,98 @@
+//! Fallback quantities for orders that omit `quantity`
+//!
+//! Configured with `DEFAULT_QUANTITY`, either a bare number applying
+//! everywhere or comma-separated `key=quantity` rules where the key is
+//! `exchange:SYMBOL`, `SYMBOL`, `exchange:*` or `*`, e.g.
+//! `bybit:BTCUSDT=0.001,ETHUSDT=0.05,*=1`. The most specific rule wins.
+
+use std::collections::HashMap;
+
+/// Rule key: (exchange, symbol), `None` matching any
+type RuleKey = (Option<String>, Option<String>);
+
+/// Per-exchange/per-symbol default order quantities
+#[derive(Debug, Default)]
+pub struct DefaultQuantities {
+    rules: HashMap<RuleKey, f64>,
+}
+
+impl DefaultQuantities {
+    /// Parse a `DEFAULT_QUANTITY` spec, skipping (and logging) malformed rules
+    pub fn parse(spec: &str) -> Self {
+        let spec = spec.trim();
+        if let Ok(quantity) = spec.parse::<f64>() {
+            return Self::from_rules([((None, None), quantity)]);
+        }
+        
+        let mut rules = Vec::new();
+        for rule in spec.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+            match Self::parse_rule(rule) {
+                Some(rule) => rules.push(rule),
+                None => tracing::warn!(rule = %rule, "default_quantity_rule_invalid"),
+            }
+        }
+        Self::from_rules(rules)
+    }
+    
+    /// Build from `DEFAULT_QUANTITY` (default: no defaults, quantity required)
+    pub fn from_env() -> Self {
+        Self::parse(&std::env::var("DEFAULT_QUANTITY").unwrap_or_default())
+    }
+    
+    fn from_rules(rules: impl IntoIterator<Item = (RuleKey, f64)>) -> Self {
+        Self {
+            rules: rules.into_iter().filter(|(_, q)| *q > 0.0 && q.is_finite()).collect(),
+        }
+    }
+    
+    fn parse_rule(rule: &str) -> Option<(RuleKey, f64)> {
+        let (key, quantity) = rule.split_once('=')?;
+        let quantity = quantity.trim().parse::<f64>().ok()?;
+        let key = match key.split_once(':') {
+            Some((exchange, symbol)) => (wildcard(exchange).map(str::to_lowercase), wildcard(symbol).map(str::to_uppercase)),
+            None => (None, wildcard(key).map(str::to_uppercase)),
+        };
+        Some((key, quantity))
+    }
+    
+    /// Default quantity for `symbol` on `exchange`, if any rule applies
+    pub fn get(&self, exchange: &str, symbol: &str) -> Option<f64> {
+        let exchange = Some(exchange.to_lowercase());
+        let symbol = Some(symbol.trim().to_uppercase());
+        [
+            (exchange.clone(), symbol.clone()),
+            (None, symbol),
+            (exchange, None),
+            (None, None),
+        ]
+            .iter()
+            .find_map(|key| self.rules.get(key).copied())
+    }
+}
+
+/// `None` for a `*` (or empty) part of a rule key
+fn wildcard(part: &str) -> Option<&str> {
+    Some(part.trim()).filter(|p| *p != "*" && !p.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_most_specific_rule_wins() {
+        let defaults = DefaultQuantities::parse("bybit:BTCUSDT=0.001, btcusdt=0.002, bybit:*=0.5, *=1, kucoin:ETH=bad");
+        
+        assert_eq!(defaults.get("bybit", "BTCUSDT"), Some(0.001));
+        assert_eq!(defaults.get("kucoin", "btcusdt"), Some(0.002));
+        assert_eq!(defaults.get("Bybit", "ETHUSDT"), Some(0.5));
+        assert_eq!(defaults.get("kucoin", "ETH"), Some(1.0));
+    }
+    
+    #[test]
+    fn test_bare_number_and_empty_spec() {
+        assert_eq!(DefaultQuantities::parse("0.01").get("mock", "BTC/USDT"), Some(0.01));
+        assert_eq!(DefaultQuantities::parse("").get("mock", "BTC/USDT"), None);
+        assert_eq!(DefaultQuantities::parse("BTCUSDT=0").get("mock", "BTCUSDT"), None);
+    }
+}
@@ -0,0 +1,230 @@
+//! Simulated good-till-date (GTD) orders
+//!
+//! An order created with `expire_at` (Unix millis) is tracked here and
+//! cancelled by a periodic sweep once that time has passed, for venues with
+//! no native GTD. Tracked expiries are written to a journal file
+//! (`GTD_JOURNAL_PATH`) on every change, so a restart doesn't orphan them:
+//! the first sweep after startup cancels whatever expired while the service
+//! was down. Without a journal, expiries live in memory only.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+use crate::clock::{self, SharedClock};
+use crate::plugins::registry::PluginRegistry;
+use crate::plugins::OrderStatus;
+
+/// Default time between sweeps
+const DEFAULT_SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A resting order to cancel at `expire_at`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GtdOrder {
+    pub plugin: String,
+    pub symbol: String,
+    pub order_id: String,
+    /// Unix millis
+    pub expire_at: i64,
+}
+
+/// Tracks GTD orders and cancels them once expired
+pub struct GtdSweeper {
+    registry: Arc<PluginRegistry>,
+    journal: Option<PathBuf>,
+    orders: Mutex<Vec<GtdOrder>>,
+    clock: SharedClock,
+}
+
+impl GtdSweeper {
+    /// Sweeper restoring any orders left in `journal`
+    pub fn new(registry: Arc<PluginRegistry>, journal: Option<PathBuf>) -> Self {
+        Self::with_clock(registry, journal, clock::system())
+    }
+    
+    /// Sweeper that reads the current time from `clock`
+    pub fn with_clock(registry: Arc<PluginRegistry>, journal: Option<PathBuf>, clock: SharedClock) -> Self {
+        let orders = journal.as_ref().map(Self::load).unwrap_or_default();
+        if !orders.is_empty() {
+            tracing::info!(orders = orders.len(), "gtd_orders_restored");
+        }
+        Self {
+            registry,
+            journal,
+            orders: Mutex::new(orders),
+            clock,
+        }
+    }
+    
+    /// Build from `GTD_JOURNAL_PATH` (default: in memory only)
+    pub fn from_env(registry: Arc<PluginRegistry>) -> Self {
+        Self::new(registry, std::env::var("GTD_JOURNAL_PATH").ok().map(PathBuf::from))
+    }
+    
+    /// Orders in the journal; a missing file is an empty journal
+    fn load(path: &PathBuf) -> Vec<GtdOrder> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+            Err(e) => {
+                tracing::error!(path = %path.display(), error = %e, "gtd_journal_unreadable");
+                return Vec::new();
+            }
+        };
+        serde_json::from_str(&text).unwrap_or_else(|e| {
+            tracing::error!(path = %path.display(), error = %e, "gtd_journal_invalid");
+            Vec::new()
+        })
+    }
+    
+    /// Rewrite the journal, via a temporary file so a crash never leaves it half written
+    fn persist(&self, orders: &[GtdOrder]) {
+        let Some(path) = &self.journal else { return };
+        let tmp = path.with_extension("tmp");
+        let written = serde_json::to_vec(orders)
+            .map_err(std::io::Error::other)
+            .and_then(|bytes| std::fs::write(&tmp, bytes))
+            .and_then(|_| std::fs::rename(&tmp, path));
+        if let Err(e) = written {
+            tracing::error!(path = %path.display(), error = %e, "gtd_journal_write_failed");
+        }
+    }
+    
+    /// Start tracking a placed order
+    pub async fn track(&self, order: GtdOrder) {
+        tracing::info!(plugin = %order.plugin, order_id = %order.order_id, expire_at = order.expire_at, "gtd_order_tracked");
+        let mut orders = self.orders.lock().await;
+        orders.push(order);
+        self.persist(&orders);
+    }
+    
+    /// Orders still being tracked
+    #[cfg(test)]
+    pub async fn pending(&self) -> Vec<GtdOrder> {
+        self.orders.lock().await.clone()
+    }
+    
+    /// Cancel every expired order once, returning those cancelled
+    ///
+    /// An order that can't be cancelled stays tracked for the next sweep,
+    /// unless it has already filled or closed.
+    pub async fn sweep(&self) -> Vec<GtdOrder> {
+        let now = self.clock.now_millis();
+        let expired: Vec<GtdOrder> = self.orders.lock().await
+            .iter()
+            .filter(|o| o.expire_at <= now)
+            .cloned()
+            .collect();
+        
+        let mut cancelled = Vec::new();
+        let mut done = Vec::new();
+        for order in expired {
+            let Some(plugin) = self.registry.get(&order.plugin).await else {
+                tracing::warn!(plugin = %order.plugin, order_id = %order.order_id, "gtd_plugin_missing");
+                done.push(order);
+                continue;
+            };
+            match plugin.cancel_order(&order.symbol, &order.order_id).await {
+                Ok(()) => {
+                    tracing::info!(plugin = %order.plugin, order_id = %order.order_id, "gtd_order_expired_cancelled");
+                    cancelled.push(order.clone());
+                    done.push(order);
+                }
+                Err(e) => {
+                    let closed = plugin.get_order(&order.symbol, &order.order_id).await
+                        .is_ok_and(|o| matches!(o.status, OrderStatus::Filled | OrderStatus::Cancelled | OrderStatus::Rejected));
+                    if closed {
+                        done.push(order);
+                    } else {
+                        tracing::warn!(plugin = %order.plugin, order_id = %order.order_id, error = %e, "gtd_cancel_failed");
+                    }
+                }
+            }
+        }
+        
+        if !done.is_empty() {
+            let mut orders = self.orders.lock().await;
+            orders.retain(|o| !done.contains(o));
+            self.persist(&orders);
+        }
+        cancelled
+    }
+    
+    /// Sweep every `GTD_SWEEP_SECS` (default: 5) in the background
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let interval = std::env::var("GTD_SWEEP_SECS").ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .map_or(DEFAULT_SWEEP_INTERVAL, Duration::from_secs);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.sweep().await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TestClock;
+    use crate::plugins::mock::MockPlugin;
+    use crate::plugins::{ExecutionPlugin, Order, OrderSide, OrderType};
+    
+    fn limit_order() -> Order {
+        Order {
+            symbol: "BTC/USDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity: 0.1,
+            price: Some(60000.0),
+            confidence: 0.7,
+            ..Default::default()
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_expired_order_from_journal_cancelled_after_restart() {
+        let mut mock = MockPlugin::new("mock");
+        mock.init(serde_json::json!({})).await.unwrap();
+        let mock = Arc::new(mock);
+        let registry = Arc::new(PluginRegistry::new());
+        registry.register("mock".to_string(), mock.clone()).await;
+        
+        let expiring = mock.execute_order(limit_order()).await.unwrap().order_id.unwrap();
+        let later = mock.execute_order(limit_order()).await.unwrap().order_id.unwrap();
+        
+        let journal = std::env::temp_dir().join(format!("fks-gtd-{}.json", std::process::id()));
+        let clock = TestClock::new(1_700_000_000_000);
+        let gtd = |order_id: &str, expire_at| GtdOrder {
+            plugin: "mock".to_string(),
+            symbol: "BTC/USDT".to_string(),
+            order_id: order_id.to_string(),
+            expire_at,
+        };
+        {
+            let sweeper = GtdSweeper::with_clock(registry.clone(), Some(journal.clone()), clock.clone());
+            sweeper.track(gtd(&expiring, 1_700_000_060_000)).await;
+            sweeper.track(gtd(&later, 1_700_000_600_000)).await;
+        }
+        
+        // The service was down while the first order expired
+        clock.advance(Duration::from_secs(120));
+        let sweeper = GtdSweeper::with_clock(registry, Some(journal.clone()), clock);
+        assert_eq!(sweeper.pending().await.len(), 2);
+        
+        let cancelled = sweeper.sweep().await;
+        assert_eq!(cancelled, vec![gtd(&expiring, 1_700_000_060_000)]);
+        assert_eq!(mock.get_order("BTC/USDT", &expiring).await.unwrap().status, OrderStatus::Cancelled);
+        assert_eq!(mock.get_order("BTC/USDT", &later).await.unwrap().status, OrderStatus::Open);
+        
+        // Only the unexpired order is left in the journal
+        assert_eq!(GtdSweeper::load(&journal), vec![gtd(&later, 1_700_000_600_000)]);
+        std::fs::remove_file(journal).unwrap();
+    }
+}
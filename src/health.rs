@@ -1,9 +1,12 @@
 /// Health check endpoints for FKS services
-use axum::{response::{Json, IntoResponse}, routing::get, Router, http::StatusCode};
+use axum::{response::{Json, IntoResponse}, routing::get, Router, http::StatusCode, extract::State};
 use serde_json::{json, Value};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
 
-pub fn health_routes<S>() -> Router<S>
+use crate::metrics;
+use crate::plugins::{now_millis, registry::PluginRegistry, HealthStatus};
+
+pub fn health_routes<S>(registry: Arc<PluginRegistry>) -> Router<S>
 where
     S: Clone + Send + Sync + 'static,
 {
@@ -11,30 +14,43 @@ where
         .route("/health", get(health_check))
         .route("/ready", get(readiness_check))
         .route("/live", get(liveness_check))
+        .route("/startup", get(startup_check))
         .route("/metrics", get(metrics))
+        .with_state(registry)
 }
 
 async fn health_check() -> Json<Value> {
     Json(json!({
         "status": "healthy",
         "service": "fks_execution",
-        "timestamp": SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
+        "timestamp": now_millis()
     }))
 }
 
-async fn readiness_check() -> Json<Value> {
-    // TODO: Add dependency checks
+async fn readiness_check(State(registry): State<Arc<PluginRegistry>>) -> Json<Value> {
+    // Plugins in an exchange maintenance window, with rejected credentials or
+    // disabled by an operator are reported but don't fail readiness
+    let health = registry.health_status_all().await;
+    let dependencies: serde_json::Map<String, Value> = registry
+        .maintenance_status_all()
+        .await
+        .into_iter()
+        .map(|(name, in_maintenance)| {
+            let plugin_health = health.get(&name).copied().unwrap_or(HealthStatus::Unconfigured);
+            let status = match (plugin_health, in_maintenance) {
+                (HealthStatus::Disabled, _) => "disabled",
+                (_, true) => "maintenance",
+                _ => "available",
+            };
+            (name, json!({ "status": status, "maintenance": in_maintenance, "health": plugin_health }))
+        })
+        .collect();
+    
     Json(json!({
         "status": "ready",
         "service": "fks_execution",
-        "timestamp": SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
-        "dependencies": {}
+        "timestamp": now_millis(),
+        "dependencies": dependencies
     }))
 }
 
@@ -42,22 +58,79 @@ async fn liveness_check() -> Json<Value> {
     Json(json!({
         "status": "alive",
         "service": "fks_execution",
-        "timestamp": SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
+        "timestamp": now_millis()
     }))
 }
 
+/// Startup probe: up once every plugin has had its init attempt
+///
+/// Unlike `/ready`, exchange reachability doesn't matter, so an exchange
+/// outage during a rollout doesn't make the startup probe flap.
+async fn startup_check(State(registry): State<Arc<PluginRegistry>>) -> (StatusCode, Json<Value>) {
+    let (status, state) = match registry.init_attempted() {
+        true => (StatusCode::OK, "started"),
+        false => (StatusCode::SERVICE_UNAVAILABLE, "starting"),
+    };
+    (status, Json(json!({
+        "status": state,
+        "service": "fks_execution",
+        "timestamp": now_millis(),
+        "plugins": registry.list_plugins().await.len()
+    })))
+}
+
 async fn metrics() -> impl IntoResponse {
-    // Return Prometheus-format metrics with fks_build_info
-    let version = env!("CARGO_PKG_VERSION");
-    let metrics_text = format!(
-        r#"# HELP fks_build_info Build information for the service
-# TYPE fks_build_info gauge
-fks_build_info{{service="fks_execution",version="{}"}} 1
-"#,
-        version
-    );
-    (StatusCode::OK, [("content-type", "text/plain; version=0.0.4; charset=utf-8")], metrics_text)
+    metrics::build_info_metric();
+    (StatusCode::OK, [("content-type", "text/plain; version=0.0.4; charset=utf-8")], metrics::render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::{to_bytes, Body}, http::Request};
+    use tower::ServiceExt;
+    
+    #[tokio::test]
+    async fn test_startup_ok_with_every_plugin_unhealthy() {
+        let registry = Arc::new(PluginRegistry::new());
+        // Never initialized, so its health check fails
+        let ccxt = Arc::new(crate::plugins::ccxt::CCXTPlugin::new("ccxt"));
+        registry.register("ccxt".to_string(), ccxt).await;
+        assert_ne!(registry.health_status_all().await["ccxt"], HealthStatus::Healthy);
+        let app: Router = health_routes(registry.clone());
+        
+        let startup = || app.clone().oneshot(Request::get("/startup").body(Body::empty()).unwrap());
+        assert_eq!(startup().await.unwrap().status(), StatusCode::SERVICE_UNAVAILABLE);
+        
+        registry.mark_init_attempted();
+        let response = startup().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(body["status"], "started");
+        assert_eq!(body["plugins"], 1);
+    }
+    
+    #[tokio::test]
+    async fn test_metrics_build_info_labels_rendered_once() {
+        let app: Router = health_routes(Arc::new(PluginRegistry::new()));
+        
+        // Scrape twice: the build info gauge must not be duplicated
+        let mut body = String::new();
+        for _ in 0..2 {
+            let response = app.clone()
+                .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            body = String::from_utf8(bytes.to_vec()).unwrap();
+        }
+        
+        let build_info: Vec<&str> = body.lines().filter(|line| line.starts_with("fks_build_info{")).collect();
+        assert_eq!(build_info.len(), 1, "{}", body);
+        assert_eq!(body.matches("commit=").count(), 1);
+        assert_eq!(body.matches("build_date=").count(), 1);
+        assert!(build_info[0].contains(&format!("version=\"{}\"", env!("CARGO_PKG_VERSION"))));
+        assert!(build_info[0].ends_with(" 1"));
+    }
 }
@@ -0,0 +1,192 @@
+//! In-flight order tracking
+//!
+//! Every order being submitted holds an [`InFlightGuard`] from [`InFlightOrders::begin`],
+//! taken by the plugin registry, so shutdown can wait for them to finish.
+//! Optionally (one-order-per-symbol), order endpoints reserve the symbol with
+//! [`InFlightOrders::try_begin`], refusing an order while another order for the
+//! same symbol on the same exchange is still being submitted, so rapid-fire
+//! webhooks can't stack positions.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How often a shutdown drain re-checks for unfinished orders
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// An order for the same (exchange, symbol) is still in flight
+#[derive(Debug, thiserror::Error, PartialEq)]
+#[error("an order for {symbol} on {exchange} is already in flight")]
+pub struct InFlightError {
+    pub exchange: String,
+    pub symbol: String,
+}
+
+/// Outcome of waiting for in-flight orders at shutdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrainReport {
+    /// Orders that finished during the grace period
+    pub drained: usize,
+    /// Orders still being submitted when the grace period ran out
+    pub abandoned: usize,
+    pub grace_exceeded: bool,
+}
+
+/// Orders currently being submitted, and the (exchange, symbol) pairs they hold
+#[derive(Default)]
+pub struct InFlightOrders {
+    enabled: bool,
+    active: Mutex<HashSet<(String, String)>>,
+    pending: AtomicUsize,
+}
+
+impl InFlightOrders {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            active: Mutex::new(HashSet::new()),
+            pending: AtomicUsize::new(0),
+        }
+    }
+    
+    /// Build from `ONE_ORDER_PER_SYMBOL` (default: disabled)
+    pub fn from_env() -> Self {
+        Self::new(matches!(std::env::var("ONE_ORDER_PER_SYMBOL").as_deref(), Ok("true" | "1")))
+    }
+    
+    /// Whether the one-order-per-symbol check is on
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+    
+    /// Mark an order as being submitted until the guard drops, for [`Self::drain`]
+    pub fn begin(self: &Arc<Self>) -> InFlightGuard {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard { orders: self.clone(), key: None, counted: true }
+    }
+    
+    /// Reserve `symbol` on `exchange` for one order until the guard drops
+    ///
+    /// Symbols are compared case-insensitively. Always succeeds when the
+    /// one-order-per-symbol check is disabled. Doesn't count toward
+    /// [`Self::pending`]; the submission itself does.
+    pub fn try_begin(self: &Arc<Self>, exchange: &str, symbol: &str) -> Result<InFlightGuard, InFlightError> {
+        let key = if self.enabled {
+            let key = (exchange.to_string(), symbol.to_uppercase());
+            if !self.active.lock().unwrap().insert(key.clone()) {
+                return Err(InFlightError {
+                    exchange: exchange.to_string(),
+                    symbol: symbol.to_string(),
+                });
+            }
+            Some(key)
+        } else {
+            None
+        };
+        
+        Ok(InFlightGuard { orders: self.clone(), key, counted: false })
+    }
+    
+    /// Orders currently being submitted
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+    
+    /// Wait up to `grace` for every in-flight order to finish
+    pub async fn drain(&self, grace: Duration) -> DrainReport {
+        let started_with = self.pending();
+        let deadline = Instant::now() + grace;
+        while self.pending() > 0 {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL.min(deadline - now)).await;
+        }
+        
+        let abandoned = self.pending();
+        DrainReport {
+            drained: started_with.saturating_sub(abandoned),
+            abandoned,
+            grace_exceeded: abandoned > 0,
+        }
+    }
+}
+
+/// Marks an order as finished, releasing its (exchange, symbol) slot, when dropped
+pub struct InFlightGuard {
+    orders: Arc<InFlightOrders>,
+    key: Option<(String, String)>,
+    /// Counted in `pending` ([`InFlightOrders::begin`])
+    counted: bool,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.orders.active.lock().unwrap().remove(&key);
+        }
+        if self.counted {
+            self.orders.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_second_order_blocked_until_first_completes() {
+        let orders = Arc::new(InFlightOrders::new(true));
+        
+        let guard = orders.try_begin("bybit", "BTCUSDT").unwrap();
+        let err = orders.try_begin("bybit", "btcusdt").err().unwrap();
+        assert_eq!(err.to_string(), "an order for btcusdt on bybit is already in flight");
+        
+        // Other symbols and other exchanges are independent
+        let _eth = orders.try_begin("bybit", "ETHUSDT").unwrap();
+        let _kucoin = orders.try_begin("kucoin", "BTCUSDT").unwrap();
+        
+        drop(guard);
+        assert!(orders.try_begin("bybit", "BTCUSDT").is_ok());
+        
+        // Reservations alone aren't submissions the drain waits for
+        assert_eq!(orders.pending(), 0);
+    }
+    
+    #[test]
+    fn test_disabled_never_blocks() {
+        let orders = Arc::new(InFlightOrders::default());
+        
+        let _first = orders.try_begin("bybit", "BTCUSDT").unwrap();
+        assert!(orders.try_begin("bybit", "BTCUSDT").is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_drain_waits_for_in_flight_order_up_to_grace() {
+        let orders = Arc::new(InFlightOrders::default());
+        
+        // Finishes well within the grace period
+        let guard = orders.begin();
+        let submit = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            drop(guard);
+        });
+        let started = Instant::now();
+        let report = orders.drain(Duration::from_secs(5)).await;
+        assert!(started.elapsed() >= Duration::from_millis(150));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(report, DrainReport { drained: 1, abandoned: 0, grace_exceeded: false });
+        submit.await.unwrap();
+        
+        // Still running when the grace period ends
+        let _stuck = orders.begin();
+        let started = Instant::now();
+        let report = orders.drain(Duration::from_millis(200)).await;
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert_eq!(report, DrainReport { drained: 0, abandoned: 1, grace_exceeded: true });
+    }
+}
@@ -1,4 +1,4 @@
-use axum::{routing::{get, post}, Router, Json, extract::{State, Path, Query}, http::StatusCode};
+use axum::{routing::{get, post}, Router, Json, extract::{State, Path, Query}, http::{StatusCode, Uri}, body::Bytes};
 use clap::Parser;
 use serde::Serialize;
 use std::{net::SocketAddr, time::{Instant, Duration}, sync::Arc};
@@ -7,14 +7,37 @@ use serde::Deserialize;
 
 // Plugin framework
 mod plugins;
+mod admin;
+mod clock;
+mod cors;
+mod default_quantity;
+mod gtd;
 mod health;
+mod in_flight;
+mod metrics;
+mod order_cap;
+mod order_groups;
+mod portfolio;
+mod publisher;
+mod reject_log;
+mod reload;
+mod replay;
+mod request_log;
+mod secrets;
+mod signals;
+mod symbols;
+mod twap;
+mod webhook;
+use webhook::{ActionAliases, TradingViewWebhook};
 use plugins::{
     registry::PluginRegistry, 
     ccxt::CCXTPlugin,
     bybit::BybitPlugin,
     kucoin::KuCoinPlugin,
-    Order, OrderSide, OrderType,
-    ExecutionPlugin
+    ibkr::IbkrPlugin,
+    Balance, ClosedPnl, FundingRate, Order, PreparedRequest, OrderSide, OrderType, OrderStatus, QuantityType, OrderPage, OrderSummary, Position, Trade,
+    CancelOutcome, ExecutionError, ExecutionPlugin, LeverageChange, OrderRef, PingResult,
+    now_millis, parse_quote_aliases
 };
 
 #[derive(Parser, Debug)]
@@ -24,54 +47,385 @@ struct Cli {
     listen: String 
 }
 
-#[derive(Serialize, Clone)] struct Signal { symbol: String, rsi: f64, ema: f64, risk_allowance: f64, latency_ms: u128 }
+#[derive(Debug, Serialize, Clone)]
+struct Signal {
+    symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rsi: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ema: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    macd: Option<f64>,
+    /// Average true range; only computed from `candles`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    atr: Option<f64>,
+    risk_allowance: f64,
+    latency_ms: u128,
+    timestamp: i64, // Unix millis
+}
 
-#[derive(Deserialize)] struct SignalRequest { symbol: Option<String>, prices: Option<Vec<f64>> }
+/// One bar as `[open, high, low, close]`
+type Candle = [f64; 4];
+
+/// Signal request; `candles` enable range indicators such as ATR and supply
+/// the closes when `prices` is omitted
+#[derive(Deserialize)]
+struct SignalRequest {
+    symbol: Option<String>,
+    prices: Option<Vec<f64>>,
+    candles: Option<Vec<Candle>>,
+}
 
-#[derive(Serialize)] struct Health { service: String, status: String }
+/// Wilder's ATR lookback, in candles
+const ATR_PERIOD: usize = 14;
 
-#[derive(Clone)]
-struct AppState { 
-    start: Instant,
-    registry: Arc<PluginRegistry>
+/// Signal query parameters: ?indicators=rsi,ema,macd
+#[derive(Deserialize, Default)]
+struct SignalQuery {
+    indicators: Option<String>,
 }
 
+/// Signal aggregation request
 #[derive(Deserialize)]
-struct TradingViewWebhook {
-    symbol: String,
-    action: String, // "buy" or "sell"
-    order_type: Option<String>, // "market", "limit"
-    quantity: f64,
-    price: Option<f64>,
-    stop_loss: Option<f64>,
-    take_profit: Option<f64>,
-    confidence: Option<f64>,
+struct AggregateSignalsRequest {
+    signals: Vec<signals::aggregate::SignalInput>,
+    #[serde(default)]
+    scheme: signals::aggregate::Scheme,
+    /// Net score magnitude below which the decision is hold
+    hold_threshold: Option<f64>,
+}
+
+/// Indicators the signal endpoint can compute
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Indicator {
+    Rsi,
+    Ema,
+    Macd,
+}
+
+impl Indicator {
+    const SUPPORTED: [Indicator; 3] = [Indicator::Rsi, Indicator::Ema, Indicator::Macd];
+    
+    /// Computed when the client doesn't ask for a specific set
+    const DEFAULT: [Indicator; 2] = [Indicator::Rsi, Indicator::Ema];
+    
+    fn name(&self) -> &'static str {
+        match self {
+            Indicator::Rsi => "rsi",
+            Indicator::Ema => "ema",
+            Indicator::Macd => "macd",
+        }
+    }
+    
+    fn parse(name: &str) -> Option<Self> {
+        Self::SUPPORTED.into_iter().find(|i| i.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Parse the `indicators` query value, rejecting unknown names
+fn parse_indicators(query: Option<&str>) -> Result<Vec<Indicator>, (StatusCode, Json<serde_json::Value>)> {
+    let Some(query) = query.filter(|q| !q.trim().is_empty()) else {
+        return Ok(Indicator::DEFAULT.to_vec());
+    };
+    
+    let unknown: Vec<&str> = query
+        .split(',')
+        .filter(|name| Indicator::parse(name).is_none())
+        .map(str::trim)
+        .collect();
+    if !unknown.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Unknown indicators: {}", unknown.join(",")),
+                "supported": Indicator::SUPPORTED.iter().map(|i| i.name()).collect::<Vec<_>>()
+            }))
+        ));
+    }
+    
+    Ok(query.split(',').filter_map(Indicator::parse).collect())
+}
+
+#[derive(Clone)]
+struct AppState { 
+    registry: Arc<PluginRegistry>,
+    nonces: Arc<replay::NonceStore>,
+    order_groups: Arc<order_groups::OrderGroupTracker>,
+    symbols: Arc<symbols::SymbolCache>,
+    /// Reject orders whose price doesn't fit their type instead of warning
+    strict_orders: bool,
+    /// How long `?mode=sync` webhooks wait for a terminal order status
+    webhook_sync_timeout: Duration,
+    /// Webhook answer when no default plugin is registered
+    webhook_missing_plugin: MissingPluginMode,
+    /// Action names accepted from webhook alerts
+    webhook_actions: Arc<ActionAliases>,
+    /// Optional one-order-per-symbol guard against stacked orders
+    in_flight: Arc<in_flight::InFlightOrders>,
+    /// Quantities used when an order or webhook omits `quantity`
+    default_quantities: Arc<default_quantity::DefaultQuantities>,
+    /// Confidence for orders that don't carry one
+    default_confidence: f64,
+    /// Running and finished TWAP jobs
+    twap: Arc<twap::TwapScheduler>,
+    /// Collapses repeated identical order rejections into periodic summaries
+    rejects: Arc<reject_log::RejectLog>,
+    /// Resting orders to cancel at their `expire_at`
+    gtd: Arc<gtd::GtdSweeper>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
 struct WebhookResponse {
     success: bool,
     order_id: Option<String>,
     error: Option<String>,
+    /// Final order status, only reported in sync mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<OrderStatus>,
+}
+
+/// Webhook answer when its plugin isn't registered (`WEBHOOK_MISSING_PLUGIN_MODE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum MissingPluginMode {
+    /// 503, so TradingView retries the alert
+    #[default]
+    Retry,
+    /// 200 with `success: false`, so TradingView drops the alert
+    Skip,
+}
+
+impl MissingPluginMode {
+    /// Parse `retry` (default) or `skip`
+    fn from_env() -> Self {
+        match std::env::var("WEBHOOK_MISSING_PLUGIN_MODE").as_deref().map(str::trim) {
+            Ok("skip") => MissingPluginMode::Skip,
+            Ok("retry") | Err(_) => MissingPluginMode::Retry,
+            Ok(other) => {
+                tracing::warn!(value = %other, "webhook_missing_plugin_mode_invalid_using_retry");
+                MissingPluginMode::Retry
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct WebhookQuery {
+    /// "sync" waits for the order to fill or be rejected before responding
+    mode: Option<String>,
 }
 
 /// Order creation request
 #[derive(Deserialize)]
 struct CreateOrderRequest {
-    exchange: String,
+    exchange: Option<String>, // default plugin when omitted
     symbol: String,
     side: String, // "buy" or "sell"
     order_type: String, // "market", "limit", etc.
-    quantity: f64,
+    #[serde(default, deserialize_with = "webhook::lenient_f64")]
+    quantity: Option<f64>, // DEFAULT_QUANTITY when omitted
+    #[serde(default, deserialize_with = "webhook::lenient_f64")]
     price: Option<f64>,
+    /// Bybit and KuCoin futures: overrides the plugin's leverage, capped by `max_leverage`
     leverage: Option<i32>,
+    #[serde(default, deserialize_with = "webhook::lenient_f64")]
     stop_loss: Option<f64>,
+    #[serde(default, deserialize_with = "webhook::lenient_f64")]
     take_profit: Option<f64>,
-    category: Option<String>, // For Bybit: "linear", "spot", etc.
+    /// Refused: the Bybit category is plugin config (`BYBIT_CATEGORY`, auto-detection)
+    category: Option<String>,
+    strategy_tag: Option<String>,
+    /// Orders sharing a group id are one-cancels-other: the first fill cancels the rest
+    #[serde(default)]
+    order_group_id: Option<String>,
+    /// Resting orders: cancel at this time (Unix millis), simulating good-till-date
+    #[serde(default)]
+    expire_at: Option<i64>,
+    /// "base" (default) or "quote" when `quantity` is an amount of the quote currency
+    #[serde(default)]
+    quantity_type: QuantityType,
+    /// Iceberg orders: the portion of a limit order shown on the book
+    #[serde(default, deserialize_with = "webhook::lenient_f64")]
+    display_quantity: Option<f64>,
+    /// Stop-limit orders: the price that activates the limit order
+    #[serde(default, deserialize_with = "webhook::lenient_f64")]
+    trigger_price: Option<f64>,
+    /// Advanced/unsafe: raw venue fields merged into the exchange request
+    #[serde(default)]
+    extra_params: Option<serde_json::Value>,
+    /// Only reduce an open position, never open or flip one
+    #[serde(default)]
+    reduce_only: bool,
+    /// Limit orders: reject rather than match on arrival (maker-only)
+    #[serde(default)]
+    post_only: bool,
+    /// Reduce-only orders: cap `quantity` at the open position size instead of
+    /// leaving over-sized orders to the exchange (which may clamp or reject)
+    #[serde(default)]
+    clamp_reduce_only: bool,
+    /// Route this order to testnet (`true`) or mainnet (`false`) rather than
+    /// the plugin's configured network (Bybit and KuCoin only)
+    #[serde(default)]
+    testnet: Option<bool>,
+    /// Market orders: worst acceptable fill in percent from the current price
+    #[serde(default, deserialize_with = "webhook::lenient_f64")]
+    max_slippage_pct: Option<f64>,
+}
+
+impl CreateOrderRequest {
+    /// Convert to a plugin order, rejecting unknown sides and order types and
+    /// invalid display quantities, using `default_quantity` when `quantity` is omitted
+    fn to_order(&self, default_quantity: Option<f64>, default_confidence: f64) -> Result<Order, String> {
+        let side = match self.side.to_lowercase().as_str() {
+            "buy" => OrderSide::Buy,
+            "sell" => OrderSide::Sell,
+            _ => return Err(format!("Invalid side: {}", self.side)),
+        };
+        
+        let order_type = match self.order_type.to_lowercase().as_str() {
+            "market" => OrderType::Market,
+            "limit" => OrderType::Limit,
+            "stop" => OrderType::Stop,
+            "stop_limit" | "stoplimit" => OrderType::StopLimit,
+            "take_profit" | "takeprofit" => OrderType::TakeProfit,
+            "stop_loss" | "stoploss" => OrderType::StopLoss,
+            _ => return Err(format!("Invalid order_type: {}", self.order_type)),
+        };
+        
+        let quantity = self.quantity.or(default_quantity)
+            .ok_or_else(|| format!("quantity is required (no default quantity configured for {})", self.symbol))?;
+        
+        if self.extra_params.as_ref().is_some_and(|extra| !extra.is_object()) {
+            return Err("extra_params must be a JSON object".to_string());
+        }
+        
+        if let Some(category) = &self.category {
+            return Err(format!("category {} cannot be set per order; configure it on the plugin", category));
+        }
+        
+        if let Some(expire_at) = self.expire_at {
+            if order_type == OrderType::Market {
+                return Err("expire_at only applies to resting orders, not market".to_string());
+            }
+            if expire_at <= now_millis() {
+                return Err(format!("expire_at {} is in the past", expire_at));
+            }
+        }
+        
+        let order = Order {
+            symbol: self.symbol.clone(),
+            side,
+            order_type,
+            quantity,
+            price: self.price,
+            stop_loss: self.stop_loss,
+            take_profit: self.take_profit,
+            confidence: default_confidence,
+            reduce_only: self.reduce_only,
+            strategy_tag: self.strategy_tag.clone(),
+            quantity_type: self.quantity_type,
+            display_quantity: self.display_quantity,
+            trigger_price: self.trigger_price,
+            extra_params: self.extra_params.clone(),
+            post_only: self.post_only,
+            testnet: self.testnet,
+            max_slippage_pct: self.max_slippage_pct,
+            leverage: self.leverage,
+        };
+        order.validate_display_quantity()?;
+        order.validate_post_only()?;
+        order.validate_max_slippage()?;
+        Ok(order)
+    }
+}
+
+/// TWAP order request: `quantity` split into `slices` equal market orders
+/// submitted evenly over `duration_secs`
+#[derive(Deserialize)]
+struct TwapOrderRequest {
+    exchange: Option<String>, // default plugin when omitted
+    symbol: String,
+    side: String, // "buy" or "sell"
+    quantity: f64,
+    duration_secs: u64,
+    slices: u32,
+    strategy_tag: Option<String>,
+}
+
+/// Order preview response: what `POST /api/v1/orders` would send, and why it might fail
+#[derive(Debug, Serialize)]
+struct PreviewOrderResponse {
+    exchange: String,
+    
+    /// False when the order would be rejected before reaching the exchange
+    valid: bool,
+    warnings: Vec<String>,
+    order: Order,
+    request: PreparedRequest,
+}
+
+/// Most orders one batch cancel request may name
+const MAX_CANCEL_BATCH: usize = 100;
+
+/// Batch cancel request: orders of one symbol, by exchange and/or client order id
+#[derive(Deserialize)]
+struct CancelBatchRequest {
+    exchange: Option<String>, // default plugin when omitted
+    symbol: String,
+    #[serde(default)]
+    order_ids: Vec<String>,
+    #[serde(default)]
+    client_order_ids: Vec<String>,
+}
+
+/// Batch cancel response, one result per requested id (order ids first)
+#[derive(Debug, Serialize)]
+struct CancelBatchResponse {
+    exchange: String,
+    cancelled: usize,
+    failed: usize,
+    results: Vec<CancelOutcome>,
+}
+
+/// Most steps one replay request may contain
+const MAX_REPLAY_STEPS: usize = 1000;
+
+/// Replay request: recorded orders and webhook signals to dry-run in timestamp order
+#[derive(Deserialize)]
+struct ReplayRequest {
+    steps: Vec<ReplayStep>,
+}
+
+/// One recorded input, either an order request or a TradingView alert payload
+#[derive(Deserialize)]
+struct ReplayStep {
+    timestamp: i64, // Unix millis
+    #[serde(flatten)]
+    input: ReplayInput,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ReplayInput {
+    Order(Box<CreateOrderRequest>),
+    Signal(serde_json::Value),
+}
+
+/// What one replayed step would have done: its preview, or why it was rejected
+#[derive(Debug, Serialize)]
+struct ReplayStepResult {
+    timestamp: i64,
+    preview: Option<PreviewOrderResponse>,
+    error: Option<String>,
+}
+
+/// Replay response, one result per step in replay order
+#[derive(Debug, Serialize)]
+struct ReplayResponse {
+    steps: Vec<ReplayStepResult>,
 }
 
 /// Order creation response
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
 struct CreateOrderResponse {
     success: bool,
     order_id: Option<String>,
@@ -86,16 +440,210 @@ struct CreateOrderResponse {
 struct SetLeverageRequest {
     symbol: String,
     leverage: i32,
+    /// Refused, as for orders: the category is plugin config
     category: Option<String>,
 }
 
 /// Set leverage response
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
 struct SetLeverageResponse {
     success: bool,
+    /// Whether the exchange actually changed anything; absent on failure
+    outcome: Option<LeverageChange>,
+    error: Option<String>,
+}
+
+/// Order list query parameters
+#[derive(Deserialize)]
+struct OrderListQuery {
+    exchange: Option<String>,
+    symbol: Option<String>,
+    status: Option<String>,
+    /// `next_cursor` of the previous page
+    cursor: Option<String>,
+    /// Page size (default 50); setting it or `cursor` returns a page
+    limit: Option<usize>,
+}
+
+/// Order list response: every order, or one page when paginating
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum OrderListResponse {
+    All(Vec<OrderSummary>),
+    Page(OrderPage),
+}
+
+/// Client order id lookup query parameters
+#[derive(Deserialize)]
+struct ClientOrderQuery {
+    exchange: Option<String>,
+    symbol: String,
+}
+
+/// Ping query parameters
+#[derive(Deserialize)]
+struct PingQuery {
+    exchange: Option<String>,
+}
+
+/// Self-test query parameters: POST /api/v1/selftest?exchange=bybit
+#[derive(Deserialize)]
+struct SelfTestQuery {
+    exchange: Option<String>,
+    /// Symbol to trade (default: BTC/USDT)
+    symbol: Option<String>,
+    /// Order size; defaults to the instrument's minimum quantity
+    quantity: Option<f64>,
+}
+
+/// Outcome of one self-test step
+#[derive(Debug, Serialize)]
+struct SelfTestStep {
+    step: &'static str,
+    success: bool,
+    latency_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
 }
 
+/// Self-test report; `passed` only when every step succeeded
+#[derive(Debug, Serialize)]
+struct SelfTestReport {
+    exchange: String,
+    symbol: String,
+    passed: bool,
+    steps: Vec<SelfTestStep>,
+}
+
+/// Symbol list query parameters
+#[derive(Deserialize)]
+struct SymbolsQuery {
+    exchange: Option<String>,
+}
+
+/// Symbol list response
+#[derive(Debug, Serialize)]
+struct SymbolsResponse {
+    exchange: String,
+    symbols: Vec<String>,
+}
+
+/// Registered exchange plugin and its recent failures
+#[derive(Debug, Serialize)]
+struct ExchangeDetail {
+    name: String,
+    default: bool,
+    enabled: bool,
+    in_maintenance: bool,
+    /// Orders go to a paper/sandbox/testnet account; false means real money
+    paper: bool,
+    /// Failed plugin calls since startup, all error kinds
+    errors_total: u64,
+    last_error: Option<metrics::LastError>,
+}
+
+/// Balance query parameters: ?exchange=bybit&assets=USDT,BTC&nonzero=true
+#[derive(Deserialize)]
+struct BalanceQuery {
+    exchange: Option<String>,
+    /// Comma-separated assets to return, in this order
+    assets: Option<String>,
+    /// Leave out assets with a zero total
+    #[serde(default)]
+    nonzero: bool,
+}
+
+/// Balance list response
+#[derive(Debug, Serialize)]
+struct BalancesResponse {
+    exchange: String,
+    balances: Vec<Balance>,
+}
+
+/// PnL query parameters: ?exchange=bybit&symbol=BTCUSDT
+#[derive(Deserialize)]
+struct PnlQuery {
+    exchange: Option<String>,
+    symbol: Option<String>,
+}
+
+/// Realized and unrealized PnL, in total and per symbol
+#[derive(Debug, Serialize)]
+struct PnlSummary {
+    exchange: String,
+    realized_pnl: f64,
+    unrealized_pnl: f64,
+    total_pnl: f64,
+    symbols: Vec<SymbolPnl>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct SymbolPnl {
+    symbol: String,
+    realized_pnl: f64,
+    unrealized_pnl: f64,
+}
+
+impl PnlSummary {
+    /// Sum open positions' unrealized PnL and closed positions' realized PnL, per symbol in name order
+    fn combine(exchange: &str, positions: &[Position], closed: &[ClosedPnl]) -> Self {
+        // (realized, unrealized) per symbol
+        let mut by_symbol: std::collections::BTreeMap<&str, (f64, f64)> = std::collections::BTreeMap::new();
+        for position in positions {
+            by_symbol.entry(&position.symbol).or_default().1 += position.unrealized_pnl;
+        }
+        for close in closed {
+            by_symbol.entry(&close.symbol).or_default().0 += close.realized_pnl;
+        }
+        
+        let realized_pnl = closed.iter().map(|c| c.realized_pnl).sum::<f64>();
+        let unrealized_pnl = positions.iter().map(|p| p.unrealized_pnl).sum::<f64>();
+        PnlSummary {
+            exchange: exchange.to_string(),
+            realized_pnl,
+            unrealized_pnl,
+            total_pnl: realized_pnl + unrealized_pnl,
+            symbols: by_symbol.into_iter()
+                .map(|(symbol, (realized_pnl, unrealized_pnl))| SymbolPnl { symbol: symbol.to_string(), realized_pnl, unrealized_pnl })
+                .collect(),
+        }
+    }
+}
+
+/// Default and largest number of fills returned by GET /api/v1/trades
+const DEFAULT_TRADES_LIMIT: usize = 50;
+const MAX_TRADES_LIMIT: usize = 500;
+
+/// Trade history query parameters: ?exchange=bybit&symbol=BTCUSDT&limit=50
+#[derive(Deserialize)]
+struct TradesQuery {
+    exchange: Option<String>,
+    symbol: Option<String>,
+    limit: Option<usize>,
+}
+
+/// Funding rate query parameters: ?exchange=bybit&symbol=BTCUSDT
+#[derive(Deserialize)]
+struct FundingQuery {
+    exchange: Option<String>,
+    symbol: String,
+}
+
+/// Redundant price query parameters
+#[derive(Deserialize)]
+struct PriceQuery {
+    symbol: String,
+}
+
+/// Fill list response, newest first
+#[derive(Debug, Serialize)]
+struct TradesResponse {
+    exchange: String,
+    trades: Vec<Trade>,
+}
+
 /// Position query parameters
 #[derive(Deserialize)]
 struct PositionQuery {
@@ -103,17 +651,12 @@ struct PositionQuery {
     symbol: Option<String>,
 }
 
-/// Position response
-#[derive(Serialize)]
-struct PositionResponse {
+/// Reduce position request
+#[derive(Deserialize)]
+struct ReducePositionRequest {
+    exchange: String,
     symbol: String,
-    side: String,
-    size: f64,
-    entry_price: f64,
-    mark_price: f64,
-    unrealized_pnl: f64,
-    leverage: i32,
-    margin: f64,
+    percent: f64, // 1-100
 }
 
 #[tokio::main]
@@ -141,16 +684,59 @@ async fn main() -> anyhow::Result<()> {
     // Initialize plugin registry
     let registry = Arc::new(PluginRegistry::new());
     
+    // Optional execution result events (RESULT_PUBLISHER=redis)
+    match publisher::from_env() {
+        Ok(result_publisher) => registry.set_publisher(result_publisher).await,
+        Err(e) => tracing::error!(error = %e, "result_publisher_config_invalid_events_disabled"),
+    }
+    
+    // Tiebreak and stale-quote fallback for orders sent to exchange "best"
+    let routing_priority = std::env::var("ROUTING_PRIORITY").unwrap_or_default();
+    registry.set_priority(plugins::registry::parse_priority(&routing_priority)).await;
+    
+    // Market-data calls past this fail instead of stalling quotes and routing
+    let call_timeout = std::env::var("PLUGIN_CALL_TIMEOUT_MS").ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_millis);
+    registry.set_call_timeout(call_timeout).await;
+    
+    // Bound concurrent exchange submissions so webhook bursts queue instead of tripping rate limits
+    let max_concurrent_orders = std::env::var("MAX_CONCURRENT_ORDERS").ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|limit| *limit > 0);
+    let order_queue_timeout = Duration::from_millis(
+        std::env::var("ORDER_QUEUE_TIMEOUT_MS").ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(5_000)
+    );
+    registry.set_order_concurrency(max_concurrent_orders, order_queue_timeout).await;
+    
+    // Orders past a daily count are refused until 00:00 UTC
+    let order_cap = order_cap::DailyOrderCap::from_env();
+    let order_cap_enabled = order_cap.is_enabled();
+    registry.set_order_cap(order_cap).await;
+    
+    // Cross-venue price checks flag quotes this far (percent) from the median
+    if let Some(pct) = std::env::var("PRICE_OUTLIER_PCT").ok().and_then(|v| v.parse::<f64>().ok()).filter(|pct| *pct > 0.0) {
+        registry.set_outlier_threshold(pct).await;
+    }
+    
+    // Plugin init retries with backoff so slow-starting sidecars are tolerated
+    let init_retry = plugins::InitRetry::from_env();
+    
     // Initialize CCXT plugin (non-fatal - service can run without it)
     let mut ccxt = CCXTPlugin::new("binance");
     let ccxt_config = serde_json::json!({
         "base_url": std::env::var("CCXT_BASE_URL").unwrap_or_else(|_| "http://localhost:8000".to_string()),
-        "webhook_secret": std::env::var("WEBHOOK_SECRET").unwrap_or_else(|_| "fks-tradingview-webhook-secret-dev-2025".to_string()),
+        "webhook_secret": secrets::read_secret("WEBHOOK_SECRET").unwrap_or_else(|| "fks-tradingview-webhook-secret-dev-2025".to_string()),
         "exchange": std::env::var("EXCHANGE").unwrap_or_else(|_| "binance".to_string()),
-        "testnet": std::env::var("TESTNET").unwrap_or_else(|_| "false".to_string()) == "true"
+        "testnet": std::env::var("TESTNET").unwrap_or_else(|_| "false".to_string()) == "true",
+        "webhook_path": std::env::var("CCXT_WEBHOOK_PATH").unwrap_or_else(|_| "/webhook/tradingview".to_string()),
+        "content_type": std::env::var("CCXT_CONTENT_TYPE").unwrap_or_else(|_| "json".to_string()),
+        "signature_algo": std::env::var("CCXT_SIGNATURE_ALGO").unwrap_or_else(|_| "hmac_sha256_hex".to_string())
     });
     
-    match ccxt.init(ccxt_config).await {
+    match init_retry.init(&mut ccxt, ccxt_config).await {
         Ok(_) => {
             registry.register("binance".to_string(), Arc::new(ccxt)).await;
             tracing::info!("ccxt_plugin_registered");
@@ -162,9 +748,9 @@ async fn main() -> anyhow::Result<()> {
     }
     
     // Initialize Bybit plugin (non-fatal - service can run without it)
-    if let (Ok(api_key), Ok(api_secret)) = (
-        std::env::var("BYBIT_API_KEY"),
-        std::env::var("BYBIT_API_SECRET")
+    if let (Some(api_key), Some(api_secret)) = (
+        secrets::read_secret("BYBIT_API_KEY"),
+        secrets::read_secret("BYBIT_API_SECRET")
     ) {
         let mut bybit = BybitPlugin::new("bybit");
         let bybit_config = serde_json::json!({
@@ -175,10 +761,15 @@ async fn main() -> anyhow::Result<()> {
             "leverage": std::env::var("BYBIT_LEVERAGE")
                 .unwrap_or_else(|_| "10".to_string())
                 .parse::<i32>()
-                .unwrap_or(10)
+                .unwrap_or(10),
+            "max_leverage": std::env::var("BYBIT_MAX_LEVERAGE").ok().and_then(|v| v.parse::<i32>().ok()),
+            "strict_leverage": std::env::var("BYBIT_STRICT_LEVERAGE").is_ok_and(|v| v == "true"),
+            "quote_aliases": parse_quote_aliases(&std::env::var("BYBIT_QUOTE_ALIASES").unwrap_or_default()),
+            "position_mode": std::env::var("BYBIT_POSITION_MODE").ok(),
+            "time_source": std::env::var("BYBIT_TIME_SOURCE").unwrap_or_else(|_| "local".to_string())
         });
         
-        match bybit.init(bybit_config).await {
+        match init_retry.init(&mut bybit, bybit_config).await {
             Ok(_) => {
                 registry.register("bybit".to_string(), Arc::new(bybit)).await;
                 tracing::info!("bybit_plugin_registered");
@@ -193,10 +784,10 @@ async fn main() -> anyhow::Result<()> {
     }
     
     // Initialize KuCoin plugin (Canada-compliant, non-fatal - service can run without it)
-    if let (Ok(api_key), Ok(api_secret), Ok(api_passphrase)) = (
-        std::env::var("KUCOIN_API_KEY"),
-        std::env::var("KUCOIN_API_SECRET"),
-        std::env::var("KUCOIN_API_PASSPHRASE")
+    if let (Some(api_key), Some(api_secret), Some(api_passphrase)) = (
+        secrets::read_secret("KUCOIN_API_KEY"),
+        secrets::read_secret("KUCOIN_API_SECRET"),
+        secrets::read_secret("KUCOIN_API_PASSPHRASE")
     ) {
         let mut kucoin = KuCoinPlugin::new("kucoin");
         let kucoin_config = serde_json::json!({
@@ -208,10 +799,14 @@ async fn main() -> anyhow::Result<()> {
             "leverage": std::env::var("KUCOIN_LEVERAGE")
                 .unwrap_or_else(|_| "10".to_string())
                 .parse::<i32>()
-                .unwrap_or(10)
+                .unwrap_or(10),
+            "max_leverage": std::env::var("KUCOIN_MAX_LEVERAGE").ok().and_then(|v| v.parse::<i32>().ok()),
+            "strict_leverage": std::env::var("KUCOIN_STRICT_LEVERAGE").is_ok_and(|v| v == "true"),
+            "quote_aliases": parse_quote_aliases(&std::env::var("KUCOIN_QUOTE_ALIASES").unwrap_or_default()),
+            "time_source": std::env::var("KUCOIN_TIME_SOURCE").unwrap_or_else(|_| "local".to_string())
         });
         
-        match kucoin.init(kucoin_config).await {
+        match init_retry.init(&mut kucoin, kucoin_config).await {
             Ok(_) => {
                 registry.register("kucoin".to_string(), Arc::new(kucoin)).await;
                 tracing::info!("kucoin_plugin_registered");
@@ -225,30 +820,162 @@ async fn main() -> anyhow::Result<()> {
         tracing::info!("kucoin_api_credentials_not_configured_skipping_kucoin_plugin");
     }
     
+    // Initialize Interactive Brokers plugin (Client Portal gateway, non-fatal - service can run without it)
+    if let Ok(account_id) = std::env::var("IBKR_ACCOUNT_ID") {
+        let mut ibkr = IbkrPlugin::new("ibkr");
+        let ibkr_config = serde_json::json!({
+            "account_id": account_id,
+            "base_url": std::env::var("IBKR_BASE_URL").unwrap_or_else(|_| "https://localhost:5000/v1/api".to_string()),
+            "verify_tls": std::env::var("IBKR_VERIFY_TLS").unwrap_or_else(|_| "false".to_string()) == "true",
+            "tickle_interval_secs": std::env::var("IBKR_TICKLE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse::<u64>()
+                .unwrap_or(60)
+        });
+        
+        match init_retry.init(&mut ibkr, ibkr_config).await {
+            Ok(_) => {
+                registry.register("ibkr".to_string(), Arc::new(ibkr)).await;
+                tracing::info!("ibkr_plugin_registered");
+            }
+            Err(e) => {
+                tracing::warn!(error=%e, "ibkr_plugin_init_failed_continuing_without");
+                // Continue without IBKR plugin - service can still run with other plugins
+            }
+        }
+    } else {
+        tracing::info!("ibkr_account_not_configured_skipping_ibkr_plugin");
+    }
+    
+    // Plugins from PLUGINS_CONFIG, re-read on SIGHUP without restarting. The
+    // env-configured plugins above are not part of the reload
+    let reloader = reload::ConfigReloader::from_env(registry.clone());
+    let plugins_config = reloader.is_some();
+    if let Some(mut reloader) = reloader {
+        if let Err(e) = reloader.reload().await {
+            tracing::error!(error = %e, "plugin_config_load_failed");
+        }
+        #[cfg(unix)]
+        if let Err(e) = reloader.spawn_sighup() {
+            tracing::warn!(error = %e, "sighup_handler_install_failed");
+        }
+    }
+    registry.mark_init_attempted();
+    
+    // Keep position gauges current; POSITION_REFRESH_SECS=0 disables the refresh
+    let refresh_secs = std::env::var("POSITION_REFRESH_SECS").ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(60);
+    if refresh_secs > 0 {
+        metrics::PositionRefresh::from_env(registry.clone()).spawn(Duration::from_secs(refresh_secs));
+    }
+    
+    // Poll grouped orders so a filled leg cancels its siblings
+    let order_groups = Arc::new(order_groups::OrderGroupTracker::new(registry.clone()));
+    let group_poll_ms = std::env::var("ORDER_GROUP_POLL_MS").ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(2000)
+        .max(100);
+    order_groups.clone().spawn(Duration::from_millis(group_poll_ms));
+    
+    // Cancel expired GTD orders, including any left in the journal by a restart
+    let gtd = Arc::new(gtd::GtdSweeper::from_env(registry.clone()));
+    gtd.clone().spawn();
+    
+    // Orders being submitted at shutdown get this long to finish
+    let shutdown_grace = Duration::from_secs(
+        std::env::var("SHUTDOWN_GRACE_SECS").ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(30)
+    );
+    let in_flight = Arc::new(in_flight::InFlightOrders::from_env());
+    
+    let rejects = Arc::new(reject_log::RejectLog::from_env());
+    rejects.clone().spawn();
+    
     let state = AppState { 
-        start: Instant::now(),
-        registry: registry.clone()
+        registry: registry.clone(),
+        nonces: Arc::new(replay::NonceStore::from_env()),
+        order_groups,
+        symbols: Arc::new(symbols::SymbolCache::from_env()),
+        strict_orders: matches!(std::env::var("STRICT_ORDER_VALIDATION").as_deref(), Ok("true" | "1")),
+        webhook_sync_timeout: Duration::from_millis(
+            std::env::var("WEBHOOK_SYNC_TIMEOUT_MS").ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(10_000)
+        ),
+        webhook_missing_plugin: MissingPluginMode::from_env(),
+        webhook_actions: Arc::new(ActionAliases::from_env()),
+        in_flight: in_flight.clone(),
+        default_quantities: Arc::new(default_quantity::DefaultQuantities::from_env()),
+        default_confidence: plugins::default_confidence(),
+        twap: Arc::new(twap::TwapScheduler::new(registry.clone())),
+        rejects,
+        gtd,
     };
     
     let signal_routes = Router::new()
         .route("/execute/signal", get(get_signal_handler))
-        .route("/execute/signal", post(post_signal_handler));
+        .route("/execute/signal", post(post_signal_handler))
+        .route("/api/v1/signals/aggregate", post(aggregate_signals_handler));
     
     let webhook_routes = Router::new()
         .route("/webhook/tradingview", post(tradingview_webhook_handler));
     
     // Order execution API routes
     let order_routes = Router::new()
-        .route("/api/v1/orders", post(create_order_handler))
+        .route("/api/v1/orders", post(create_order_handler).get(list_orders_handler))
+        .route("/api/v1/orders/preview", post(preview_order_handler))
+        .route("/api/v1/orders/cancel-batch", post(cancel_batch_handler))
+        .route("/api/v1/orders/by-client-id/{client_id}", get(get_order_by_client_id_handler))
+        .route("/api/v1/replay", post(replay_handler))
+        .route("/api/v1/orders/twap", post(create_twap_handler))
+        .route("/api/v1/orders/twap/{id}", get(get_twap_handler).delete(cancel_twap_handler))
+        .route("/api/v1/exchanges", get(list_exchanges_handler))
         .route("/api/v1/exchanges/{exchange}/leverage", post(set_leverage_handler))
-        .route("/api/v1/positions", get(get_positions_handler));
+        .route("/api/v1/positions", get(get_positions_handler))
+        .route("/api/v1/balances", get(get_balances_handler))
+        .route("/api/v1/portfolio", get(get_portfolio_handler))
+        .route("/api/v1/pnl", get(get_pnl_handler))
+        .route("/api/v1/trades", get(get_trades_handler))
+        .route("/api/v1/funding", get(get_funding_handler))
+        .route("/api/v1/price", get(get_price_handler))
+        .route("/api/v1/positions/reduce", post(reduce_position_handler))
+        .route("/api/v1/ping", get(ping_handler))
+        .route("/api/v1/selftest", post(selftest_handler))
+        .route("/api/v1/symbols", get(list_symbols_handler));
+    
+    // Optional behaviour reported by GET /api/v1/diag
+    let features = std::collections::BTreeMap::from([
+        ("strict_order_validation", state.strict_orders),
+        ("one_order_per_symbol", state.in_flight.is_enabled()),
+        ("daily_order_cap", order_cap_enabled),
+        ("gtd_journal", std::env::var("GTD_JOURNAL_PATH").is_ok()),
+        ("result_publisher", std::env::var("RESULT_PUBLISHER").is_ok_and(|v| !matches!(v.as_str(), "" | "none"))),
+        // SIGHUP reloads only PLUGINS_CONFIG plugins, never the env-configured ones
+        ("plugins_config", plugins_config),
+        ("position_refresh", refresh_secs > 0),
+    ].map(|(name, enabled)| (name.to_string(), enabled)));
     
     let app = Router::new()
-        .merge(health::health_routes())
+        .merge(health::health_routes(registry.clone()))
+        .merge(admin::admin_routes(admin::AdminState::from_env(registry.clone(), features)))
         .merge(signal_routes)
         .merge(webhook_routes)
         .merge(order_routes)
+        .fallback(not_found_handler)
         .with_state(Arc::new(state));
+    
+    // Cross-origin access for browser dashboards; same-origin only unless ALLOWED_ORIGINS is set
+    let app = match cors::from_env() {
+        Some(cors) => app.layer(cors),
+        None => app,
+    };
+    // Outermost, so CORS preflights and requests rejected by other layers are logged too
+    let app = app.layer(axum::middleware::from_fn_with_state(
+        request_log::RequestLog::from_env(),
+        request_log::middleware,
+    ));
     let addr: SocketAddr = match cli.listen.parse() { Ok(a) => a, Err(e) => { tracing::error!(error=%e, "addr_parse_failed"); return Err(e.into()); } };
     tracing::info!(%addr, "binding_listener");
     let listener = match tokio::net::TcpListener::bind(addr).await { Ok(l) => l, Err(e) => { tracing::error!(error=%e, "bind_failed"); return Err(e.into()); } };
@@ -261,7 +988,17 @@ async fn main() -> anyhow::Result<()> {
             tracing::warn!("server_future_completed_unexpectedly");
         }
         _ = shutdown_signal() => {
-            tracing::info!("shutdown signal received");
+            // The listener is closed; requests already being handled keep running
+            let in_flight = registry.in_flight();
+            tracing::info!(in_flight = in_flight.pending(), grace_secs = shutdown_grace.as_secs(), "shutdown signal received");
+            let report = in_flight.drain(shutdown_grace).await;
+            if report.grace_exceeded {
+                tracing::warn!(drained = report.drained, abandoned = report.abandoned, "shutdown_grace_exceeded_orders_abandoned");
+            } else {
+                tracing::info!(drained = report.drained, "shutdown_drain_complete");
+            }
+            metrics::record_shutdown_drain(&report);
+            return Ok(());
         }
     }
     // If we get here the server ended unexpectedly; keep process alive for inspection
@@ -287,165 +1024,509 @@ async fn shutdown_signal() {
     tokio::select! { _ = ctrl_c => {}, _ = terminate => {} }
 }
 
-async fn get_signal_handler() -> Json<Signal> {
-    build_signal(None).await
+/// JSON 404 for unmatched routes, so clients never get axum's plaintext body
+async fn not_found_handler(uri: Uri) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "error": "not found", "path": uri.path() }))
+    )
+}
+
+async fn get_signal_handler(
+    Query(query): Query<SignalQuery>
+) -> Result<Json<Signal>, (StatusCode, Json<serde_json::Value>)> {
+    metrics::SIGNAL_REQUESTS_TOTAL.with_label_values(&["GET"]).inc();
+    let indicators = parse_indicators(query.indicators.as_deref())?;
+    Ok(build_signal(None, &[], &indicators).await)
+}
+
+async fn post_signal_handler(
+    Query(query): Query<SignalQuery>,
+    Json(req): Json<SignalRequest>
+) -> Result<Json<Signal>, (StatusCode, Json<serde_json::Value>)> {
+    metrics::SIGNAL_REQUESTS_TOTAL.with_label_values(&["POST"]).inc();
+    let indicators = parse_indicators(query.indicators.as_deref())?;
+    
+    // Omitting prices falls back to the default series; sending none is a client error
+    if req.prices.as_ref().is_some_and(|p| p.is_empty()) {
+        return Err((StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": "prices cannot be empty" }))));
+    }
+    if req.candles.as_ref().is_some_and(|c| c.is_empty()) {
+        return Err((StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": "candles cannot be empty" }))));
+    }
+    let candles = req.candles.unwrap_or_default();
+    if let Some(i) = candles.iter().position(|[_, high, low, _]| high < low) {
+        return Err((StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": format!("candles[{}]: high is below low", i) }))));
+    }
+    let prices = req.prices.or_else(|| (!candles.is_empty()).then(|| candles.iter().map(|c| c[3]).collect()));
+    Ok(build_signal(req.symbol.zip(prices), &candles, &indicators).await)
 }
 
-async fn post_signal_handler(Json(req): Json<SignalRequest>) -> Json<Signal> {
-    let symbol = req.symbol.clone();
-    let prices = req.prices.clone();
-    build_signal(symbol.zip(prices)).await
+/// Combine several agents' signals: POST /api/v1/signals/aggregate
+async fn aggregate_signals_handler(
+    Json(req): Json<AggregateSignalsRequest>
+) -> Result<Json<signals::aggregate::Decision>, (StatusCode, Json<serde_json::Value>)> {
+    let bad_request = |error: String| (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": error })));
+    
+    signals::aggregate::validate(&req.signals).map_err(bad_request)?;
+    let hold_threshold = req.hold_threshold.unwrap_or(signals::aggregate::DEFAULT_HOLD_THRESHOLD);
+    if !(0.0..=1.0).contains(&hold_threshold) {
+        return Err(bad_request(format!("hold_threshold: expected a value in [0, 1], got {}", hold_threshold)));
+    }
+    
+    let decision = signals::aggregate::aggregate(&req.signals, req.scheme, hold_threshold);
+    tracing::info!(
+        signals = decision.signals,
+        scheme = ?decision.scheme,
+        action = ?decision.action,
+        confidence = decision.confidence,
+        "signals_aggregated"
+    );
+    Ok(Json(decision))
 }
 
-async fn build_signal(input: Option<(String, Vec<f64>)>) -> Json<Signal> {
+async fn build_signal(input: Option<(String, Vec<f64>)>, candles: &[Candle], indicators: &[Indicator]) -> Json<Signal> {
     let start = Instant::now();
     let (symbol, prices) = match input {
-        Some((sym, p)) if !p.is_empty() => (sym, p),
-        _ => ("ES".to_string(), vec![4420.0, 4422.0, 4419.5, 4425.0, 4424.0])
+        Some((sym, p)) => (sym, p),
+        None => ("ES".to_string(), vec![4420.0, 4422.0, 4419.5, 4425.0, 4424.0])
     };
-    let rsi = 55.0; // placeholder
-    let ema: f64 = prices.iter().sum::<f64>() / prices.len() as f64;
+    let wants = |i: Indicator| indicators.contains(&i);
+    // Price-based indicators are left out rather than divided by an empty series
+    let has_prices = !prices.is_empty();
+    let rsi = wants(Indicator::Rsi).then_some(55.0); // placeholder
+    let ema = (wants(Indicator::Ema) && has_prices).then(|| prices.iter().sum::<f64>() / prices.len() as f64);
+    let macd = (wants(Indicator::Macd) && has_prices).then(|| exp_moving_average(&prices, 12) - exp_moving_average(&prices, 26));
+    let atr = average_true_range(candles, ATR_PERIOD);
     let risk_allowance = 150000.0 * 0.01;
     tokio::time::sleep(Duration::from_millis(5)).await;
-    Json(Signal { symbol, rsi, ema, risk_allowance, latency_ms: start.elapsed().as_millis() })
+    
+    let elapsed = start.elapsed();
+    metrics::SIGNAL_COMPUTE_SECONDS.observe(elapsed.as_secs_f64());
+    if let Some(rsi) = rsi {
+        metrics::SIGNAL_LAST_RSI.with_label_values(&[&symbol]).set(rsi);
+    }
+    Json(Signal { symbol, rsi, ema, macd, atr, risk_allowance, latency_ms: elapsed.as_millis(), timestamp: now_millis() })
 }
 
-async fn health_handler(State(state): State<Arc<AppState>>) -> Json<Health> {
-    let uptime = state.start.elapsed().as_secs();
-    
-    // Check all plugin health
-    let plugins_healthy = state.registry.health_check_all().await.iter().all(|(_, healthy)| *healthy);
-    let status = if plugins_healthy { "healthy" } else { "degraded" };
+/// Exponential moving average over the full series, seeded with the first price
+fn exp_moving_average(prices: &[f64], period: usize) -> f64 {
+    let alpha = 2.0 / (period as f64 + 1.0);
+    prices.iter().skip(1).fold(prices[0], |ema, p| alpha * p + (1.0 - alpha) * ema)
+}
+
+/// Wilder's average true range over `candles` (`None` when there are none)
+///
+/// The first true range is the bar's high-low span; later ones also reach to
+/// the previous close. The first `period` ranges are averaged, then smoothed
+/// as `(atr * (period - 1) + tr) / period`. Shorter series average what they have.
+fn average_true_range(candles: &[Candle], period: usize) -> Option<f64> {
+    let first = candles.first()?;
+    let true_ranges = std::iter::once(first[1] - first[2]).chain(candles.windows(2).map(|pair| {
+        let (prev_close, [_, high, low, _]) = (pair[0][3], pair[1]);
+        (high - low).max((high - prev_close).abs()).max((low - prev_close).abs())
+    }));
     
-    Json(Health { 
-        service: format!("fks-execution|uptime={uptime}s|plugins={}", state.registry.list_plugins().await.len()), 
-        status: status.into() 
-    })
+    let period = period.clamp(1, candles.len());
+    let (mut atr, mut seeded) = (0.0, 0);
+    for tr in true_ranges {
+        if seeded < period {
+            seeded += 1;
+            atr += (tr - atr) / seeded as f64;
+        } else {
+            atr = (atr * (period - 1) as f64 + tr) / period as f64;
+        }
+    }
+    Some(atr)
+}
+
+/// During an outage, let the operator choose between TradingView retries and dropped alerts
+fn webhook_plugin_missing(state: &AppState, symbol: &str) -> Result<Json<WebhookResponse>, (StatusCode, Json<WebhookResponse>)> {
+    let error = "No default plugin configured".to_string();
+    tracing::warn!(symbol = %symbol, mode = ?state.webhook_missing_plugin, "webhook_plugin_missing");
+    match state.webhook_missing_plugin {
+        MissingPluginMode::Retry => Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(WebhookResponse {
+                success: false,
+                order_id: None,
+                error: Some(error),
+                status: None,
+            })
+        )),
+        MissingPluginMode::Skip => Ok(Json(WebhookResponse {
+            success: false,
+            order_id: None,
+            error: Some(format!("skipped: {}", error)),
+            status: None,
+        })),
+    }
 }
 
 async fn tradingview_webhook_handler(
     State(state): State<Arc<AppState>>,
-    Json(webhook): Json<TradingViewWebhook>
+    Query(query): Query<WebhookQuery>,
+    body: Bytes
 ) -> Result<Json<WebhookResponse>, (StatusCode, Json<WebhookResponse>)> {
+    let sync = query.mode.as_deref() == Some("sync");
+    // Parsed by hand: TradingView may send JSON as text/plain, and every
+    // field-level problem is reported at once
+    let webhook = TradingViewWebhook::parse(&body, &state.webhook_actions).map_err(|problems| {
+        tracing::warn!(problems = ?problems, "webhook_rejected_invalid");
+        (
+            StatusCode::BAD_REQUEST,
+            Json(WebhookResponse {
+                success: false,
+                order_id: None,
+                error: Some(problems.join("; ")),
+                status: None,
+            })
+        )
+    })?;
     tracing::info!(symbol = %webhook.symbol, action = %webhook.action, "webhook_received");
     
-    // Convert TradingView action to OrderSide
-    let side = match webhook.action.to_lowercase().as_str() {
-        "buy" => OrderSide::Buy,
-        "sell" => OrderSide::Sell,
-        _ => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(WebhookResponse {
-                    success: false,
-                    order_id: None,
-                    error: Some(format!("Invalid action: {}", webhook.action))
-                })
-            ));
-        }
-    };
-    
-    // Convert order type
-    let order_type = match webhook.order_type.as_deref().unwrap_or("market") {
-        "market" => OrderType::Market,
-        "limit" => OrderType::Limit,
-        "stop" => OrderType::Stop,
-        "stop_limit" => OrderType::StopLimit,
-        _ => OrderType::Market,
-    };
+    // Reject stale or replayed payloads before doing any work
+    let timestamp = webhook.timestamp.unwrap_or_default(); // presence checked by validation
+    if let Err(e) = state.nonces.check(timestamp, &webhook.symbol, webhook.nonce.as_deref()) {
+        tracing::warn!(symbol = %webhook.symbol, error = %e, "webhook_rejected_replay");
+        let status = match e {
+            replay::ReplayError::Stale { .. } => StatusCode::BAD_REQUEST,
+            replay::ReplayError::Replayed => StatusCode::CONFLICT,
+        };
+        return Err((
+            status,
+            Json(WebhookResponse {
+                success: false,
+                order_id: None,
+                error: Some(e.to_string()),
+                status: None,
+            })
+        ));
+    }
     
-    // Create order
-    let order = Order {
-        symbol: webhook.symbol.clone(),
-        side,
-        order_type,
-        quantity: webhook.quantity,
-        price: webhook.price,
-        stop_loss: webhook.stop_loss,
-        take_profit: webhook.take_profit,
-        confidence: webhook.confidence.unwrap_or(0.7),
+    // A close alert is sized and directed by the position it closes
+    let position = match webhook.closes_position() {
+        true => {
+            let Some(plugin) = state.registry.resolve(None).await else {
+                return webhook_plugin_missing(&state, &webhook.symbol);
+            };
+            plugin.get_positions(Some(webhook.symbol.trim())).await
+                .map_err(|e| {
+                    tracing::warn!(symbol = %webhook.symbol, error = %e, "webhook_position_lookup_failed");
+                    (
+                        plugin_error_status(e.as_ref()),
+                        Json(WebhookResponse {
+                            success: false,
+                            order_id: None,
+                            error: Some(e.to_string()),
+                            status: None,
+                        })
+                    )
+                })?
+                .into_iter()
+                .find(|p| p.size > 0.0)
+        }
+        false => None,
     };
     
+    let venue = order_venue(&state, None).await;
+    let default_quantity = state.default_quantities.get(&venue, &webhook.symbol);
+    let order = webhook.to_order(position.as_ref(), default_quantity, state.default_confidence).map_err(|e| {
+        tracing::warn!(symbol = %webhook.symbol, error = %e, "webhook_rejected_invalid");
+        (
+            StatusCode::BAD_REQUEST,
+            Json(WebhookResponse {
+                success: false,
+                order_id: None,
+                error: Some(e),
+                status: None,
+            })
+        )
+    })?;
+    
+    check_order_price(&state, &order).map_err(|issue| {
+        tracing::warn!(symbol = %order.symbol, issue = %issue, "webhook_rejected_price_mismatch");
+        (
+            StatusCode::BAD_REQUEST,
+            Json(WebhookResponse {
+                success: false,
+                order_id: None,
+                error: Some(issue),
+                status: None,
+            })
+        )
+    })?;
+    
+    // Inverted stop-loss/take-profit would only be rejected by the exchange after a round trip
+    if let Err(e) = validate_order_protection(&state.registry, &order, None).await {
+        tracing::warn!(symbol = %order.symbol, error = %e, "webhook_rejected_invalid_protection");
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(WebhookResponse {
+                success: false,
+                order_id: None,
+                error: Some(e),
+                status: None,
+            })
+        ));
+    }
+    
+    if state.registry.resolve(None).await.is_none() {
+        return webhook_plugin_missing(&state, &order.symbol);
+    }
+    
+    let in_flight = state.in_flight.try_begin(&venue, &order.symbol).map_err(|e| {
+        tracing::warn!(exchange = %venue, symbol = %order.symbol, "webhook_rejected_in_flight");
+        (
+            StatusCode::CONFLICT,
+            Json(WebhookResponse {
+                success: false,
+                order_id: None,
+                error: Some(e.to_string()),
+                status: None,
+            })
+        )
+    })?;
+    
     // Execute order via plugin registry (use default plugin)
-    match state.registry.execute_order(order, None).await {
+    let symbol = order.symbol.clone();
+    let executed = state.registry.execute_order(order, None).await;
+    drop(in_flight); // sync mode keeps polling, but submission is done
+    match executed {
         Ok(result) => {
             if result.success {
                 tracing::info!(order_id = ?result.order_id, filled = result.filled_quantity, "order_executed");
-                Ok(Json(WebhookResponse {
-                    success: true,
-                    order_id: result.order_id,
-                    error: None,
-                }))
+                match result.order_id {
+                    Some(order_id) if sync => await_webhook_outcome(&state, &symbol, order_id).await,
+                    order_id => Ok(Json(WebhookResponse {
+                        success: true,
+                        order_id,
+                        error: None,
+                        status: None,
+                    })),
+                }
             } else {
-                tracing::warn!(error = ?result.error, "order_failed");
+                if state.rejects.record(&venue, &symbol, result.error.as_deref().unwrap_or("unknown")) {
+                    tracing::warn!(error = ?result.error, "order_failed");
+                }
                 Err((
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(WebhookResponse {
                         success: false,
                         order_id: None,
                         error: result.error,
+                        status: None,
                     })
                 ))
             }
         },
         Err(e) => {
-            tracing::error!(error = %e, "order_execution_error");
+            if state.rejects.record(&venue, &symbol, &e.to_string()) {
+                tracing::error!(error = %e, "order_execution_error");
+            }
+            // An order refused while queued for a submission slot can be retried,
+            // one refused by the daily cap after 00:00 UTC
+            let status = match e.downcast_ref::<ExecutionError>() {
+                Some(ExecutionError::Overloaded(_)) => StatusCode::SERVICE_UNAVAILABLE,
+                _ if e.is::<order_cap::OrderCapError>() => StatusCode::TOO_MANY_REQUESTS,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
             Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
+                status,
                 Json(WebhookResponse {
                     success: false,
                     order_id: None,
-                    error: Some(format!("Execution error: {}", e))
+                    error: Some(format!("Execution error: {}", e)),
+                    status: None,
                 })
             ))
         }
     }
 }
 
+const WEBHOOK_SYNC_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Sync webhook mode: poll the default plugin until the order fills or is
+/// rejected, answering 504 with the order id if neither happens in time
+async fn await_webhook_outcome(
+    state: &AppState,
+    symbol: &str,
+    order_id: String,
+) -> Result<Json<WebhookResponse>, (StatusCode, Json<WebhookResponse>)> {
+    let respond = |code: StatusCode, status: Option<OrderStatus>, error: Option<String>| {
+        let response = WebhookResponse {
+            success: code.is_success(),
+            order_id: Some(order_id.clone()),
+            error,
+            status,
+        };
+        if code.is_success() { Ok(Json(response)) } else { Err((code, Json(response))) }
+    };
+    let Some(plugin) = state.registry.resolve(None).await else {
+        return respond(StatusCode::INTERNAL_SERVER_ERROR, None, Some("No default plugin configured".to_string()));
+    };
+    
+    let deadline = Instant::now() + state.webhook_sync_timeout;
+    loop {
+        match plugin.get_order(symbol, &order_id).await {
+            Ok(summary) => match summary.status {
+                OrderStatus::Filled => return respond(StatusCode::OK, Some(summary.status), None),
+                OrderStatus::Rejected | OrderStatus::Cancelled => {
+                    tracing::warn!(order_id = %order_id, status = ?summary.status, "webhook_sync_order_not_filled");
+                    return respond(
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        Some(summary.status),
+                        Some(format!("Order ended {:?} without filling", summary.status)),
+                    );
+                }
+                OrderStatus::Open | OrderStatus::PartiallyFilled => {}
+            },
+            // Polling can't help if the plugin has no order lookup at all
+            Err(e) if matches!(e.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))) => {
+                return respond(plugin_error_status(e.as_ref()), None, Some(e.to_string()));
+            }
+            Err(e) => {
+                metrics::record_plugin_error(plugin.name(), e.as_ref());
+                tracing::warn!(order_id = %order_id, error = %e, "webhook_sync_poll_failed");
+            }
+        }
+        
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            tracing::warn!(order_id = %order_id, "webhook_sync_timeout");
+            return respond(
+                StatusCode::GATEWAY_TIMEOUT,
+                None,
+                Some(format!("Order {} not filled within {}ms", order_id, state.webhook_sync_timeout.as_millis())),
+            );
+        }
+        tokio::time::sleep(remaining.min(WEBHOOK_SYNC_POLL_INTERVAL)).await;
+    }
+}
+
+/// Check stop-loss/take-profit direction before submitting
+///
+/// Orders without a limit price are checked against the plugin's last price;
+/// if that can't be fetched only the stop-loss/take-profit ordering is checked.
+async fn validate_order_protection(
+    registry: &PluginRegistry,
+    order: &Order,
+    exchange: Option<&str>,
+) -> Result<(), String> {
+    if !order.has_protection() {
+        return Ok(());
+    }
+    
+    let market_price = match order.price {
+        Some(_) => None,
+        None => registry.fetch_data(&order.symbol, exchange).await.ok().map(|data| data.last),
+    };
+    order.validate(market_price)
+}
+
+/// Cap a reduce-only order at the size of the position it reduces
+///
+/// Rejects the order when there is no open position on the opposite side.
+async fn clamp_reduce_only(
+    registry: &PluginRegistry,
+    order: &mut Order,
+    exchange: Option<&str>,
+) -> Result<(), String> {
+    let Some(plugin) = registry.resolve(exchange).await else {
+        return Ok(());
+    };
+    
+    let positions = plugin.get_positions(Some(&order.symbol)).await
+        .map_err(|e| format!("cannot clamp reduce-only order, positions unavailable: {}", e))?;
+    let position = positions
+        .iter()
+        .find(|p| p.size > 0.0 && p.side.closing_side() == order.side)
+        .ok_or_else(|| format!("No open position for {} to reduce on {}", order.symbol, plugin.name()))?;
+    
+    if order.quantity > position.size {
+        tracing::info!(
+            plugin = %plugin.name(),
+            symbol = %order.symbol,
+            requested = order.quantity,
+            position = position.size,
+            "reduce_only_quantity_clamped"
+        );
+        order.quantity = position.size;
+    }
+    Ok(())
+}
+
+/// Warn about a price that doesn't fit the order type, or refuse the order
+/// under `STRICT_ORDER_VALIDATION`; shared by the order and webhook endpoints
+fn check_order_price(state: &AppState, order: &Order) -> Result<(), String> {
+    let Some(issue) = order.price_issue() else {
+        return Ok(());
+    };
+    if state.strict_orders {
+        return Err(issue);
+    }
+    tracing::warn!(symbol = %order.symbol, order_type = ?order.order_type, issue = %issue, "order_price_mismatch");
+    Ok(())
+}
+
 /// Create order endpoint: POST /api/v1/orders
 async fn create_order_handler(
     State(state): State<Arc<AppState>>,
     Json(req): Json<CreateOrderRequest>
 ) -> Result<Json<CreateOrderResponse>, (StatusCode, Json<CreateOrderResponse>)> {
     tracing::info!(
-        exchange = %req.exchange,
+        exchange = ?req.exchange,
         symbol = %req.symbol,
         side = %req.side,
         order_type = %req.order_type,
         "create_order_request"
     );
     
-    // Convert side
-    let side = match req.side.to_lowercase().as_str() {
-        "buy" => OrderSide::Buy,
-        "sell" => OrderSide::Sell,
-        _ => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(CreateOrderResponse {
-                    success: false,
-                    order_id: None,
-                    filled_quantity: 0.0,
-                    average_price: 0.0,
-                    error: Some(format!("Invalid side: {}", req.side)),
-                    timestamp: std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_millis() as i64,
-                })
-            ));
-        }
-    };
+    let venue = order_venue(&state, req.exchange.as_deref()).await;
+    let mut order = req.to_order(state.default_quantities.get(&venue, &req.symbol), state.default_confidence).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(CreateOrderResponse {
+                success: false,
+                order_id: None,
+                filled_quantity: 0.0,
+                average_price: 0.0,
+                error: Some(e),
+                timestamp: now_millis(),
+            })
+        )
+    })?;
     
-    // Convert order type
-    let order_type = match req.order_type.to_lowercase().as_str() {
-        "market" => OrderType::Market,
-        "limit" => OrderType::Limit,
-        "stop" => OrderType::Stop,
-        "stop_limit" | "stoplimit" => OrderType::StopLimit,
-        "take_profit" | "takeprofit" => OrderType::TakeProfit,
-        "stop_loss" | "stoploss" => OrderType::StopLoss,
-        _ => {
+    check_order_price(&state, &order).map_err(|issue| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(CreateOrderResponse {
+                success: false,
+                order_id: None,
+                filled_quantity: 0.0,
+                average_price: 0.0,
+                error: Some(issue),
+                timestamp: now_millis(),
+            })
+        )
+    })?;
+    
+    if let Err(e) = validate_order_protection(&state.registry, &order, req.exchange.as_deref()).await {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(CreateOrderResponse {
+                success: false,
+                order_id: None,
+                filled_quantity: 0.0,
+                average_price: 0.0,
+                error: Some(e),
+                timestamp: now_millis(),
+            })
+        ));
+    }
+    
+    if order.reduce_only && req.clamp_reduce_only {
+        if let Err(e) = clamp_reduce_only(&state.registry, &mut order, req.exchange.as_deref()).await {
             return Err((
                 StatusCode::BAD_REQUEST,
                 Json(CreateOrderResponse {
@@ -453,38 +1534,50 @@ async fn create_order_handler(
                     order_id: None,
                     filled_quantity: 0.0,
                     average_price: 0.0,
-                    error: Some(format!("Invalid order_type: {}", req.order_type)),
-                    timestamp: std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_millis() as i64,
+                    error: Some(e),
+                    timestamp: now_millis(),
                 })
             ));
         }
-    };
+    }
     
-    // Create order
-    let order = Order {
-        symbol: req.symbol.clone(),
-        side,
-        order_type,
-        quantity: req.quantity,
-        price: req.price,
-        stop_loss: req.stop_loss,
-        take_profit: req.take_profit,
-        confidence: 0.7, // Default confidence
-    };
+    let _in_flight = state.in_flight.try_begin(&venue, &order.symbol).map_err(|e| {
+        tracing::warn!(exchange = %venue, symbol = %order.symbol, "order_rejected_in_flight");
+        (
+            StatusCode::CONFLICT,
+            Json(CreateOrderResponse {
+                success: false,
+                order_id: None,
+                filled_quantity: 0.0,
+                average_price: 0.0,
+                error: Some(e.to_string()),
+                timestamp: now_millis(),
+            })
+        )
+    })?;
     
     // Execute order via specified plugin
-    match state.registry.execute_order(order, Some(&req.exchange)).await {
+    match state.registry.execute_order(order, req.exchange.as_deref()).await {
         Ok(result) => {
-            tracing::info!(
-                exchange = %req.exchange,
-                symbol = %req.symbol,
-                order_id = ?result.order_id,
-                filled = result.filled_quantity,
-                "order_executed"
-            );
+            if result.success {
+                tracing::info!(
+                    exchange = ?req.exchange,
+                    symbol = %req.symbol,
+                    order_id = ?result.order_id,
+                    filled = result.filled_quantity,
+                    "order_executed"
+                );
+            } else if state.rejects.record(&venue, &req.symbol, result.error.as_deref().unwrap_or("unknown")) {
+                tracing::warn!(exchange = ?req.exchange, symbol = %req.symbol, error = ?result.error, "order_rejected");
+            }
+            
+            if let (Some(group_id), Some(order_id)) = (&req.order_group_id, &result.order_id) {
+                register_group_leg(&state, group_id, req.exchange.as_deref(), order_id, &req.symbol).await;
+            }
+            if let (Some(expire_at), Some(order_id)) = (req.expire_at, &result.order_id) {
+                track_gtd(&state, req.exchange.as_deref(), order_id, &req.symbol, expire_at).await;
+            }
+            
             Ok(Json(CreateOrderResponse {
                 success: result.success,
                 order_id: result.order_id,
@@ -495,112 +1588,2175 @@ async fn create_order_handler(
             }))
         },
         Err(e) => {
-            tracing::error!(exchange = %req.exchange, error = %e, "order_execution_error");
+            if state.rejects.record(&venue, &req.symbol, &e.to_string()) {
+                tracing::error!(exchange = ?req.exchange, error = %e, "order_execution_error");
+            }
             Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
+                plugin_error_status(e.as_ref()),
                 Json(CreateOrderResponse {
                     success: false,
                     order_id: None,
                     filled_quantity: 0.0,
                     average_price: 0.0,
                     error: Some(format!("Execution error: {}", e)),
-                    timestamp: std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_millis() as i64,
+                    timestamp: now_millis(),
                 })
             ))
         }
     }
 }
 
-/// Set leverage endpoint: POST /api/v1/exchanges/{exchange}/leverage
-async fn set_leverage_handler(
+/// Batch cancel endpoint: POST /api/v1/orders/cancel-batch
+///
+/// Uses the venue's batch endpoint; orders that could not be cancelled are
+/// reported in `results` without failing the request.
+async fn cancel_batch_handler(
     State(state): State<Arc<AppState>>,
-    Path(exchange): Path<String>,
-    Json(req): Json<SetLeverageRequest>
-) -> Result<Json<SetLeverageResponse>, (StatusCode, Json<SetLeverageResponse>)> {
-    tracing::info!(
-        exchange = %exchange,
-        symbol = %req.symbol,
-        leverage = %req.leverage,
-        "set_leverage_request"
-    );
+    Json(req): Json<CancelBatchRequest>
+) -> Result<Json<CancelBatchResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let error = |status: StatusCode, message: String| (status, Json(serde_json::json!({ "error": message })));
     
-    // Get plugin
-    let _plugin = state.registry.get(&exchange).await
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(SetLeverageResponse {
-                    success: false,
-                    error: Some(format!("Exchange plugin '{}' not found", exchange)),
-                })
-            )
+    let orders: Vec<OrderRef> = req.order_ids.into_iter().map(OrderRef::OrderId)
+        .chain(req.client_order_ids.into_iter().map(OrderRef::ClientOrderId))
+        .collect();
+    if orders.is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "order_ids or client_order_ids is required".to_string()));
+    }
+    if orders.len() > MAX_CANCEL_BATCH {
+        return Err(error(StatusCode::BAD_REQUEST, format!("at most {} orders per batch, got {}", MAX_CANCEL_BATCH, orders.len())));
+    }
+    
+    let results = state.registry.cancel_batch(&req.symbol, &orders, req.exchange.as_deref()).await
+        .map_err(|e| {
+            tracing::error!(exchange = ?req.exchange, symbol = %req.symbol, error = %e, "cancel_batch_error");
+            error(plugin_error_status(e.as_ref()), e.to_string())
         })?;
     
-    // Check if plugin is Bybit plugin (has set_leverage method)
-    // We need to downcast to BybitPlugin to access set_leverage
-    // For now, we'll try to get it as a trait object and call a method
-    // This requires adding set_leverage to the ExecutionPlugin trait or using a type check
+    let cancelled = results.iter().filter(|r| r.success).count();
+    Ok(Json(CancelBatchResponse {
+        exchange: order_venue(&state, req.exchange.as_deref()).await,
+        cancelled,
+        failed: results.len() - cancelled,
+        results,
+    }))
+}
+
+/// Start a TWAP job: POST /api/v1/orders/twap
+async fn create_twap_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<TwapOrderRequest>
+) -> Result<Json<twap::TwapProgress>, (StatusCode, Json<serde_json::Value>)> {
+    let error = |status: StatusCode, message: String| (status, Json(serde_json::json!({ "error": message })));
+    
+    let side = match req.side.to_lowercase().as_str() {
+        "buy" => OrderSide::Buy,
+        "sell" => OrderSide::Sell,
+        _ => return Err(error(StatusCode::BAD_REQUEST, format!("Invalid side: {}", req.side))),
+    };
+    if state.registry.resolve(req.exchange.as_deref()).await.is_none() {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            format!("Exchange plugin '{}' not found", req.exchange.as_deref().unwrap_or("default"))
+        ));
+    }
+    
+    let spec = twap::TwapSpec {
+        exchange: req.exchange,
+        symbol: req.symbol,
+        side,
+        quantity: req.quantity,
+        duration: Duration::from_secs(req.duration_secs),
+        slices: req.slices,
+        strategy_tag: req.strategy_tag,
+        confidence: state.default_confidence,
+    };
+    let progress = state.twap.start(spec).await.map_err(|e| error(StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(progress))
+}
+
+/// TWAP job progress: GET /api/v1/orders/twap/{id}
+async fn get_twap_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>
+) -> Result<Json<twap::TwapProgress>, (StatusCode, Json<serde_json::Value>)> {
+    state.twap.progress(&id).await
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": format!("TWAP job '{}' not found", id) }))))
+}
+
+/// Cancel a TWAP job's remaining slices: DELETE /api/v1/orders/twap/{id}
+async fn cancel_twap_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>
+) -> Result<Json<twap::TwapProgress>, (StatusCode, Json<serde_json::Value>)> {
+    state.twap.cancel(&id).await
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": format!("TWAP job '{}' not found", id) }))))
+}
+
+/// Name of the plugin an order for `exchange` routes to, for per-venue bookkeeping
+async fn order_venue(state: &AppState, exchange: Option<&str>) -> String {
+    match state.registry.resolve(exchange).await {
+        Some(plugin) => plugin.name().to_string(),
+        None => exchange.unwrap_or("default").to_string(),
+    }
+}
+
+/// Track a placed order as a leg of its order group
+async fn register_group_leg(state: &AppState, group_id: &str, exchange: Option<&str>, order_id: &str, symbol: &str) {
+    let Some(plugin) = state.registry.resolve(exchange).await else { return };
+    let leg = order_groups::GroupLeg {
+        order_id: order_id.to_string(),
+        symbol: symbol.to_string(),
+    };
+    if let Err(e) = state.order_groups.add_leg(group_id, plugin.name(), leg).await {
+        tracing::warn!(group = %group_id, order_id = %order_id, error = %e, "order_group_register_failed");
+    }
+}
+
+async fn track_gtd(state: &AppState, exchange: Option<&str>, order_id: &str, symbol: &str, expire_at: i64) {
+    let Some(plugin) = state.registry.resolve(exchange).await else { return };
+    state.gtd.track(gtd::GtdOrder {
+        plugin: plugin.name().to_string(),
+        symbol: symbol.to_string(),
+        order_id: order_id.to_string(),
+        expire_at,
+    }).await;
+}
+
+/// Order preview endpoint: POST /api/v1/orders/preview
+///
+/// Runs the same conversion, validation and request building as order
+/// creation, but returns the exchange request instead of sending it.
+async fn preview_order_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreateOrderRequest>
+) -> Result<Json<PreviewOrderResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let error = |status: StatusCode, message: String| (status, Json(serde_json::json!({ "error": message })));
+    
+    let plugin = state.registry.resolve(req.exchange.as_deref()).await
+        .ok_or_else(|| error(
+            StatusCode::NOT_FOUND,
+            format!("Exchange plugin '{}' not found", req.exchange.as_deref().unwrap_or("default"))
+        ))?;
+    let default_quantity = state.default_quantities.get(plugin.name(), &req.symbol);
+    let order = req.to_order(default_quantity, state.default_confidence).map_err(|e| error(StatusCode::BAD_REQUEST, e))?;
+    
+    preview_order(&state, plugin, order).await
+        .map(Json)
+        .map_err(|(status, message)| error(status, message))
+}
+
+/// Validate `order` for `plugin` and build its exchange request without sending it
+async fn preview_order(
+    state: &AppState,
+    plugin: Arc<dyn ExecutionPlugin>,
+    order: Order
+) -> Result<PreviewOrderResponse, (StatusCode, String)> {
+    let mut valid = true;
+    let mut warnings = Vec::new();
+    
+    if !state.registry.is_enabled(plugin.name()).await {
+        valid = false;
+        warnings.push(format!("Plugin '{}' is disabled", plugin.name()));
+    }
+    if plugin.in_maintenance() {
+        valid = false;
+        warnings.push(format!("Plugin '{}' is in an exchange maintenance window", plugin.name()));
+    }
+    if let Some(issue) = order.price_issue() {
+        valid &= !state.strict_orders;
+        warnings.push(issue);
+    }
+    if let Err(e) = validate_order_protection(&state.registry, &order, Some(plugin.name())).await {
+        valid = false;
+        warnings.push(e);
+    }
+    
+    // Quantities are sent as given; flag ones the exchange is likely to reject
+    if let Ok(info) = plugin.instrument_info(&order.symbol).await {
+        let rounded = info.round_qty(order.quantity);
+        if (rounded - order.quantity).abs() > f64::EPSILON {
+            warnings.push(format!("quantity {} is not a multiple of the lot step {} (rounded: {})", order.quantity, info.qty_step, rounded));
+        }
+        if let Some(price) = order.price {
+            let rounded = info.round_price(price);
+            if (rounded - price).abs() > f64::EPSILON {
+                warnings.push(format!("price {} is not a multiple of the tick size {} (rounded: {})", price, info.tick_size, rounded));
+            }
+        }
+        if order.quantity < info.min_qty {
+            warnings.push(format!("quantity {} is below the minimum order quantity {}", order.quantity, info.min_qty));
+        }
+    }
+    
+    let request = plugin.preview_order(&order).await
+        .map_err(|e| {
+            metrics::record_plugin_error(plugin.name(), e.as_ref());
+            (plugin_error_status(e.as_ref()), e.to_string())
+        })?;
     
-    // For now, return an error if not Bybit
-    if exchange != "bybit" {
+    Ok(PreviewOrderResponse {
+        exchange: plugin.name().to_string(),
+        valid,
+        warnings,
+        order,
+        request,
+    })
+}
+
+/// Replay endpoint: POST /api/v1/replay
+///
+/// Dry-runs recorded orders and webhook signals in timestamp order (ties
+/// keep their position) through the preview path, so nothing is sent and
+/// webhook replay protection is skipped. A step that fails doesn't stop the
+/// ones after it.
+async fn replay_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ReplayRequest>
+) -> Result<Json<ReplayResponse>, (StatusCode, Json<serde_json::Value>)> {
+    if req.steps.len() > MAX_REPLAY_STEPS {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json(SetLeverageResponse {
-                success: false,
-                error: Some(format!("Leverage setting only supported for Bybit, got: {}", exchange)),
-            })
+            Json(serde_json::json!({ "error": format!("at most {} steps per replay, got {}", MAX_REPLAY_STEPS, req.steps.len()) }))
         ));
     }
     
-    // Try to get Bybit plugin from registry and call set_leverage
-    // This is a limitation - we need to store plugin type information
-    // For now, we'll require Bybit to be the exchange
-    // TODO: Add set_leverage to ExecutionPlugin trait or use a plugin-specific registry
+    let mut steps = req.steps;
+    steps.sort_by_key(|step| step.timestamp);
     
-    // Since we can't easily downcast, we'll need to add this to the trait or use a workaround
-    // For Day 17, we'll document this limitation and implement the basic structure
+    let mut results = Vec::with_capacity(steps.len());
+    for step in steps {
+        let (preview, error) = match replay_step(&state, step.timestamp, step.input).await {
+            Ok(preview) => (Some(preview), None),
+            Err(e) => (None, Some(e)),
+        };
+        results.push(ReplayStepResult { timestamp: step.timestamp, preview, error });
+    }
+    tracing::info!(steps = results.len(), failed = results.iter().filter(|r| r.error.is_some()).count(), "replay_completed");
+    Ok(Json(ReplayResponse { steps: results }))
+}
+
+/// Convert one recorded input as its endpoint would, then preview it
+async fn replay_step(state: &AppState, timestamp: i64, input: ReplayInput) -> Result<PreviewOrderResponse, String> {
+    let (exchange, order) = match input {
+        ReplayInput::Order(req) => {
+            let venue = order_venue(state, req.exchange.as_deref()).await;
+            let order = req.to_order(state.default_quantities.get(&venue, &req.symbol), state.default_confidence)?;
+            (req.exchange, order)
+        }
+        ReplayInput::Signal(mut payload) => {
+            // Recorded alerts may predate the timestamp requirement
+            if let Some(fields) = payload.as_object_mut() {
+                fields.entry("timestamp").or_insert_with(|| serde_json::json!(timestamp));
+            }
+            let webhook = TradingViewWebhook::parse(payload.to_string().as_bytes(), &state.webhook_actions)
+                .map_err(|problems| problems.join("; "))?;
+            let position = match (webhook.closes_position(), state.registry.resolve(None).await) {
+                (true, Some(plugin)) => plugin.get_positions(Some(webhook.symbol.trim())).await
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .find(|p| p.size > 0.0),
+                _ => None,
+            };
+            let venue = order_venue(state, None).await;
+            let default_quantity = state.default_quantities.get(&venue, &webhook.symbol);
+            (None, webhook.to_order(position.as_ref(), default_quantity, state.default_confidence)?)
+        }
+    };
     
-    Err((
-        StatusCode::NOT_IMPLEMENTED,
-        Json(SetLeverageResponse {
-            success: false,
-            error: Some("Leverage setting requires plugin-specific implementation. Use Bybit plugin directly.".to_string()),
-        })
-    ))
+    let plugin = state.registry.resolve(exchange.as_deref()).await
+        .ok_or_else(|| format!("Exchange plugin '{}' not found", exchange.as_deref().unwrap_or("default")))?;
+    preview_order(state, plugin, order).await.map_err(|(_, message)| message)
 }
 
-/// Get positions endpoint: GET /api/v1/positions?exchange=bybit&symbol=BTCUSDT
-async fn get_positions_handler(
+/// Map a plugin error (or the registry's daily order cap, 429) to an HTTP status
+fn plugin_error_status(error: &(dyn std::error::Error + Send + Sync + 'static)) -> StatusCode {
+    if error.is::<order_cap::OrderCapError>() {
+        return StatusCode::TOO_MANY_REQUESTS;
+    }
+    match error.downcast_ref::<ExecutionError>() {
+        Some(ExecutionError::Unsupported(_)) => StatusCode::NOT_IMPLEMENTED,
+        Some(ExecutionError::Network(_)) => StatusCode::BAD_GATEWAY,
+        Some(ExecutionError::Disabled(_)) => StatusCode::SERVICE_UNAVAILABLE,
+        Some(ExecutionError::Overloaded(_)) => StatusCode::SERVICE_UNAVAILABLE,
+        Some(ExecutionError::PluginNotFound(_)) => StatusCode::NOT_FOUND,
+        Some(ExecutionError::InvalidSymbol { .. }) => StatusCode::BAD_REQUEST,
+        Some(ExecutionError::Exchange { .. }) => StatusCode::UNPROCESSABLE_ENTITY,
+        Some(ExecutionError::OutcomeUnknown(_)) => StatusCode::GATEWAY_TIMEOUT,
+        None => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// List orders endpoint: GET /api/v1/orders?exchange=bybit&symbol=BTCUSDT&status=open
+async fn list_orders_handler(
     State(state): State<Arc<AppState>>,
-    Query(params): Query<PositionQuery>
-) -> Result<Json<PositionResponse>, (StatusCode, Json<serde_json::Value>)> {
+    Query(params): Query<OrderListQuery>
+) -> Result<Json<OrderListResponse>, (StatusCode, Json<serde_json::Value>)> {
     tracing::info!(
-        exchange = %params.exchange,
+        exchange = ?params.exchange,
         symbol = ?params.symbol,
-        "get_positions_request"
+        status = ?params.status,
+        cursor = ?params.cursor,
+        limit = ?params.limit,
+        "list_orders_request"
     );
     
-    // Get plugin
-    let _plugin = state.registry.get(&params.exchange).await
+    let statuses = match params.status.as_deref() {
+        Some(filter) => Some(OrderStatus::parse_filter(filter).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("Invalid status filter: {}", filter)
+                }))
+            )
+        })?),
+        None => None,
+    };
+    
+    let plugin = state.registry.resolve(params.exchange.as_deref()).await
         .ok_or_else(|| {
             (
                 StatusCode::NOT_FOUND,
                 Json(serde_json::json!({
-                    "error": format!("Exchange plugin '{}' not found", params.exchange)
+                    "error": format!("Exchange plugin '{}' not found", params.exchange.as_deref().unwrap_or("default"))
+                }))
+            )
+        })?;
+    
+    let paginated = params.cursor.is_some() || params.limit.is_some();
+    let page = match paginated {
+        true => plugin.list_orders_page(params.symbol.as_deref(), params.cursor.as_deref(), params.limit.unwrap_or(50)).await,
+        false => plugin.list_orders(params.symbol.as_deref()).await
+            .map(|orders| OrderPage { orders, next_cursor: None }),
+    };
+    let mut page = page
+        .map_err(|e| {
+            tracing::error!(plugin = %plugin.name(), error = %e, "list_orders_error");
+            metrics::record_plugin_error(plugin.name(), e.as_ref());
+            (
+                plugin_error_status(e.as_ref()),
+                Json(serde_json::json!({ "error": e.to_string() }))
+            )
+        })?;
+    
+    // Filtered after paging, so a page may hold fewer than `limit` orders
+    if let Some(statuses) = statuses {
+        page.orders.retain(|o| statuses.contains(&o.status));
+    }
+    
+    Ok(Json(match paginated {
+        true => OrderListResponse::Page(page),
+        false => OrderListResponse::All(page.orders),
+    }))
+}
+
+/// Single order by client order id: GET /api/v1/orders/by-client-id/{client_id}?exchange=bybit&symbol=BTCUSDT
+async fn get_order_by_client_id_handler(
+    State(state): State<Arc<AppState>>,
+    Path(client_id): Path<String>,
+    Query(params): Query<ClientOrderQuery>
+) -> Result<Json<OrderSummary>, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!(
+        exchange = ?params.exchange,
+        symbol = %params.symbol,
+        client_order_id = %client_id,
+        "get_order_by_client_id_request"
+    );
+    
+    let plugin = state.registry.resolve(params.exchange.as_deref()).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Exchange plugin '{}' not found", params.exchange.as_deref().unwrap_or("default"))
                 }))
             )
         })?;
     
-    // For now, position queries require plugin-specific implementation
-    // This is similar to leverage - we need plugin-specific methods or trait extensions
+    let order = plugin.get_order_by_client_id(&params.symbol, &client_id).await
+        .map_err(|e| {
+            tracing::error!(plugin = %plugin.name(), error = %e, "get_order_by_client_id_error");
+            metrics::record_plugin_error(plugin.name(), e.as_ref());
+            (
+                plugin_error_status(e.as_ref()),
+                Json(serde_json::json!({ "error": e.to_string() }))
+            )
+        })?;
     
-    Err((
-        StatusCode::NOT_IMPLEMENTED,
-        Json(serde_json::json!({
-            "error": "Position queries require plugin-specific implementation. Use Bybit plugin directly."
-        }))
-    ))
+    order.map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": format!("No order with client order id '{}'", client_id)
+            }))
+        )
+    })
+}
+
+/// Run `call` as a self-test step, recording its latency and outcome
+async fn selftest_step<T, F>(steps: &mut Vec<SelfTestStep>, step: &'static str, call: F) -> Option<T>
+where
+    F: std::future::Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+{
+    let started = Instant::now();
+    let result = call.await;
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+    let (success, error) = match &result {
+        Ok(_) => (true, None),
+        Err(e) => (false, Some(e.to_string())),
+    };
+    steps.push(SelfTestStep { step, success, latency_ms, detail: None, error });
+    result.ok()
+}
+
+/// Deployment smoke test: POST /api/v1/selftest?exchange=bybit
+///
+/// Places a minimum-size buy limit at half the bid and cancels it right
+/// away, exercising quoting, signing, placement and cancellation. Only runs
+/// against plugins in paper/testnet mode.
+async fn selftest_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SelfTestQuery>
+) -> Result<Json<SelfTestReport>, (StatusCode, Json<serde_json::Value>)> {
+    let plugin = state.registry.resolve(params.exchange.as_deref()).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Exchange plugin '{}' not found", params.exchange.as_deref().unwrap_or("default"))
+                }))
+            )
+        })?;
+    if !plugin.is_paper() {
+        tracing::warn!(plugin = %plugin.name(), "selftest_refused_live_plugin");
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": format!("{} is trading live; the self-test only runs in paper/testnet mode", plugin.name())
+            }))
+        ));
+    }
+    if state.registry.is_halted() {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "Trading is halted" }))
+        ));
+    }
+    if !state.registry.is_enabled(plugin.name()).await {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": format!("Plugin '{}' is disabled", plugin.name()) }))
+        ));
+    }
+    
+    let symbol = params.symbol.unwrap_or_else(|| "BTC/USDT".to_string());
+    let _in_flight = state.in_flight.try_begin(plugin.name(), &symbol).map_err(|e| {
+        (StatusCode::CONFLICT, Json(serde_json::json!({ "error": e.to_string() })))
+    })?;
+    let mut steps = Vec::new();
+    let report = |steps: Vec<SelfTestStep>| {
+        let passed = steps.iter().all(|s| s.success);
+        tracing::info!(plugin = %plugin.name(), symbol = %symbol, passed, "selftest_completed");
+        Json(SelfTestReport { exchange: plugin.name().to_string(), symbol: symbol.clone(), passed, steps })
+    };
+    
+    let Some(quote) = selftest_step(&mut steps, "quote", plugin.fetch_data(&symbol)).await else {
+        return Ok(report(steps));
+    };
+    let instrument = match params.quantity {
+        Some(_) => None,
+        None => match selftest_step(&mut steps, "instrument", plugin.instrument_info(&symbol)).await {
+            Some(instrument) => Some(instrument),
+            None => return Ok(report(steps)),
+        },
+    };
+    
+    // Far below the market, so the order rests instead of filling
+    let reference = if quote.bid > 0.0 { quote.bid } else { quote.last };
+    let price = instrument.as_ref().map_or(reference / 2.0, |i| i.round_price(reference / 2.0));
+    let quantity = params.quantity
+        .or_else(|| instrument.as_ref().map(|i| i.round_qty(i.min_qty.max(i.qty_step))))
+        .unwrap_or_default();
+    let order = Order {
+        symbol: symbol.clone(),
+        side: OrderSide::Buy,
+        order_type: OrderType::Limit,
+        quantity,
+        price: Some(price),
+        confidence: state.default_confidence,
+        strategy_tag: Some("selftest".to_string()),
+        ..Default::default()
+    };
+    
+    let placed = selftest_step(&mut steps, "place", async {
+        let result = state.registry.execute_order(order, Some(plugin.name())).await?;
+        match (result.success, result.order_id) {
+            (true, Some(order_id)) => Ok(order_id),
+            _ => Err(result.error.unwrap_or_else(|| "order was not accepted".to_string()).into()),
+        }
+    }).await;
+    let Some(order_id) = placed else {
+        return Ok(report(steps));
+    };
+    if let Some(step) = steps.last_mut() {
+        step.detail = Some(format!("order {} for {} at {}", order_id, quantity, price));
+    }
+    
+    selftest_step(&mut steps, "cancel", plugin.cancel_order(&symbol, &order_id)).await;
+    Ok(report(steps))
+}
+
+/// Exchange latency probe: GET /api/v1/ping?exchange=bybit
+async fn ping_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<PingQuery>
+) -> Result<Json<PingResult>, (StatusCode, Json<serde_json::Value>)> {
+    let plugin = state.registry.resolve(params.exchange.as_deref()).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Exchange plugin '{}' not found", params.exchange.as_deref().unwrap_or("default"))
+                }))
+            )
+        })?;
+    
+    let ping = plugin.ping().await
+        .map_err(|e| {
+            tracing::warn!(plugin = %plugin.name(), error = %e, "ping_error");
+            metrics::record_plugin_error(plugin.name(), e.as_ref());
+            (
+                plugin_error_status(e.as_ref()),
+                Json(serde_json::json!({ "error": e.to_string() }))
+            )
+        })?;
+    
+    metrics::EXCHANGE_PING_MS
+        .with_label_values(&[plugin.name()])
+        .set(ping.latency_ms);
+    tracing::info!(plugin = %plugin.name(), latency_ms = ping.latency_ms, clock_skew_ms = ?ping.clock_skew_ms, "exchange_ping");
+    
+    Ok(Json(ping))
+}
+
+/// Tradable symbols in the exchange's naming: GET /api/v1/symbols?exchange=bybit
+async fn list_symbols_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SymbolsQuery>
+) -> Result<Json<SymbolsResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let plugin = state.registry.resolve(params.exchange.as_deref()).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Exchange plugin '{}' not found", params.exchange.as_deref().unwrap_or("default"))
+                }))
+            )
+        })?;
+    
+    let symbols = state.symbols.get(plugin.as_ref()).await
+        .map_err(|e| {
+            tracing::error!(plugin = %plugin.name(), error = %e, "list_symbols_error");
+            metrics::record_plugin_error(plugin.name(), e.as_ref());
+            (
+                plugin_error_status(e.as_ref()),
+                Json(serde_json::json!({ "error": e.to_string() }))
+            )
+        })?;
+    
+    Ok(Json(SymbolsResponse {
+        exchange: plugin.name().to_string(),
+        symbols: symbols.to_vec(),
+    }))
+}
+
+/// Exchange list endpoint: GET /api/v1/exchanges
+///
+/// Reports each plugin's state and its last failed call, to spot a flaky exchange.
+async fn list_exchanges_handler(State(state): State<Arc<AppState>>) -> Json<Vec<ExchangeDetail>> {
+    let default = state.registry.get_default().await.map(|p| p.name().to_string());
+    let mut names = state.registry.list_plugins().await;
+    names.sort();
+    
+    let mut exchanges = Vec::with_capacity(names.len());
+    for name in names {
+        let Some(plugin) = state.registry.get(&name).await else { continue };
+        exchanges.push(ExchangeDetail {
+            default: default.as_deref() == Some(name.as_str()),
+            enabled: state.registry.is_enabled(&name).await,
+            in_maintenance: plugin.in_maintenance(),
+            paper: plugin.is_paper(),
+            errors_total: metrics::plugin_error_count(&name),
+            last_error: metrics::last_plugin_error(&name),
+            name,
+        });
+    }
+    Json(exchanges)
+}
+
+/// Set leverage endpoint: POST /api/v1/exchanges/{exchange}/leverage
+async fn set_leverage_handler(
+    State(state): State<Arc<AppState>>,
+    Path(exchange): Path<String>,
+    Json(req): Json<SetLeverageRequest>
+) -> Result<Json<SetLeverageResponse>, (StatusCode, Json<SetLeverageResponse>)> {
+    tracing::info!(
+        exchange = %exchange,
+        symbol = %req.symbol,
+        leverage = %req.leverage,
+        "set_leverage_request"
+    );
+    
+    // Get plugin
+    let plugin = state.registry.get(&exchange).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(SetLeverageResponse {
+                    success: false,
+                    outcome: None,
+                    error: Some(format!("Exchange plugin '{}' not found", exchange)),
+                })
+            )
+        })?;
+    
+    if let Some(category) = &req.category {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(SetLeverageResponse {
+                success: false,
+                outcome: None,
+                error: Some(format!("category {} cannot be set per request; configure it on the plugin", category)),
+            })
+        ));
+    }
+    
+    let outcome = plugin.set_leverage(&req.symbol, req.leverage).await
+        .map_err(|e| {
+            tracing::error!(plugin = %plugin.name(), symbol = %req.symbol, error = %e, "set_leverage_error");
+            metrics::record_plugin_error(plugin.name(), e.as_ref());
+            (
+                plugin_error_status(e.as_ref()),
+                Json(SetLeverageResponse {
+                    success: false,
+                    outcome: None,
+                    error: Some(e.to_string()),
+                })
+            )
+        })?;
+    
+    Ok(Json(SetLeverageResponse {
+        success: true,
+        outcome: Some(outcome),
+        error: None,
+    }))
+}
+
+/// Get positions endpoint: GET /api/v1/positions?exchange=bybit&symbol=BTCUSDT
+async fn get_positions_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<PositionQuery>
+) -> Result<Json<Vec<Position>>, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!(
+        exchange = %params.exchange,
+        symbol = ?params.symbol,
+        "get_positions_request"
+    );
+    
+    // Get plugin
+    let plugin = state.registry.get(&params.exchange).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Exchange plugin '{}' not found", params.exchange)
+                }))
+            )
+        })?;
+    
+    let positions = plugin.get_positions(params.symbol.as_deref()).await
+        .map_err(|e| {
+            tracing::error!(plugin = %plugin.name(), error = %e, "get_positions_error");
+            metrics::record_plugin_error(plugin.name(), e.as_ref());
+            (
+                plugin_error_status(e.as_ref()),
+                Json(serde_json::json!({ "error": e.to_string() }))
+            )
+        })?;
+    
+    Ok(Json(positions))
+}
+
+/// Get balances endpoint: GET /api/v1/balances?exchange=bybit&assets=USDT,BTC&nonzero=true
+async fn get_balances_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<BalanceQuery>
+) -> Result<Json<BalancesResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let plugin = state.registry.resolve(params.exchange.as_deref()).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Exchange plugin '{}' not found", params.exchange.as_deref().unwrap_or("default"))
+                }))
+            )
+        })?;
+    
+    let balances = plugin.get_balances().await
+        .map_err(|e| {
+            tracing::error!(plugin = %plugin.name(), error = %e, "get_balances_error");
+            metrics::record_plugin_error(plugin.name(), e.as_ref());
+            (
+                plugin_error_status(e.as_ref()),
+                Json(serde_json::json!({ "error": e.to_string() }))
+            )
+        })?;
+    
+    Ok(Json(BalancesResponse {
+        exchange: plugin.name().to_string(),
+        balances: filter_balances(balances, params.assets.as_deref(), params.nonzero),
+    }))
+}
+
+/// Portfolio endpoint: GET /api/v1/portfolio
+///
+/// Always 200; venues that couldn't be reached are listed under `errors`.
+async fn get_portfolio_handler(State(state): State<Arc<AppState>>) -> Json<portfolio::Portfolio> {
+    Json(portfolio::collect(&state.registry).await)
+}
+
+/// PnL endpoint: GET /api/v1/pnl?exchange=bybit&symbol=BTCUSDT
+///
+/// Unrealized PnL comes from open positions, realized PnL from the exchange's
+/// closed-PnL history (whatever window it reports).
+async fn get_pnl_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<PnlQuery>
+) -> Result<Json<PnlSummary>, (StatusCode, Json<serde_json::Value>)> {
+    let plugin = state.registry.resolve(params.exchange.as_deref()).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Exchange plugin '{}' not found", params.exchange.as_deref().unwrap_or("default"))
+                }))
+            )
+        })?;
+    
+    let symbol = params.symbol.as_deref();
+    let (positions, closed) = tokio::join!(plugin.get_positions(symbol), plugin.get_closed_pnl(symbol));
+    let to_response = |e: Box<dyn std::error::Error + Send + Sync>| {
+        tracing::error!(plugin = %plugin.name(), error = %e, "get_pnl_error");
+        metrics::record_plugin_error(plugin.name(), e.as_ref());
+        (
+            plugin_error_status(e.as_ref()),
+            Json(serde_json::json!({ "error": e.to_string() }))
+        )
+    };
+    let positions = positions.map_err(to_response)?;
+    let closed = closed.map_err(to_response)?;
+    
+    Ok(Json(PnlSummary::combine(plugin.name(), &positions, &closed)))
+}
+
+/// Trade history endpoint: GET /api/v1/trades?exchange=bybit&symbol=BTCUSDT&limit=50
+///
+/// Individual fills rather than orders: a partially filled order shows up once
+/// per fill.
+async fn get_trades_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<TradesQuery>
+) -> Result<Json<TradesResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let plugin = state.registry.resolve(params.exchange.as_deref()).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Exchange plugin '{}' not found", params.exchange.as_deref().unwrap_or("default"))
+                }))
+            )
+        })?;
+    
+    let limit = params.limit.unwrap_or(DEFAULT_TRADES_LIMIT).clamp(1, MAX_TRADES_LIMIT);
+    let trades = plugin.fetch_trades(params.symbol.as_deref(), limit).await
+        .map_err(|e| {
+            tracing::error!(plugin = %plugin.name(), error = %e, "get_trades_error");
+            metrics::record_plugin_error(plugin.name(), e.as_ref());
+            (
+                plugin_error_status(e.as_ref()),
+                Json(serde_json::json!({ "error": e.to_string() }))
+            )
+        })?;
+    
+    Ok(Json(TradesResponse {
+        exchange: plugin.name().to_string(),
+        trades,
+    }))
+}
+
+/// Get a symbol's price cross-checked across every enabled exchange
+async fn get_price_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<PriceQuery>
+) -> Result<Json<plugins::registry::RedundantPrice>, (StatusCode, Json<serde_json::Value>)> {
+    let price = state.registry.fetch_data_redundant(&params.symbol).await
+        .map_err(|e| {
+            tracing::warn!(symbol = %params.symbol, error = %e, "redundant_price_error");
+            (
+                plugin_error_status(e.as_ref()),
+                Json(serde_json::json!({ "error": e.to_string() }))
+            )
+        })?;
+    Ok(Json(price))
+}
+
+/// Funding rate endpoint: GET /api/v1/funding?exchange=bybit&symbol=BTCUSDT
+async fn get_funding_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<FundingQuery>
+) -> Result<Json<FundingRate>, (StatusCode, Json<serde_json::Value>)> {
+    let plugin = state.registry.resolve(params.exchange.as_deref()).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Exchange plugin '{}' not found", params.exchange.as_deref().unwrap_or("default"))
+                }))
+            )
+        })?;
+    
+    let funding = plugin.fetch_funding_rate(&params.symbol).await
+        .map_err(|e| {
+            tracing::error!(plugin = %plugin.name(), symbol = %params.symbol, error = %e, "get_funding_error");
+            metrics::record_plugin_error(plugin.name(), e.as_ref());
+            (
+                plugin_error_status(e.as_ref()),
+                Json(serde_json::json!({ "error": e.to_string() }))
+            )
+        })?;
+    
+    Ok(Json(funding))
+}
+
+/// Keep the listed assets in list order (all assets when `assets` is empty), optionally dropping zero totals
+fn filter_balances(balances: Vec<Balance>, assets: Option<&str>, nonzero: bool) -> Vec<Balance> {
+    let balances = balances.into_iter().filter(|b| !nonzero || b.total != 0.0);
+    
+    let wanted: Vec<String> = assets.unwrap_or_default()
+        .split(',')
+        .map(|a| a.trim().to_uppercase())
+        .filter(|a| !a.is_empty())
+        .collect();
+    if wanted.is_empty() {
+        return balances.collect();
+    }
+    
+    let mut selected: Vec<(usize, Balance)> = balances
+        .filter_map(|b| wanted.iter().position(|a| b.asset.eq_ignore_ascii_case(a)).map(|rank| (rank, b)))
+        .collect();
+    selected.sort_by_key(|(rank, _)| *rank);
+    selected.into_iter().map(|(_, b)| b).collect()
+}
+
+/// Reduce position endpoint: POST /api/v1/positions/reduce
+///
+/// Submits a reduce-only market order for `percent` of the open position,
+/// rounded to the instrument's lot step in the plugin's quantity rounding
+/// mode (down unless configured otherwise).
+async fn reduce_position_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ReducePositionRequest>
+) -> Result<Json<CreateOrderResponse>, (StatusCode, Json<serde_json::Value>)> {
+    tracing::info!(
+        exchange = %req.exchange,
+        symbol = %req.symbol,
+        percent = %req.percent,
+        "reduce_position_request"
+    );
+    
+    let bad_request = |error: String| (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": error })));
+    
+    if !(1.0..=100.0).contains(&req.percent) {
+        return Err(bad_request(format!("percent must be between 1 and 100, got {}", req.percent)));
+    }
+    
+    let plugin = state.registry.get(&req.exchange).await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Exchange plugin '{}' not found", req.exchange)
+                }))
+            )
+        })?;
+    
+    let plugin_error = |e: Box<dyn std::error::Error + Send + Sync>| {
+        tracing::error!(plugin = %plugin.name(), error = %e, "reduce_position_error");
+        metrics::record_plugin_error(plugin.name(), e.as_ref());
+        (
+            plugin_error_status(e.as_ref()),
+            Json(serde_json::json!({ "error": e.to_string() }))
+        )
+    };
+    
+    let position = plugin.get_positions(Some(&req.symbol)).await
+        .map_err(plugin_error)?
+        .into_iter()
+        .find(|p| p.size > 0.0)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("No open position for {} on {}", req.symbol, req.exchange)
+                }))
+            )
+        })?;
+    
+    let mut quantity = if req.percent >= 100.0 {
+        position.size
+    } else {
+        position.size * req.percent / 100.0
+    };
+    
+    // Without instrument rules the exchange is left to validate the size
+    match plugin.instrument_info(&req.symbol).await {
+        Ok(info) => quantity = info.round_qty(quantity),
+        Err(e) => tracing::warn!(plugin = %plugin.name(), error = %e, "instrument_info_unavailable_skipping_rounding"),
+    }
+    
+    if quantity <= 0.0 {
+        return Err(bad_request(format!(
+            "{}% of position size {} rounds to zero",
+            req.percent, position.size
+        )));
+    }
+    
+    let order = Order {
+        symbol: req.symbol.clone(),
+        side: position.side.closing_side(),
+        order_type: OrderType::Market,
+        quantity,
+        confidence: state.default_confidence,
+        reduce_only: true,
+        ..Default::default()
+    };
+    
+    let _in_flight = state.in_flight.try_begin(&req.exchange, &req.symbol).map_err(|e| {
+        tracing::warn!(exchange = %req.exchange, symbol = %req.symbol, "reduce_position_rejected_in_flight");
+        (StatusCode::CONFLICT, Json(serde_json::json!({ "error": e.to_string() })))
+    })?;
+    
+    // Through the registry, so halts, disabled plugins, the order semaphore and
+    // publishing apply; being reduce-only, it is exempt from the daily order cap
+    let result = state.registry.execute_order(order, Some(&req.exchange)).await.map_err(|e| {
+        tracing::error!(plugin = %req.exchange, error = %e, "reduce_position_error");
+        (plugin_error_status(e.as_ref()), Json(serde_json::json!({ "error": e.to_string() })))
+    })?;
+    
+    tracing::info!(
+        exchange = %req.exchange,
+        symbol = %req.symbol,
+        quantity = %quantity,
+        order_id = ?result.order_id,
+        "position_reduced"
+    );
+    
+    Ok(Json(CreateOrderResponse {
+        success: result.success,
+        order_id: result.order_id,
+        filled_quantity: result.filled_quantity,
+        average_price: result.average_price,
+        error: result.error,
+        timestamp: result.timestamp,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plugins::mock::MockPlugin;
+    use plugins::ExecutionPlugin;
+    
+    /// App state with a single initialized mock plugin registered as "mock"
+    async fn mock_state() -> Arc<AppState> {
+        mock_state_with(false).await
+    }
+    
+    async fn mock_state_with(strict_orders: bool) -> Arc<AppState> {
+        let registry = Arc::new(PluginRegistry::new());
+        let mut mock = MockPlugin::new("mock");
+        mock.init(serde_json::json!({})).await.unwrap();
+        registry.register("mock".to_string(), Arc::new(mock)).await;
+        
+        Arc::new(AppState {
+            order_groups: Arc::new(order_groups::OrderGroupTracker::new(registry.clone())),
+            twap: Arc::new(twap::TwapScheduler::new(registry.clone())),
+            rejects: Arc::new(reject_log::RejectLog::new(0)),
+            gtd: Arc::new(gtd::GtdSweeper::new(registry.clone(), None)),
+            registry,
+            nonces: Arc::new(replay::NonceStore::default()),
+            symbols: Arc::new(symbols::SymbolCache::default()),
+            strict_orders,
+            webhook_sync_timeout: Duration::from_secs(2),
+            webhook_missing_plugin: MissingPluginMode::default(),
+            webhook_actions: Arc::new(ActionAliases::default()),
+            in_flight: Arc::new(in_flight::InFlightOrders::default()),
+            default_quantities: Arc::new(default_quantity::DefaultQuantities::default()),
+            default_confidence: plugins::DEFAULT_CONFIDENCE,
+        })
+    }
+    
+    fn order_query(exchange: Option<&str>, symbol: Option<&str>, status: Option<&str>) -> Query<OrderListQuery> {
+        Query(OrderListQuery {
+            exchange: exchange.map(str::to_string),
+            symbol: symbol.map(str::to_string),
+            status: status.map(str::to_string),
+            cursor: None,
+            limit: None,
+        })
+    }
+    
+    /// Orders of an unpaginated list response
+    fn listed(Json(response): Json<OrderListResponse>) -> Vec<OrderSummary> {
+        match response {
+            OrderListResponse::All(orders) => orders,
+            OrderListResponse::Page(_) => panic!("expected the full list"),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_list_orders_filters() {
+        let state = mock_state().await;
+        
+        let orders = listed(list_orders_handler(State(state.clone()), order_query(Some("mock"), None, Some("open"))).await.unwrap());
+        assert_eq!(orders.len(), 2);
+        
+        let orders = listed(list_orders_handler(State(state.clone()), order_query(Some("mock"), None, Some("partially_filled"))).await.unwrap());
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].symbol, "ETH/USDT");
+        
+        let orders = listed(list_orders_handler(State(state.clone()), order_query(None, Some("BTC/USDT"), None)).await.unwrap());
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].order_id, "MOCK-OPEN-1");
+        
+        let orders = listed(list_orders_handler(State(state.clone()), order_query(Some("mock"), None, Some("filled"))).await.unwrap());
+        assert!(orders.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_list_orders_errors() {
+        let state = mock_state().await;
+        
+        let (status, _) = list_orders_handler(State(state.clone()), order_query(Some("mock"), None, Some("bogus"))).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        
+        let (status, _) = list_orders_handler(State(state.clone()), order_query(Some("nope"), None, None)).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_selftest_places_and_cancels_on_paper_only() {
+        let state = mock_state().await;
+        let query = |exchange: &str| Query(SelfTestQuery { exchange: Some(exchange.to_string()), symbol: None, quantity: None });
+        
+        let Json(report) = selftest_handler(State(state.clone()), query("mock")).await.unwrap();
+        assert!(report.passed, "{:?}", report);
+        let steps: Vec<&str> = report.steps.iter().map(|s| s.step).collect();
+        assert_eq!(steps, vec!["quote", "instrument", "place", "cancel"]);
+        
+        // The test order was left cancelled, not resting
+        let plugin = state.registry.get("mock").await.unwrap();
+        let detail = report.steps[2].detail.as_deref().unwrap();
+        let order_id = detail.split_whitespace().nth(1).unwrap();
+        let order = plugin.get_order("BTC/USDT", order_id).await.unwrap();
+        assert_eq!(order.status, OrderStatus::Cancelled);
+        assert_eq!(order.quantity, 0.001);
+        
+        // A plugin on a live network is refused before anything is sent
+        state.registry.register("live".to_string(), Arc::new(plugins::bybit::BybitPlugin::new("live"))).await;
+        let (status, Json(body)) = selftest_handler(State(state.clone()), query("live")).await.unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(body["error"].as_str().unwrap().contains("trading live"));
+    }
+    
+    #[tokio::test]
+    async fn test_list_orders_pages_through_cursor() {
+        let state = mock_state().await;
+        let page = |cursor: Option<&str>| {
+            let mut query = order_query(Some("mock"), None, None);
+            query.cursor = cursor.map(str::to_string);
+            query.limit = Some(1);
+            list_orders_handler(State(state.clone()), query)
+        };
+        
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let Json(OrderListResponse::Page(page)) = page(cursor.as_deref()).await.unwrap() else {
+                panic!("expected a page");
+            };
+            assert_eq!(page.orders.len(), 1);
+            seen.extend(page.orders.into_iter().map(|o| o.order_id));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(seen, vec!["MOCK-OPEN-1", "MOCK-OPEN-2"]);
+        
+        let (status, _) = page(Some("not-a-cursor")).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    
+    #[tokio::test]
+    async fn test_get_order_by_client_id() {
+        let state = mock_state().await;
+        let query = |symbol: &str| Query(ClientOrderQuery { exchange: Some("mock".to_string()), symbol: symbol.to_string() });
+        
+        let Json(order) = get_order_by_client_id_handler(State(state.clone()), Path("fks-mock-2".to_string()), query("ETH/USDT")).await.unwrap();
+        assert_eq!(order.order_id, "MOCK-OPEN-2");
+        assert_eq!(order.client_order_id.as_deref(), Some("fks-mock-2"));
+        
+        let (status, _) = get_order_by_client_id_handler(State(state.clone()), Path("fks-unknown".to_string()), query("ETH/USDT")).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_signal_only_requested_indicators() {
+        let query = SignalQuery { indicators: Some("rsi".to_string()) };
+        let Json(signal) = get_signal_handler(Query(query)).await.unwrap();
+        
+        let json = serde_json::to_value(&signal).unwrap();
+        assert!(json.get("rsi").is_some());
+        assert!(json.get("ema").is_none());
+        assert!(json.get("macd").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_signal_request_counted_and_timed() {
+        let requests = metrics::SIGNAL_REQUESTS_TOTAL.with_label_values(&["POST"]);
+        let (before, computed_before) = (requests.get(), metrics::SIGNAL_COMPUTE_SECONDS.get_sample_count());
+        
+        let req = SignalRequest { symbol: Some("METRICSUSDT".to_string()), prices: Some(vec![100.0, 101.0]), candles: None };
+        let Json(signal) = post_signal_handler(Query(SignalQuery::default()), Json(req)).await.unwrap();
+        
+        assert!(requests.get() > before);
+        assert!(metrics::SIGNAL_COMPUTE_SECONDS.get_sample_count() > computed_before);
+        assert_eq!(metrics::SIGNAL_LAST_RSI.with_label_values(&["METRICSUSDT"]).get(), signal.rsi.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_aggregate_signals_endpoint() {
+        let req: AggregateSignalsRequest = serde_json::from_value(serde_json::json!({
+            "signals": [
+                { "action": "buy", "confidence": 0.6 },
+                { "action": "buy", "confidence": 0.6 }
+            ]
+        })).unwrap();
+        let Json(decision) = aggregate_signals_handler(Json(req)).await.unwrap();
+        assert_eq!(decision.action, signals::aggregate::Action::Buy);
+        assert!((decision.confidence - 0.84).abs() < 1e-9);
+        
+        let req: AggregateSignalsRequest = serde_json::from_value(serde_json::json!({
+            "signals": [{ "action": "sell", "confidence": 0.5 }],
+            "scheme": "max",
+            "hold_threshold": 2.0
+        })).unwrap();
+        let (status, _) = aggregate_signals_handler(Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+    
+    #[tokio::test]
+    async fn test_signal_empty_prices_rejected_omitted_prices_default() {
+        let req = SignalRequest { symbol: Some("BTCUSDT".to_string()), prices: Some(Vec::new()), candles: None };
+        let (status, Json(body)) = post_signal_handler(Query(SignalQuery::default()), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "prices cannot be empty");
+        
+        let req = SignalRequest { symbol: Some("BTCUSDT".to_string()), prices: None, candles: None };
+        let Json(signal) = post_signal_handler(Query(SignalQuery::default()), Json(req)).await.unwrap();
+        assert_eq!(signal.symbol, "ES");
+        assert!(signal.ema.is_some());
+        
+        let req = SignalRequest { symbol: Some("BTCUSDT".to_string()), prices: Some(vec![100.0, 102.0]), candles: None };
+        let Json(signal) = post_signal_handler(Query(SignalQuery::default()), Json(req)).await.unwrap();
+        assert_eq!(signal.symbol, "BTCUSDT");
+        assert_eq!(signal.ema, Some(101.0));
+    }
+    
+    #[test]
+    fn test_average_true_range_known_series() {
+        let candles = [
+            [10.0, 12.0, 9.0, 11.0],   // TR 3 (high - low)
+            [11.0, 13.0, 10.0, 12.0],  // TR 3
+            [12.0, 12.0, 8.0, 9.0],    // TR 4 (high - low, prev close 12)
+            [16.0, 17.0, 16.0, 16.5],  // TR 8 (gap: high - prev close 9)
+            [16.5, 16.5, 15.5, 16.0],  // TR 1
+        ];
+        // Seed (3 + 3 + 4) / 3, then Wilder smoothing over 8 and 1
+        let seed = 10.0 / 3.0;
+        let expected = ((seed * 2.0 + 8.0) / 3.0 * 2.0 + 1.0) / 3.0;
+        assert!((average_true_range(&candles, 3).unwrap() - expected).abs() < 1e-12);
+        
+        // Fewer candles than the period average what there is
+        assert_eq!(average_true_range(&candles[..2], 14), Some(3.0));
+        assert_eq!(average_true_range(&[], 14), None);
+    }
+    
+    #[tokio::test]
+    async fn test_signal_candles_add_atr_and_supply_closes() {
+        let req: SignalRequest = serde_json::from_value(serde_json::json!({
+            "symbol": "BTCUSDT",
+            "candles": [[100.0, 104.0, 98.0, 102.0], [102.0, 106.0, 100.0, 104.0]]
+        })).unwrap();
+        let Json(signal) = post_signal_handler(Query(SignalQuery::default()), Json(req)).await.unwrap();
+        assert_eq!(signal.symbol, "BTCUSDT");
+        assert_eq!(signal.ema, Some(103.0));
+        assert_eq!(signal.atr, Some(6.0));
+        
+        // Close-only input carries no ATR
+        let req = SignalRequest { symbol: Some("BTCUSDT".to_string()), prices: Some(vec![100.0, 102.0]), candles: None };
+        let Json(signal) = post_signal_handler(Query(SignalQuery::default()), Json(req)).await.unwrap();
+        assert!(serde_json::to_value(&signal).unwrap().get("atr").is_none());
+        
+        let req = SignalRequest { symbol: None, prices: None, candles: Some(vec![[100.0, 98.0, 104.0, 102.0]]) };
+        let (status, Json(body)) = post_signal_handler(Query(SignalQuery::default()), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "candles[0]: high is below low");
+    }
+    
+    #[tokio::test]
+    async fn test_signal_empty_series_skips_price_indicators() {
+        let indicators = [Indicator::Rsi, Indicator::Ema, Indicator::Macd];
+        let Json(signal) = build_signal(Some(("BTCUSDT".to_string(), Vec::new())), &[], &indicators).await;
+        assert!(signal.rsi.is_some());
+        assert!(signal.ema.is_none());
+        assert!(signal.macd.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_signal_default_indicators() {
+        let Json(signal) = get_signal_handler(Query(SignalQuery::default())).await.unwrap();
+        
+        assert!(signal.rsi.is_some());
+        assert!(signal.ema.is_some());
+        assert!(signal.macd.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_signal_timestamp_is_millis() {
+        let before = now_millis();
+        let Json(signal) = get_signal_handler(Query(SignalQuery::default())).await.unwrap();
+        
+        assert!(signal.timestamp >= before && signal.timestamp <= now_millis());
+    }
+    
+    #[tokio::test]
+    async fn test_signal_unknown_indicator_rejected() {
+        let query = SignalQuery { indicators: Some("rsi,vwap".to_string()) };
+        let (status, Json(body)) = get_signal_handler(Query(query)).await.unwrap_err();
+        
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("vwap"));
+        assert_eq!(body["supported"], serde_json::json!(["rsi", "ema", "macd"]));
+    }
+    
+    #[tokio::test]
+    async fn test_pnl_combines_realized_and_unrealized() {
+        let state = mock_state().await;
+        
+        let Json(pnl) = get_pnl_handler(State(state.clone()), Query(PnlQuery { exchange: None, symbol: None })).await.unwrap();
+        assert_eq!(pnl.exchange, "mock");
+        assert_eq!(pnl.unrealized_pnl, 350.0);
+        assert_eq!(pnl.realized_pnl, 94.5);
+        assert_eq!(pnl.total_pnl, 444.5);
+        assert_eq!(pnl.symbols, vec![
+            SymbolPnl { symbol: "BTC/USDT".to_string(), realized_pnl: 120.0, unrealized_pnl: 250.0 },
+            SymbolPnl { symbol: "ETH/USDT".to_string(), realized_pnl: -25.5, unrealized_pnl: 100.0 },
+        ]);
+        
+        let query = Query(PnlQuery { exchange: Some("mock".to_string()), symbol: Some("BTC/USDT".to_string()) });
+        let Json(pnl) = get_pnl_handler(State(state.clone()), query).await.unwrap();
+        assert_eq!(pnl.total_pnl, 370.0);
+        assert_eq!(pnl.symbols.len(), 1);
+        
+        let query = Query(PnlQuery { exchange: Some("missing".to_string()), symbol: None });
+        let (status, _) = get_pnl_handler(State(state), query).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_trades_endpoint_lists_fills() {
+        let state = mock_state().await;
+        
+        let query = Query(TradesQuery { exchange: None, symbol: None, limit: None });
+        let Json(resp) = get_trades_handler(State(state.clone()), query).await.unwrap();
+        let body = serde_json::to_value(&resp).unwrap();
+        assert_eq!(body["exchange"], "mock");
+        assert_eq!(body["trades"].as_array().unwrap().len(), 3);
+        let fill = &body["trades"][1];
+        assert_eq!(fill["order_id"], "mock-order-1");
+        assert_eq!(fill["symbol"], "BTC/USDT");
+        assert_eq!(fill["side"], "buy");
+        assert_eq!(fill["price"], 67510.0);
+        assert_eq!(fill["quantity"], 0.2);
+        assert_eq!(fill["fee_currency"], "USDT");
+        assert!(fill["fee"].as_f64().unwrap() > 0.0);
+        assert!(fill["timestamp"].as_i64().unwrap() > 0);
+        
+        // Two fills of the same order, capped by limit
+        let query = Query(TradesQuery { exchange: Some("mock".to_string()), symbol: Some("BTC/USDT".to_string()), limit: Some(1) });
+        let Json(resp) = get_trades_handler(State(state.clone()), query).await.unwrap();
+        assert_eq!(resp.trades.len(), 1);
+        assert_eq!(resp.trades[0].trade_id, "mock-trade-2");
+        
+        let query = Query(TradesQuery { exchange: Some("missing".to_string()), symbol: None, limit: None });
+        let (status, _) = get_trades_handler(State(state), query).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_set_leverage_reports_changed_then_unchanged() {
+        let state = mock_state().await;
+        let request = || Json(SetLeverageRequest { symbol: "BTC/USDT".to_string(), leverage: 5, category: None });
+        
+        let Json(first) = set_leverage_handler(State(state.clone()), Path("mock".to_string()), request()).await.unwrap();
+        assert!(first.success);
+        assert_eq!(first.outcome, Some(LeverageChange::Changed));
+        
+        let Json(repeat) = set_leverage_handler(State(state.clone()), Path("mock".to_string()), request()).await.unwrap();
+        let body = serde_json::to_value(&repeat).unwrap();
+        assert_eq!(body["success"], true);
+        assert_eq!(body["outcome"], "unchanged");
+        
+        // Venues without leverage answer 501
+        state.registry.register("ccxt".to_string(), Arc::new(CCXTPlugin::new("ccxt"))).await;
+        let (status, Json(err)) = set_leverage_handler(State(state), Path("ccxt".to_string()), request()).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(err.outcome, None);
+        assert_eq!(err.error.as_deref(), Some("unsupported: ccxt does not support leverage changes"));
+    }
+    
+    #[tokio::test]
+    async fn test_leverage_above_strict_max_is_422() {
+        let state = mock_state().await;
+        let mut bybit = BybitPlugin::new("bybit");
+        bybit.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "max_leverage": 20,
+            "strict_leverage": true,
+            "base_url": "http://127.0.0.1:1"
+        })).await.unwrap();
+        state.registry.register("bybit".to_string(), Arc::new(bybit)).await;
+        
+        let request = Json(SetLeverageRequest { symbol: "BTCUSDT".to_string(), leverage: 50, category: None });
+        let (status, Json(err)) = set_leverage_handler(State(state.clone()), Path("bybit".to_string()), request).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(err.error.as_deref(), Some("leverage_above_max: bybit leverage 50x exceeds max_leverage 20x"));
+        
+        // Per-order leverage is refused by venues that can't send it
+        state.registry.register("ccxt".to_string(), Arc::new(CCXTPlugin::new("ccxt"))).await;
+        let Json(mut req) = create_order_request("buy", None, None);
+        req.exchange = Some("ccxt".to_string());
+        req.leverage = Some(5);
+        let (status, _) = create_order_handler(State(state), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+    }
+    
+    #[tokio::test]
+    async fn test_set_leverage_rejects_category() {
+        let state = mock_state().await;
+        let request = Json(SetLeverageRequest { symbol: "BTC/USDT".to_string(), leverage: 5, category: Some("inverse".to_string()) });
+        
+        let (status, Json(err)) = set_leverage_handler(State(state), Path("mock".to_string()), request).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(err.error.unwrap().contains("category inverse cannot be set per request"));
+    }
+    
+    #[tokio::test]
+    async fn test_unknown_exchange_is_404_and_unsupported_method_is_501() {
+        let state = mock_state().await;
+        state.registry.register("ccxt".to_string(), Arc::new(CCXTPlugin::new("ccxt"))).await;
+        let request = || Json(SetLeverageRequest { symbol: "BTC/USDT".to_string(), leverage: 5, category: None });
+        
+        // Typo'd exchange name
+        let (status, _) = set_leverage_handler(State(state.clone()), Path("cxct".to_string()), request()).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        
+        // Known exchange without leverage support
+        let (status, _) = set_leverage_handler(State(state.clone()), Path("ccxt".to_string()), request()).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+        
+        // Orders routed through the registry get the same distinction
+        let Json(mut req) = create_order_request("buy", None, None);
+        req.exchange = Some("mokc".to_string());
+        let (status, Json(resp)) = create_order_handler(State(state.clone()), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(resp.error.as_deref(), Some("Execution error: Plugin 'mokc' not found"));
+        
+        let err = state.registry.execute_order(create_order_request("buy", None, None).to_order(None, plugins::DEFAULT_CONFIDENCE).unwrap(), Some("mokc")).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::PluginNotFound(name)) if name == "mokc"));
+    }
+    
+    #[tokio::test]
+    async fn test_cancel_batch_reports_each_id() {
+        let state = mock_state().await;
+        let mut resting = Vec::new();
+        for _ in 0..2 {
+            let Json(mut req) = create_order_request("buy", None, None);
+            req.order_type = "limit".to_string();
+            req.price = Some(60000.0);
+            let Json(resp) = create_order_handler(State(state.clone()), Json(req)).await.unwrap();
+            resting.push(resp.order_id.unwrap());
+        }
+        
+        let req = CancelBatchRequest {
+            exchange: Some("mock".to_string()),
+            symbol: "BTC/USDT".to_string(),
+            order_ids: vec![resting[0].clone(), "no-such-order".to_string(), resting[1].clone()],
+            client_order_ids: vec!["no-such-client-id".to_string()],
+        };
+        let Json(resp) = cancel_batch_handler(State(state.clone()), Json(req)).await.unwrap();
+        assert_eq!(resp.exchange, "mock");
+        assert_eq!((resp.cancelled, resp.failed), (2, 2));
+        
+        let body = serde_json::to_value(&resp.results).unwrap();
+        assert_eq!(body[0], serde_json::json!({ "order_id": resting[0], "success": true, "error": null }));
+        assert_eq!(body[1]["order_id"], "no-such-order");
+        assert_eq!(body[1]["success"], false);
+        assert_eq!(body[1]["error"], "Mock order no-such-order not found");
+        assert_eq!(body[2]["success"], true);
+        assert_eq!(body[3]["client_order_id"], "no-such-client-id");
+        assert_eq!(body[3]["success"], false);
+        
+        let plugin = state.registry.get("mock").await.unwrap();
+        assert_eq!(plugin.get_order("BTC/USDT", &resting[0]).await.unwrap().status, OrderStatus::Cancelled);
+        
+        // Nothing to cancel, or a venue without batch cancellation
+        let empty = CancelBatchRequest { exchange: Some("mock".to_string()), symbol: "BTC/USDT".to_string(), order_ids: Vec::new(), client_order_ids: Vec::new() };
+        let (status, _) = cancel_batch_handler(State(state.clone()), Json(empty)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        
+        state.registry.register("ccxt".to_string(), Arc::new(CCXTPlugin::new("ccxt"))).await;
+        let req = CancelBatchRequest { exchange: Some("ccxt".to_string()), symbol: "BTC/USDT".to_string(), order_ids: vec!["1".to_string()], client_order_ids: Vec::new() };
+        let (status, _) = cancel_batch_handler(State(state), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+    }
+    
+    #[tokio::test]
+    async fn test_twap_endpoints_start_poll_and_cancel() {
+        let state = mock_state().await;
+        let request = |exchange: &str, side: &str| TwapOrderRequest {
+            exchange: Some(exchange.to_string()),
+            symbol: "BTC/USDT".to_string(),
+            side: side.to_string(),
+            quantity: 0.3,
+            duration_secs: 60,
+            slices: 3,
+            strategy_tag: None,
+        };
+        
+        let Json(started) = create_twap_handler(State(state.clone()), Json(request("mock", "buy"))).await.unwrap();
+        assert_eq!(started.slices, 3);
+        let Json(progress) = get_twap_handler(State(state.clone()), Path(started.id.clone())).await.unwrap();
+        assert_eq!(progress.id, started.id);
+        
+        let Json(cancelled) = cancel_twap_handler(State(state.clone()), Path(started.id.clone())).await.unwrap();
+        assert_eq!(cancelled.status, twap::TwapStatus::Cancelled);
+        
+        let (status, _) = create_twap_handler(State(state.clone()), Json(request("mock", "hold"))).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = create_twap_handler(State(state.clone()), Json(request("missing", "buy"))).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = get_twap_handler(State(state), Path("twap-missing".to_string())).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_unknown_path_returns_json_404() {
+        use axum::body::{to_bytes, Body};
+        use tower::ServiceExt;
+        
+        let app: Router = Router::new()
+            .route("/api/v1/ping", get(|| async { "pong" }))
+            .fallback(not_found_handler);
+        let response = app
+            .oneshot(axum::http::Request::get("/api/v1/nope?x=1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body, serde_json::json!({ "error": "not found", "path": "/api/v1/nope" }));
+    }
+    
+    #[tokio::test]
+    async fn test_funding_endpoint_returns_rate() {
+        let state = mock_state().await;
+        
+        let query = Query(FundingQuery { exchange: Some("mock".to_string()), symbol: "BTC/USDT".to_string() });
+        let Json(funding) = get_funding_handler(State(state.clone()), query).await.unwrap();
+        let body = serde_json::to_value(&funding).unwrap();
+        assert_eq!(body["symbol"], "BTC/USDT");
+        assert_eq!(body["rate"], 0.0001);
+        assert!(funding.next_funding_time > funding.timestamp);
+        assert!(funding.next_funding_time - funding.timestamp <= 8 * 60 * 60 * 1000);
+        
+        // Venues without perpetuals answer 501
+        state.registry.register("ccxt".to_string(), Arc::new(CCXTPlugin::new("ccxt"))).await;
+        let query = Query(FundingQuery { exchange: Some("ccxt".to_string()), symbol: "BTC/USDT".to_string() });
+        let (status, Json(err)) = get_funding_handler(State(state.clone()), query).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(err["error"], "unsupported: ccxt does not support funding rates");
+        
+        let query = Query(FundingQuery { exchange: Some("missing".to_string()), symbol: "BTC/USDT".to_string() });
+        let (status, _) = get_funding_handler(State(state), query).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_price_endpoint_maps_typed_errors() {
+        let state = mock_state().await;
+        let query = || Query(PriceQuery { symbol: "BTCUSTD".to_string() });
+        
+        let Json(price) = get_price_handler(State(state.clone()), query()).await.unwrap();
+        assert_eq!(price.freshest.plugin, "mock");
+        
+        // Only venues that don't list the symbol: a client error, not a gateway one
+        state.registry.set_enabled("mock", false).await.unwrap();
+        let stub = plugins::tests::StubPlugin::new("alpha").with_quote(67490.0, 67510.0, 0).listing(&["BTCUSDT"]);
+        state.registry.register("alpha".to_string(), stub.arc()).await;
+        let (status, Json(err)) = get_price_handler(State(state.clone()), query()).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(err["error"], "Unknown symbol 'BTCUSTD'; did you mean BTCUSDT?");
+        
+        state.registry.set_enabled("alpha", false).await.unwrap();
+        let (status, _) = get_price_handler(State(state), query()).await.unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+    
+    #[tokio::test]
+    async fn test_failing_plugin_counted_and_listed_with_last_error() {
+        let state = mock_state().await;
+        // Never initialized, so every call fails with a plain (uncategorized) error
+        state.registry.register("flaky-mock".to_string(), Arc::new(MockPlugin::new("flaky-mock"))).await;
+        
+        let query = Query(BalanceQuery { exchange: Some("flaky-mock".to_string()), assets: None, nonzero: false });
+        let (status, _) = get_balances_handler(State(state.clone()), query).await.unwrap_err();
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(metrics::PLUGIN_ERRORS_TOTAL.with_label_values(&["flaky-mock", "other"]).get(), 1);
+        assert_eq!(metrics::PLUGIN_ERRORS_TOTAL.with_label_values(&["flaky-mock", "network"]).get(), 0);
+        
+        let Json(exchanges) = list_exchanges_handler(State(state)).await;
+        let names: Vec<&str> = exchanges.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["flaky-mock", "mock"]);
+        
+        let flaky = &exchanges[0];
+        assert!(!flaky.default);
+        assert_eq!(flaky.errors_total, 1);
+        let last_error = flaky.last_error.as_ref().unwrap();
+        assert_eq!(last_error.kind, "other");
+        assert_eq!(last_error.message, "Plugin not initialized");
+        assert!(exchanges[1].default);
+        assert!(exchanges[1].last_error.is_none());
+        assert!(exchanges.iter().all(|e| e.paper));
+    }
+    
+    fn balance_query(assets: Option<&str>, nonzero: bool) -> Query<BalanceQuery> {
+        Query(BalanceQuery {
+            exchange: None,
+            assets: assets.map(str::to_string),
+            nonzero,
+        })
+    }
+    
+    #[tokio::test]
+    async fn test_balances_filtered_and_ordered_by_assets() {
+        let state = mock_state().await;
+        let assets = |resp: &BalancesResponse| resp.balances.iter().map(|b| b.asset.clone()).collect::<Vec<_>>();
+        
+        let Json(resp) = get_balances_handler(State(state.clone()), balance_query(None, false)).await.unwrap();
+        assert_eq!(resp.exchange, "mock");
+        assert_eq!(assets(&resp), vec!["BTC", "ETH", "SOL", "USDT"]);
+        
+        // Listed order wins; case and unknown assets don't matter
+        let Json(resp) = get_balances_handler(State(state.clone()), balance_query(Some("usdt, BTC,DOGE"), false)).await.unwrap();
+        assert_eq!(assets(&resp), vec!["USDT", "BTC"]);
+        assert_eq!(resp.balances[0].total, 10000.0);
+    }
+    
+    #[tokio::test]
+    async fn test_balances_nonzero_hides_empty_assets() {
+        let state = mock_state().await;
+        let assets = |resp: &BalancesResponse| resp.balances.iter().map(|b| b.asset.clone()).collect::<Vec<_>>();
+        
+        let Json(resp) = get_balances_handler(State(state.clone()), balance_query(None, true)).await.unwrap();
+        assert_eq!(assets(&resp), vec!["BTC", "ETH", "USDT"]);
+        
+        let Json(resp) = get_balances_handler(State(state.clone()), balance_query(Some("SOL,USDT"), true)).await.unwrap();
+        assert_eq!(assets(&resp), vec!["USDT"]);
+        
+        let query: BalanceQuery = serde_qs::from_str("assets=BTC&nonzero=true").unwrap();
+        assert!(query.nonzero);
+    }
+    
+    #[tokio::test]
+    async fn test_list_symbols_endpoint() {
+        let state = mock_state().await;
+        
+        let Json(resp) = list_symbols_handler(State(state.clone()), Query(SymbolsQuery { exchange: None })).await.unwrap();
+        assert_eq!(resp.exchange, "mock");
+        assert!(resp.symbols.contains(&"BTC/USDT".to_string()));
+        
+        let Json(again) = list_symbols_handler(State(state.clone()), Query(SymbolsQuery { exchange: Some("mock".to_string()) })).await.unwrap();
+        assert_eq!(resp.symbols, again.symbols);
+        
+        let (status, _) = list_symbols_handler(State(state), Query(SymbolsQuery { exchange: Some("nope".to_string()) }))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_ping_reports_latency_and_metric() {
+        let state = mock_state().await;
+        
+        let query = PingQuery { exchange: Some("mock".to_string()) };
+        let Json(ping) = ping_handler(State(state.clone()), Query(query)).await.unwrap();
+        assert_eq!(ping.latency_ms, plugins::mock::MOCK_PING_LATENCY_MS as f64);
+        assert_eq!(ping.clock_skew_ms, Some(0));
+        
+        let gauge = metrics::EXCHANGE_PING_MS.with_label_values(&["mock"]).get();
+        assert_eq!(gauge, plugins::mock::MOCK_PING_LATENCY_MS as f64);
+        assert!(metrics::render().contains("fks_execution_exchange_ping_ms"));
+        
+        let query = PingQuery { exchange: Some("missing".to_string()) };
+        let (status, _) = ping_handler(State(state), Query(query)).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    fn reduce_request(exchange: &str, symbol: &str, percent: f64) -> Json<ReducePositionRequest> {
+        Json(ReducePositionRequest {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            percent,
+        })
+    }
+    
+    fn create_order_request(side: &str, stop_loss: Option<f64>, take_profit: Option<f64>) -> Json<CreateOrderRequest> {
+        Json(CreateOrderRequest {
+            exchange: Some("mock".to_string()),
+            symbol: "BTC/USDT".to_string(),
+            side: side.to_string(),
+            order_type: "market".to_string(),
+            quantity: Some(0.1),
+            price: None,
+            leverage: None,
+            stop_loss,
+            take_profit,
+            category: None,
+            strategy_tag: None,
+            quantity_type: QuantityType::Base,
+            display_quantity: None,
+            trigger_price: None,
+            extra_params: None,
+            post_only: false,
+            reduce_only: false,
+            clamp_reduce_only: false,
+            order_group_id: None,
+            expire_at: None,
+            testnet: None,
+            max_slippage_pct: None,
+        })
+    }
+    
+    #[test]
+    fn test_create_order_request_accepts_numeric_strings() {
+        let parse = |quantity: &str, price: &str| serde_json::from_str::<CreateOrderRequest>(&format!(
+            r#"{{"symbol": "BTC/USDT", "side": "buy", "order_type": "limit", "quantity": {}, "price": {}, "stop_loss": "59000.5"}}"#,
+            quantity, price
+        ));
+        
+        let numeric = parse("0.01", "60000").unwrap();
+        let text = parse(r#""0.01""#, r#"" 60000 ""#).unwrap();
+        for req in [&numeric, &text] {
+            assert_eq!(req.quantity, Some(0.01));
+            assert_eq!(req.price, Some(60000.0));
+            assert_eq!(req.stop_loss, Some(59000.5));
+        }
+        assert_eq!(parse("null", "null").unwrap().price, None);
+        let omitted: CreateOrderRequest = serde_json::from_str(r#"{"symbol": "BTC/USDT", "side": "buy", "order_type": "market"}"#).unwrap();
+        assert_eq!((omitted.quantity, omitted.take_profit), (None, None));
+        
+        let err = parse(r#""abc""#, "60000").err().unwrap();
+        assert!(err.to_string().contains(r#"expected a number or numeric string, got "abc""#), "{}", err);
+    }
+    
+    #[tokio::test]
+    async fn test_reduce_only_clamped_to_position_size() {
+        let state = mock_state().await;
+        
+        // Mock holds a 0.5 BTC/USDT long
+        let mut req = create_order_request("sell", None, None).0;
+        req.quantity = Some(2.0);
+        req.reduce_only = true;
+        req.clamp_reduce_only = true;
+        let Json(resp) = create_order_handler(State(state.clone()), Json(req)).await.unwrap();
+        assert!(resp.success);
+        assert_eq!(resp.filled_quantity, 0.5);
+        
+        // Within the position size the quantity is left alone
+        let mut req = create_order_request("sell", None, None).0;
+        req.reduce_only = true;
+        req.clamp_reduce_only = true;
+        let Json(resp) = create_order_handler(State(state), Json(req)).await.unwrap();
+        assert_eq!(resp.filled_quantity, 0.1);
+    }
+    
+    #[tokio::test]
+    async fn test_reduce_only_clamp_rejected_without_position() {
+        let state = mock_state().await;
+        
+        // A buy cannot reduce the BTC/USDT long
+        let mut req = create_order_request("buy", None, None).0;
+        req.reduce_only = true;
+        req.clamp_reduce_only = true;
+        let (status, Json(resp)) = create_order_handler(State(state.clone()), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(resp.error.as_deref(), Some("No open position for BTC/USDT to reduce on mock"));
+        
+        let mut req = create_order_request("sell", None, None).0;
+        req.symbol = "SOL/USDT".to_string();
+        req.reduce_only = true;
+        req.clamp_reduce_only = true;
+        let (status, _) = create_order_handler(State(state), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+    
+    #[tokio::test]
+    async fn test_concurrent_same_symbol_orders_one_conflicts() {
+        let state = Arc::try_unwrap(mock_state().await).ok().unwrap();
+        let state = Arc::new(AppState { in_flight: Arc::new(in_flight::InFlightOrders::new(true)), ..state });
+        
+        let (first, second) = tokio::join!(
+            create_order_handler(State(state.clone()), create_order_request("buy", None, None)),
+            create_order_handler(State(state.clone()), create_order_request("buy", None, None)),
+        );
+        let (executed, blocked) = match (first, second) {
+            (Ok(executed), Err(blocked)) | (Err(blocked), Ok(executed)) => (executed, blocked),
+            _ => panic!("expected one execution and one conflict"),
+        };
+        assert!(executed.success);
+        assert_eq!(blocked.0, StatusCode::CONFLICT);
+        assert_eq!(blocked.1.error.as_deref(), Some("an order for BTC/USDT on mock is already in flight"));
+        
+        // The slot is released once the first order completes
+        assert!(create_order_handler(State(state), create_order_request("buy", None, None)).await.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_daily_order_cap_rejects_order_past_limit() {
+        let state = mock_state().await;
+        let cap = order_cap::DailyOrderCap::new(None, std::collections::HashMap::from([("mock".to_string(), 2)]));
+        state.registry.set_order_cap(cap).await;
+        
+        for _ in 0..2 {
+            assert!(create_order_handler(State(state.clone()), create_order_request("buy", None, None)).await.is_ok());
+        }
+        let (status, Json(resp)) = create_order_handler(State(state), create_order_request("buy", None, None)).await.unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.error.as_deref(), Some("Execution error: daily order cap of 2 reached on mock, resets at 00:00 UTC"));
+    }
+    
+    #[tokio::test]
+    async fn test_create_order_registers_group_legs() {
+        let state = mock_state().await;
+        
+        for (order_type, price) in [("take_profit", 70000.0), ("stop_loss", 65000.0)] {
+            let Json(mut req) = create_order_request("sell", None, None);
+            req.order_type = order_type.to_string();
+            req.price = Some(price);
+            req.order_group_id = Some("exit-1".to_string());
+            let Json(resp) = create_order_handler(State(state.clone()), Json(req)).await.unwrap();
+            assert!(resp.success);
+        }
+        assert_eq!(state.order_groups.len().await, 1);
+        
+        // Ungrouped orders are not tracked
+        let Json(resp) = create_order_handler(State(state.clone()), create_order_request("buy", None, None)).await.unwrap();
+        assert!(resp.success);
+        assert_eq!(state.order_groups.len().await, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_preview_order_reports_request_and_warnings() {
+        let state = mock_state().await;
+        
+        let Json(preview) = preview_order_handler(State(state.clone()), create_order_request("buy", Some(67000.0), None))
+            .await
+            .unwrap();
+        assert!(preview.valid);
+        assert!(preview.warnings.is_empty());
+        assert_eq!(preview.exchange, "mock");
+        assert_eq!(preview.request.url, "mock://orders");
+        assert_eq!(preview.request.body["stop_loss"], 67000.0);
+        
+        // Would be rejected, but still previewed
+        let Json(preview) = preview_order_handler(State(state.clone()), create_order_request("buy", Some(68000.0), None))
+            .await
+            .unwrap();
+        assert!(!preview.valid);
+        assert!(preview.warnings[0].contains("stop_loss 68000 must be below entry 67500"));
+        
+        let Json(mut req) = create_order_request("hold", None, None);
+        req.exchange = None;
+        let (status, _) = preview_order_handler(State(state), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+    
+    #[tokio::test]
+    async fn test_replay_previews_steps_in_timestamp_order() {
+        let state = mock_state().await;
+        let plugin = state.registry.get("mock").await.unwrap();
+        let orders_before = plugin.list_orders(None).await.unwrap().len();
+        let req: ReplayRequest = serde_json::from_value(serde_json::json!({
+            "steps": [
+                {
+                    "timestamp": 1_700_000_120_000i64,
+                    "signal": { "symbol": "BTC/USDT", "action": "sell", "quantity": "0.05" }
+                },
+                {
+                    "timestamp": 1_700_000_000_000i64,
+                    "order": { "exchange": "mock", "symbol": "BTC/USDT", "side": "buy", "order_type": "limit", "quantity": 0.1, "price": 67000.0 }
+                },
+                {
+                    "timestamp": 1_700_000_060_000i64,
+                    "order": { "exchange": "mock", "symbol": "BTC/USDT", "side": "hold", "order_type": "market", "quantity": 0.1 }
+                }
+            ]
+        })).unwrap();
+        
+        let Json(resp) = replay_handler(State(state.clone()), Json(req)).await.unwrap();
+        let timestamps: Vec<i64> = resp.steps.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![1_700_000_000_000, 1_700_000_060_000, 1_700_000_120_000]);
+        
+        let limit = resp.steps[0].preview.as_ref().unwrap();
+        assert!(limit.valid);
+        assert_eq!(limit.exchange, "mock");
+        assert_eq!(limit.order.price, Some(67000.0));
+        
+        assert!(resp.steps[1].preview.is_none());
+        assert_eq!(resp.steps[1].error.as_deref(), Some("Invalid side: hold"));
+        
+        // Signals go to the default plugin, as webhooks do
+        let signal = resp.steps[2].preview.as_ref().unwrap();
+        assert_eq!(signal.order.side, OrderSide::Sell);
+        assert_eq!(signal.order.quantity, 0.05);
+        
+        // Dry run: nothing reached the exchange
+        assert_eq!(plugin.list_orders(None).await.unwrap().len(), orders_before);
+    }
+    
+    #[tokio::test]
+    async fn test_webhook_sync_mode_reports_fill() {
+        let state = mock_state().await;
+        let body = serde_json::json!({
+            "symbol": "BTC/USDT",
+            "action": "buy",
+            "order_type": "market",
+            "quantity": 0.01,
+            "timestamp": now_millis(),
+        });
+        let query = Query(WebhookQuery { mode: Some("sync".to_string()) });
+        
+        let Json(resp) = tradingview_webhook_handler(State(state), query, Bytes::from(body.to_string())).await
+            .unwrap_or_else(|(status, _)| panic!("sync webhook failed with {}", status));
+        assert!(resp.success);
+        assert!(resp.order_id.is_some());
+        assert_eq!(resp.status, Some(OrderStatus::Filled));
+    }
+    
+    #[tokio::test]
+    async fn test_webhook_missing_plugin_retry_or_skip() {
+        let webhook = |nonce: &str| Bytes::from(serde_json::json!({
+            "symbol": "BTC/USDT",
+            "action": "buy",
+            "quantity": 0.01,
+            "timestamp": now_millis(),
+            "nonce": nonce,
+        }).to_string());
+        let no_mode = || Query(WebhookQuery { mode: None });
+        let state = Arc::try_unwrap(mock_state().await).ok().unwrap();
+        let state = AppState { registry: Arc::new(PluginRegistry::new()), ..state };
+        
+        let retry = Arc::new(AppState { webhook_missing_plugin: MissingPluginMode::Retry, ..state.clone() });
+        let (status, Json(resp)) = tradingview_webhook_handler(State(retry), no_mode(), webhook("retry")).await.unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(resp.error.as_deref(), Some("No default plugin configured"));
+        
+        let skip = Arc::new(AppState { webhook_missing_plugin: MissingPluginMode::Skip, ..state });
+        let Json(resp) = tradingview_webhook_handler(State(skip), no_mode(), webhook("skip")).await
+            .unwrap_or_else(|(status, _)| panic!("skip mode answered {}", status));
+        assert!(!resp.success);
+        assert_eq!(resp.error.as_deref(), Some("skipped: No default plugin configured"));
+    }
+    
+    #[tokio::test]
+    async fn test_webhook_default_quantity_applied_or_required() {
+        let webhook = |symbol: &str| Bytes::from(serde_json::json!({
+            "symbol": symbol,
+            "action": "buy",
+            "timestamp": now_millis(),
+        }).to_string());
+        let no_mode = || Query(WebhookQuery { mode: None });
+        
+        let state = Arc::try_unwrap(mock_state().await).ok().unwrap();
+        let state = Arc::new(AppState {
+            default_quantities: Arc::new(default_quantity::DefaultQuantities::parse("mock:BTC/USDT=0.002")),
+            ..state
+        });
+        
+        let Json(resp) = tradingview_webhook_handler(State(state.clone()), no_mode(), webhook("BTC/USDT")).await
+            .unwrap_or_else(|(status, _)| panic!("webhook failed with {}", status));
+        let plugin = state.registry.get("mock").await.unwrap();
+        let placed = plugin.get_order("BTC/USDT", resp.order_id.as_deref().unwrap()).await.unwrap();
+        assert_eq!(placed.quantity, 0.002);
+        
+        let (status, Json(resp)) = tradingview_webhook_handler(State(state.clone()), no_mode(), webhook("ETH/USDT")).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(resp.error.as_deref(), Some("quantity: required, no default quantity configured for ETH/USDT"));
+        
+        // REST orders share the same defaults
+        let Json(mut req) = create_order_request("buy", None, None);
+        req.quantity = None;
+        assert!(create_order_handler(State(state.clone()), Json(req)).await.unwrap().success);
+    }
+    
+    #[tokio::test]
+    async fn test_webhook_close_alias_closes_open_position() {
+        let webhook = |symbol: &str, action: &str| Bytes::from(serde_json::json!({
+            "symbol": symbol,
+            "action": action,
+            "timestamp": now_millis(),
+        }).to_string());
+        let no_mode = || Query(WebhookQuery { mode: None });
+        
+        let state = Arc::try_unwrap(mock_state().await).ok().unwrap();
+        let state = Arc::new(AppState {
+            webhook_actions: Arc::new(ActionAliases::parse("flat=close")),
+            ..state
+        });
+        
+        // The mock is long 0.5 BTC/USDT, so closing sells all of it
+        let Json(resp) = tradingview_webhook_handler(State(state.clone()), no_mode(), webhook("BTC/USDT", "FLAT")).await
+            .unwrap_or_else(|(status, Json(resp))| panic!("close webhook failed with {}: {:?}", status, resp.error));
+        let plugin = state.registry.get("mock").await.unwrap();
+        let placed = plugin.get_order("BTC/USDT", resp.order_id.as_deref().unwrap()).await.unwrap();
+        assert_eq!((placed.side, placed.quantity), (OrderSide::Sell, 0.5));
+        
+        let (status, Json(resp)) = tradingview_webhook_handler(State(state), no_mode(), webhook("SOL/USDT", "exit")).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(resp.error.as_deref(), Some("action: no open position for SOL/USDT to close"));
+    }
+    
+    #[tokio::test]
+    async fn test_order_price_mismatch_warns_or_rejects_when_strict() {
+        let request = |order_type: &str, price: Option<f64>| {
+            let Json(mut req) = create_order_request("buy", None, None);
+            req.order_type = order_type.to_string();
+            req.price = price;
+            Json(req)
+        };
+        
+        for strict in [false, true] {
+            let state = mock_state_with(strict).await;
+            
+            for (order_type, price, issue) in [
+                ("market", Some(67500.0), Some("price 67500 is ignored for market orders")),
+                ("limit", None, Some("price is required for Limit orders")),
+                ("stop_limit", None, Some("price is required for StopLimit orders")),
+                ("market", None, None),
+                ("limit", Some(67000.0), None),
+            ] {
+                let Json(preview) = preview_order_handler(State(state.clone()), request(order_type, price)).await.unwrap();
+                let created = create_order_handler(State(state.clone()), request(order_type, price)).await;
+                
+                match issue {
+                    Some(issue) => {
+                        assert_eq!(preview.warnings, vec![issue.to_string()], "{} {:?}", order_type, price);
+                        assert_eq!(preview.valid, !strict);
+                        if strict {
+                            let (status, Json(resp)) = created.unwrap_err();
+                            assert_eq!(status, StatusCode::BAD_REQUEST);
+                            assert_eq!(resp.error.as_deref(), Some(issue));
+                        } else {
+                            assert!(created.is_ok(), "{} {:?}", order_type, price);
+                        }
+                    }
+                    None => {
+                        assert!(preview.warnings.is_empty());
+                        assert!(preview.valid);
+                        assert!(created.is_ok());
+                    }
+                }
+            }
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_webhook_price_mismatch_warns_or_rejects_when_strict() {
+        let webhook = |nonce: &str| Bytes::from(serde_json::json!({
+            "symbol": "BTC/USDT",
+            "action": "buy",
+            "order_type": "market",
+            "price": 67500.0,
+            "quantity": 0.01,
+            "timestamp": now_millis(),
+            "nonce": nonce,
+        }).to_string());
+        let no_mode = || Query(WebhookQuery { mode: None });
+        
+        let lenient = mock_state_with(false).await;
+        let Json(resp) = tradingview_webhook_handler(State(lenient), no_mode(), webhook("lenient")).await
+            .unwrap_or_else(|(status, _)| panic!("webhook failed with {}", status));
+        assert!(resp.success);
+        
+        let strict = mock_state_with(true).await;
+        let (status, Json(resp)) = tradingview_webhook_handler(State(strict), no_mode(), webhook("strict")).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(resp.error.as_deref(), Some("price 67500 is ignored for market orders"));
+    }
+    
+    #[tokio::test]
+    async fn test_create_order_validates_display_quantity() {
+        let state = mock_state().await;
+        
+        let mut req = create_order_request("buy", None, None).0;
+        req.display_quantity = Some(0.05);
+        let (status, Json(resp)) = create_order_handler(State(state.clone()), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(resp.error.unwrap().contains("only applies to limit orders"));
+        
+        let mut req = create_order_request("buy", None, None).0;
+        req.order_type = "limit".to_string();
+        req.price = Some(67000.0);
+        req.display_quantity = Some(0.5);
+        let (status, Json(resp)) = create_order_handler(State(state.clone()), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(resp.error.unwrap().contains("exceeds quantity 0.1"));
+        
+        let mut req = create_order_request("buy", None, None).0;
+        req.order_type = "limit".to_string();
+        req.price = Some(67000.0);
+        req.display_quantity = Some(0.05);
+        let Json(resp) = create_order_handler(State(state), Json(req)).await.unwrap();
+        assert!(resp.success);
+    }
+    
+    #[tokio::test]
+    async fn test_create_order_rejects_non_object_extra_params() {
+        let state = mock_state().await;
+        
+        let mut req = create_order_request("buy", None, None).0;
+        req.extra_params = Some(serde_json::json!(["smpType"]));
+        let (status, Json(resp)) = create_order_handler(State(state), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(resp.error.unwrap(), "extra_params must be a JSON object");
+    }
+    
+    #[tokio::test]
+    async fn test_create_order_rejects_category() {
+        let state = mock_state().await;
+        
+        let mut req = create_order_request("buy", None, None).0;
+        req.category = Some("spot".to_string());
+        let (status, Json(resp)) = create_order_handler(State(state), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(resp.error.unwrap().contains("category spot cannot be set per order"));
+    }
+    
+    #[tokio::test]
+    async fn test_create_order_rejects_inverted_protection() {
+        let state = mock_state().await;
+        
+        // Mock BTC/USDT trades at 67500
+        let (status, Json(resp)) = create_order_handler(State(state.clone()), create_order_request("buy", Some(68000.0), None))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(resp.error.unwrap().contains("stop_loss 68000 must be below entry 67500"));
+        
+        let (status, _) = create_order_handler(State(state.clone()), create_order_request("sell", None, Some(68000.0)))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        
+        let Json(resp) = create_order_handler(State(state), create_order_request("buy", Some(67000.0), Some(69000.0)))
+            .await
+            .unwrap();
+        assert!(resp.success);
+    }
+    
+    #[tokio::test]
+    async fn test_reduce_position_by_percent() {
+        let state = mock_state().await;
+        
+        // Mock holds a 0.5 BTC long
+        let Json(resp) = reduce_position_handler(State(state.clone()), reduce_request("mock", "BTC/USDT", 50.0)).await.unwrap();
+        assert!(resp.success);
+        assert_eq!(resp.filled_quantity, 0.25);
+        
+        let Json(resp) = reduce_position_handler(State(state.clone()), reduce_request("mock", "BTC/USDT", 100.0)).await.unwrap();
+        assert_eq!(resp.filled_quantity, 0.5);
+        
+        // Short position is reduced by buying; 33% of 2.0 rounds down to the 0.001 lot step
+        let Json(resp) = reduce_position_handler(State(state), reduce_request("mock", "ETH/USDT", 33.0)).await.unwrap();
+        assert_eq!(resp.filled_quantity, 0.66);
+    }
+    
+    #[tokio::test]
+    async fn test_reduce_position_rejections() {
+        let state = mock_state().await;
+        
+        for percent in [0.0, 0.5, 100.5, -10.0] {
+            let (status, _) = reduce_position_handler(State(state.clone()), reduce_request("mock", "BTC/USDT", percent)).await.unwrap_err();
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+        }
+        
+        let (status, Json(body)) = reduce_position_handler(State(state.clone()), reduce_request("mock", "SOL/USDT", 50.0)).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].as_str().unwrap().contains("No open position"));
+        
+        let (status, _) = reduce_position_handler(State(state), reduce_request("missing", "BTC/USDT", 50.0)).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[tokio::test]
+    async fn test_reduce_position_respects_halt_and_disabled_plugin() {
+        let state = mock_state().await;
+        
+        state.registry.halt();
+        let (status, Json(body)) = reduce_position_handler(State(state.clone()), reduce_request("mock", "BTC/USDT", 50.0)).await.unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body["error"].as_str().unwrap().contains("halted"));
+        state.registry.resume();
+        
+        state.registry.set_enabled("mock", false).await.unwrap();
+        let (status, Json(body)) = reduce_position_handler(State(state), reduce_request("mock", "BTC/USDT", 50.0)).await.unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body["error"].as_str().unwrap().contains("disabled"));
+    }
+    
+    #[tokio::test]
+    async fn test_get_positions() {
+        let state = mock_state().await;
+        
+        let query = PositionQuery { exchange: "mock".to_string(), symbol: Some("ETH/USDT".to_string()) };
+        let Json(positions) = get_positions_handler(State(state), Query(query)).await.unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].side, plugins::PositionSide::Short);
+    }
 }
@@ -0,0 +1,442 @@
+//! Prometheus metrics exported on `/metrics`
+
+use prometheus::core::Collector;
+use prometheus::{GaugeVec, Histogram, HistogramOpts, IntCounterVec, IntGauge, IntGaugeVec, Opts, TextEncoder};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use crate::in_flight::DrainReport;
+use crate::plugins::{now_millis, registry::PluginRegistry, ExecutionError, ExecutionPlugin, Position};
+
+/// Build metadata: version, plus the GIT_COMMIT and BUILD_DATE set at compile time
+static BUILD_INFO: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    let gauge = IntGaugeVec::new(
+        Opts::new("fks_build_info", "Build information for the service"),
+        &["service", "version", "commit", "build_date"],
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(gauge.clone())).expect("metric registered once");
+    gauge
+});
+
+/// Round-trip latency to each exchange's time endpoint, from `/api/v1/ping`
+pub static EXCHANGE_PING_MS: LazyLock<GaugeVec> = LazyLock::new(|| {
+    let gauge = GaugeVec::new(
+        Opts::new("fks_execution_exchange_ping_ms", "Round-trip latency to the exchange in milliseconds"),
+        &["exchange"],
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(gauge.clone())).expect("metric registered once");
+    gauge
+});
+
+/// Orders routed to the default plugin because no exchange was specified
+pub static DEFAULT_ROUTED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new("fks_execution_default_routed_total", "Orders routed to the default plugin"),
+        &["plugin"],
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(counter.clone())).expect("metric registered once");
+    counter
+});
+
+/// Open positions per exchange, from the periodic position refresh
+pub static OPEN_POSITIONS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    let gauge = IntGaugeVec::new(
+        Opts::new("fks_execution_open_positions", "Open positions on the exchange"),
+        &["exchange"],
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(gauge.clone())).expect("metric registered once");
+    gauge
+});
+
+/// Unrealized PnL summed over each exchange's open positions
+pub static UNREALIZED_PNL: LazyLock<GaugeVec> = LazyLock::new(|| {
+    let gauge = GaugeVec::new(
+        Opts::new("fks_execution_unrealized_pnl", "Unrealized PnL across open positions on the exchange"),
+        &["exchange"],
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(gauge.clone())).expect("metric registered once");
+    gauge
+});
+
+/// Offset applied to signed timestamps for exchange-synced clocks
+pub static CLOCK_OFFSET_MS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    let gauge = IntGaugeVec::new(
+        Opts::new("fks_execution_clock_offset_ms", "Exchange server time minus local time used for request signing"),
+        &["exchange"],
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(gauge.clone())).expect("metric registered once");
+    gauge
+});
+
+/// Failed plugin calls, by plugin and `ExecutionError` kind
+pub static PLUGIN_ERRORS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new("fks_execution_plugin_errors_total", "Failed plugin calls by error kind"),
+        &["plugin", "kind"],
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(counter.clone())).expect("metric registered once");
+    counter
+});
+
+/// Unix millis of each plugin's most recent failed call
+pub static PLUGIN_LAST_ERROR_MS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    let gauge = IntGaugeVec::new(
+        Opts::new("fks_execution_plugin_last_error_timestamp_ms", "Time of the plugin's last failed call in Unix millis"),
+        &["plugin"],
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(gauge.clone())).expect("metric registered once");
+    gauge
+});
+
+/// Signal endpoint calls, by HTTP method
+pub static SIGNAL_REQUESTS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new("fks_execution_signal_requests_total", "Requests to the signal endpoint"),
+        &["method"],
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(counter.clone())).expect("metric registered once");
+    counter
+});
+
+/// Time to compute a signal's indicators
+pub static SIGNAL_COMPUTE_SECONDS: LazyLock<Histogram> = LazyLock::new(|| {
+    let buckets = prometheus::exponential_buckets(0.0005, 2.0, 12).expect("valid buckets");
+    let histogram = Histogram::with_opts(
+        HistogramOpts::new("fks_execution_signal_compute_seconds", "Time to compute a signal in seconds").buckets(buckets),
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(histogram.clone())).expect("metric registered once");
+    histogram
+});
+
+/// Most recently computed RSI per symbol
+pub static SIGNAL_LAST_RSI: LazyLock<GaugeVec> = LazyLock::new(|| {
+    let gauge = GaugeVec::new(
+        Opts::new("fks_execution_signal_last_rsi", "RSI of the last signal computed for the symbol"),
+        &["symbol"],
+    )
+    .expect("valid metric definition");
+    prometheus::register(Box::new(gauge.clone())).expect("metric registered once");
+    gauge
+});
+
+/// In-flight orders that finished during the shutdown grace period
+pub static SHUTDOWN_DRAINED_ORDERS: LazyLock<IntGauge> = LazyLock::new(|| {
+    let gauge = IntGauge::new("fks_execution_shutdown_drained_orders", "In-flight orders completed during the shutdown grace period")
+        .expect("valid metric definition");
+    prometheus::register(Box::new(gauge.clone())).expect("metric registered once");
+    gauge
+});
+
+/// In-flight orders still unfinished when the shutdown grace period ran out
+pub static SHUTDOWN_ABANDONED_ORDERS: LazyLock<IntGauge> = LazyLock::new(|| {
+    let gauge = IntGauge::new("fks_execution_shutdown_abandoned_orders", "In-flight orders unfinished when the shutdown grace period ended")
+        .expect("valid metric definition");
+    prometheus::register(Box::new(gauge.clone())).expect("metric registered once");
+    gauge
+});
+
+/// 1 when shutdown gave up waiting on in-flight orders
+pub static SHUTDOWN_GRACE_EXCEEDED: LazyLock<IntGauge> = LazyLock::new(|| {
+    let gauge = IntGauge::new("fks_execution_shutdown_grace_exceeded", "Whether the shutdown grace period ran out before in-flight orders finished")
+        .expect("valid metric definition");
+    prometheus::register(Box::new(gauge.clone())).expect("metric registered once");
+    gauge
+});
+
+/// Most recent failed call of a plugin, shown on `/api/v1/exchanges`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LastError {
+    pub kind: &'static str,
+    pub message: String,
+    pub timestamp: i64,
+}
+
+static LAST_ERRORS: LazyLock<Mutex<HashMap<String, LastError>>> = LazyLock::new(Default::default);
+
+/// `kind` label for an error: its [`ExecutionError::kind`], or `other`
+pub fn error_kind(error: &(dyn Error + Send + Sync + 'static)) -> &'static str {
+    error.downcast_ref::<ExecutionError>().map_or("other", ExecutionError::kind)
+}
+
+/// Count a failed plugin call and remember it as the plugin's last error
+pub fn record_plugin_error(plugin: &str, error: &(dyn Error + Send + Sync + 'static)) {
+    let last = LastError {
+        kind: error_kind(error),
+        message: error.to_string(),
+        timestamp: now_millis(),
+    };
+    PLUGIN_ERRORS_TOTAL.with_label_values(&[plugin, last.kind]).inc();
+    PLUGIN_LAST_ERROR_MS.with_label_values(&[plugin]).set(last.timestamp);
+    LAST_ERRORS.lock().unwrap().insert(plugin.to_string(), last);
+}
+
+/// The plugin's most recent failed call, if any
+pub fn last_plugin_error(plugin: &str) -> Option<LastError> {
+    LAST_ERRORS.lock().unwrap().get(plugin).cloned()
+}
+
+/// Failed calls of a plugin across every `kind` label recorded for it
+pub fn plugin_error_count(plugin: &str) -> u64 {
+    PLUGIN_ERRORS_TOTAL.collect().iter()
+        .flat_map(|family| family.get_metric())
+        .filter(|metric| metric.get_label().iter().any(|label| label.get_name() == "plugin" && label.get_value() == plugin))
+        .map(|metric| metric.get_counter().get_value() as u64)
+        .sum()
+}
+
+/// Longest a failing plugin is skipped, in refresh rounds
+const MAX_BACKOFF_ROUNDS: u32 = 32;
+
+#[derive(Debug, Default)]
+struct Backoff {
+    failures: u32,
+    skip_rounds: u32,
+}
+
+/// Periodic refresh of the position gauges
+///
+/// Each plugin is asked for all of its positions in one bulk call; per-symbol
+/// queries (for `POSITION_REFRESH_SYMBOLS`) are only made when a plugin can't
+/// list positions without a symbol. A plugin that errors is skipped for
+/// exponentially more rounds until it recovers, so a rate-limited exchange
+/// isn't hammered.
+pub struct PositionRefresh {
+    registry: Arc<PluginRegistry>,
+    symbols: Vec<String>,
+    backoff: HashMap<String, Backoff>,
+}
+
+fn is_unsupported(error: &(dyn Error + Send + Sync + 'static)) -> bool {
+    matches!(error.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_)))
+}
+
+impl PositionRefresh {
+    pub fn new(registry: Arc<PluginRegistry>, symbols: Vec<String>) -> Self {
+        Self {
+            registry,
+            symbols,
+            backoff: HashMap::new(),
+        }
+    }
+    
+    /// Build from `POSITION_REFRESH_SYMBOLS` (comma-separated, per-symbol fallback only)
+    pub fn from_env(registry: Arc<PluginRegistry>) -> Self {
+        let symbols = std::env::var("POSITION_REFRESH_SYMBOLS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect();
+        Self::new(registry, symbols)
+    }
+    
+    /// Positions for one plugin, or `None` when it can't report any
+    async fn positions(
+        &self,
+        plugin: &dyn ExecutionPlugin,
+    ) -> Result<Option<Vec<Position>>, Box<dyn Error + Send + Sync>> {
+        match plugin.get_all_positions().await {
+            Ok(positions) => return Ok(Some(positions)),
+            Err(e) if !is_unsupported(e.as_ref()) => return Err(e),
+            Err(_) if self.symbols.is_empty() => return Ok(None),
+            Err(_) => {}
+        }
+        
+        let mut positions = Vec::new();
+        for symbol in &self.symbols {
+            match plugin.get_positions(Some(symbol)).await {
+                Ok(found) => positions.extend(found),
+                Err(e) if is_unsupported(e.as_ref()) => return Ok(None),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Some(positions))
+    }
+    
+    /// Refresh every enabled plugin once, honouring backoff
+    pub async fn refresh_once(&mut self) {
+        for name in self.registry.list_plugins().await {
+            if !self.registry.is_enabled(&name).await {
+                continue;
+            }
+            let Some(plugin) = self.registry.get(&name).await else { continue };
+            
+            let backoff = self.backoff.entry(name.clone()).or_default();
+            if backoff.skip_rounds > 0 {
+                backoff.skip_rounds -= 1;
+                continue;
+            }
+            
+            let result = self.positions(plugin.as_ref()).await;
+            let backoff = self.backoff.entry(name.clone()).or_default();
+            match result {
+                Ok(Some(positions)) => {
+                    *backoff = Backoff::default();
+                    OPEN_POSITIONS.with_label_values(&[&name]).set(positions.len() as i64);
+                    UNREALIZED_PNL.with_label_values(&[&name]).set(positions.iter().map(|p| p.unrealized_pnl).sum());
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    record_plugin_error(&name, e.as_ref());
+                    backoff.failures += 1;
+                    backoff.skip_rounds = (1u32 << backoff.failures.min(5)).min(MAX_BACKOFF_ROUNDS) - 1;
+                    tracing::warn!(
+                        plugin = %name,
+                        error = %e,
+                        failures = backoff.failures,
+                        skip_rounds = backoff.skip_rounds,
+                        "position_refresh_failed"
+                    );
+                }
+            }
+        }
+    }
+    
+    /// Run `refresh_once` every `interval` in the background
+    pub fn spawn(mut self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.refresh_once().await;
+            }
+        })
+    }
+}
+
+/// Record a shutdown drain and flush the final metric values to the log
+///
+/// The process exits right after, so this is the last chance to see them.
+pub fn record_shutdown_drain(report: &DrainReport) {
+    SHUTDOWN_DRAINED_ORDERS.set(report.drained as i64);
+    SHUTDOWN_ABANDONED_ORDERS.set(report.abandoned as i64);
+    SHUTDOWN_GRACE_EXCEEDED.set(report.grace_exceeded as i64);
+    tracing::info!(metrics = %render(), "final_metrics");
+}
+
+/// `fks_build_info` for this binary, always 1; labels default to "unknown"
+/// when the build didn't set GIT_COMMIT/BUILD_DATE
+pub fn build_info_metric() -> IntGauge {
+    let gauge = BUILD_INFO.with_label_values(&[
+        "fks_execution",
+        env!("CARGO_PKG_VERSION"),
+        option_env!("GIT_COMMIT").unwrap_or("unknown"),
+        option_env!("BUILD_DATE").unwrap_or("unknown"),
+    ]);
+    gauge.set(1);
+    gauge
+}
+
+/// Render all registered metrics in the Prometheus text format
+pub fn render() -> String {
+    TextEncoder::new()
+        .encode_to_string(&prometheus::gather())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::{bybit::BybitPlugin, mock::MockPlugin};
+    
+    #[tokio::test]
+    async fn test_refresh_sets_position_gauges() {
+        let registry = Arc::new(PluginRegistry::new());
+        let mut mock = MockPlugin::new("refresh-mock");
+        mock.init(serde_json::json!({})).await.unwrap();
+        registry.register("refresh-mock".to_string(), Arc::new(mock)).await;
+        
+        let mut refresh = PositionRefresh::new(registry, Vec::new());
+        refresh.refresh_once().await;
+        
+        assert_eq!(OPEN_POSITIONS.with_label_values(&["refresh-mock"]).get(), 2);
+        assert!(render().contains("fks_execution_open_positions{exchange=\"refresh-mock\"} 2"));
+    }
+    
+    #[test]
+    fn test_plugin_errors_counted_by_kind() {
+        let network: Box<dyn Error + Send + Sync> = ExecutionError::Network("gateway returned HTML".to_string()).into();
+        let other: Box<dyn Error + Send + Sync> = "Plugin not initialized".into();
+        
+        record_plugin_error("kind-test", network.as_ref());
+        record_plugin_error("kind-test", network.as_ref());
+        record_plugin_error("kind-test", other.as_ref());
+        
+        assert_eq!(PLUGIN_ERRORS_TOTAL.with_label_values(&["kind-test", "network"]).get(), 2);
+        assert_eq!(PLUGIN_ERRORS_TOTAL.with_label_values(&["kind-test", "other"]).get(), 1);
+        assert_eq!(plugin_error_count("kind-test"), 3);
+        
+        let last = last_plugin_error("kind-test").unwrap();
+        assert_eq!(last.kind, "other");
+        assert_eq!(last.message, "Plugin not initialized");
+        assert_eq!(PLUGIN_LAST_ERROR_MS.with_label_values(&["kind-test"]).get(), last.timestamp);
+        assert!(last_plugin_error("kind-test-unused").is_none());
+    }
+    
+    #[test]
+    fn test_every_error_kind_counted() {
+        let errors = [
+            ExecutionError::Unsupported(String::new()),
+            ExecutionError::Network(String::new()),
+            ExecutionError::Disabled(String::new()),
+            ExecutionError::Overloaded(String::new()),
+            ExecutionError::PluginNotFound(String::new()),
+            ExecutionError::InvalidSymbol { symbol: String::new(), suggestions: Vec::new() },
+            ExecutionError::Exchange { code: "test", message: String::new() },
+            ExecutionError::OutcomeUnknown(String::new()),
+        ];
+        // No wildcard arm: a new variant fails to compile until it is listed above
+        for error in &errors {
+            match error {
+                ExecutionError::Unsupported(_) | ExecutionError::Network(_) | ExecutionError::Disabled(_)
+                | ExecutionError::Overloaded(_) | ExecutionError::PluginNotFound(_) | ExecutionError::InvalidSymbol { .. }
+                | ExecutionError::Exchange { .. } | ExecutionError::OutcomeUnknown(_) => {}
+            }
+        }
+        
+        // Each variant gets a label of its own and all of them reach the total
+        for error in errors {
+            let kind = error.kind();
+            let error: Box<dyn Error + Send + Sync> = error.into();
+            record_plugin_error("every-kind-test", error.as_ref());
+            assert_eq!(PLUGIN_ERRORS_TOTAL.with_label_values(&["every-kind-test", kind]).get(), 1, "{}", kind);
+        }
+        assert_eq!(plugin_error_count("every-kind-test"), 8);
+    }
+    
+    #[tokio::test]
+    async fn test_failing_plugin_backs_off() {
+        let registry = Arc::new(PluginRegistry::new());
+        // Never initialized, so every position query fails
+        registry.register("refresh-broken".to_string(), Arc::new(BybitPlugin::new("refresh-broken"))).await;
+        
+        let mut refresh = PositionRefresh::new(registry, Vec::new());
+        refresh.refresh_once().await;
+        assert_eq!(refresh.backoff["refresh-broken"].failures, 1);
+        assert_eq!(refresh.backoff["refresh-broken"].skip_rounds, 1);
+        
+        // Skipped round: no new attempt
+        refresh.refresh_once().await;
+        assert_eq!(refresh.backoff["refresh-broken"].failures, 1);
+        assert_eq!(refresh.backoff["refresh-broken"].skip_rounds, 0);
+        
+        refresh.refresh_once().await;
+        assert_eq!(refresh.backoff["refresh-broken"].failures, 2);
+        assert_eq!(refresh.backoff["refresh-broken"].skip_rounds, 3);
+    }
+}
@@ -0,0 +1,167 @@
+//! Daily order cap, a blunt guard against runaway strategies
+//!
+//! Configured with `MAX_DAILY_ORDERS`, either a bare number capping all
+//! exchanges combined or comma-separated `exchange=count` rules, where `*`
+//! is the combined cap, e.g. `bybit=200,kucoin=100,*=250`. The plugin
+//! registry counts every order it submits (API orders, webhooks, TWAP slices,
+//! self-tests), whether or not the exchange accepts it; reduce-only orders are
+//! exempt so positions can always be closed. Once a cap is reached further
+//! orders are refused until the counters reset at UTC midnight.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::clock::{self, SharedClock};
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// A daily cap was reached
+#[derive(Debug, thiserror::Error, PartialEq)]
+#[error("daily order cap of {limit} reached{}, resets at 00:00 UTC", exchange.as_ref().map(|e| format!(" on {}", e)).unwrap_or_default())]
+pub struct OrderCapError {
+    /// Exchange whose cap was reached, `None` for the combined cap
+    pub exchange: Option<String>,
+    pub limit: u64,
+}
+
+/// Orders counted so far on one UTC day
+#[derive(Debug, Default)]
+struct DayCounts {
+    day: i64,
+    total: u64,
+    by_exchange: HashMap<String, u64>,
+}
+
+/// Per-exchange and combined daily order counters
+#[derive(Debug)]
+pub struct DailyOrderCap {
+    combined: Option<u64>,
+    per_exchange: HashMap<String, u64>,
+    counts: Mutex<DayCounts>,
+    clock: SharedClock,
+}
+
+impl Default for DailyOrderCap {
+    fn default() -> Self {
+        Self::new(None, HashMap::new())
+    }
+}
+
+impl DailyOrderCap {
+    pub fn new(combined: Option<u64>, per_exchange: HashMap<String, u64>) -> Self {
+        Self::with_clock(combined, per_exchange, clock::system())
+    }
+    
+    /// Cap that reads the current time from `clock`
+    pub fn with_clock(combined: Option<u64>, per_exchange: HashMap<String, u64>, clock: SharedClock) -> Self {
+        Self {
+            combined,
+            per_exchange: per_exchange.into_iter().map(|(e, limit)| (e.to_lowercase(), limit)).collect(),
+            counts: Mutex::new(DayCounts::default()),
+            clock,
+        }
+    }
+    
+    /// Parse a `MAX_DAILY_ORDERS` spec, skipping (and logging) malformed rules
+    pub fn parse(spec: &str) -> Self {
+        let spec = spec.trim();
+        if let Ok(limit) = spec.parse::<u64>() {
+            return Self::new(Some(limit), HashMap::new());
+        }
+        
+        let mut combined = None;
+        let mut per_exchange = HashMap::new();
+        for rule in spec.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+            let parsed = rule.split_once('=')
+                .and_then(|(exchange, limit)| Some((exchange.trim(), limit.trim().parse::<u64>().ok()?)));
+            match parsed {
+                Some(("*", limit)) => combined = Some(limit),
+                Some((exchange, limit)) if !exchange.is_empty() => {
+                    per_exchange.insert(exchange.to_string(), limit);
+                }
+                _ => tracing::warn!(rule = %rule, "max_daily_orders_rule_invalid"),
+            }
+        }
+        Self::new(combined, per_exchange)
+    }
+    
+    /// Build from `MAX_DAILY_ORDERS` (default: no cap)
+    pub fn from_env() -> Self {
+        Self::parse(&std::env::var("MAX_DAILY_ORDERS").unwrap_or_default())
+    }
+    
+    /// Whether any cap is configured
+    pub fn is_enabled(&self) -> bool {
+        self.combined.is_some() || !self.per_exchange.is_empty()
+    }
+    
+    /// Count an order for `exchange`, or refuse it if a cap is already reached
+    pub fn try_count(&self, exchange: &str) -> Result<(), OrderCapError> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let exchange = exchange.to_lowercase();
+        let today = self.clock.now_millis().div_euclid(MILLIS_PER_DAY);
+        
+        let mut counts = self.counts.lock().unwrap();
+        if counts.day != today {
+            *counts = DayCounts { day: today, ..DayCounts::default() };
+        }
+        if let Some(&limit) = self.per_exchange.get(&exchange) {
+            if counts.by_exchange.get(&exchange).copied().unwrap_or(0) >= limit {
+                return Err(OrderCapError { exchange: Some(exchange), limit });
+            }
+        }
+        if let Some(limit) = self.combined.filter(|limit| counts.total >= *limit) {
+            return Err(OrderCapError { exchange: None, limit });
+        }
+        
+        counts.total += 1;
+        *counts.by_exchange.entry(exchange).or_insert(0) += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TestClock;
+    use std::time::Duration;
+    
+    #[test]
+    fn test_order_past_cap_rejected_until_utc_midnight() {
+        // 2023-11-14 22:13:20 UTC
+        let clock = TestClock::new(1_700_000_000_000);
+        let cap = DailyOrderCap::with_clock(Some(5), HashMap::from([("bybit".to_string(), 3)]), clock.clone());
+        
+        for _ in 0..3 {
+            assert!(cap.try_count("Bybit").is_ok());
+        }
+        let err = cap.try_count("bybit").unwrap_err();
+        assert_eq!(err.to_string(), "daily order cap of 3 reached on bybit, resets at 00:00 UTC");
+        
+        // Other exchanges only share the combined cap
+        assert!(cap.try_count("kucoin").is_ok());
+        assert!(cap.try_count("kucoin").is_ok());
+        let err = cap.try_count("kucoin").unwrap_err();
+        assert_eq!(err, OrderCapError { exchange: None, limit: 5 });
+        
+        clock.advance(Duration::from_secs(2 * 3600));
+        assert!(cap.try_count("bybit").is_ok());
+    }
+    
+    #[test]
+    fn test_parse_spec() {
+        let cap = DailyOrderCap::parse("500");
+        assert_eq!((cap.combined, cap.per_exchange.len()), (Some(500), 0));
+        
+        let cap = DailyOrderCap::parse("bybit=200, KuCoin=100, *=250, broken");
+        assert_eq!(cap.combined, Some(250));
+        assert_eq!(cap.per_exchange, HashMap::from([("bybit".to_string(), 200), ("kucoin".to_string(), 100)]));
+        
+        let cap = DailyOrderCap::parse("");
+        for _ in 0..1000 {
+            assert!(cap.try_count("bybit").is_ok());
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Order groups: cancel the rest of a group when one leg fills
+//!
+//! Exchanges without native OCO support leave a stop-loss resting after its
+//! take-profit filled (or the other way round). Orders submitted with the same
+//! `order_group_id` are tracked here; a background poll checks each leg and,
+//! once any leg has started filling, cancels its siblings that are still
+//! resting. Plugins that can't look orders up get no OCO: their groups are
+//! dropped on the first poll.
+//!
+//! Groups are meant for the exit legs of a position (take-profit and
+//! stop-loss). The entry order is placed on its own and should not share the
+//! group, otherwise its fill would cancel the exits it was meant to protect.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+use crate::plugins::registry::PluginRegistry;
+use crate::plugins::{ExecutionError, OrderStatus};
+
+/// One resting order in a group
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupLeg {
+    pub order_id: String,
+    pub symbol: String,
+}
+
+#[derive(Debug)]
+struct OrderGroup {
+    plugin: String,
+    legs: Vec<GroupLeg>,
+}
+
+/// Tracks order groups and cancels siblings of filled legs
+pub struct OrderGroupTracker {
+    registry: Arc<PluginRegistry>,
+    groups: Mutex<HashMap<String, OrderGroup>>,
+}
+
+impl OrderGroupTracker {
+    pub fn new(registry: Arc<PluginRegistry>) -> Self {
+        Self {
+            registry,
+            groups: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    /// Add a placed order to a group, creating the group on its first leg
+    ///
+    /// All legs of a group must be on the same plugin.
+    pub async fn add_leg(&self, group_id: &str, plugin: &str, leg: GroupLeg) -> Result<(), String> {
+        let mut groups = self.groups.lock().await;
+        let group = groups.entry(group_id.to_string()).or_insert_with(|| OrderGroup {
+            plugin: plugin.to_string(),
+            legs: Vec::new(),
+        });
+        if group.plugin != plugin {
+            return Err(format!(
+                "Order group '{}' belongs to plugin '{}', not '{}'",
+                group_id, group.plugin, plugin
+            ));
+        }
+        group.legs.push(leg);
+        Ok(())
+    }
+    
+    /// Number of groups still being watched
+    #[cfg(test)]
+    pub async fn len(&self) -> usize {
+        self.groups.lock().await.len()
+    }
+    
+    /// Check every group once, cancelling siblings of any filled or partially filled leg
+    ///
+    /// A group is dropped once a leg has (partially) filled, every leg is
+    /// closed, or the plugin can't look orders up at all. Legs whose status
+    /// can't be fetched otherwise are retried on the next poll.
+    pub async fn poll_once(&self) {
+        let snapshot: Vec<(String, String, Vec<GroupLeg>)> = self.groups.lock().await
+            .iter()
+            .map(|(id, group)| (id.clone(), group.plugin.clone(), group.legs.clone()))
+            .collect();
+        
+        for (group_id, plugin_name, legs) in snapshot {
+            let Some(plugin) = self.registry.get(&plugin_name).await else {
+                tracing::warn!(group = %group_id, plugin = %plugin_name, "order_group_plugin_missing");
+                self.groups.lock().await.remove(&group_id);
+                continue;
+            };
+            
+            let mut statuses = Vec::with_capacity(legs.len());
+            let mut unsupported = false;
+            for leg in &legs {
+                match plugin.get_order(&leg.symbol, &leg.order_id).await {
+                    Ok(order) => statuses.push(Some(order.status)),
+                    Err(e) if matches!(e.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))) => {
+                        unsupported = true;
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::warn!(group = %group_id, order_id = %leg.order_id, error = %e, "order_group_status_failed");
+                        statuses.push(None);
+                    }
+                }
+            }
+            if unsupported {
+                tracing::warn!(group = %group_id, plugin = %plugin_name, "order_group_dropped_order_lookup_unsupported");
+                self.groups.lock().await.remove(&group_id);
+                continue;
+            }
+            
+            let filling = |s: &Option<OrderStatus>| matches!(s, Some(OrderStatus::Filled | OrderStatus::PartiallyFilled));
+            let Some(filled) = statuses.iter().position(filling) else {
+                let all_closed = statuses.iter().all(|s| matches!(
+                    s,
+                    Some(OrderStatus::Cancelled | OrderStatus::Rejected)
+                ));
+                if all_closed {
+                    self.groups.lock().await.remove(&group_id);
+                }
+                continue;
+            };
+            
+            tracing::info!(group = %group_id, order_id = %legs[filled].order_id, "order_group_leg_filled");
+            for (leg, status) in legs.iter().zip(&statuses) {
+                let resting = matches!(status, Some(OrderStatus::Open | OrderStatus::PartiallyFilled) | None);
+                if leg.order_id == legs[filled].order_id || !resting {
+                    continue;
+                }
+                match plugin.cancel_order(&leg.symbol, &leg.order_id).await {
+                    Ok(()) => tracing::info!(group = %group_id, order_id = %leg.order_id, "order_group_sibling_cancelled"),
+                    Err(e) => tracing::warn!(group = %group_id, order_id = %leg.order_id, error = %e, "order_group_cancel_failed"),
+                }
+            }
+            self.groups.lock().await.remove(&group_id);
+        }
+    }
+    
+    /// Run `poll_once` every `interval` in the background
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.poll_once().await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::ccxt::CCXTPlugin;
+    use crate::plugins::mock::MockPlugin;
+    use crate::plugins::{ExecutionPlugin, Order, OrderSide, OrderType};
+    
+    fn exit_order(order_type: OrderType, price: f64) -> Order {
+        Order {
+            symbol: "BTC/USDT".to_string(),
+            side: OrderSide::Sell,
+            order_type,
+            quantity: 0.1,
+            price: Some(price),
+            confidence: 0.7,
+            reduce_only: true,
+            ..Default::default()
+        }
+    }
+    
+    async fn setup() -> (Arc<MockPlugin>, OrderGroupTracker, GroupLeg, GroupLeg) {
+        let mut mock = MockPlugin::new("mock");
+        mock.init(serde_json::json!({})).await.unwrap();
+        let mock = Arc::new(mock);
+        let registry = Arc::new(PluginRegistry::new());
+        registry.register("mock".to_string(), mock.clone()).await;
+        let tracker = OrderGroupTracker::new(registry);
+        
+        let mut legs = Vec::new();
+        for order in [exit_order(OrderType::TakeProfit, 70000.0), exit_order(OrderType::StopLoss, 65000.0)] {
+            let order_id = mock.execute_order(order).await.unwrap().order_id.unwrap();
+            let leg = GroupLeg { order_id, symbol: "BTC/USDT".to_string() };
+            tracker.add_leg("exit-1", "mock", leg.clone()).await.unwrap();
+            legs.push(leg);
+        }
+        let stop_loss = legs.pop().unwrap();
+        let take_profit = legs.pop().unwrap();
+        (mock, tracker, take_profit, stop_loss)
+    }
+    
+    #[tokio::test]
+    async fn test_take_profit_fill_cancels_stop_loss() {
+        let (mock, tracker, take_profit, stop_loss) = setup().await;
+        
+        // Nothing filled yet: both legs keep resting
+        tracker.poll_once().await;
+        assert_eq!(tracker.len().await, 1);
+        assert_eq!(mock.get_order("BTC/USDT", &stop_loss.order_id).await.unwrap().status, OrderStatus::Open);
+        
+        mock.set_order_status(&take_profit.order_id, OrderStatus::Filled);
+        tracker.poll_once().await;
+        
+        assert_eq!(mock.get_order("BTC/USDT", &stop_loss.order_id).await.unwrap().status, OrderStatus::Cancelled);
+        assert_eq!(mock.get_order("BTC/USDT", &take_profit.order_id).await.unwrap().status, OrderStatus::Filled);
+        assert_eq!(tracker.len().await, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_partial_fill_cancels_siblings() {
+        let (mock, tracker, take_profit, stop_loss) = setup().await;
+        
+        // The stop-loss started filling: the take-profit must not stay on the book
+        mock.set_order_status(&stop_loss.order_id, OrderStatus::PartiallyFilled);
+        tracker.poll_once().await;
+        
+        assert_eq!(mock.get_order("BTC/USDT", &take_profit.order_id).await.unwrap().status, OrderStatus::Cancelled);
+        assert_eq!(mock.get_order("BTC/USDT", &stop_loss.order_id).await.unwrap().status, OrderStatus::PartiallyFilled);
+        assert_eq!(tracker.len().await, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_group_dropped_when_plugin_cannot_look_up_orders() {
+        let registry = Arc::new(PluginRegistry::new());
+        registry.register("ccxt".to_string(), Arc::new(CCXTPlugin::new("ccxt"))).await;
+        let tracker = OrderGroupTracker::new(registry);
+        let leg = GroupLeg { order_id: "bridge-1".to_string(), symbol: "BTC/USDT".to_string() };
+        tracker.add_leg("exit-1", "ccxt", leg).await.unwrap();
+        
+        tracker.poll_once().await;
+        assert_eq!(tracker.len().await, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_closed_group_dropped_without_cancels() {
+        let (mock, tracker, take_profit, stop_loss) = setup().await;
+        
+        mock.set_order_status(&take_profit.order_id, OrderStatus::Cancelled);
+        mock.set_order_status(&stop_loss.order_id, OrderStatus::Cancelled);
+        tracker.poll_once().await;
+        assert_eq!(tracker.len().await, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_group_bound_to_one_plugin() {
+        let (_, tracker, _, _) = setup().await;
+        let leg = GroupLeg { order_id: "X".to_string(), symbol: "BTC/USDT".to_string() };
+        let err = tracker.add_leg("exit-1", "bybit", leg).await.unwrap_err();
+        assert!(err.contains("belongs to plugin 'mock'"));
+    }
+}
@@ -3,17 +3,22 @@
 //! Direct integration with Bybit API for futures trading (linear contracts).
 //! Supports order placement, leverage management, and position queries.
 
-use super::{ExecutionPlugin, ExecutionResult, MarketData, Order, OrderSide, OrderType};
+use super::headers::ClientHeaders;
+use super::leverage::LeverageLimit;
+use super::tls::ClientTlsConfig;
+use super::{apply_quote_alias, client_order_id, now_millis, Balance, CancelOutcome, ClosedPnl, ExecutionError, ExecutionPlugin, ExecutionResult, FundingRate, HealthStatus, InstrumentInfo, InstrumentRounding, LeverageChange, MaintenanceState, MarketData, Order, OrderPage, OrderRef, PreparedRequest, OrderSide, OrderStatus, OrderSummary, OrderType, PingResult, Position, PositionSide, QuantityType, merge_extra_params, reject_display_quantity, TimeSource, TimeSync, Trade, INVALID_CURSOR};
 use async_trait::async_trait;
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// Configuration for Bybit plugin
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BybitConfig {
     /// Bybit API key
     pub api_key: String,
@@ -25,27 +30,222 @@ pub struct BybitConfig {
     #[serde(default)]
     pub testnet: bool,
     
-    /// Category: "linear" for futures (default)
+    /// Category: "linear" for futures (default), "inverse" or "spot"
     #[serde(default = "default_category")]
     pub category: String,
     
+    /// Look up each symbol's category on first use and cache it, so linear,
+    /// inverse and spot symbols can share one plugin; `category` is tried
+    /// first and is the fallback for unlisted symbols (default: false)
+    #[serde(default)]
+    pub auto_category: bool,
+    
     /// Default leverage (default: 10)
     #[serde(default = "default_leverage")]
     pub leverage: i32,
+    
+    /// Override the REST endpoint (e.g. a proxy); takes precedence over `testnet`
+    #[serde(default)]
+    pub base_url: Option<String>,
+    
+    /// Quote-currency aliases applied to incoming symbols (e.g. `USD` -> `USDT`)
+    #[serde(default)]
+    pub quote_aliases: HashMap<String, String>,
+    
+    /// Position mode; when set, the account is switched to it on init
+    /// (default: one-way, left unchanged on the account)
+    #[serde(default)]
+    pub position_mode: Option<PositionMode>,
+    
+    /// Unit of `quantity` for spot market orders (default: base coin, as for
+    /// derivatives; Bybit itself treats spot market buys as quote coin)
+    #[serde(default)]
+    pub spot_market_unit: MarketUnit,
+    
+    /// Clock for signed timestamps: "local" (default) or "exchange", which
+    /// corrects local time by the measured offset to the server clock
+    #[serde(default)]
+    pub time_source: TimeSource,
+    
+    /// Seconds between server clock offset measurements (default: 300)
+    #[serde(default = "super::default_time_sync_secs")]
+    pub time_sync_secs: u64,
+    
+    /// Symbol whose ticker the health check fetches; pick one listed in the
+    /// configured category and region (default: BTCUSDT)
+    #[serde(default = "default_health_check_symbol")]
+    pub health_check_symbol: String,
+    
+    /// Settle coins queried when listing all positions or open orders outside
+    /// spot, one request sweep per coin (default: USDT and USDC)
+    #[serde(default = "default_settle_coins")]
+    pub settle_coins: Vec<String>,
+    
+    /// Client certificate / private CA for endpoints requiring mutual TLS
+    #[serde(flatten)]
+    pub tls: ClientTlsConfig,
+    
+    /// `user_agent` / `headers` sent on every request
+    #[serde(flatten)]
+    pub client_headers: ClientHeaders,
+    
+    /// `max_leverage` / `strict_leverage` cap on `leverage`, per-order leverage and set-leverage requests
+    #[serde(flatten)]
+    pub leverage_limit: LeverageLimit,
+    
+    /// `qty_rounding` / `price_rounding`: "down", "nearest" or "up" onto the
+    /// instrument's steps (default: down for quantities, nearest for prices)
+    #[serde(flatten)]
+    pub rounding: InstrumentRounding,
+}
+
+/// Unit of a spot market order quantity (Bybit `marketUnit`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MarketUnit {
+    /// Quantity in the base coin (e.g. BTC for BTCUSDT)
+    #[default]
+    BaseCoin,
+    
+    /// Quantity in the quote coin (e.g. USDT for BTCUSDT)
+    QuoteCoin,
+}
+
+/// Bybit position mode for linear/inverse contracts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionMode {
+    /// One position per symbol (`positionIdx` 0)
+    #[default]
+    OneWay,
+    
+    /// Separate long and short positions (`positionIdx` 1 / 2)
+    Hedge,
+}
+
+impl PositionMode {
+    /// `positionIdx` for an order on the given side; in hedge mode a
+    /// reduce-only order targets the position it closes (a sell closes the long)
+    fn position_idx(self, side: &OrderSide, reduce_only: bool) -> i32 {
+        match (self, side, reduce_only) {
+            (PositionMode::OneWay, _, _) => 0,
+            (PositionMode::Hedge, OrderSide::Buy, false) | (PositionMode::Hedge, OrderSide::Sell, true) => 1,
+            (PositionMode::Hedge, OrderSide::Sell, false) | (PositionMode::Hedge, OrderSide::Buy, true) => 2,
+        }
+    }
+    
+    /// `mode` value for `/v5/position/switch-mode`
+    fn switch_mode_value(self) -> i32 {
+        match self {
+            PositionMode::OneWay => 0,
+            PositionMode::Hedge => 3,
+        }
+    }
 }
 
+/// Bybit return code when the requested position mode is already active
+const POSITION_MODE_NOT_MODIFIED: i32 = 110025;
+
+/// Bybit return code when the symbol is already at the requested leverage
+const LEVERAGE_NOT_MODIFIED: i32 = 110043;
+
 fn default_category() -> String {
     "linear".to_string()
 }
 
+fn default_health_check_symbol() -> String {
+    "BTCUSDT".to_string()
+}
+
+fn default_settle_coins() -> Vec<String> {
+    vec!["USDT".to_string(), "USDC".to_string()]
+}
+
 fn default_leverage() -> i32 {
     10
 }
 
+/// Largest page `/v5/position/list` returns
+const POSITION_PAGE_LIMIT: u32 = 200;
+
+/// Upper bound on position pages fetched in one bulk query
+const MAX_POSITION_PAGES: usize = 10;
+
+/// Largest page `/v5/market/instruments-info` returns
+const INSTRUMENT_PAGE_LIMIT: u32 = 1000;
+
+/// Upper bound on instrument pages fetched when listing symbols
+const MAX_INSTRUMENT_PAGES: usize = 10;
+
+/// Categories searched when `auto_category` is on, after the configured one
+const CATEGORIES: &[&str] = &["linear", "inverse", "spot"];
+
+/// Largest page `/v5/position/closed-pnl` returns
+const CLOSED_PNL_PAGE_LIMIT: u32 = 100;
+
+/// Largest page `/v5/execution/list` returns
+const EXECUTION_PAGE_LIMIT: usize = 100;
+
+/// Maximum length of a Bybit `orderLinkId`
+const ORDER_LINK_ID_MAX_LEN: usize = 36;
+
+/// `X-BAPI-RECV-WINDOW` sent with signed requests: Bybit rejects a request
+/// arriving later than this after its timestamp
+const RECV_WINDOW_MS: u64 = 5000;
+
+/// Bybit v5 return codes sent while the service is restarting for maintenance
+const MAINTENANCE_RET_CODES: &[i64] = &[10016];
+
+/// Bybit v5 return codes for rejected credentials
+/// (invalid key, bad signature, permission denied, expired key)
+const AUTH_FAILED_RET_CODES: &[i64] = &[10003, 10004, 10005, 33004];
+
+/// Bybit v5 return codes for a post-only (LIMIT_MAKER) order that would take liquidity
+const POST_ONLY_RET_CODES: &[i32] = &[170218];
+
+/// Most orders one `/v5/order/cancel-batch` request may carry
+const CANCEL_BATCH_LIMIT: usize = 10;
+
+/// Bybit instrument names a user-supplied perpetual symbol may refer to
+///
+/// Covers TradingView (`BTCUSDT.P`), CCXT (`BTC/USDC:USDC`) and dashed
+/// (`BTC-PERP`) spellings. USDC-settled perpetuals are named `BTCPERP` on
+/// Bybit, so a bare `-PERP` suffix tries that before the USDT contract.
+fn perpetual_candidates(symbol: &str) -> Vec<String> {
+    let upper = symbol.to_uppercase();
+    let pair = upper.split(':').next().unwrap_or_default();
+    let compact: String = pair.chars().filter(|c| !matches!(c, '/' | '-' | '_')).collect();
+    
+    let mut candidates = Vec::new();
+    if let Some(core) = compact.strip_suffix(".P") {
+        candidates.push(core.to_string());
+    } else if let Some(base) = compact.strip_suffix("PERP").filter(|b| !b.is_empty()) {
+        match base.strip_suffix("USDT") {
+            Some(_) => candidates.push(base.to_string()),
+            None => {
+                let base = base.strip_suffix("USDC").unwrap_or(base);
+                candidates.push(format!("{}PERP", base));
+                candidates.push(format!("{}USDT", base));
+            }
+        }
+    } else if let Some(base) = compact.strip_suffix("USDC").filter(|b| !b.is_empty()) {
+        candidates.push(format!("{}PERP", base));
+        candidates.push(compact.clone());
+    } else {
+        candidates.push(compact);
+    }
+    
+    candidates.retain(|c| c != symbol);
+    candidates.dedup();
+    candidates
+}
+
 /// Bybit API response structure
 #[derive(Debug, Deserialize)]
 struct BybitResponse<T> {
+    #[serde(default)]
     ret_code: i32,
+    #[serde(default)]
     ret_msg: String,
     result: Option<T>,
     #[serde(rename = "retCode")]
@@ -75,32 +275,266 @@ struct BybitOrderResult {
     order_link_id: Option<String>,
 }
 
+/// Bybit open order list result
+#[derive(Debug, Deserialize)]
+struct BybitOrderListResult {
+    list: Option<Vec<BybitOpenOrder>>,
+    #[serde(rename = "nextPageCursor", default)]
+    next_page_cursor: Option<String>,
+}
+
+/// Bybit order as returned by `/v5/order/realtime`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitOpenOrder {
+    order_id: String,
+    #[serde(default)]
+    order_link_id: String,
+    symbol: String,
+    side: String,
+    order_type: String,
+    #[serde(default)]
+    price: String,
+    qty: String,
+    #[serde(default)]
+    cum_exec_qty: String,
+    #[serde(default)]
+    avg_price: String,
+    order_status: String,
+    #[serde(default)]
+    created_time: String,
+}
+
+impl BybitOpenOrder {
+    fn into_summary(self) -> OrderSummary {
+        let status = match self.order_status.as_str() {
+            "PartiallyFilled" => OrderStatus::PartiallyFilled,
+            "Filled" => OrderStatus::Filled,
+            "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" => OrderStatus::Cancelled,
+            "Rejected" => OrderStatus::Rejected,
+            // New, Untriggered, Triggered, Active
+            _ => OrderStatus::Open,
+        };
+        
+        OrderSummary {
+            order_id: self.order_id,
+            client_order_id: (!self.order_link_id.is_empty()).then_some(self.order_link_id),
+            symbol: self.symbol,
+            side: if self.side == "Sell" { OrderSide::Sell } else { OrderSide::Buy },
+            order_type: self.order_type.to_lowercase(),
+            quantity: self.qty.parse().unwrap_or(0.0),
+            filled_quantity: self.cum_exec_qty.parse().unwrap_or(0.0),
+            price: self.price.parse().ok().filter(|p: &f64| *p > 0.0),
+            average_price: self.avg_price.parse().ok().filter(|p: &f64| *p > 0.0),
+            status,
+            timestamp: self.created_time.parse().unwrap_or(0),
+        }
+    }
+}
+
 /// Bybit position result
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct BybitPositionResult {
     list: Option<Vec<BybitPosition>>,
+    #[serde(default)]
+    next_page_cursor: Option<String>,
 }
 
 /// Bybit position
 #[derive(Debug, Clone, Deserialize)]
-struct BybitPosition {
+#[serde(rename_all = "camelCase")]
+pub struct BybitPosition {
     symbol: String,
     side: String,
     size: String,
+    #[serde(rename = "avgPrice")]
     entry_price: String,
     mark_price: String,
     #[serde(rename = "unrealisedPnl")]
     unrealized_pnl: Option<String>,
     leverage: String,
+    #[allow(dead_code)] // kept for callers of get_position
     position_value: Option<String>,
 }
 
+impl BybitPosition {
+    /// Normalize; `None` for the empty placeholder Bybit returns when flat
+    fn into_position(self) -> Option<Position> {
+        let size: f64 = self.size.parse().unwrap_or(0.0);
+        let side = match self.side.as_str() {
+            "Buy" => PositionSide::Long,
+            "Sell" => PositionSide::Short,
+            _ => return None,
+        };
+        if size <= 0.0 {
+            return None;
+        }
+        
+        Some(Position {
+            symbol: self.symbol,
+            side,
+            size,
+            entry_price: self.entry_price.parse().unwrap_or(0.0),
+            mark_price: self.mark_price.parse().unwrap_or(0.0),
+            unrealized_pnl: self.unrealized_pnl.and_then(|p| p.parse().ok()).unwrap_or(0.0),
+            leverage: self.leverage.parse().unwrap_or(0.0),
+        })
+    }
+}
+
+/// Bybit instrument list result
+#[derive(Debug, Deserialize)]
+struct BybitInstrumentResult {
+    list: Option<Vec<BybitInstrument>>,
+    #[serde(rename = "nextPageCursor", default)]
+    next_page_cursor: Option<String>,
+}
+
+/// Bybit instrument as returned by `/v5/market/instruments-info`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitInstrument {
+    symbol: String,
+    /// `Trading`, `PreLaunch`, `Delivering`, `Closed`, ...
+    #[serde(default)]
+    status: Option<String>,
+    lot_size_filter: BybitLotSizeFilter,
+    price_filter: BybitPriceFilter,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitLotSizeFilter {
+    /// Derivatives quantity step
+    qty_step: Option<String>,
+    /// Spot quantity step
+    base_precision: Option<String>,
+    #[serde(default)]
+    min_order_qty: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitPriceFilter {
+    tick_size: String,
+}
+
+/// `/v5/account/wallet-balance` result
+#[derive(Debug, Deserialize)]
+struct BybitWalletResult {
+    list: Option<Vec<BybitWallet>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitWallet {
+    #[serde(default)]
+    coin: Vec<BybitCoinBalance>,
+}
+
+/// Per-coin balance; numeric fields are strings and may be empty
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitCoinBalance {
+    coin: String,
+    #[serde(default)]
+    wallet_balance: String,
+    #[serde(default)]
+    locked: String,
+}
+
+/// `/v5/position/closed-pnl` result
+#[derive(Debug, Deserialize)]
+struct BybitClosedPnlResult {
+    list: Option<Vec<BybitClosedPnl>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitClosedPnl {
+    symbol: String,
+    closed_size: String,
+    closed_pnl: String,
+    updated_time: String,
+}
+
+/// `/v5/execution/list` result
+#[derive(Debug, Deserialize)]
+struct BybitExecutionResult {
+    list: Option<Vec<BybitExecution>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitExecution {
+    symbol: String,
+    order_id: String,
+    exec_id: String,
+    side: String,
+    exec_price: String,
+    exec_qty: String,
+    #[serde(default)]
+    exec_fee: String,
+    #[serde(default)]
+    fee_currency: Option<String>,
+    exec_time: String,
+}
+
+/// Per-entry status of a batch request, in request order (`retExtInfo`)
+#[derive(Debug, Deserialize)]
+struct BybitBatchStatus {
+    #[serde(rename = "retExtInfo")]
+    ret_ext_info: Option<BybitBatchStatusList>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitBatchStatusList {
+    #[serde(default)]
+    list: Vec<BybitBatchEntryStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitBatchEntryStatus {
+    code: i32,
+    #[serde(default)]
+    msg: String,
+}
+
+/// `/v5/market/tickers` result
+#[derive(Debug, Deserialize)]
+struct BybitTickerResult {
+    list: Option<Vec<BybitTicker>>,
+}
+
+/// Ticker fields are camelCase strings (`lastPrice`, `bid1Price`, `volume24h`)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitTicker {
+    last_price: String,
+    bid1_price: String,
+    ask1_price: String,
+    #[serde(rename = "volume24h")]
+    volume_24h: Option<String>,
+    /// Derivatives only; empty or absent for spot
+    funding_rate: Option<String>,
+    next_funding_time: Option<String>,
+}
+
 /// Bybit Plugin implementation
 pub struct BybitPlugin {
     name: String,
     config: Arc<RwLock<Option<BybitConfig>>>,
     client: Client,
     base_url: String,
+    maintenance: MaintenanceState,
+    /// Offset to the exchange clock for signed timestamps
+    time_sync: TimeSync,
+    /// Log each request's prehash and signature (`DEBUG_SIGN=true`)
+    debug_sign: bool,
+    /// Trading instrument names, loaded on the first symbol that needs remapping
+    instruments: Arc<RwLock<Option<Arc<HashSet<String>>>>>,
+    /// Category each symbol was found in (`auto_category`)
+    categories: Arc<RwLock<HashMap<String, String>>>,
 }
 
 impl BybitPlugin {
@@ -113,11 +547,281 @@ impl BybitPlugin {
                 .build()
                 .expect("Failed to create HTTP client"),
             base_url: "https://api.bybit.com".to_string(),
+            maintenance: MaintenanceState::default(),
+            time_sync: TimeSync::default(),
+            debug_sign: super::debug_sign_enabled(),
+            instruments: Arc::new(RwLock::new(None)),
+            categories: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+    
+    /// Detect a maintenance response (HTTP 503 or a maintenance retCode)
+    ///
+    /// Opens the maintenance window so subsequent calls fail fast.
+    fn check_maintenance(&self, status: reqwest::StatusCode, text: &str) -> Option<ExecutionError> {
+        let ret_code = serde_json::from_str::<serde_json::Value>(text)
+            .ok()
+            .and_then(|v| v.get("retCode").or_else(|| v.get("ret_code")).and_then(|c| c.as_i64()));
+        
+        let in_maintenance = status == reqwest::StatusCode::SERVICE_UNAVAILABLE
+            || ret_code.is_some_and(|c| MAINTENANCE_RET_CODES.contains(&c));
+        if !in_maintenance {
+            return None;
+        }
+        
+        self.maintenance.enter(MaintenanceState::DEFAULT_WINDOW);
+        tracing::warn!(plugin = %self.name, status = %status, ret_code = ?ret_code, "Bybit maintenance detected");
+        Some(MaintenanceState::error(&self.name, &format!("HTTP {}, retCode {:?}", status, ret_code)))
+    }
+    
+    /// Classify the response of an authenticated ping
+    ///
+    /// Any retCode other than an auth failure means the signature was accepted.
+    fn classify_auth_response(status: reqwest::StatusCode, text: &str) -> HealthStatus {
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return HealthStatus::AuthFailed;
+        }
+        
+        let ret_code = serde_json::from_str::<serde_json::Value>(text)
+            .ok()
+            .and_then(|v| v.get("retCode").or_else(|| v.get("ret_code")).and_then(|c| c.as_i64()));
+        
+        match ret_code {
+            Some(code) if AUTH_FAILED_RET_CODES.contains(&code) => HealthStatus::AuthFailed,
+            Some(_) if status.is_success() => HealthStatus::Healthy,
+            _ => HealthStatus::Unreachable,
+        }
+    }
+    
+    /// Map a rejected post-only order to [`ExecutionError::Exchange`] so callers can reprice
+    fn post_only_rejection(ret_code: i32, ret_msg: &str) -> Option<ExecutionError> {
+        if !POST_ONLY_RET_CODES.contains(&ret_code) && !super::is_post_only_message(ret_msg) {
+            return None;
+        }
+        Some(ExecutionError::post_only_would_cross("Bybit", &format!("{} - {}", ret_code, ret_msg)))
+    }
+    
+    /// Verify the API credentials against the wallet balance endpoint
+    async fn auth_ping(&self, config: &BybitConfig) -> HealthStatus {
+        let endpoint = format!("{}/v5/account/wallet-balance", self.base_url);
+        let params = serde_json::json!({ "accountType": "UNIFIED" });
+        
+        let query_string = match serde_qs::to_string(&params) {
+            Ok(q) => q,
+            Err(_) => return HealthStatus::Unreachable,
+        };
+        let headers = match self.create_headers_get(&config.api_key, &config.api_secret, RECV_WINDOW_MS, &query_string).await {
+            Ok(h) => h,
+            // Credentials that can't even be encoded as headers are unusable
+            Err(_) => return HealthStatus::AuthFailed,
+        };
+        
+        let response = match self.client.get(&endpoint).headers(headers).query(&params).send().await {
+            Ok(r) => r,
+            Err(_) => return HealthStatus::Unreachable,
+        };
+        
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        
+        if self.check_maintenance(status, &text).is_some() {
+            return HealthStatus::Unreachable;
+        }
+        
+        let health = Self::classify_auth_response(status, &text);
+        if health == HealthStatus::AuthFailed {
+            tracing::warn!(plugin = %self.name, status = %status, body = %text, "Bybit rejected API credentials");
+        }
+        health
+    }
+    
+    /// Apply the configured quote-currency aliases to a symbol
+    fn normalize_symbol(&self, config: &BybitConfig, symbol: &str) -> String {
+        apply_quote_alias(&self.name, symbol, &config.quote_aliases)
+    }
+    
+    /// Apply quote aliases, then map perpetual spellings onto a listed instrument
+    ///
+    /// Symbols that are already listed, or that match no listed candidate,
+    /// pass through; an unavailable instrument list is not fatal.
+    async fn resolve_symbol(&self, config: &BybitConfig, symbol: &str) -> String {
+        let normalized = self.normalize_symbol(config, symbol);
+        let candidates = perpetual_candidates(&normalized);
+        if candidates.is_empty() {
+            return normalized;
+        }
+        
+        let known = match self.known_instruments(config).await {
+            Ok(known) => known,
+            Err(e) => {
+                tracing::warn!(plugin = %self.name, symbol = %normalized, error = %e, "Bybit instrument list unavailable, symbol not remapped");
+                return normalized;
+            }
+        };
+        match Self::match_instrument(&normalized, candidates, &known) {
+            Some(instrument) => {
+                tracing::info!(plugin = %self.name, from = %normalized, to = %instrument, "Remapped Bybit instrument");
+                instrument
+            }
+            None => normalized,
+        }
+    }
+    
+    /// First candidate that is a listed instrument, unless `symbol` itself is
+    fn match_instrument(symbol: &str, candidates: Vec<String>, known: &HashSet<String>) -> Option<String> {
+        if known.contains(symbol) {
+            return None;
+        }
+        candidates.into_iter().find(|c| known.contains(c))
+    }
+    
+    /// Listed instruments for the configured category, fetched once
+    async fn known_instruments(&self, config: &BybitConfig) -> Result<Arc<HashSet<String>>, Box<dyn Error + Send + Sync>> {
+        if let Some(known) = self.instruments.read().await.as_ref() {
+            return Ok(known.clone());
+        }
+        
+        let known: Arc<HashSet<String>> = Arc::new(self.fetch_symbols(config).await?.into_iter().collect());
+        *self.instruments.write().await = Some(known.clone());
+        Ok(known)
+    }
+    
+    /// Refuse a symbol missing from the configured category's instrument list
+    ///
+    /// An unavailable list is not fatal; the exchange has the final say.
+    async fn check_listed(&self, config: &BybitConfig, symbol: &str) -> Result<(), ExecutionError> {
+        let known = match self.known_instruments(config).await {
+            Ok(known) => known,
+            Err(e) => {
+                tracing::debug!(plugin = %self.name, symbol = %symbol, error = %e, "Bybit instrument list unavailable, symbol not validated");
+                return Ok(());
+            }
+        };
+        match known.contains(symbol) {
+            true => Ok(()),
+            false => Err(ExecutionError::invalid_symbol(symbol, known.iter())),
+        }
+    }
+    
+    /// The config to use for `symbol`: with `auto_category`, its category is
+    /// replaced by the one the symbol is listed in
+    async fn symbol_config<'a>(&self, config: &'a BybitConfig, symbol: &str) -> Cow<'a, BybitConfig> {
+        if !config.auto_category {
+            return Cow::Borrowed(config);
+        }
+        match self.detect_category(config, symbol).await {
+            Some(category) if category != config.category => Cow::Owned(BybitConfig { category, ..config.clone() }),
+            _ => Cow::Borrowed(config),
+        }
+    }
+    
+    /// Category listing `symbol`, searching the configured one first
+    ///
+    /// Found categories are cached; a symbol listed nowhere (or a failed
+    /// lookup) is asked about again next time.
+    async fn detect_category(&self, config: &BybitConfig, symbol: &str) -> Option<String> {
+        if let Some(category) = self.categories.read().await.get(symbol) {
+            return Some(category.clone());
+        }
+        
+        let others = CATEGORIES.iter().copied().filter(|c| *c != config.category);
+        for category in std::iter::once(config.category.as_str()).chain(others) {
+            match self.is_listed(category, symbol).await {
+                Ok(true) => {
+                    tracing::info!(plugin = %self.name, symbol = %symbol, category = %category, "Detected Bybit category");
+                    self.categories.write().await.insert(symbol.to_string(), category.to_string());
+                    return Some(category.to_string());
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!(plugin = %self.name, symbol = %symbol, category = %category, error = %e, "Bybit category lookup failed");
+                    return None;
+                }
+            }
+        }
+        
+        tracing::warn!(plugin = %self.name, symbol = %symbol, fallback = %config.category, "Bybit symbol not listed in any category");
+        None
+    }
+    
+    /// Whether `symbol` is a trading instrument in `category`
+    async fn is_listed(&self, category: &str, symbol: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let endpoint = format!("{}/v5/market/instruments-info", self.base_url);
+        let params = serde_json::json!({
+            "category": category,
+            "symbol": symbol,
+        });
+        
+        // Public endpoint, no authentication required
+        let response = self.client
+            .get(&endpoint)
+            .query(&params)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("Bybit", status, &text));
+        }
+        
+        let (symbols, _) = Self::parse_symbol_page(&text)?;
+        Ok(symbols.iter().any(|s| s == symbol))
+    }
+    
+    /// Page through `/v5/market/instruments-info` for the configured category
+    async fn fetch_symbols(&self, config: &BybitConfig) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let endpoint = format!("{}/v5/market/instruments-info", self.base_url);
+        let mut symbols = Vec::new();
+        let mut cursor: Option<String> = None;
+        
+        // Derivatives are paged; spot returns everything in one response
+        for _ in 0..MAX_INSTRUMENT_PAGES {
+            let mut params = serde_json::json!({
+                "category": config.category,
+                "limit": INSTRUMENT_PAGE_LIMIT,
+            });
+            if let Some(cursor) = &cursor {
+                params["cursor"] = serde_json::json!(cursor);
+            }
+            
+            // Public endpoint, no authentication required
+            let response = self.client
+                .get(&endpoint)
+                .query(&params)
+                .send()
+                .await?;
+            
+            let status = response.status();
+            let text = response.text().await?;
+            
+            if let Some(err) = self.check_maintenance(status, &text) {
+                return Err(err.into());
+            }
+            
+            if !status.is_success() {
+                return Err(super::http_error("Bybit", status, &text));
+            }
+            
+            let (page, next) = Self::parse_symbol_page(&text)?;
+            symbols.extend(page);
+            match next {
+                Some(next) => cursor = Some(next),
+                None => return Ok(symbols),
+            }
         }
+        
+        tracing::warn!(plugin = %self.name, pages = MAX_INSTRUMENT_PAGES, "Bybit instrument list truncated");
+        Ok(symbols)
     }
     
-    /// Get base URL (testnet or mainnet)
-    fn get_base_url(&self, testnet: bool) -> &str {
+    /// Mainnet or testnet host
+    fn network_url(testnet: bool) -> &'static str {
         if testnet {
             "https://api-testnet.bybit.com"
         } else {
@@ -125,6 +829,15 @@ impl BybitPlugin {
         }
     }
     
+    /// Base URL for one call: the configured endpoint, unless `testnet`
+    /// selects the other network for just this call
+    fn get_base_url(&self, config: &BybitConfig, testnet: Option<bool>) -> &str {
+        match testnet {
+            Some(testnet) if testnet != config.testnet => Self::network_url(testnet),
+            _ => &self.base_url,
+        }
+    }
+    
     /// Generate HMAC-SHA256 signature for Bybit API
     fn generate_signature(secret: &str, message: &str) -> String {
         use hmac::{Hmac, Mac};
@@ -139,6 +852,21 @@ impl BybitPlugin {
         hex::encode(result.into_bytes())
     }
     
+    /// Timestamp for a signed request, re-syncing with the exchange clock when due
+    async fn signing_timestamp(&self) -> i64 {
+        if self.time_sync.start_refresh() {
+            match self.ping().await {
+                Ok(PingResult { clock_skew_ms: Some(skew), .. }) => {
+                    tracing::debug!(plugin = %self.name, offset_ms = skew, "Bybit clock offset synced");
+                    self.time_sync.record(&self.name, skew);
+                }
+                Ok(_) => tracing::warn!(plugin = %self.name, "Bybit server time missing, keeping clock offset"),
+                Err(e) => tracing::warn!(plugin = %self.name, error = %e, "Bybit clock sync failed, keeping clock offset"),
+            }
+        }
+        self.time_sync.now_millis()
+    }
+    
     /// Create authenticated request headers for POST requests (JSON body)
     async fn create_headers_post(
         &self,
@@ -147,14 +875,14 @@ impl BybitPlugin {
         recv_window: u64,
         json_body: &str,
     ) -> Result<reqwest::header::HeaderMap, Box<dyn Error + Send + Sync>> {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
+        let timestamp = self.signing_timestamp().await;
         
         // For POST: timestamp + api_key + recv_window + json_body
         let message = format!("{}{}{}{}", timestamp, api_key, recv_window, json_body);
         let signature = Self::generate_signature(api_secret, &message);
+        if self.debug_sign {
+            super::log_signed_request(&self.name, &timestamp.to_string(), &message, &signature, api_secret);
+        }
         
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert("X-BAPI-API-KEY", api_key.parse()?);
@@ -175,14 +903,14 @@ impl BybitPlugin {
         recv_window: u64,
         query_string: &str,
     ) -> Result<reqwest::header::HeaderMap, Box<dyn Error + Send + Sync>> {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
+        let timestamp = self.signing_timestamp().await;
         
         // For GET: timestamp + api_key + recv_window + query_string
         let message = format!("{}{}{}{}", timestamp, api_key, recv_window, query_string);
         let signature = Self::generate_signature(api_secret, &message);
+        if self.debug_sign {
+            super::log_signed_request(&self.name, &timestamp.to_string(), &message, &signature, api_secret);
+        }
         
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert("X-BAPI-API-KEY", api_key.parse()?);
@@ -194,32 +922,25 @@ impl BybitPlugin {
         Ok(headers)
     }
     
-    /// Set leverage for a symbol (Bybit-specific)
-    pub async fn set_leverage(
-        &self,
-        symbol: &str,
-        leverage: i32,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+    /// Switch the account's position mode for USDT-settled contracts (Bybit-specific)
+    pub async fn set_position_mode(&self, mode: PositionMode) -> Result<(), Box<dyn Error + Send + Sync>> {
         let config = self.config.read().await;
         let config = config.as_ref()
             .ok_or("Plugin not initialized")?;
         
-        let base_url = self.get_base_url(config.testnet);
-        let endpoint = format!("{}/v5/position/set-leverage", base_url);
+        let endpoint = format!("{}/v5/position/switch-mode", self.base_url);
         
         let params = serde_json::json!({
             "category": config.category,
-            "symbol": symbol,
-            "buyLeverage": leverage.to_string(),
-            "sellLeverage": leverage.to_string(),
+            "coin": "USDT",
+            "mode": mode.switch_mode_value(),
         });
         
-        // For POST requests, signature is calculated from JSON body
         let json_body = serde_json::to_string(&params)?;
         let headers = self.create_headers_post(
             &config.api_key,
             &config.api_secret,
-            5000,
+            RECV_WINDOW_MS,
             &json_body,
         ).await?;
         
@@ -233,118 +954,53 @@ impl BybitPlugin {
         let status = response.status();
         let text = response.text().await?;
         
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
         if !status.is_success() {
-            return Err(format!("Bybit API error ({}): {}", status, text).into());
+            return Err(super::http_error("Bybit", status, &text));
         }
         
-        let bybit_resp: BybitResponse<serde_json::Value> = serde_json::from_str(&text)?;
+        let bybit_resp: BybitResponse<serde_json::Value> = super::parse_response("Bybit", &text)?;
         
-        if !bybit_resp.is_success() {
+        if !bybit_resp.is_success() && bybit_resp.ret_code() != POSITION_MODE_NOT_MODIFIED {
             return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
         }
         
-        tracing::info!(plugin = %self.name, symbol = %symbol, leverage = %leverage, "Leverage set successfully");
+        tracing::info!(plugin = %self.name, mode = ?mode, "Position mode set");
         Ok(())
     }
     
-    /// Get positions for a symbol
-    pub async fn get_position(
-        &self,
-        symbol: &str,
-    ) -> Result<Option<BybitPosition>, Box<dyn Error + Send + Sync>> {
-        let config = self.config.read().await;
-        let config = config.as_ref()
-            .ok_or("Plugin not initialized")?;
-        
-        let base_url = self.get_base_url(config.testnet);
-        let endpoint = format!("{}/v5/position/list", base_url);
-        
-        let params = serde_json::json!({
-            "category": config.category,
-            "symbol": symbol,
-        });
-        
-        let query_string = serde_qs::to_string(&params)?;
-        let headers = self.create_headers_get(
-            &config.api_key,
-            &config.api_secret,
-            5000,
-            &query_string,
-        ).await?;
-        
-        let response = self.client
-            .get(&endpoint)
-            .headers(headers)
-            .query(&params)
-            .send()
-            .await?;
-        
-        let status = response.status();
-        let text = response.text().await?;
-        
-        if !status.is_success() {
-            return Err(format!("Bybit API error ({}): {}", status, text).into());
-        }
-        
-        let bybit_resp: BybitResponse<BybitPositionResult> = serde_json::from_str(&text)?;
-        
-        if !bybit_resp.is_success() {
-            return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
-        }
-        
-        if let Some(result) = bybit_resp.result {
-            if let Some(list) = result.list {
-                if let Some(position) = list.first() {
-                    return Ok(Some(position.clone()));
-                }
-            }
+    /// Refuse quote-denominated quantities outside spot market orders, the
+    /// only orders Bybit can size by `marketUnit=quoteCoin`
+    fn check_quantity_type(&self, config: &BybitConfig, order: &Order) -> Result<(), ExecutionError> {
+        let spot_market = config.category == "spot" && matches!(order.order_type, OrderType::Market);
+        match order.quantity_type {
+            QuantityType::Quote if !spot_market => Err(ExecutionError::Unsupported(format!(
+                "{} accepts quote-denominated quantities only on spot market orders", self.name
+            ))),
+            _ => Ok(()),
         }
-        
-        Ok(None)
     }
-}
-
-#[async_trait]
-impl ExecutionPlugin for BybitPlugin {
-    async fn init(&mut self, config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let bybit_config: BybitConfig = serde_json::from_value(config)?;
-        
-        tracing::info!(
-            plugin = %self.name,
-            testnet = %bybit_config.testnet,
-            category = %bybit_config.category,
-            "Initializing Bybit plugin"
-        );
-        
-        // Validate API keys are not empty
-        if bybit_config.api_key.is_empty() || bybit_config.api_secret.is_empty() {
-            return Err("Bybit API key and secret must be provided".into());
+    
+    /// An order's own leverage, capped like the configured one; spot has none
+    fn order_leverage(&self, config: &BybitConfig, order: &Order) -> Result<Option<i32>, ExecutionError> {
+        match order.leverage {
+            Some(_) if config.category == "spot" => Err(ExecutionError::Unsupported(format!("{} spot orders carry no leverage", self.name))),
+            Some(leverage) => config.leverage_limit.apply(&self.name, leverage).map(Some),
+            None => Ok(None),
         }
-        
-        // Update base URL
-        self.base_url = self.get_base_url(bybit_config.testnet).to_string();
-        
-        // Test connection with a simple API call (non-blocking, log warning if fails)
-        // We'll do this on first order execution
-        
-        *self.config.write().await = Some(bybit_config);
-        
-        tracing::info!(plugin = %self.name, "Bybit plugin initialized successfully");
-        Ok(())
     }
     
-    async fn execute_order(
-        &self,
-        order: Order,
-    ) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
-        let config = self.config.read().await;
-        let config = config.as_ref()
-            .ok_or("Plugin not initialized")?;
-        
-        let base_url = self.get_base_url(config.testnet);
-        let endpoint = format!("{}/v5/order/create", base_url);
-        
-        // Convert Order to Bybit format
+    /// Build the `/v5/order/create` body for an order
+    ///
+    /// Spot orders carry no `positionIdx`, `leverage` or `reduceOnly` (Bybit
+    /// rejects them); spot market orders state their `marketUnit` explicitly,
+    /// quote coin for quote-denominated orders.
+    /// Stop-limits are conditional limit orders with a `triggerPrice`. A market
+    /// order's `max_slippage_pct` maps to Bybit's percent slippage tolerance.
+    fn build_order_params(&self, config: &BybitConfig, order: &Order) -> serde_json::Value {
         let side = match order.side {
             OrderSide::Buy => "Buy",
             OrderSide::Sell => "Sell",
@@ -352,28 +1008,61 @@ impl ExecutionPlugin for BybitPlugin {
         
         let order_type = match order.order_type {
             OrderType::Market => "Market",
-            OrderType::Limit => "Limit",
+            OrderType::Limit | OrderType::StopLimit => "Limit",
             OrderType::Stop => "Stop",
-            OrderType::StopLimit => "StopLimit",
             OrderType::TakeProfit => "TakeProfit",
             OrderType::StopLoss => "StopLoss",
         };
         
-        // Build order parameters
         let mut params = serde_json::json!({
             "category": config.category,
-            "symbol": order.symbol,
+            "symbol": self.normalize_symbol(config, &order.symbol),
             "side": side,
             "orderType": order_type,
             "qty": format!("{}", order.quantity),
-            "positionIdx": 0, // One-way mode
+            "orderLinkId": client_order_id(order, ORDER_LINK_ID_MAX_LEN),
         });
         
+        if config.category == "spot" {
+            if matches!(order.order_type, OrderType::Market) {
+                let unit = match order.quantity_type {
+                    QuantityType::Quote => MarketUnit::QuoteCoin,
+                    QuantityType::Base => config.spot_market_unit,
+                };
+                params["marketUnit"] = serde_json::json!(unit);
+            }
+        } else {
+            params["positionIdx"] = serde_json::json!(config.position_mode.unwrap_or_default().position_idx(&order.side, order.reduce_only));
+            params["leverage"] = serde_json::json!(format!("{}", order.leverage.unwrap_or(config.leverage)));
+            if order.reduce_only {
+                params["reduceOnly"] = serde_json::json!(true);
+            }
+        }
+        
         // Add price for limit orders
         if let Some(price) = order.price {
             params["price"] = serde_json::json!(format!("{}", price));
         }
         
+        if order.post_only {
+            params["timeInForce"] = serde_json::json!("PostOnly");
+        }
+        
+        if let (OrderType::Market, Some(pct)) = (&order.order_type, order.max_slippage_pct) {
+            params["slippageToleranceType"] = serde_json::json!("Percent");
+            params["slippageTolerance"] = serde_json::json!(format!("{}", pct));
+        }
+        
+        if let (OrderType::StopLimit, Some(trigger)) = (&order.order_type, order.trigger_price) {
+            params["triggerPrice"] = serde_json::json!(format!("{}", trigger));
+            if config.category == "spot" {
+                params["orderFilter"] = serde_json::json!("StopOrder");
+            } else {
+                // Buy stops trigger on a rise to the trigger price (1), sell stops on a fall (2)
+                params["triggerDirection"] = serde_json::json!(if order.side == OrderSide::Buy { 1 } else { 2 });
+            }
+        }
+        
         // Add stop-loss and take-profit if provided
         if let Some(stop_loss) = order.stop_loss {
             params["stopLoss"] = serde_json::json!(format!("{}", stop_loss));
@@ -383,122 +1072,136 @@ impl ExecutionPlugin for BybitPlugin {
             params["takeProfit"] = serde_json::json!(format!("{}", take_profit));
         }
         
-        // Set leverage from config (if not already set)
-        if let Some(_lev) = params.get("leverage") {
-            // Leverage already in params
-        } else {
-            params["leverage"] = serde_json::json!(format!("{}", config.leverage));
+        merge_extra_params(&self.name, &mut params, order);
+        params
+    }
+    
+    /// The `/v5/order/create` request for an order
+    fn build_order_request(&self, config: &BybitConfig, order: &Order) -> PreparedRequest {
+        let path = "/v5/order/create".to_string();
+        PreparedRequest {
+            method: "POST".to_string(),
+            url: format!("{}{}", self.get_base_url(config, order.testnet), path),
+            path,
+            body: self.build_order_params(config, order),
         }
-        
-        // For POST requests, signature is calculated from JSON body
-        let json_body = serde_json::to_string(&params)?;
-        let headers = self.create_headers_post(
+    }
+    
+    /// Query `/v5/order/realtime`, which by id also returns recently filled and cancelled orders
+    async fn query_orders(
+        &self,
+        config: &BybitConfig,
+        base_url: &str,
+        params: &serde_json::Value,
+    ) -> Result<Vec<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        Ok(self.query_order_page(config, base_url, params).await?.orders)
+    }
+    
+    /// Query one page of `/v5/order/realtime`, keeping Bybit's cursor for the next
+    async fn query_order_page(
+        &self,
+        config: &BybitConfig,
+        base_url: &str,
+        params: &serde_json::Value,
+    ) -> Result<OrderPage, Box<dyn Error + Send + Sync>> {
+        let endpoint = format!("{}/v5/order/realtime", base_url);
+        let query_string = serde_qs::to_string(params)?;
+        let headers = self.create_headers_get(
             &config.api_key,
             &config.api_secret,
-            5000,
-            &json_body,
+            RECV_WINDOW_MS,
+            &query_string,
         ).await?;
         
         let response = self.client
-            .post(&endpoint)
+            .get(&endpoint)
             .headers(headers)
-            .json(&params)
+            .query(params)
             .send()
             .await?;
         
         let status = response.status();
         let text = response.text().await?;
         
-        if !status.is_success() {
-            return Ok(ExecutionResult {
-                success: false,
-                order_id: None,
-                filled_quantity: 0.0,
-                average_price: 0.0,
-                error: Some(format!("HTTP {}: {}", status, text)),
-                timestamp: SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as i64,
-            });
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
         }
         
-        let bybit_resp: BybitResponse<BybitOrderResult> = serde_json::from_str(&text)?;
-        
-        if !bybit_resp.is_success() {
-            return Ok(ExecutionResult {
-                success: false,
-                order_id: None,
-                filled_quantity: 0.0,
-                average_price: 0.0,
-                error: Some(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg())),
-                timestamp: SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as i64,
-            });
+        if !status.is_success() {
+            return Err(super::http_error("Bybit", status, &text));
         }
         
-        // Extract order ID from result
-        let order_id = bybit_resp.result.as_ref()
-            .and_then(|r| r.order_id.clone())
-            .or_else(|| bybit_resp.result.as_ref().and_then(|r| r.order_link_id.clone()));
-        
-        tracing::info!(
-            plugin = %self.name,
-            symbol = %order.symbol,
-            side = %side,
-            order_id = ?order_id,
-            "Order placed successfully"
-        );
-        
-        // For market orders, we assume immediate fill
-        // For limit orders, filled_quantity will be 0 until filled
-        let filled_quantity = match order.order_type {
-            OrderType::Market => order.quantity,
-            _ => 0.0,
-        };
-        
-        // Get current market price as average_price (for market orders)
-        let average_price = match order.order_type {
-            OrderType::Market => {
-                // Try to fetch current price
-                if let Ok(market_data) = self.fetch_data(&order.symbol).await {
-                    market_data.last
-                } else {
-                    order.price.unwrap_or(0.0)
-                }
-            },
-            _ => order.price.unwrap_or(0.0),
+        Self::parse_order_page(&text)
+    }
+    
+    /// Settle an order whose create request timed out
+    ///
+    /// The request may still have reached Bybit, so the order is looked up by
+    /// the `orderLinkId` sent with it, on the host it was sent to, until the
+    /// receive window has passed; an order that hasn't appeared by then is
+    /// reported as outcome unknown.
+    async fn reconcile_timeout(
+        &self,
+        config: &BybitConfig,
+        order: &Order,
+        request: &PreparedRequest,
+        sent: Instant,
+        timeout: Box<dyn Error + Send + Sync>,
+    ) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
+        let Some(link_id) = request.body["orderLinkId"].as_str() else {
+            return Err(timeout);
         };
+        tracing::warn!(plugin = %self.name, order_link_id = %link_id, "order_create_timed_out_reconciling");
         
-        Ok(ExecutionResult {
-            success: true,
-            order_id,
-            filled_quantity,
-            average_price,
-            error: None,
-            timestamp: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as i64,
-        })
+        let params = serde_json::json!({
+            "category": config.category,
+            "symbol": self.normalize_symbol(config, &order.symbol),
+            "orderLinkId": link_id,
+        });
+        let base_url = request.base_url().unwrap_or(&self.base_url);
+        let window = Duration::from_millis(RECV_WINDOW_MS);
+        Ok(super::reconcile_by_client_id(&self.name, link_id, sent, window, || async {
+            Ok(self.query_orders(config, base_url, &params).await?.into_iter().next())
+        }).await?)
     }
     
-    async fn fetch_data(&self, symbol: &str) -> Result<MarketData, Box<dyn Error + Send + Sync>> {
-        let config = self.config.read().await;
-        let config = config.as_ref()
-            .ok_or("Plugin not initialized")?;
+    /// Sign and submit a prepared request, returning the status and raw body
+    async fn send(
+        &self,
+        config: &BybitConfig,
+        request: &PreparedRequest,
+    ) -> Result<(reqwest::StatusCode, String), Box<dyn Error + Send + Sync>> {
+        // For POST requests, signature is calculated from JSON body
+        let json_body = serde_json::to_string(&request.body)?;
+        let headers = self.create_headers_post(
+            &config.api_key,
+            &config.api_secret,
+            RECV_WINDOW_MS,
+            &json_body,
+        ).await?;
         
-        let base_url = self.get_base_url(config.testnet);
-        let endpoint = format!("{}/v5/market/tickers", base_url);
+        let response = self.client
+            .post(&request.url)
+            .headers(headers)
+            .body(json_body)
+            .send()
+            .await?;
         
+        let status = response.status();
+        let text = response.text().await?;
+        Ok((status, text))
+    }
+    
+    /// Raw `/v5/market/tickers` response for one symbol (public, unsigned)
+    async fn get_ticker(&self, config: &BybitConfig, symbol: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let endpoint = format!("{}/v5/market/tickers", self.base_url);
+        let symbol = self.resolve_symbol(config, symbol).await;
+        let config = self.symbol_config(config, &symbol).await;
         let params = serde_json::json!({
             "category": config.category,
             "symbol": symbol,
         });
         
-        // Public endpoint, no authentication required
         let response = self.client
             .get(&endpoint)
             .query(&params)
@@ -508,75 +1211,2387 @@ impl ExecutionPlugin for BybitPlugin {
         let status = response.status();
         let text = response.text().await?;
         
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
         if !status.is_success() {
-            return Err(format!("Bybit API error ({}): {}", status, text).into());
+            return Err(super::http_error("Bybit", status, &text));
         }
         
-        #[derive(Deserialize)]
-        struct TickerResult {
-            list: Option<Vec<Ticker>>,
+        Ok(text)
+    }
+    
+    /// Parse the first entry of `/v5/market/tickers` into market data
+    fn parse_ticker(symbol: &str, text: &str) -> Result<MarketData, Box<dyn Error + Send + Sync>> {
+        let bybit_resp: BybitResponse<BybitTickerResult> = super::parse_response("Bybit", text)?;
+        
+        if !bybit_resp.is_success() {
+            return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
         }
         
-        #[derive(Deserialize)]
-        struct Ticker {
-            last_price: String,
-            bid1_price: String,
-            ask1_price: String,
-            volume24h: Option<String>,
+        let Some(ticker) = bybit_resp.result.and_then(|r| r.list).and_then(|list| list.into_iter().next()) else {
+            return Err(format!("No market data found for symbol: {}", symbol).into());
+        };
+        
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: ticker.bid1_price.parse::<f64>()?,
+            ask: ticker.ask1_price.parse::<f64>()?,
+            last: ticker.last_price.parse::<f64>()?,
+            volume: ticker.volume_24h
+                .as_deref()
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(0.0),
+            timestamp: now_millis(),
+            extra: serde_json::json!({}),
+        })
+    }
+    
+    /// Funding rate and next settlement from a derivatives `/v5/market/tickers` payload
+    fn parse_funding_rate(symbol: &str, text: &str) -> Result<FundingRate, Box<dyn Error + Send + Sync>> {
+        let bybit_resp: BybitResponse<BybitTickerResult> = super::parse_response("Bybit", text)?;
+        
+        if !bybit_resp.is_success() {
+            return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
         }
         
-        let bybit_resp: BybitResponse<TickerResult> = serde_json::from_str(&text)?;
+        let Some(ticker) = bybit_resp.result.and_then(|r| r.list).and_then(|list| list.into_iter().next()) else {
+            return Err(format!("No market data found for symbol: {}", symbol).into());
+        };
+        let (Some(rate), Some(next)) = (
+            ticker.funding_rate.filter(|r| !r.is_empty()),
+            ticker.next_funding_time.filter(|t| !t.is_empty()),
+        ) else {
+            return Err(format!("No funding rate for {} (not a perpetual?)", symbol).into());
+        };
+        
+        Ok(FundingRate {
+            symbol: symbol.to_string(),
+            rate: rate.parse::<f64>()?,
+            next_funding_time: next.parse::<i64>()?,
+            timestamp: now_millis(),
+        })
+    }
+    
+    /// Parse a `/v5/position/set-leverage` response; "leverage not modified" is a no-op
+    fn parse_set_leverage(text: &str) -> Result<LeverageChange, Box<dyn Error + Send + Sync>> {
+        let bybit_resp: BybitResponse<serde_json::Value> = super::parse_response("Bybit", text)?;
+        
+        match bybit_resp.ret_code() {
+            0 => Ok(LeverageChange::Changed),
+            LEVERAGE_NOT_MODIFIED => Ok(LeverageChange::Unchanged),
+            code => Err(format!("Bybit API error: {} - {}", code, bybit_resp.ret_msg()).into()),
+        }
+    }
+    
+    /// Parse `/v5/position/closed-pnl` into realized PnL records
+    fn parse_closed_pnl(text: &str) -> Result<Vec<ClosedPnl>, Box<dyn Error + Send + Sync>> {
+        let bybit_resp: BybitResponse<BybitClosedPnlResult> = super::parse_response("Bybit", text)?;
         
         if !bybit_resp.is_success() {
             return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
         }
         
-        if let Some(result) = bybit_resp.result {
-            if let Some(list) = result.list {
-                if let Some(ticker) = list.first() {
-                    let last = ticker.last_price.parse::<f64>()?;
-                    let bid = ticker.bid1_price.parse::<f64>()?;
-                    let ask = ticker.ask1_price.parse::<f64>()?;
-                    let volume = ticker.volume24h
-                        .as_ref()
-                        .and_then(|v| v.parse::<f64>().ok())
-                        .unwrap_or(0.0);
-                    
-                    return Ok(MarketData {
-                        symbol: symbol.to_string(),
-                        bid,
-                        ask,
-                        last,
-                        volume,
-                        timestamp: SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .unwrap()
-                            .as_millis() as i64,
-                        extra: serde_json::json!({}),
-                    });
-                }
-            }
+        bybit_resp.result
+            .and_then(|r| r.list)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| Ok(ClosedPnl {
+                quantity: c.closed_size.parse()?,
+                realized_pnl: c.closed_pnl.parse()?,
+                closed_at: c.updated_time.parse()?,
+                symbol: c.symbol,
+            }))
+            .collect()
+    }
+    
+    /// Parse `/v5/execution/list` into fills
+    fn parse_executions(text: &str) -> Result<Vec<Trade>, Box<dyn Error + Send + Sync>> {
+        let bybit_resp: BybitResponse<BybitExecutionResult> = super::parse_response("Bybit", text)?;
+        
+        if !bybit_resp.is_success() {
+            return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
         }
         
-        Err(format!("No market data found for symbol: {}", symbol).into())
+        bybit_resp.result
+            .and_then(|r| r.list)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| Ok(Trade {
+                side: if e.side == "Sell" { OrderSide::Sell } else { OrderSide::Buy },
+                price: e.exec_price.parse()?,
+                quantity: e.exec_qty.parse()?,
+                fee: e.exec_fee.parse().unwrap_or(0.0),
+                fee_currency: e.fee_currency.filter(|c| !c.is_empty()),
+                timestamp: e.exec_time.parse()?,
+                trade_id: e.exec_id,
+                order_id: e.order_id,
+                symbol: e.symbol,
+            }))
+            .collect()
     }
     
-    fn name(&self) -> &str {
-        &self.name
+    /// Parse `/v5/account/wallet-balance` into per-coin balances
+    fn parse_wallet_balance(text: &str) -> Result<Vec<Balance>, Box<dyn Error + Send + Sync>> {
+        let bybit_resp: BybitResponse<BybitWalletResult> = super::parse_response("Bybit", text)?;
+        
+        if !bybit_resp.is_success() {
+            return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
+        }
+        
+        Ok(bybit_resp.result
+            .and_then(|r| r.list)
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|wallet| wallet.coin)
+            .map(|coin| {
+                let total = coin.wallet_balance.parse().unwrap_or(0.0);
+                let locked = coin.locked.parse().unwrap_or(0.0);
+                Balance {
+                    asset: coin.coin,
+                    free: f64::max(total - locked, 0.0),
+                    locked,
+                    total,
+                }
+            })
+            .collect())
     }
     
-    async fn health_check(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
-        // Check if plugin is initialized
-        let config = self.config.read().await;
-        if config.is_none() {
-            return Ok(false);
+    /// Parse a `/v5/market/instruments-info` response body
+    fn parse_instrument(text: &str) -> Result<InstrumentInfo, Box<dyn Error + Send + Sync>> {
+        let bybit_resp: BybitResponse<BybitInstrumentResult> = super::parse_response("Bybit", text)?;
+        
+        if !bybit_resp.is_success() {
+            return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
+        }
+        
+        let instrument = bybit_resp.result
+            .and_then(|r| r.list)
+            .and_then(|l| l.into_iter().next())
+            .ok_or("Instrument not found")?;
+        
+        let lot = instrument.lot_size_filter;
+        let qty_step = lot.qty_step.or(lot.base_precision).ok_or("Instrument has no quantity step")?;
+        
+        Ok(InstrumentInfo {
+            symbol: instrument.symbol,
+            qty_step: qty_step.parse()?,
+            min_qty: lot.min_order_qty.parse().unwrap_or(0.0),
+            tick_size: instrument.price_filter.tick_size.parse()?,
+            rounding: InstrumentRounding::default(),
+        })
+    }
+    
+    /// Parse one page of `/v5/market/instruments-info`: trading symbols and the next cursor
+    fn parse_symbol_page(text: &str) -> Result<(Vec<String>, Option<String>), Box<dyn Error + Send + Sync>> {
+        let bybit_resp: BybitResponse<BybitInstrumentResult> = super::parse_response("Bybit", text)?;
+        
+        if !bybit_resp.is_success() {
+            return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
         }
         
-        // Try to fetch market data as a health check (public endpoint)
-        // This verifies connectivity to Bybit API
-        match self.fetch_data("BTCUSDT").await {
-            Ok(_) => Ok(true),
-            Err(_) => Ok(false), // Return false but don't error
+        let result = bybit_resp.result.ok_or("Missing instruments result")?;
+        let symbols = result.list
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|i| i.status.as_deref().is_none_or(|s| s == "Trading"))
+            .map(|i| i.symbol)
+            .collect();
+        Ok((symbols, result.next_page_cursor.filter(|c| !c.is_empty())))
+    }
+    
+    /// Parse a `/v5/order/cancel-batch` response: one outcome per entry of `orders`
+    fn parse_cancel_batch(orders: &[OrderRef], text: &str) -> Result<Vec<CancelOutcome>, Box<dyn Error + Send + Sync>> {
+        let bybit_resp: BybitResponse<serde_json::Value> = super::parse_response("Bybit", text)?;
+        
+        if !bybit_resp.is_success() {
+            return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
         }
+        
+        let statuses = super::parse_response::<BybitBatchStatus>("Bybit", text)?
+            .ret_ext_info
+            .map(|info| info.list)
+            .unwrap_or_default();
+        Ok(orders.iter()
+            .enumerate()
+            .map(|(i, order)| match statuses.get(i) {
+                Some(status) if status.code == 0 => CancelOutcome::cancelled(order.clone()),
+                Some(status) => CancelOutcome::failed(order.clone(), format!("Bybit API error: {} - {}", status.code, status.msg)),
+                None => CancelOutcome::failed(order.clone(), "Bybit returned no status for this order"),
+            })
+            .collect())
+    }
+    
+    /// Parse a `/v5/order/realtime` response body
+    fn parse_order_page(text: &str) -> Result<OrderPage, Box<dyn Error + Send + Sync>> {
+        let bybit_resp: BybitResponse<BybitOrderListResult> = super::parse_response("Bybit", text)?;
+        
+        if !bybit_resp.is_success() {
+            return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
+        }
+        
+        let (list, next_cursor) = bybit_resp.result
+            .map(|r| (r.list, r.next_page_cursor))
+            .unwrap_or_default();
+        Ok(OrderPage {
+            orders: list
+                .unwrap_or_default()
+                .into_iter()
+                .map(BybitOpenOrder::into_summary)
+                .collect(),
+            next_cursor: next_cursor.filter(|c| !c.is_empty()),
+        })
+    }
+    
+    /// Get positions for a symbol
+    #[allow(dead_code)] // public plugin API, not routed over HTTP
+    pub async fn get_position(
+        &self,
+        symbol: &str,
+    ) -> Result<Option<BybitPosition>, Box<dyn Error + Send + Sync>> {
+        Ok(self.fetch_positions(Some(symbol)).await?.into_iter().next())
+    }
+    
+    /// Fetch raw positions, for one symbol or all positions in the configured settle coins
+    ///
+    /// The unfiltered query pages through `/v5/position/list` at the maximum
+    /// page size, so a whole account usually comes back in one request.
+    async fn fetch_positions(
+        &self,
+        symbol: Option<&str>,
+    ) -> Result<Vec<BybitPosition>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let base_url = &self.base_url;
+        let endpoint = format!("{}/v5/position/list", base_url);
+        
+        let mut params = serde_json::json!({
+            "category": config.category,
+        });
+        let queries = match symbol {
+            Some(symbol) => {
+                params["symbol"] = serde_json::json!(self.normalize_symbol(config, symbol));
+                vec![params]
+            }
+            None => config.settle_coins.iter()
+                .map(|coin| {
+                    let mut params = params.clone();
+                    params["settleCoin"] = serde_json::json!(coin);
+                    params["limit"] = serde_json::json!(POSITION_PAGE_LIMIT);
+                    params
+                })
+                .collect(),
+        };
+        
+        let mut positions = Vec::new();
+        for mut params in queries {
+            for _ in 0..MAX_POSITION_PAGES {
+                let query_string = serde_qs::to_string(&params)?;
+                let headers = self.create_headers_get(
+                    &config.api_key,
+                    &config.api_secret,
+                    RECV_WINDOW_MS,
+                    &query_string,
+                ).await?;
+                
+                let response = self.client
+                    .get(&endpoint)
+                    .headers(headers)
+                    .query(&params)
+                    .send()
+                    .await?;
+                
+                let status = response.status();
+                let text = response.text().await?;
+                
+                if let Some(err) = self.check_maintenance(status, &text) {
+                    return Err(err.into());
+                }
+                
+                if !status.is_success() {
+                    return Err(super::http_error("Bybit", status, &text));
+                }
+                
+                let bybit_resp: BybitResponse<BybitPositionResult> = super::parse_response("Bybit", &text)?;
+                
+                if !bybit_resp.is_success() {
+                    return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
+                }
+                
+                let Some(result) = bybit_resp.result else { break };
+                positions.extend(result.list.unwrap_or_default());
+                
+                match result.next_page_cursor.filter(|c| !c.is_empty()) {
+                    Some(cursor) if symbol.is_none() => params["cursor"] = serde_json::json!(cursor),
+                    _ => break,
+                }
+            }
+        }
+        
+        Ok(positions)
+    }
+}
+
+#[async_trait]
+impl ExecutionPlugin for BybitPlugin {
+    async fn init(&mut self, config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut bybit_config: BybitConfig = serde_json::from_value(config)?;
+        
+        // Client certificate / private CA and custom headers, validated here rather than on the first request
+        if bybit_config.tls.is_configured() || bybit_config.client_headers.is_configured() {
+            self.client = bybit_config.tls.build_client(
+                bybit_config.client_headers.apply(Client::builder().timeout(std::time::Duration::from_secs(30)))?
+            )?;
+        }
+        
+        tracing::info!(
+            plugin = %self.name,
+            testnet = %bybit_config.testnet,
+            category = %bybit_config.category,
+            "Initializing Bybit plugin"
+        );
+        
+        // Validate API keys are not empty
+        if bybit_config.api_key.is_empty() || bybit_config.api_secret.is_empty() {
+            return Err("Bybit API key and secret must be provided".into());
+        }
+        
+        if bybit_config.settle_coins.is_empty() {
+            return Err("Bybit settle_coins must list at least one coin".into());
+        }
+        
+        // Every order carries this leverage, so the cap applies here once
+        bybit_config.leverage = bybit_config.leverage_limit.apply(&self.name, bybit_config.leverage)?;
+        
+        // Update base URL
+        self.base_url = match &bybit_config.base_url {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => Self::network_url(bybit_config.testnet).to_string(),
+        };
+        
+        // Test connection with a simple API call (non-blocking, log warning if fails)
+        // We'll do this on first order execution
+        
+        self.time_sync = TimeSync::new(
+            bybit_config.time_source,
+            std::time::Duration::from_secs(bybit_config.time_sync_secs),
+        );
+        
+        let position_mode = bybit_config.position_mode.filter(|_| bybit_config.category != "spot");
+        *self.config.write().await = Some(bybit_config);
+        *self.instruments.write().await = None;
+        self.categories.write().await.clear();
+        
+        // Position mode only applies to derivatives; failure is non-fatal but orders may be rejected
+        if let Some(mode) = position_mode {
+            if let Err(e) = self.set_position_mode(mode).await {
+                tracing::warn!(plugin = %self.name, mode = ?mode, error = %e, "Failed to set Bybit position mode");
+            }
+        }
+        
+        tracing::info!(plugin = %self.name, "Bybit plugin initialized successfully");
+        Ok(())
+    }
+    
+    async fn execute_order(
+        &self,
+        order: Order,
+    ) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
+        // v5 order/create has no visible-size parameter
+        reject_display_quantity(&self.name, &order)?;
+        order.validate_stop_limit()?;
+        
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        if self.maintenance.is_active() {
+            return Err(MaintenanceState::error(&self.name, "orders rejected until the window closes").into());
+        }
+        
+        let order = Order { symbol: self.resolve_symbol(config, &order.symbol).await, ..order };
+        let symbol_config = self.symbol_config(config, &order.symbol).await;
+        // A symbol auto-detected in another category is listed there by definition
+        if symbol_config.category == config.category {
+            self.check_listed(config, &order.symbol).await?;
+        }
+        let config = &*symbol_config;
+        self.check_quantity_type(config, &order)?;
+        let order = Order { leverage: self.order_leverage(config, &order)?, ..order };
+        let side = match order.side {
+            OrderSide::Buy => "Buy",
+            OrderSide::Sell => "Sell",
+        };
+        let request = self.build_order_request(config, &order);
+        let sent = Instant::now();
+        let (status, text) = match self.send(config, &request).await {
+            Ok(reply) => reply,
+            Err(e) if super::is_timeout(&*e) => return self.reconcile_timeout(config, &order, &request, sent, e).await,
+            Err(e) => return Err(e),
+        };
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Ok(ExecutionResult {
+                success: false,
+                order_id: None,
+                filled_quantity: 0.0,
+                average_price: 0.0,
+                error: Some(super::http_error("Bybit", status, &text).to_string()),
+                timestamp: now_millis(),
+            });
+        }
+        
+        let bybit_resp: BybitResponse<BybitOrderResult> = super::parse_response("Bybit", &text)?;
+        
+        if !bybit_resp.is_success() {
+            if let Some(err) = Self::post_only_rejection(bybit_resp.ret_code(), bybit_resp.ret_msg()).filter(|_| order.post_only) {
+                tracing::warn!(plugin = %self.name, symbol = %order.symbol, "post_only_would_cross");
+                return Err(err.into());
+            }
+            return Ok(ExecutionResult {
+                success: false,
+                order_id: None,
+                filled_quantity: 0.0,
+                average_price: 0.0,
+                error: Some(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg())),
+                timestamp: now_millis(),
+            });
+        }
+        
+        // Extract order ID from result
+        let order_id = bybit_resp.result.as_ref()
+            .and_then(|r| r.order_id.clone())
+            .or_else(|| bybit_resp.result.as_ref().and_then(|r| r.order_link_id.clone()));
+        
+        tracing::info!(
+            plugin = %self.name,
+            symbol = %order.symbol,
+            side = %side,
+            order_id = ?order_id,
+            "Order placed successfully"
+        );
+        
+        // For market orders, we assume immediate fill
+        // For limit orders, filled_quantity will be 0 until filled
+        let filled_quantity = match order.order_type {
+            OrderType::Market => order.quantity,
+            _ => 0.0,
+        };
+        
+        // Get current market price as average_price (for market orders)
+        let average_price = match order.order_type {
+            OrderType::Market => {
+                // Try to fetch current price
+                if let Ok(market_data) = self.fetch_data(&order.symbol).await {
+                    market_data.last
+                } else {
+                    order.price.unwrap_or(0.0)
+                }
+            },
+            _ => order.price.unwrap_or(0.0),
+        };
+        
+        Ok(ExecutionResult {
+            success: true,
+            order_id,
+            filled_quantity,
+            average_price,
+            error: None,
+            timestamp: now_millis(),
+        })
+    }
+    
+    async fn preview_order(&self, order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        reject_display_quantity(&self.name, order)?;
+        order.validate_stop_limit()?;
+        
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let order = Order { symbol: self.resolve_symbol(config, &order.symbol).await, ..order.clone() };
+        let config = self.symbol_config(config, &order.symbol).await;
+        self.check_quantity_type(&config, &order)?;
+        let order = Order { leverage: self.order_leverage(&config, &order)?, ..order };
+        Ok(self.build_order_request(&config, &order))
+    }
+    
+    async fn fetch_data(&self, symbol: &str) -> Result<MarketData, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let text = self.get_ticker(config, symbol).await?;
+        Self::parse_ticker(symbol, &text)
+    }
+    
+    async fn fetch_funding_rate(&self, symbol: &str) -> Result<FundingRate, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let symbol_config = self.symbol_config(config, &self.normalize_symbol(config, symbol)).await;
+        if symbol_config.category == "spot" {
+            return Err(ExecutionError::Unsupported("Bybit spot has no funding rate".to_string()).into());
+        }
+        
+        let text = self.get_ticker(config, symbol).await?;
+        Self::parse_funding_rate(symbol, &text)
+    }
+    
+    async fn set_leverage(&self, symbol: &str, leverage: i32) -> Result<LeverageChange, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let leverage = config.leverage_limit.apply(&self.name, leverage)?;
+        let config = &*self.symbol_config(config, &self.normalize_symbol(config, symbol)).await;
+        let base_url = &self.base_url;
+        let endpoint = format!("{}/v5/position/set-leverage", base_url);
+        
+        let params = serde_json::json!({
+            "category": config.category,
+            "symbol": self.normalize_symbol(config, symbol),
+            "buyLeverage": leverage.to_string(),
+            "sellLeverage": leverage.to_string(),
+        });
+        
+        // For POST requests, signature is calculated from JSON body
+        let json_body = serde_json::to_string(&params)?;
+        let headers = self.create_headers_post(
+            &config.api_key,
+            &config.api_secret,
+            RECV_WINDOW_MS,
+            &json_body,
+        ).await?;
+        
+        let response = self.client
+            .post(&endpoint)
+            .headers(headers)
+            .json(&params)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("Bybit", status, &text));
+        }
+        
+        let change = Self::parse_set_leverage(&text)?;
+        match change {
+            LeverageChange::Changed => tracing::info!(plugin = %self.name, symbol = %symbol, leverage = %leverage, "Leverage set successfully"),
+            LeverageChange::Unchanged => tracing::debug!(plugin = %self.name, symbol = %symbol, leverage = %leverage, "Leverage already set"),
+        }
+        Ok(change)
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    async fn health_check(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        Ok(self.health_status().await == HealthStatus::Healthy)
+    }
+    
+    async fn health_status(&self) -> HealthStatus {
+        // Check if plugin is initialized
+        let config = match self.config.read().await.clone() {
+            Some(c) => c,
+            None => return HealthStatus::Unconfigured,
+        };
+        
+        if self.maintenance.is_active() {
+            return HealthStatus::Unreachable;
+        }
+        
+        // Public endpoint first, so network problems aren't reported as auth failures
+        if self.fetch_data(&config.health_check_symbol).await.is_err() {
+            return HealthStatus::Unreachable;
+        }
+        
+        // Public-only health when no credentials are configured
+        if config.api_key.is_empty() || config.api_secret.is_empty() {
+            return HealthStatus::Healthy;
+        }
+        
+        self.auth_ping(&config).await
+    }
+    
+    async fn list_orders(&self, symbol: Option<&str>) -> Result<Vec<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        let mut orders = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = self.list_orders_page(symbol, cursor.as_deref(), 50).await?;
+            orders.extend(page.orders);
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(orders),
+            }
+        }
+    }
+    
+    /// Bybit's `nextPageCursor` is the cursor; pages hold at most 50 orders.
+    /// Without a symbol outside spot, each settle coin is paged in turn and
+    /// the cursor is prefixed with the coin it belongs to (`USDC:<cursor>`).
+    async fn list_orders_page(&self, symbol: Option<&str>, cursor: Option<&str>, limit: usize) -> Result<OrderPage, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let mut params = serde_json::json!({
+            "category": config.category,
+            "limit": limit.clamp(1, 50),
+        });
+        if let Some(symbol) = symbol {
+            params["symbol"] = serde_json::json!(self.normalize_symbol(config, symbol));
+        }
+        if symbol.is_some() || config.category == "spot" {
+            if let Some(cursor) = cursor {
+                params["cursor"] = serde_json::json!(cursor);
+            }
+            return self.query_order_page(config, &self.base_url, &params).await;
+        }
+        
+        // Linear/inverse require either a symbol or a settle coin
+        let (coin, cursor) = match cursor {
+            None => (0, None),
+            Some(cursor) => cursor.split_once(':')
+                .and_then(|(coin, rest)| {
+                    let coin = config.settle_coins.iter().position(|c| c == coin)?;
+                    Some((coin, Some(rest).filter(|rest| !rest.is_empty())))
+                })
+                .ok_or_else(|| ExecutionError::Exchange {
+                    code: INVALID_CURSOR,
+                    message: format!("Invalid cursor '{}'", cursor),
+                })?,
+        };
+        params["settleCoin"] = serde_json::json!(config.settle_coins[coin]);
+        if let Some(cursor) = cursor {
+            params["cursor"] = serde_json::json!(cursor);
+        }
+        
+        let mut page = self.query_order_page(config, &self.base_url, &params).await?;
+        page.next_cursor = match page.next_cursor {
+            Some(next) => Some(format!("{}:{}", config.settle_coins[coin], next)),
+            None => config.settle_coins.get(coin + 1).map(|next_coin| format!("{}:", next_coin)),
+        };
+        Ok(page)
+    }
+    
+    async fn get_order(&self, symbol: &str, order_id: &str) -> Result<OrderSummary, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let symbol = self.normalize_symbol(config, symbol);
+        let config = &*self.symbol_config(config, &symbol).await;
+        let params = serde_json::json!({
+            "category": config.category,
+            "symbol": symbol,
+            "orderId": order_id,
+        });
+        
+        self.query_orders(config, &self.base_url, &params).await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("Bybit order {} not found", order_id).into())
+    }
+    
+    async fn get_order_by_client_id(&self, symbol: &str, client_order_id: &str) -> Result<Option<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let symbol = self.normalize_symbol(config, symbol);
+        let config = &*self.symbol_config(config, &symbol).await;
+        let params = serde_json::json!({
+            "category": config.category,
+            "symbol": symbol,
+            "orderLinkId": client_order_id,
+        });
+        
+        Ok(self.query_orders(config, &self.base_url, &params).await?.into_iter().next())
+    }
+    
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let endpoint = format!("{}/v5/order/cancel", self.base_url);
+        let normalized = self.normalize_symbol(config, symbol);
+        let config = &*self.symbol_config(config, &normalized).await;
+        let params = serde_json::json!({
+            "category": config.category,
+            "symbol": normalized,
+            "orderId": order_id,
+        });
+        
+        let json_body = serde_json::to_string(&params)?;
+        let headers = self.create_headers_post(
+            &config.api_key,
+            &config.api_secret,
+            RECV_WINDOW_MS,
+            &json_body,
+        ).await?;
+        
+        let response = self.client
+            .post(&endpoint)
+            .headers(headers)
+            .json(&params)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("Bybit", status, &text));
+        }
+        
+        let bybit_resp: BybitResponse<serde_json::Value> = super::parse_response("Bybit", &text)?;
+        if !bybit_resp.is_success() {
+            return Err(format!("Bybit API error: {} - {}", bybit_resp.ret_code(), bybit_resp.ret_msg()).into());
+        }
+        
+        tracing::info!(plugin = %self.name, symbol = %symbol, order_id = %order_id, "Order cancelled");
+        Ok(())
+    }
+    
+    /// Sent in requests of up to 10 orders; once one request has gone
+    /// through, a later one that fails is reported on each of its orders
+    async fn cancel_batch(&self, symbol: &str, orders: &[OrderRef]) -> Result<Vec<CancelOutcome>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let symbol = self.normalize_symbol(config, symbol);
+        let config = &*self.symbol_config(config, &symbol).await;
+        let endpoint = format!("{}/v5/order/cancel-batch", self.base_url);
+        
+        let mut outcomes = Vec::with_capacity(orders.len());
+        for chunk in orders.chunks(CANCEL_BATCH_LIMIT) {
+            let request: Vec<serde_json::Value> = chunk.iter()
+                .map(|order| match order {
+                    OrderRef::OrderId(id) => serde_json::json!({ "symbol": symbol, "orderId": id }),
+                    OrderRef::ClientOrderId(id) => serde_json::json!({ "symbol": symbol, "orderLinkId": id }),
+                })
+                .collect();
+            let params = serde_json::json!({
+                "category": config.category,
+                "request": request,
+            });
+            let prepared = PreparedRequest {
+                method: "POST".to_string(),
+                url: endpoint.clone(),
+                path: "/v5/order/cancel-batch".to_string(),
+                body: params,
+            };
+            
+            let sent = match self.send(config, &prepared).await {
+                Ok((status, text)) => match self.check_maintenance(status, &text) {
+                    Some(err) => Err(err.into()),
+                    None if !status.is_success() => Err(super::http_error("Bybit", status, &text)),
+                    None => Self::parse_cancel_batch(chunk, &text),
+                },
+                Err(e) => Err(e),
+            };
+            match sent {
+                Ok(chunk_outcomes) => outcomes.extend(chunk_outcomes),
+                Err(e) if outcomes.is_empty() => return Err(e),
+                Err(e) => {
+                    tracing::warn!(plugin = %self.name, symbol = %symbol, error = %e, "cancel_batch_chunk_failed");
+                    outcomes.extend(chunk.iter().map(|order| CancelOutcome::failed(order.clone(), e.to_string())));
+                }
+            }
+        }
+        Ok(outcomes)
+    }
+    
+    async fn get_positions(&self, symbol: Option<&str>) -> Result<Vec<Position>, Box<dyn Error + Send + Sync>> {
+        Ok(self.fetch_positions(symbol).await?
+            .into_iter()
+            .filter_map(BybitPosition::into_position)
+            .collect())
+    }
+    
+    async fn get_balances(&self) -> Result<Vec<Balance>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let endpoint = format!("{}/v5/account/wallet-balance", self.base_url);
+        let params = serde_json::json!({ "accountType": "UNIFIED" });
+        
+        let query_string = serde_qs::to_string(&params)?;
+        let headers = self.create_headers_get(
+            &config.api_key,
+            &config.api_secret,
+            RECV_WINDOW_MS,
+            &query_string,
+        ).await?;
+        
+        let response = self.client
+            .get(&endpoint)
+            .headers(headers)
+            .query(&params)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("Bybit", status, &text));
+        }
+        
+        Self::parse_wallet_balance(&text)
+    }
+    
+    /// Most recent closes only: Bybit covers the last 7 days, one page of up to 100
+    async fn get_closed_pnl(&self, symbol: Option<&str>) -> Result<Vec<ClosedPnl>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let endpoint = format!("{}/v5/position/closed-pnl", self.base_url);
+        let mut params = serde_json::json!({
+            "category": config.category,
+            "limit": CLOSED_PNL_PAGE_LIMIT,
+        });
+        if let Some(symbol) = symbol {
+            params["symbol"] = serde_json::json!(self.normalize_symbol(config, symbol));
+        }
+        
+        let query_string = serde_qs::to_string(&params)?;
+        let headers = self.create_headers_get(
+            &config.api_key,
+            &config.api_secret,
+            RECV_WINDOW_MS,
+            &query_string,
+        ).await?;
+        
+        let response = self.client
+            .get(&endpoint)
+            .headers(headers)
+            .query(&params)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("Bybit", status, &text));
+        }
+        
+        Self::parse_closed_pnl(&text)
+    }
+    
+    /// Most recent fills only: one page of up to 100 from the last 7 days
+    async fn fetch_trades(&self, symbol: Option<&str>, limit: usize) -> Result<Vec<Trade>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let endpoint = format!("{}/v5/execution/list", self.base_url);
+        let mut params = serde_json::json!({
+            "category": config.category,
+            "limit": limit.clamp(1, EXECUTION_PAGE_LIMIT),
+        });
+        if let Some(symbol) = symbol {
+            params["symbol"] = serde_json::json!(self.normalize_symbol(config, symbol));
+        }
+        
+        let query_string = serde_qs::to_string(&params)?;
+        let headers = self.create_headers_get(
+            &config.api_key,
+            &config.api_secret,
+            RECV_WINDOW_MS,
+            &query_string,
+        ).await?;
+        
+        let response = self.client
+            .get(&endpoint)
+            .headers(headers)
+            .query(&params)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("Bybit", status, &text));
+        }
+        
+        Self::parse_executions(&text)
+    }
+    
+    async fn instrument_info(&self, symbol: &str) -> Result<InstrumentInfo, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let endpoint = format!("{}/v5/market/instruments-info", self.base_url);
+        let symbol = self.resolve_symbol(config, symbol).await;
+        let config = self.symbol_config(config, &symbol).await;
+        let params = serde_json::json!({
+            "category": config.category,
+            "symbol": symbol,
+        });
+        
+        // Public endpoint, no authentication required
+        let response = self.client
+            .get(&endpoint)
+            .query(&params)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("Bybit", status, &text));
+        }
+        
+        Ok(InstrumentInfo { rounding: config.rounding, ..Self::parse_instrument(&text)? })
+    }
+    
+    async fn list_symbols(&self) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        self.fetch_symbols(config).await
+    }
+    
+    async fn ping(&self) -> Result<PingResult, Box<dyn Error + Send + Sync>> {
+        let endpoint = format!("{}/v5/market/time", self.base_url);
+        
+        let sent_at = now_millis();
+        let start = std::time::Instant::now();
+        let response = self.client.get(&endpoint).send().await?;
+        let elapsed = start.elapsed();
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("Bybit", status, &text));
+        }
+        
+        // Top-level `time` is the server time in millis
+        let server_time = super::parse_response::<serde_json::Value>("Bybit", &text)?
+            .get("time")
+            .and_then(|t| t.as_i64());
+        
+        Ok(PingResult::measure(sent_at, elapsed, server_time))
+    }
+    
+    fn in_maintenance(&self) -> bool {
+        self.maintenance.is_active()
+    }
+    
+    /// Paper only on the testnet host; a custom `base_url` can't be told apart from live
+    fn is_paper(&self) -> bool {
+        self.base_url == Self::network_url(true)
+    }
+    
+    async fn effective_config(&self) -> Option<serde_json::Value> {
+        let config = self.config.read().await;
+        serde_json::to_value(config.as_ref()?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::spawn_mock_exchange;
+    use crate::plugins::LEVERAGE_ABOVE_MAX;
+    
+    /// A plugin pointed at a mock exchange, with `extra` added to its config
+    async fn init_with_base_url(base_url: &str, extra: serde_json::Value) -> BybitPlugin {
+        let mut config = serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "base_url": base_url
+        });
+        config.as_object_mut().unwrap().extend(extra.as_object().cloned().unwrap_or_default());
+        let mut plugin = BybitPlugin::new("test-bybit");
+        plugin.init(config).await.unwrap();
+        plugin
+    }
+    
+    #[tokio::test]
+    async fn test_debug_sign_logs_prehash_without_secret() {
+        let logs = crate::plugins::tests::LogCapture::default();
+        let _guard = logs.install();
+        
+        let mut plugin = BybitPlugin::new("test-bybit");
+        plugin.debug_sign = true;
+        let headers = plugin.create_headers_post("key123", "s3cr3t-value", 5000, "{\"symbol\":\"BTCUSDT\"}").await.unwrap();
+        
+        let timestamp = headers["X-BAPI-TIMESTAMP"].to_str().unwrap();
+        let output = logs.contents();
+        assert!(output.contains(&format!("prehash={}key1235000{{\"symbol\":\"BTCUSDT\"}}", timestamp)));
+        assert!(output.contains(headers["X-BAPI-SIGN"].to_str().unwrap()));
+        assert!(!output.contains("s3cr3t-value"));
+        
+        // Off by default
+        let logs = crate::plugins::tests::LogCapture::default();
+        let _guard = logs.install();
+        plugin.debug_sign = false;
+        plugin.create_headers_get("key123", "s3cr3t-value", 5000, "category=linear").await.unwrap();
+        assert!(!logs.contents().contains("signed_request"));
+    }
+    
+    #[test]
+    fn test_parse_symbol_page() {
+        let text = r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","nextPageCursor":"page2","list":[
+            {"symbol":"BTCUSDT","status":"Trading","lotSizeFilter":{"qtyStep":"0.001","minOrderQty":"0.001"},"priceFilter":{"tickSize":"0.10"}},
+            {"symbol":"OLDUSDT","status":"Closed","lotSizeFilter":{"qtyStep":"1","minOrderQty":"1"},"priceFilter":{"tickSize":"0.0001"}}
+        ]}}"#;
+        let (symbols, cursor) = BybitPlugin::parse_symbol_page(text).unwrap();
+        assert_eq!(symbols, vec!["BTCUSDT"]);
+        assert_eq!(cursor.as_deref(), Some("page2"));
+        
+        let text = r#"{"retCode":0,"retMsg":"OK","result":{"category":"spot","nextPageCursor":"","list":[]}}"#;
+        let (symbols, cursor) = BybitPlugin::parse_symbol_page(text).unwrap();
+        assert!(symbols.is_empty());
+        assert!(cursor.is_none());
+    }
+    
+    #[test]
+    fn test_parse_cancel_batch_per_order_status() {
+        let orders = vec![
+            OrderRef::OrderId("1001".to_string()),
+            OrderRef::ClientOrderId("fks-gone".to_string()),
+            OrderRef::OrderId("1003".to_string()),
+        ];
+        let text = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"category":"linear","symbol":"BTCUSDT","orderId":"1001","orderLinkId":""},
+            {"category":"linear","symbol":"BTCUSDT","orderId":"","orderLinkId":"fks-gone"}
+        ]},"retExtInfo":{"list":[{"code":0,"msg":"OK"},{"code":110001,"msg":"order not exists or too late to cancel"}]}}"#;
+        
+        let outcomes = BybitPlugin::parse_cancel_batch(&orders, text).unwrap();
+        assert_eq!(outcomes[0], CancelOutcome::cancelled(orders[0].clone()));
+        assert_eq!(outcomes[1].error.as_deref(), Some("Bybit API error: 110001 - order not exists or too late to cancel"));
+        assert_eq!(outcomes[2].error.as_deref(), Some("Bybit returned no status for this order"));
+        
+        let rejected = r#"{"retCode":10001,"retMsg":"params error","result":{},"retExtInfo":{}}"#;
+        let err = BybitPlugin::parse_cancel_batch(&orders, rejected).unwrap_err();
+        assert_eq!(err.to_string(), "Bybit API error: 10001 - params error");
+    }
+    
+    #[tokio::test]
+    async fn test_preview_order_builds_payload_without_sending() {
+        let mut plugin = BybitPlugin::new("test-bybit");
+        // Nothing listens here: any request would fail the test
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "base_url": "http://127.0.0.1:9"
+        })).await.unwrap();
+        
+        let mut order = test_order();
+        order.order_type = OrderType::Limit;
+        order.price = Some(67000.0);
+        order.stop_loss = Some(66000.0);
+        order.strategy_tag = Some("trend".to_string());
+        
+        let preview = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(preview.method, "POST");
+        assert_eq!(preview.url, "http://127.0.0.1:9/v5/order/create");
+        
+        let body = &preview.body;
+        assert_eq!(body["category"], "linear");
+        assert_eq!(body["symbol"], "BTCUSDT");
+        assert_eq!(body["side"], "Buy");
+        assert_eq!(body["orderType"], "Limit");
+        assert_eq!(body["qty"], "0.01");
+        assert_eq!(body["price"], "67000");
+        assert_eq!(body["stopLoss"], "66000");
+        assert_eq!(body["positionIdx"], 0);
+        assert!(body["orderLinkId"].as_str().unwrap().starts_with("fks-trend-"));
+    }
+    
+    /// Bybit listing `linear` symbols only; counts instrument lookups
+    async fn mock_bybit_linear_instruments(linear: &'static [&'static str]) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use axum::{extract::Query, routing::get, Json, Router};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let counter = lookups.clone();
+        let app = Router::new()
+            .route("/v5/market/instruments-info", get(move |Query(query): Query<HashMap<String, String>>| async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let symbol = query.get("symbol").cloned().unwrap_or_default();
+                let list: Vec<serde_json::Value> = match query.get("category").map(String::as_str) {
+                    Some("linear") if linear.contains(&symbol.as_str()) => vec![serde_json::json!({
+                        "symbol": symbol,
+                        "status": "Trading",
+                        "lotSizeFilter": { "qtyStep": "0.001", "minOrderQty": "0.001" },
+                        "priceFilter": { "tickSize": "0.1" }
+                    })],
+                    _ => Vec::new(),
+                };
+                Json(serde_json::json!({ "retCode": 0, "retMsg": "OK", "result": { "list": list } }))
+            }));
+        
+        (spawn_mock_exchange(app).await, lookups)
+    }
+    
+    #[tokio::test]
+    async fn test_auto_category_resolves_linear_symbol() {
+        use std::sync::atomic::Ordering;
+        
+        let (base_url, lookups) = mock_bybit_linear_instruments(&["BTCUSDT"]).await;
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "category": "spot",
+            "auto_category": true
+        })).await;
+        
+        // Not listed in spot (the configured category), found in linear
+        let body = plugin.preview_order(&test_order()).await.unwrap().body;
+        assert_eq!(body["category"], "linear");
+        assert_eq!(body["positionIdx"], 0);
+        assert!(body.get("marketUnit").is_none());
+        assert_eq!(lookups.load(Ordering::SeqCst), 2);
+        
+        // Cached after the first order
+        let body = plugin.preview_order(&test_order()).await.unwrap().body;
+        assert_eq!(body["category"], "linear");
+        assert_eq!(lookups.load(Ordering::SeqCst), 2);
+        
+        // Listed nowhere: the configured category is used
+        let mut order = test_order();
+        order.symbol = "NOPEUSDT".to_string();
+        let body = plugin.preview_order(&order).await.unwrap().body;
+        assert_eq!(body["category"], "spot");
+    }
+    
+    #[tokio::test]
+    async fn test_unlisted_symbol_rejected_locally_with_suggestions() {
+        use axum::{routing::{get, post}, Json, Router};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        let orders_sent = Arc::new(AtomicUsize::new(0));
+        let counter = orders_sent.clone();
+        let app = Router::new()
+            .route("/v5/market/instruments-info", get(|| async {
+                let list: Vec<_> = ["BTCUSDT", "ETHUSDT", "SOLUSDT", "BTCPERP"].iter()
+                    .map(|symbol| serde_json::json!({
+                        "symbol": symbol,
+                        "status": "Trading",
+                        "lotSizeFilter": { "qtyStep": "0.001", "minOrderQty": "0.001" },
+                        "priceFilter": { "tickSize": "0.1" }
+                    }))
+                    .collect();
+                Json(serde_json::json!({ "retCode": 0, "retMsg": "OK", "result": { "list": list } }))
+            }))
+            .route("/v5/order/create", post(move || async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Json(serde_json::json!({ "retCode": 10001, "retMsg": "symbol invalid", "result": {} }))
+            }));
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({})).await;
+        
+        let mut order = test_order();
+        order.symbol = "BTCUSTD".to_string();
+        let err = plugin.execute_order(order).await.unwrap_err();
+        match err.downcast_ref::<ExecutionError>() {
+            Some(ExecutionError::InvalidSymbol { symbol, suggestions }) => {
+                assert_eq!(symbol, "BTCUSTD");
+                assert_eq!(suggestions, &vec!["BTCUSDT".to_string()]);
+            }
+            other => panic!("expected InvalidSymbol, got {:?}", other),
+        }
+        assert_eq!(err.to_string(), "Unknown symbol 'BTCUSTD'; did you mean BTCUSDT?");
+        
+        let mut order = test_order();
+        order.symbol = "DOGEJPY".to_string();
+        assert_eq!(plugin.execute_order(order).await.unwrap_err().to_string(), "Unknown symbol 'DOGEJPY'");
+        assert_eq!(orders_sent.load(Ordering::SeqCst), 0);
+        
+        // Listed symbols still reach the exchange
+        let result = plugin.execute_order(test_order()).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(orders_sent.load(Ordering::SeqCst), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_testnet_override_selects_host_per_order() {
+        let mut plugin = init_with_base_url("http://127.0.0.1:9", serde_json::json!({})).await;
+        
+        let mut order = test_order();
+        order.testnet = Some(true);
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.url, "https://api-testnet.bybit.com/v5/order/create");
+        
+        // Asking for the configured network keeps the configured endpoint
+        order.testnet = Some(false);
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.url, "http://127.0.0.1:9/v5/order/create");
+        assert!(!plugin.is_paper());
+        
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "testnet": true
+        })).await.unwrap();
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.url, "https://api.bybit.com/v5/order/create");
+        // Per-order overrides don't change the plugin's own network
+        assert!(plugin.is_paper());
+    }
+    
+    #[tokio::test]
+    async fn test_display_quantity_unsupported() {
+        let plugin = init_plugin().await;
+        
+        let mut order = test_order();
+        order.order_type = OrderType::Limit;
+        order.price = Some(67000.0);
+        order.display_quantity = Some(0.005);
+        
+        let err = plugin.preview_order(&order).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+        let err = plugin.execute_order(order).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_extra_params_merged_without_overriding() {
+        let plugin = init_plugin().await;
+        let config = plugin.config.read().await;
+        let config = config.as_ref().unwrap();
+        
+        let mut order = test_order();
+        order.extra_params = Some(serde_json::json!({"smpType": "CancelMaker", "symbol": "ETHUSDT"}));
+        
+        let request = plugin.build_order_request(config, &order);
+        assert_eq!(request.body["smpType"], "CancelMaker");
+        assert_eq!(request.body["symbol"], "BTCUSDT");
+        assert_eq!(request.body["side"], "Buy");
+    }
+    
+    #[test]
+    fn test_post_only_reject_codes_mapped() {
+        use crate::plugins::POST_ONLY_WOULD_CROSS;
+        
+        let err = BybitPlugin::post_only_rejection(170218, "The LIMIT-MAKER order is rejected due to invalid price.").unwrap();
+        assert!(matches!(err, ExecutionError::Exchange { code: POST_ONLY_WOULD_CROSS, .. }));
+        assert!(err.to_string().starts_with("post_only_would_cross: Bybit rejected the post-only order"));
+        
+        // Codes Bybit doesn't reserve for it are matched on the message
+        assert!(BybitPlugin::post_only_rejection(10001, "PostOnly order would take liquidity").is_some());
+        assert!(BybitPlugin::post_only_rejection(10001, "params error: qty invalid").is_none());
+        assert!(BybitPlugin::post_only_rejection(110007, "ab not enough for new order").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_post_only_rejection_returned_as_exchange_error() {
+        use axum::{routing::post, Json, Router};
+        
+        let app = Router::new().route("/v5/order/create", post(|| async {
+            Json(serde_json::json!({ "retCode": 170218, "retMsg": "The LIMIT-MAKER order is rejected due to invalid price.", "result": {} }))
+        }));
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "category": "spot"
+        })).await;
+        
+        let mut order = test_order();
+        order.order_type = OrderType::Limit;
+        order.price = Some(67600.0);
+        order.post_only = true;
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.body["timeInForce"], "PostOnly");
+        
+        let err = plugin.execute_order(order.clone()).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::Exchange { code: "post_only_would_cross", .. })
+        ));
+        
+        // Without post_only the same code stays an ordinary failed result
+        order.post_only = false;
+        let result = plugin.execute_order(order).await.unwrap();
+        assert!(!result.success);
+    }
+    
+    #[tokio::test]
+    async fn test_build_order_request_market_limit_stop() {
+        let plugin = init_plugin().await;
+        let config = plugin.config.read().await;
+        let config = config.as_ref().unwrap();
+        
+        let market = plugin.build_order_request(config, &test_order());
+        assert_eq!(market.path, "/v5/order/create");
+        assert_eq!(market.url, "https://api.bybit.com/v5/order/create");
+        assert_eq!(market.body["orderType"], "Market");
+        assert!(market.body.get("price").is_none());
+        
+        let mut order = test_order();
+        order.order_type = OrderType::Limit;
+        order.side = OrderSide::Sell;
+        order.price = Some(68000.0);
+        let limit = plugin.build_order_request(config, &order);
+        assert_eq!(limit.body["orderType"], "Limit");
+        assert_eq!(limit.body["side"], "Sell");
+        assert_eq!(limit.body["price"], "68000");
+        
+        order.order_type = OrderType::Stop;
+        order.price = None;
+        order.stop_loss = Some(66000.0);
+        let stop = plugin.build_order_request(config, &order);
+        assert_eq!(stop.body["orderType"], "Stop");
+        assert_eq!(stop.body["stopLoss"], "66000");
+    }
+    
+    #[tokio::test]
+    async fn test_market_slippage_uses_native_tolerance() {
+        let plugin = init_plugin().await;
+        let config = plugin.config.read().await;
+        let config = config.as_ref().unwrap();
+        
+        let mut order = test_order();
+        order.max_slippage_pct = Some(0.5);
+        let request = plugin.build_order_request(config, &order);
+        assert_eq!(request.body["orderType"], "Market");
+        assert_eq!(request.body["slippageToleranceType"], "Percent");
+        assert_eq!(request.body["slippageTolerance"], "0.5");
+        assert!(request.body.get("price").is_none());
+        
+        let plain = plugin.build_order_request(config, &test_order());
+        assert!(plain.body.get("slippageTolerance").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_stop_limit_maps_trigger_and_limit_price() {
+        let plugin = init_plugin().await;
+        
+        let mut order = test_order();
+        order.order_type = OrderType::StopLimit;
+        order.trigger_price = Some(68000.0);
+        order.price = Some(68100.0);
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.body["orderType"], "Limit");
+        assert_eq!(request.body["triggerPrice"], "68000");
+        assert_eq!(request.body["price"], "68100");
+        assert_eq!(request.body["triggerDirection"], 1);
+        assert!(request.body.get("stopLoss").is_none());
+        
+        order.side = OrderSide::Sell;
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.body["triggerDirection"], 2);
+        
+        order.trigger_price = None;
+        let err = plugin.preview_order(&order).await.unwrap_err();
+        assert_eq!(err.to_string(), "trigger_price is required for StopLimit orders");
+        let err = plugin.execute_order(order.clone()).await.unwrap_err();
+        assert_eq!(err.to_string(), "trigger_price is required for StopLimit orders");
+        
+        order.trigger_price = Some(66000.0);
+        order.price = None;
+        let err = plugin.preview_order(&order).await.unwrap_err();
+        assert_eq!(err.to_string(), "price (the limit) is required for StopLimit orders");
+    }
+    
+    /// Bybit whose order create hangs; the realtime lookup answers from `orders`
+    async fn mock_bybit_slow_create(orders: serde_json::Value) -> String {
+        use axum::{extract::Query, routing::{get, post}, Json, Router};
+        
+        let app = Router::new()
+            .route("/v5/order/create", post(|| async {
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                Json(serde_json::json!({ "retCode": 0, "retMsg": "OK", "result": {} }))
+            }))
+            .route("/v5/order/realtime", get(move |Query(query): Query<HashMap<String, String>>| async move {
+                let link_id = query.get("orderLinkId").cloned().unwrap_or_default();
+                let list: Vec<serde_json::Value> = orders.as_array().unwrap().iter()
+                    .map(|o| {
+                        let mut o = o.clone();
+                        o["orderLinkId"] = serde_json::json!(link_id);
+                        o
+                    })
+                    .collect();
+                Json(serde_json::json!({ "retCode": 0, "retMsg": "OK", "result": { "list": list } }))
+            }));
+        
+        spawn_mock_exchange(app).await
+    }
+    
+    async fn init_impatient_plugin(base_url: &str) -> BybitPlugin {
+        let mut plugin = init_with_base_url(base_url, serde_json::json!({})).await;
+        plugin.client = Client::builder()
+            .timeout(std::time::Duration::from_millis(200))
+            .build()
+            .unwrap();
+        plugin
+    }
+    
+    #[tokio::test]
+    async fn test_create_timeout_reconciled_by_order_link_id() {
+        let base_url = mock_bybit_slow_create(serde_json::json!([{
+            "orderId": "bybit-42",
+            "symbol": "BTCUSDT",
+            "side": "Buy",
+            "orderType": "Limit",
+            "price": "67000",
+            "qty": "0.01",
+            "cumExecQty": "0.01",
+            "avgPrice": "66990.5",
+            "orderStatus": "Filled",
+            "createdTime": "1700000000000"
+        }])).await;
+        let plugin = init_impatient_plugin(&base_url).await;
+        
+        let mut order = test_order();
+        order.order_type = OrderType::Limit;
+        order.price = Some(67000.0);
+        let result = plugin.execute_order(order).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.order_id.as_deref(), Some("bybit-42"));
+        assert_eq!(result.filled_quantity, 0.01);
+        assert_eq!(result.average_price, 66990.5);
+    }
+    
+    #[tokio::test]
+    async fn test_create_timeout_without_order_is_outcome_unknown() {
+        let base_url = mock_bybit_slow_create(serde_json::json!([])).await;
+        let plugin = init_impatient_plugin(&base_url).await;
+        
+        // Polled until the receive window has passed, then left undecided
+        let started = std::time::Instant::now();
+        let err = plugin.execute_order(test_order()).await.unwrap_err();
+        assert!(started.elapsed() >= std::time::Duration::from_millis(RECV_WINDOW_MS));
+        match err.downcast_ref::<ExecutionError>() {
+            Some(ExecutionError::OutcomeUnknown(message)) => assert!(message.contains("client id fks-")),
+            other => panic!("expected OutcomeUnknown, got {:?}", other),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_testnet_order_timeout_reconciled_on_testnet_host() {
+        // Stands in for the testnet host; the configured one refuses connections
+        let testnet_url = mock_bybit_slow_create(serde_json::json!([{
+            "orderId": "testnet-7",
+            "symbol": "BTCUSDT",
+            "side": "Buy",
+            "orderType": "Market",
+            "qty": "0.01",
+            "cumExecQty": "0.01",
+            "avgPrice": "67000",
+            "orderStatus": "Filled",
+            "createdTime": "1700000000000"
+        }])).await;
+        let plugin = init_with_base_url("http://127.0.0.1:9", serde_json::json!({})).await;
+        let config = plugin.config.read().await.clone().unwrap();
+        
+        let mut order = test_order();
+        order.testnet = Some(true);
+        let mut request = plugin.build_order_request(&config, &order);
+        assert_eq!(request.base_url(), Some("https://api-testnet.bybit.com"));
+        request.url = format!("{}{}", testnet_url, request.path);
+        
+        let result = plugin.reconcile_timeout(&config, &order, &request, Instant::now(), "timed out".into()).await.unwrap();
+        assert_eq!(result.order_id.as_deref(), Some("testnet-7"));
+    }
+    
+    async fn init_plugin() -> BybitPlugin {
+        let mut plugin = BybitPlugin::new("test-bybit");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret"
+        })).await.unwrap();
+        plugin
+    }
+    
+    fn test_order() -> Order {
+        Order {
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: 0.01,
+            confidence: 0.75,
+            ..Default::default()
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_maintenance_ret_code_marks_plugin_unavailable() {
+        let plugin = init_plugin().await;
+        
+        let body = r#"{"retCode":10016,"retMsg":"Service is restarting","result":{}}"#;
+        let err = plugin.check_maintenance(reqwest::StatusCode::OK, body).unwrap();
+        assert!(matches!(err, ExecutionError::Unsupported(ref msg) if msg.contains("maintenance")));
+        assert!(plugin.in_maintenance());
+        assert!(!plugin.health_check().await.unwrap());
+        
+        // Orders fail fast with a typed error instead of hitting the exchange
+        let err = plugin.execute_order(test_order()).await.unwrap_err();
+        let err = err.downcast_ref::<ExecutionError>().unwrap();
+        assert!(err.to_string().contains("maintenance"));
+    }
+    
+    #[test]
+    fn test_parse_ticker_v5_payload() {
+        let body = r#"{
+            "retCode": 0,
+            "retMsg": "OK",
+            "result": {
+                "category": "linear",
+                "list": [{
+                    "symbol": "BTCUSDT",
+                    "lastPrice": "67512.30",
+                    "indexPrice": "67520.11",
+                    "markPrice": "67515.00",
+                    "prevPrice24h": "66890.00",
+                    "price24hPcnt": "0.009302",
+                    "highPrice24h": "67980.00",
+                    "lowPrice24h": "66500.10",
+                    "prevPrice1h": "67400.00",
+                    "openInterest": "55012.341",
+                    "openInterestValue": "3714052871.22",
+                    "turnover24h": "4521300123.4510",
+                    "volume24h": "67210.5520",
+                    "fundingRate": "0.0001",
+                    "nextFundingTime": "1718380800000",
+                    "predictedDeliveryPrice": "",
+                    "basisRate": "",
+                    "deliveryFeeRate": "",
+                    "deliveryTime": "0",
+                    "ask1Size": "1.204",
+                    "bid1Price": "67512.20",
+                    "ask1Price": "67512.40",
+                    "bid1Size": "3.551",
+                    "basis": ""
+                }]
+            },
+            "retExtInfo": {},
+            "time": 1718370000000
+        }"#;
+        
+        let data = BybitPlugin::parse_ticker("BTC/USDT", body).unwrap();
+        assert_eq!(data.symbol, "BTC/USDT");
+        assert_eq!(data.last, 67512.30);
+        assert_eq!(data.bid, 67512.20);
+        assert_eq!(data.ask, 67512.40);
+        assert_eq!(data.volume, 67210.5520);
+        
+        let empty = r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","list":[]}}"#;
+        assert!(BybitPlugin::parse_ticker("BTC/USDT", empty).is_err());
+    }
+    
+    #[test]
+    fn test_parse_funding_rate() {
+        let linear = r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","list":[{
+            "symbol":"BTCUSDT","lastPrice":"67512.30","bid1Price":"67512.20","ask1Price":"67512.40",
+            "fundingRate":"-0.000125","nextFundingTime":"1718380800000"
+        }]}}"#;
+        let funding = BybitPlugin::parse_funding_rate("BTC/USDT", linear).unwrap();
+        assert_eq!(funding.symbol, "BTC/USDT");
+        assert_eq!(funding.rate, -0.000125);
+        assert_eq!(funding.next_funding_time, 1718380800000);
+        
+        // Spot tickers carry no funding fields
+        let spot = r#"{"retCode":0,"retMsg":"OK","result":{"category":"spot","list":[{
+            "symbol":"BTCUSDT","lastPrice":"67512.30","bid1Price":"67512.20","ask1Price":"67512.40"
+        }]}}"#;
+        let err = BybitPlugin::parse_funding_rate("BTCUSDT", spot).unwrap_err();
+        assert_eq!(err.to_string(), "No funding rate for BTCUSDT (not a perpetual?)");
+    }
+    
+    #[test]
+    fn test_parse_set_leverage_not_modified_is_unchanged() {
+        let changed = r#"{"retCode":0,"retMsg":"OK","result":{}}"#;
+        assert_eq!(BybitPlugin::parse_set_leverage(changed).unwrap(), LeverageChange::Changed);
+        
+        let not_modified = r#"{"retCode":110043,"retMsg":"leverage not modified","result":{}}"#;
+        assert_eq!(BybitPlugin::parse_set_leverage(not_modified).unwrap(), LeverageChange::Unchanged);
+        
+        let rejected = r#"{"retCode":10001,"retMsg":"leverage invalid","result":{}}"#;
+        let err = BybitPlugin::parse_set_leverage(rejected).unwrap_err();
+        assert_eq!(err.to_string(), "Bybit API error: 10001 - leverage invalid");
+    }
+    
+    #[test]
+    fn test_parse_closed_pnl() {
+        let body = r#"{
+            "retCode": 0,
+            "retMsg": "OK",
+            "result": {
+                "category": "linear",
+                "list": [{
+                    "symbol": "BTCUSDT",
+                    "orderId": "1c9e2a5f-0d1b-4d3a-9a1e-8f2b1c7e6d54",
+                    "side": "Sell",
+                    "qty": "0.01",
+                    "orderPrice": "67300.0",
+                    "orderType": "Market",
+                    "execType": "Trade",
+                    "closedSize": "0.01",
+                    "cumEntryValue": "670",
+                    "avgEntryPrice": "67000",
+                    "cumExitValue": "675",
+                    "avgExitPrice": "67500",
+                    "closedPnl": "4.5875",
+                    "fillCount": "1",
+                    "leverage": "10",
+                    "createdTime": "1718369000000",
+                    "updatedTime": "1718369000123"
+                }],
+                "nextPageCursor": ""
+            }
+        }"#;
+        
+        let closed = BybitPlugin::parse_closed_pnl(body).unwrap();
+        assert_eq!(closed, vec![ClosedPnl {
+            symbol: "BTCUSDT".to_string(),
+            quantity: 0.01,
+            realized_pnl: 4.5875,
+            closed_at: 1718369000123,
+        }]);
+    }
+    
+    #[test]
+    fn test_parse_executions() {
+        let body = r#"{
+            "retCode": 0,
+            "retMsg": "OK",
+            "result": {
+                "category": "linear",
+                "list": [{
+                    "symbol": "BTCUSDT",
+                    "orderId": "1c9e2a5f-0d1b-4d3a-9a1e-8f2b1c7e6d54",
+                    "orderLinkId": "",
+                    "side": "Sell",
+                    "orderPrice": "67300.0",
+                    "orderQty": "0.02",
+                    "orderType": "Market",
+                    "execFee": "0.37125",
+                    "feeCurrency": "USDT",
+                    "execId": "a8c0f2d1-5b6e-5c4f-9e3d-2f1a0b9c8d7e",
+                    "execPrice": "67500",
+                    "execQty": "0.01",
+                    "execType": "Trade",
+                    "execValue": "675",
+                    "execTime": "1718369000123",
+                    "isMaker": false,
+                    "feeRate": "0.00055"
+                }],
+                "nextPageCursor": ""
+            }
+        }"#;
+        
+        let trades = BybitPlugin::parse_executions(body).unwrap();
+        assert_eq!(trades, vec![Trade {
+            trade_id: "a8c0f2d1-5b6e-5c4f-9e3d-2f1a0b9c8d7e".to_string(),
+            order_id: "1c9e2a5f-0d1b-4d3a-9a1e-8f2b1c7e6d54".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Sell,
+            price: 67500.0,
+            quantity: 0.01,
+            fee: 0.37125,
+            fee_currency: Some("USDT".to_string()),
+            timestamp: 1718369000123,
+        }]);
+    }
+    
+    #[test]
+    fn test_parse_wallet_balance() {
+        let body = r#"{
+            "retCode": 0,
+            "retMsg": "OK",
+            "result": {
+                "list": [{
+                    "accountType": "UNIFIED",
+                    "coin": [
+                        { "coin": "USDT", "walletBalance": "1000.5", "locked": "200" },
+                        { "coin": "BTC", "walletBalance": "0.01", "locked": "" }
+                    ]
+                }]
+            }
+        }"#;
+        
+        let balances = BybitPlugin::parse_wallet_balance(body).unwrap();
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[0], Balance { asset: "USDT".to_string(), free: 800.5, locked: 200.0, total: 1000.5 });
+        assert_eq!(balances[1].free, 0.01);
+        assert_eq!(balances[1].locked, 0.0);
+    }
+    
+    #[test]
+    fn test_parse_order_list() {
+        let body = r#"{
+            "retCode": 0,
+            "retMsg": "OK",
+            "result": {
+                "category": "linear",
+                "nextPageCursor": "",
+                "list": [
+                    {
+                        "orderId": "fd4300ae-7847-404e-b947-b46980a4d140",
+                        "orderLinkId": "fks-1",
+                        "symbol": "ETHUSDT",
+                        "side": "Buy",
+                        "orderType": "Limit",
+                        "price": "1600.00",
+                        "qty": "0.10",
+                        "cumExecQty": "0.04",
+                        "orderStatus": "PartiallyFilled",
+                        "createdTime": "1684738540559"
+                    },
+                    {
+                        "orderId": "a1",
+                        "orderLinkId": "",
+                        "symbol": "BTCUSDT",
+                        "side": "Sell",
+                        "orderType": "Market",
+                        "price": "0",
+                        "qty": "1",
+                        "cumExecQty": "0",
+                        "orderStatus": "New",
+                        "createdTime": "1684738540000"
+                    }
+                ]
+            }
+        }"#;
+        
+        let page = BybitPlugin::parse_order_page(body).unwrap();
+        assert!(page.next_cursor.is_none());
+        let orders = page.orders;
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].status, OrderStatus::PartiallyFilled);
+        assert_eq!(orders[0].client_order_id.as_deref(), Some("fks-1"));
+        assert_eq!(orders[0].filled_quantity, 0.04);
+        assert_eq!(orders[0].price, Some(1600.0));
+        assert_eq!(orders[0].timestamp, 1684738540559);
+        assert_eq!(orders[1].side, OrderSide::Sell);
+        assert_eq!(orders[1].status, OrderStatus::Open);
+        assert_eq!(orders[1].client_order_id, None);
+        assert_eq!(orders[1].price, None);
+    }
+    
+    /// Start a mock Bybit API whose private endpoints reject the credentials
+    async fn mock_bybit_rejecting_auth() -> String {
+        use axum::{routing::get, Json, Router};
+        
+        let app = Router::new()
+            .route("/v5/market/tickers", get(|| async {
+                Json(serde_json::json!({
+                    "retCode": 0,
+                    "retMsg": "OK",
+                    "result": {
+                        "category": "linear",
+                        "list": [{
+                            "symbol": "BTCUSDT",
+                            "lastPrice": "67500.00",
+                            "bid1Price": "67499.50",
+                            "ask1Price": "67500.50",
+                            "volume24h": "1234.5"
+                        }]
+                    }
+                }))
+            }))
+            .route("/v5/account/wallet-balance", get(|| async {
+                Json(serde_json::json!({ "retCode": 10003, "retMsg": "API key is invalid.", "result": {} }))
+            }));
+        
+        let base_url = spawn_mock_exchange(app).await;
+        
+        base_url
+    }
+    
+    #[tokio::test]
+    async fn test_health_status_auth_failed() {
+        let base_url = mock_bybit_rejecting_auth().await;
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "api_key": "bad-key",
+            "api_secret": "bad-secret"
+        })).await;
+        
+        // Market data is reachable, but the credentials are not accepted
+        assert_eq!(plugin.fetch_data("BTCUSDT").await.unwrap().last, 67500.0);
+        assert_eq!(plugin.health_status().await, HealthStatus::AuthFailed);
+        assert!(!plugin.health_check().await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_health_check_queries_configured_symbol() {
+        use axum::{extract::Query, routing::get, Json, Router};
+        use std::sync::Mutex;
+        
+        let queried: Arc<Mutex<HashMap<String, usize>>> = Arc::default();
+        let counts = queried.clone();
+        let app = Router::new()
+            .route("/v5/market/tickers", get(move |Query(params): Query<HashMap<String, String>>| async move {
+                let symbol = params.get("symbol").cloned().unwrap_or_default();
+                *counts.lock().unwrap().entry(symbol.clone()).or_default() += 1;
+                Json(serde_json::json!({
+                    "retCode": 0,
+                    "retMsg": "OK",
+                    "result": {
+                        "category": "spot",
+                        "list": [{ "symbol": symbol, "lastPrice": "3500.00", "bid1Price": "3499.90", "ask1Price": "3500.10", "volume24h": "10" }]
+                    }
+                }))
+            }))
+            .route("/v5/account/wallet-balance", get(|| async {
+                Json(serde_json::json!({ "retCode": 0, "retMsg": "OK", "result": { "list": [] } }))
+            }));
+        
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "category": "spot",
+            "health_check_symbol": "ETHUSDC"
+        })).await;
+        
+        assert_eq!(plugin.health_status().await, HealthStatus::Healthy);
+        let queried = queried.lock().unwrap().clone();
+        assert_eq!(queried, HashMap::from([("ETHUSDC".to_string(), 1)]));
+    }
+    
+    #[tokio::test]
+    async fn test_list_orders_page_passes_bybit_cursor_through() {
+        use axum::{extract::Query, routing::get, Json, Router};
+        
+        let app = Router::new()
+            .route("/v5/order/realtime", get(|Query(params): Query<HashMap<String, String>>| async move {
+                let order = |id: &str| serde_json::json!({
+                    "orderId": id, "orderLinkId": "", "symbol": "BTCUSDT", "side": "Buy", "orderType": "Limit",
+                    "price": "60000", "qty": "0.01", "cumExecQty": "0", "orderStatus": "New", "createdTime": "1700000000000"
+                });
+                let settle_coin = params.get("settleCoin").map(String::as_str);
+                let (list, next) = match (settle_coin, params.get("cursor").map(String::as_str)) {
+                    (Some("USDT"), None) => (vec![order("1"), order("2")], "page-2"),
+                    (Some("USDT"), Some("page-2")) => (vec![order("3")], ""),
+                    (Some("USDC"), None) => (vec![order("4")], ""),
+                    _ => (vec![], ""),
+                };
+                assert_eq!(params.get("limit").map(String::as_str), Some("2"));
+                Json(serde_json::json!({
+                    "retCode": 0,
+                    "retMsg": "OK",
+                    "result": { "category": "linear", "nextPageCursor": next, "list": list }
+                }))
+            }));
+        
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({})).await;
+        
+        let first = plugin.list_orders_page(None, None, 2).await.unwrap();
+        assert_eq!(first.orders.iter().map(|o| o.order_id.as_str()).collect::<Vec<_>>(), vec!["1", "2"]);
+        assert_eq!(first.next_cursor.as_deref(), Some("USDT:page-2"));
+        
+        // The last USDT page hands over to the next settle coin
+        let second = plugin.list_orders_page(None, first.next_cursor.as_deref(), 2).await.unwrap();
+        assert_eq!(second.orders.len(), 1);
+        assert_eq!(second.orders[0].order_id, "3");
+        assert_eq!(second.next_cursor.as_deref(), Some("USDC:"));
+        
+        let last = plugin.list_orders_page(None, second.next_cursor.as_deref(), 2).await.unwrap();
+        assert_eq!(last.orders[0].order_id, "4");
+        assert!(last.next_cursor.is_none());
+        
+        let err = plugin.list_orders_page(None, Some("page-2"), 2).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Exchange { code: INVALID_CURSOR, .. })));
+    }
+    
+    #[tokio::test]
+    async fn test_configured_user_agent_and_headers_sent() {
+        use axum::{http::HeaderMap, routing::get, Json, Router};
+        use std::sync::Mutex;
+        
+        let seen: Arc<Mutex<Vec<(String, String)>>> = Arc::default();
+        let captured = seen.clone();
+        let app = Router::new()
+            .route("/v5/market/tickers", get(move |headers: HeaderMap| async move {
+                let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+                captured.lock().unwrap().push((header("user-agent"), header("referer")));
+                Json(serde_json::json!({
+                    "retCode": 0,
+                    "retMsg": "OK",
+                    "result": {
+                        "category": "linear",
+                        "list": [{ "symbol": "BTCUSDT", "lastPrice": "67500.00", "bid1Price": "67499.50", "ask1Price": "67500.50", "volume24h": "1" }]
+                    }
+                }))
+            }));
+        
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "user_agent": "fks-execution/1.0",
+            "headers": { "Referer": "fks" }
+        })).await;
+        plugin.fetch_data("BTCUSDT").await.unwrap();
+        
+        let seen = seen.lock().unwrap().clone();
+        assert_eq!(seen, vec![("fks-execution/1.0".to_string(), "fks".to_string())]);
+    }
+    
+    #[tokio::test]
+    async fn test_health_status_unconfigured_and_unreachable() {
+        let plugin = BybitPlugin::new("test-bybit");
+        assert_eq!(plugin.health_status().await, HealthStatus::Unconfigured);
+        
+        let plugin = init_with_base_url("http://127.0.0.1:1", serde_json::json!({})).await;
+        assert_eq!(plugin.health_status().await, HealthStatus::Unreachable);
+    }
+    
+    #[test]
+    fn test_classify_auth_response() {
+        use reqwest::StatusCode;
+        
+        let ok = r#"{"retCode":0,"retMsg":"OK","result":{"list":[]}}"#;
+        assert_eq!(BybitPlugin::classify_auth_response(StatusCode::OK, ok), HealthStatus::Healthy);
+        
+        let bad_sign = r#"{"retCode":10004,"retMsg":"error sign!","result":{}}"#;
+        assert_eq!(BybitPlugin::classify_auth_response(StatusCode::OK, bad_sign), HealthStatus::AuthFailed);
+        assert_eq!(BybitPlugin::classify_auth_response(StatusCode::UNAUTHORIZED, ""), HealthStatus::AuthFailed);
+        
+        // Signature accepted, request rejected for another reason
+        let params = r#"{"retCode":10001,"retMsg":"accountType only support UNIFIED","result":{}}"#;
+        assert_eq!(BybitPlugin::classify_auth_response(StatusCode::OK, params), HealthStatus::Healthy);
+        
+        assert_eq!(BybitPlugin::classify_auth_response(StatusCode::BAD_GATEWAY, "<html>"), HealthStatus::Unreachable);
+    }
+    
+    #[tokio::test]
+    async fn test_quote_alias_applied_to_symbol() {
+        let mut plugin = BybitPlugin::new("test-bybit");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "quote_aliases": { "USD": "USDT" }
+        })).await.unwrap();
+        
+        let config = plugin.config.read().await.clone().unwrap();
+        assert_eq!(plugin.normalize_symbol(&config, "BTCUSD"), "BTCUSDT");
+        assert_eq!(plugin.normalize_symbol(&config, "BTCUSDT"), "BTCUSDT");
+        assert_eq!(plugin.normalize_symbol(&config, "ETHBTC"), "ETHBTC");
+    }
+    
+    #[tokio::test]
+    async fn test_exchange_time_source_offsets_signed_timestamp() {
+        use axum::{routing::get, Json, Router};
+        
+        // Server clock five seconds ahead of ours
+        let app = Router::new().route("/v5/market/time", get(|| async {
+            Json(serde_json::json!({ "retCode": 0, "retMsg": "OK", "result": {}, "time": now_millis() + 5000 }))
+        }));
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "time_source": "exchange"
+        })).await;
+        
+        let before = now_millis();
+        let headers = plugin.create_headers_get("test-key", "test-secret", 5000, "category=linear").await.unwrap();
+        let timestamp: i64 = headers["X-BAPI-TIMESTAMP"].to_str().unwrap().parse().unwrap();
+        
+        assert!((plugin.time_sync.offset_ms() - 5000).abs() < 1000, "offset {}", plugin.time_sync.offset_ms());
+        assert!(timestamp >= before + 4000 && timestamp <= now_millis() + 6000, "timestamp {} vs {}", timestamp, before);
+        let expected = BybitPlugin::generate_signature("test-secret", &format!("{}test-key5000category=linear", timestamp));
+        assert_eq!(headers["X-BAPI-SIGN"].to_str().unwrap(), expected);
+    }
+    
+    #[test]
+    fn test_perpetual_symbol_variants_match_listed_instruments() {
+        let known: HashSet<String> = ["BTCUSDT", "BTCPERP", "ETHUSDT", "ETHPERP", "SOLUSDT"]
+            .into_iter().map(str::to_string).collect();
+        let resolve = |symbol: &str| BybitPlugin::match_instrument(symbol, perpetual_candidates(symbol), &known);
+        
+        assert_eq!(resolve("BTC-PERP").as_deref(), Some("BTCPERP"));
+        assert_eq!(resolve("btc-perp").as_deref(), Some("BTCPERP"));
+        assert_eq!(resolve("BTC/USDC:USDC").as_deref(), Some("BTCPERP"));
+        assert_eq!(resolve("ETHUSDC").as_deref(), Some("ETHPERP"));
+        assert_eq!(resolve("BTCUSDT.P").as_deref(), Some("BTCUSDT"));
+        assert_eq!(resolve("ETH/USDT:USDT").as_deref(), Some("ETHUSDT"));
+        assert_eq!(resolve("BTCUSDT-PERP").as_deref(), Some("BTCUSDT"));
+        // No USDC contract listed: fall back to the USDT perpetual
+        assert_eq!(resolve("SOL-PERP").as_deref(), Some("SOLUSDT"));
+        
+        // Listed or unknown symbols are left alone
+        assert_eq!(resolve("BTCPERP"), None);
+        assert_eq!(resolve("BTCUSDT"), None);
+        assert_eq!(resolve("DOGE-PERP"), None);
+        assert!(perpetual_candidates("BTCUSDT").is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_order_symbol_remapped_to_listed_perpetual() {
+        use axum::{routing::get, Json, Router};
+        
+        let app = Router::new().route("/v5/market/instruments-info", get(|| async {
+            Json(serde_json::json!({
+                "retCode": 0,
+                "retMsg": "OK",
+                "result": {
+                    "category": "linear",
+                    "list": [
+                        { "symbol": "BTCPERP", "status": "Trading", "lotSizeFilter": { "qtyStep": "0.001" }, "priceFilter": { "tickSize": "0.5" } },
+                        { "symbol": "BTCUSDT", "status": "Trading", "lotSizeFilter": { "qtyStep": "0.001" }, "priceFilter": { "tickSize": "0.1" } }
+                    ],
+                    "nextPageCursor": ""
+                }
+            }))
+        }));
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({})).await;
+        
+        let mut order = test_order();
+        order.symbol = "BTC-PERP".to_string();
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.body["symbol"], "BTCPERP");
+        
+        order.symbol = "BTCUSDT.P".to_string();
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.body["symbol"], "BTCUSDT");
+    }
+    
+    #[test]
+    fn test_parse_instrument_and_position() {
+        let body = r#"{
+            "retCode": 0,
+            "retMsg": "OK",
+            "result": {
+                "category": "linear",
+                "list": [{
+                    "symbol": "BTCUSDT",
+                    "lotSizeFilter": { "qtyStep": "0.001", "minOrderQty": "0.001", "maxOrderQty": "100" },
+                    "priceFilter": { "tickSize": "0.10" }
+                }]
+            }
+        }"#;
+        let info = BybitPlugin::parse_instrument(body).unwrap();
+        assert_eq!(info.qty_step, 0.001);
+        assert_eq!(info.tick_size, 0.1);
+        
+        let position: BybitPosition = serde_json::from_str(r#"{
+            "symbol": "BTCUSDT",
+            "side": "Sell",
+            "size": "0.25",
+            "avgPrice": "67000",
+            "markPrice": "66500",
+            "unrealisedPnl": "125",
+            "leverage": "10",
+            "positionValue": "16750"
+        }"#).unwrap();
+        let position = position.into_position().unwrap();
+        assert_eq!(position.side, PositionSide::Short);
+        assert_eq!(position.size, 0.25);
+        assert_eq!(position.entry_price, 67000.0);
+    }
+    
+    #[test]
+    fn test_hedge_mode_position_idx() {
+        assert_eq!(PositionMode::Hedge.position_idx(&OrderSide::Buy, false), 1);
+        assert_eq!(PositionMode::Hedge.position_idx(&OrderSide::Sell, false), 2);
+        assert_eq!(PositionMode::OneWay.position_idx(&OrderSide::Buy, false), 0);
+        assert_eq!(PositionMode::OneWay.position_idx(&OrderSide::Sell, true), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_hedge_mode_reduce_only_targets_closed_position() {
+        let plugin = init_plugin().await;
+        let mut config = plugin.config.read().await.clone().unwrap();
+        config.position_mode = Some(PositionMode::Hedge);
+        
+        // Selling to close a long uses the long side's idx, buying to close a short the short's
+        let mut order = Order { side: OrderSide::Sell, reduce_only: true, ..test_order() };
+        let params = plugin.build_order_params(&config, &order);
+        assert_eq!(params["positionIdx"], 1);
+        assert_eq!(params["reduceOnly"], true);
+        
+        order.side = OrderSide::Buy;
+        assert_eq!(plugin.build_order_params(&config, &order)["positionIdx"], 2);
+        
+        // Opening orders keep the side's own idx
+        order.reduce_only = false;
+        assert_eq!(plugin.build_order_params(&config, &order)["positionIdx"], 1);
+    }
+    
+    #[test]
+    fn test_position_mode_config() {
+        let config: BybitConfig = serde_json::from_value(serde_json::json!({
+            "api_key": "k",
+            "api_secret": "s",
+            "position_mode": "hedge"
+        })).unwrap();
+        assert_eq!(config.position_mode, Some(PositionMode::Hedge));
+        
+        let config: BybitConfig = serde_json::from_value(serde_json::json!({
+            "api_key": "k",
+            "api_secret": "s"
+        })).unwrap();
+        assert_eq!(config.position_mode.unwrap_or_default(), PositionMode::OneWay);
+    }
+    
+    #[tokio::test]
+    async fn test_http_503_is_maintenance() {
+        let plugin = init_plugin().await;
+        
+        assert!(plugin.check_maintenance(reqwest::StatusCode::SERVICE_UNAVAILABLE, "").is_some());
+        assert!(plugin.in_maintenance());
+    }
+    
+    #[tokio::test]
+    async fn test_regular_error_is_not_maintenance() {
+        let plugin = init_plugin().await;
+        
+        let body = r#"{"retCode":10001,"retMsg":"params error","result":{}}"#;
+        assert!(plugin.check_maintenance(reqwest::StatusCode::OK, body).is_none());
+        assert!(!plugin.in_maintenance());
+    }
+    
+    #[tokio::test]
+    async fn test_spot_market_buy_omits_futures_params() {
+        let mut plugin = BybitPlugin::new("test-bybit");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "category": "spot"
+        })).await.unwrap();
+        
+        let config = plugin.config.read().await.clone().unwrap();
+        let mut order = test_order();
+        order.reduce_only = true;
+        let params = plugin.build_order_params(&config, &order);
+        
+        assert_eq!(params["category"], "spot");
+        assert_eq!(params["orderType"], "Market");
+        assert_eq!(params["marketUnit"], "baseCoin");
+        assert_eq!(params["qty"], "0.01");
+        assert!(params.get("positionIdx").is_none());
+        assert!(params.get("leverage").is_none());
+        assert!(params.get("reduceOnly").is_none());
+        
+        // Limit orders are priced, so no marketUnit
+        order.order_type = OrderType::Limit;
+        order.price = Some(67000.0);
+        let params = plugin.build_order_params(&config, &order);
+        assert!(params.get("marketUnit").is_none());
+        assert_eq!(params["price"], "67000");
+    }
+    
+    #[tokio::test]
+    async fn test_spot_market_unit_configurable() {
+        let mut plugin = BybitPlugin::new("test-bybit");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "category": "spot",
+            "spot_market_unit": "quoteCoin"
+        })).await.unwrap();
+        
+        let config = plugin.config.read().await.clone().unwrap();
+        let params = plugin.build_order_params(&config, &test_order());
+        assert_eq!(params["marketUnit"], "quoteCoin");
+    }
+    
+    #[tokio::test]
+    async fn test_quote_quantity_sent_as_quote_coin_on_spot_only() {
+        let mut plugin = BybitPlugin::new("test-bybit");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "category": "spot"
+        })).await.unwrap();
+        let mut config = plugin.config.read().await.clone().unwrap();
+        
+        let mut order = test_order();
+        order.quantity = 250.0;
+        order.quantity_type = QuantityType::Quote;
+        assert!(plugin.check_quantity_type(&config, &order).is_ok());
+        let params = plugin.build_order_params(&config, &order);
+        assert_eq!(params["marketUnit"], "quoteCoin");
+        assert_eq!(params["qty"], "250");
+        
+        // Linear contracts and priced orders have no quote sizing
+        order.order_type = OrderType::Limit;
+        order.price = Some(67000.0);
+        assert!(matches!(plugin.check_quantity_type(&config, &order), Err(ExecutionError::Unsupported(_))));
+        
+        order.order_type = OrderType::Market;
+        order.price = None;
+        config.category = "linear".to_string();
+        assert!(matches!(plugin.check_quantity_type(&config, &order), Err(ExecutionError::Unsupported(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_leverage_clamped_to_max_leverage() {
+        use axum::{routing::post, Json, Router};
+        use std::sync::Mutex;
+        
+        let sent: Arc<Mutex<Vec<serde_json::Value>>> = Arc::default();
+        let requests = sent.clone();
+        let app = Router::new().route("/v5/position/set-leverage", post(move |Json(body): Json<serde_json::Value>| async move {
+            requests.lock().unwrap().push(body);
+            Json(serde_json::json!({ "retCode": 0, "retMsg": "OK", "result": {} }))
+        }));
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "leverage": 100,
+            "max_leverage": 20
+        })).await;
+        
+        // Leverage sent with each order
+        let config = plugin.config.read().await.clone().unwrap();
+        assert_eq!(plugin.build_order_params(&config, &test_order())["leverage"], "20");
+        
+        plugin.set_leverage("BTCUSDT", 100).await.unwrap();
+        plugin.set_leverage("BTCUSDT", 5).await.unwrap();
+        let sent = sent.lock().unwrap().clone();
+        assert_eq!(sent[0]["buyLeverage"], "20");
+        assert_eq!(sent[0]["sellLeverage"], "20");
+        assert_eq!(sent[1]["buyLeverage"], "5");
+        
+        // An order's own leverage is capped the same way
+        let mut order = test_order();
+        order.leverage = Some(50);
+        assert_eq!(plugin.preview_order(&order).await.unwrap().body["leverage"], "20");
+        order.leverage = Some(5);
+        assert_eq!(plugin.preview_order(&order).await.unwrap().body["leverage"], "5");
+        
+        // Spot orders carry no leverage to override
+        let spot = BybitConfig { category: "spot".to_string(), ..config };
+        assert!(matches!(plugin.order_leverage(&spot, &order), Err(ExecutionError::Unsupported(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_strict_leverage_rejects_above_max() {
+        let mut plugin = BybitPlugin::new("test-bybit");
+        let err = plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "leverage": 100,
+            "max_leverage": 20,
+            "strict_leverage": true
+        })).await.unwrap_err();
+        assert_eq!(err.to_string(), "leverage_above_max: test-bybit leverage 100x exceeds max_leverage 20x");
+        
+        // Nothing is sent for a rejected set-leverage request
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "max_leverage": 20,
+            "strict_leverage": true,
+            "base_url": "http://127.0.0.1:1"
+        })).await.unwrap();
+        let err = plugin.set_leverage("BTCUSDT", 50).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Exchange { code: LEVERAGE_ABOVE_MAX, .. })));
+        assert_eq!(err.to_string(), "leverage_above_max: test-bybit leverage 50x exceeds max_leverage 20x");
+    }
+    
+    #[tokio::test]
+    async fn test_linear_order_keeps_futures_params() {
+        let plugin = init_plugin().await;
+        let config = plugin.config.read().await.clone().unwrap();
+        let mut order = test_order();
+        order.reduce_only = true;
+        let params = plugin.build_order_params(&config, &order);
+        
+        assert_eq!(params["category"], "linear");
+        assert_eq!(params["positionIdx"], 0);
+        assert_eq!(params["leverage"], "10");
+        assert_eq!(params["reduceOnly"], true);
+        assert!(params.get("marketUnit").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_bulk_positions_one_request_per_settle_coin() {
+        use axum::{extract::Query, routing::get, Json, Router};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        static REQUESTS: AtomicUsize = AtomicUsize::new(0);
+        
+        let app = Router::new().route("/v5/position/list", get(|Query(params): Query<HashMap<String, String>>| async move {
+            REQUESTS.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(params.get("limit").map(String::as_str), Some("200"));
+            let list = match params.get("settleCoin").map(String::as_str) {
+                Some("USDT") => serde_json::json!([
+                    { "symbol": "BTCUSDT", "side": "Buy", "size": "0.5", "avgPrice": "67000", "markPrice": "67500", "unrealisedPnl": "250", "leverage": "10" },
+                    { "symbol": "ETHUSDT", "side": "Sell", "size": "2", "avgPrice": "3550", "markPrice": "3500", "unrealisedPnl": "100", "leverage": "5" },
+                    { "symbol": "SOLUSDT", "side": "", "size": "0", "avgPrice": "0", "markPrice": "150", "unrealisedPnl": "0", "leverage": "10" }
+                ]),
+                Some("USDC") => serde_json::json!([
+                    { "symbol": "BTCPERP", "side": "Buy", "size": "0.1", "avgPrice": "67100", "markPrice": "67500", "unrealisedPnl": "40", "leverage": "10" }
+                ]),
+                other => panic!("unexpected settleCoin {:?}", other),
+            };
+            Json(serde_json::json!({
+                "retCode": 0,
+                "retMsg": "OK",
+                "result": { "category": "linear", "nextPageCursor": "", "list": list }
+            }))
+        }));
+        
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({})).await;
+        
+        let positions = plugin.get_all_positions().await.unwrap();
+        assert_eq!(REQUESTS.load(Ordering::SeqCst), 2);
+        assert_eq!(positions.len(), 3);
+        assert_eq!(positions[0].symbol, "BTCUSDT");
+        assert_eq!(positions[1].side, PositionSide::Short);
+        assert_eq!(positions[2].symbol, "BTCPERP");
+        
+        // Only the configured settle coins are queried
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "settle_coins": ["USDC"]
+        })).await;
+        
+        let positions = plugin.get_all_positions().await.unwrap();
+        assert_eq!(REQUESTS.load(Ordering::SeqCst), 3);
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].symbol, "BTCPERP");
     }
 }
@@ -3,9 +3,10 @@
 //! Integrates with external CCXT services via HTTP API calls.
 //! The CCXT service should be running separately and accessible via HTTP.
 
-use super::{ExecutionPlugin, ExecutionResult, MarketData, Order, OrderSide, OrderType};
+use super::headers::ClientHeaders;
+use super::tls::ClientTlsConfig;
+use super::{merge_extra_params, now_millis, reject_display_quantity, reject_leverage, reject_max_slippage, reject_post_only, reject_quote_quantity, reject_testnet_override, ExecutionPlugin, ExecutionResult, MarketData, Order, OrderSide, OrderType, PreparedRequest};
 use async_trait::async_trait;
-use chrono::Utc;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
@@ -13,7 +14,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 /// Configuration for CCXT plugin
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CCXTConfig {
     /// Base URL of the CCXT service (e.g., "http://localhost:8000")
     pub base_url: String,
@@ -28,15 +29,88 @@ pub struct CCXTConfig {
     /// Whether to use testnet
     #[serde(default)]
     pub testnet: bool,
+    
+    /// Path of the bridge's webhook endpoint (default: "/webhook/tradingview")
+    #[serde(default = "default_webhook_path")]
+    pub webhook_path: String,
+    
+    /// Body encoding the bridge expects (default: JSON)
+    #[serde(default)]
+    pub content_type: WebhookContentType,
+    
+    /// How the `X-Webhook-Signature` header is computed (default: hex HMAC-SHA256)
+    #[serde(default)]
+    pub signature_algo: SignatureAlgo,
+    
+    /// Client certificate / private CA for endpoints requiring mutual TLS
+    #[serde(flatten)]
+    pub tls: ClientTlsConfig,
+    
+    /// `user_agent` / `headers` sent on every request
+    #[serde(flatten)]
+    pub client_headers: ClientHeaders,
 }
 
 fn default_exchange() -> String {
     "binance".to_string()
 }
 
+fn default_webhook_path() -> String {
+    "/webhook/tradingview".to_string()
+}
+
+/// Encoding of the webhook body sent to the CCXT bridge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookContentType {
+    /// `application/json`
+    #[default]
+    Json,
+    
+    /// `application/x-www-form-urlencoded`
+    Form,
+}
+
+impl WebhookContentType {
+    fn mime(self) -> &'static str {
+        match self {
+            WebhookContentType::Json => "application/json",
+            WebhookContentType::Form => "application/x-www-form-urlencoded",
+        }
+    }
+    
+    /// Serialize the payload exactly as it will be transmitted (and signed)
+    fn encode(self, payload: &serde_json::Value) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(match self {
+            WebhookContentType::Json => serde_json::to_string(payload)?,
+            WebhookContentType::Form => {
+                // Absent optional fields are left out rather than sent empty
+                let fields: serde_json::Map<String, serde_json::Value> = payload.as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(_, v)| !v.is_null())
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+                serde_qs::to_string(&fields)?
+            }
+        })
+    }
+}
+
+/// Webhook signature scheme the CCXT bridge verifies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureAlgo {
+    #[default]
+    HmacSha256Hex,
+    HmacSha256Base64,
+    HmacSha512Hex,
+}
+
 /// TradingView webhook payload format
 #[derive(Debug, Serialize)]
 struct WebhookPayload {
+    /// Unix millis
     timestamp: i64,
     symbol: String,
     action: String, // "buy" or "sell"
@@ -63,6 +137,8 @@ pub struct CCXTPlugin {
     name: String,
     config: Arc<RwLock<Option<CCXTConfig>>>,
     client: Client,
+    /// The bridge was configured for the exchange's testnet
+    testnet: bool,
 }
 
 impl CCXTPlugin {
@@ -74,21 +150,99 @@ impl CCXTPlugin {
                 .timeout(std::time::Duration::from_secs(30))
                 .build()
                 .expect("Failed to create HTTP client"),
+            testnet: false,
         }
     }
     
-    /// Generate HMAC-SHA256 signature for webhook
-    fn generate_signature(payload: &str, secret: &str) -> String {
-        use hmac::{Hmac, Mac};
-        use sha2::Sha256;
+    /// Convert an order into the bridge's webhook payload (timestamp in Unix millis)
+    fn build_payload(order: &Order) -> WebhookPayload {
+        let action = match order.side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        };
+        
+        let order_type_str = match order.order_type {
+            OrderType::Market => "market",
+            OrderType::Limit => "limit",
+            OrderType::Stop => "stop",
+            OrderType::StopLimit => "stop_limit",
+            OrderType::TakeProfit => "take_profit",
+            OrderType::StopLoss => "stop_loss",
+        };
+        
+        WebhookPayload {
+            timestamp: now_millis(),
+            symbol: order.symbol.clone(),
+            action: action.to_string(),
+            order_type: order_type_str.to_string(),
+            quantity: order.quantity,
+            price: order.price,
+            stop_loss: order.stop_loss,
+            take_profit: order.take_profit,
+            confidence: order.confidence,
+        }
+    }
+    
+    /// The webhook request for an order
+    fn build_order_request(config: &CCXTConfig, order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        let path = format!("/{}", config.webhook_path.trim_start_matches('/'));
+        let mut body = serde_json::to_value(Self::build_payload(order))?;
+        merge_extra_params("ccxt", &mut body, order);
+        Ok(PreparedRequest {
+            method: "POST".to_string(),
+            url: format!("{}{}", config.base_url.trim_end_matches('/'), path),
+            path,
+            body,
+        })
+    }
+    
+    /// Encode, sign and submit a prepared request, returning the status and raw body
+    async fn send(
+        &self,
+        config: &CCXTConfig,
+        request: &PreparedRequest,
+    ) -> Result<(reqwest::StatusCode, String), Box<dyn Error + Send + Sync>> {
+        // Sign the exact bytes sent
+        let body = config.content_type.encode(&request.body)?;
+        let signature = Self::generate_signature(&body, &config.webhook_secret, config.signature_algo);
+        
+        let response = self.client
+            .post(&request.url)
+            .header("X-Webhook-Signature", signature)
+            .header("Content-Type", config.content_type.mime())
+            .body(body)
+            .send()
+            .await?;
         
-        type HmacSha256 = Hmac<Sha256>;
+        let status = response.status();
+        let text = response.text().await?;
+        Ok((status, text))
+    }
+    
+    /// Generate the webhook signature with the configured algorithm
+    fn generate_signature(payload: &str, secret: &str, algo: SignatureAlgo) -> String {
+        use base64::{Engine as _, engine::general_purpose};
+        use hmac::{Hmac, Mac};
+        use sha2::{Sha256, Sha512};
         
-        let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
-            .expect("HMAC can take key of any size");
-        mac.update(payload.as_bytes());
-        let result = mac.finalize();
-        hex::encode(result.into_bytes())
+        let digest = match algo {
+            SignatureAlgo::HmacSha256Hex | SignatureAlgo::HmacSha256Base64 => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+                    .expect("HMAC can take key of any size");
+                mac.update(payload.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+            SignatureAlgo::HmacSha512Hex => {
+                let mut mac = Hmac::<Sha512>::new_from_slice(secret.as_bytes())
+                    .expect("HMAC can take key of any size");
+                mac.update(payload.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+        };
+        match algo {
+            SignatureAlgo::HmacSha256Base64 => general_purpose::STANDARD.encode(digest),
+            SignatureAlgo::HmacSha256Hex | SignatureAlgo::HmacSha512Hex => hex::encode(digest),
+        }
     }
 }
 
@@ -97,6 +251,13 @@ impl ExecutionPlugin for CCXTPlugin {
     async fn init(&mut self, config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
         let ccxt_config: CCXTConfig = serde_json::from_value(config)?;
         
+        // Client certificate / private CA and custom headers, validated here rather than on the first request
+        if ccxt_config.tls.is_configured() || ccxt_config.client_headers.is_configured() {
+            self.client = ccxt_config.tls.build_client(
+                ccxt_config.client_headers.apply(Client::builder().timeout(std::time::Duration::from_secs(30)))?
+            )?;
+        }
+        
         tracing::info!(
             plugin = %self.name,
             base_url = %ccxt_config.base_url,
@@ -128,6 +289,7 @@ impl ExecutionPlugin for CCXTPlugin {
             }
         }
         
+        self.testnet = ccxt_config.testnet;
         *self.config.write().await = Some(ccxt_config);
         
         tracing::info!(plugin = %self.name, "CCXT plugin initialized successfully");
@@ -138,39 +300,18 @@ impl ExecutionPlugin for CCXTPlugin {
         &self,
         order: Order,
     ) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
+        reject_display_quantity(&self.name, &order)?;
+        reject_post_only(&self.name, &order)?;
+        reject_testnet_override(&self.name, &order)?;
+        reject_leverage(&self.name, &order)?;
+        reject_max_slippage(&self.name, &order)?;
+        reject_quote_quantity(&self.name, &order)?;
+        
         let config = self.config.read().await;
         let config = config.as_ref()
             .ok_or("Plugin not initialized")?;
         
-        // Convert Order to webhook payload
-        let action = match order.side {
-            OrderSide::Buy => "buy",
-            OrderSide::Sell => "sell",
-        };
-        
-        let order_type_str = match order.order_type {
-            OrderType::Market => "market",
-            OrderType::Limit => "limit",
-            OrderType::Stop => "stop",
-            OrderType::StopLimit => "stop_limit",
-            OrderType::TakeProfit => "take_profit",
-            OrderType::StopLoss => "stop_loss",
-        };
-        
-        let payload = WebhookPayload {
-            timestamp: Utc::now().timestamp(),
-            symbol: order.symbol.clone(),
-            action: action.to_string(),
-            order_type: order_type_str.to_string(),
-            quantity: order.quantity,
-            price: order.price,
-            stop_loss: order.stop_loss,
-            take_profit: order.take_profit,
-            confidence: order.confidence,
-        };
-        
-        let payload_json = serde_json::to_string(&payload)?;
-        let signature = Self::generate_signature(&payload_json, &config.webhook_secret);
+        let request = Self::build_order_request(config, &order)?;
         
         tracing::info!(
             plugin = %self.name,
@@ -180,18 +321,13 @@ impl ExecutionPlugin for CCXTPlugin {
             "Sending order to CCXT service"
         );
         
-        // Send webhook to CCXT service
-        let webhook_url = format!("{}/webhook/tradingview", config.base_url);
-        let response = self.client
-            .post(&webhook_url)
-            .header("X-Webhook-Signature", signature)
-            .header("Content-Type", "application/json")
-            .body(payload_json)
-            .send()
-            .await?;
+        let (status_code, text) = self.send(config, &request).await?;
         
-        let status_code = response.status();
-        let webhook_response: WebhookResponse = response.json().await?;
+        // A failed request without a JSON body never reached the bridge's handler
+        if !status_code.is_success() && serde_json::from_str::<serde::de::IgnoredAny>(&text).is_err() {
+            return Err(super::http_error("CCXT bridge", status_code, &text));
+        }
+        let webhook_response: WebhookResponse = super::parse_response("CCXT bridge", &text)?;
         
         // Map webhook response to ExecutionResult
         let success = status_code.is_success() && webhook_response.status != "error";
@@ -202,10 +338,25 @@ impl ExecutionPlugin for CCXTPlugin {
             filled_quantity: webhook_response.filled_quantity.unwrap_or(0.0),
             average_price: webhook_response.average_price.unwrap_or(0.0),
             error: if !success { webhook_response.message } else { None },
-            timestamp: Utc::now().timestamp_millis(),
+            timestamp: now_millis(),
         })
     }
     
+    async fn preview_order(&self, order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        reject_display_quantity(&self.name, order)?;
+        reject_post_only(&self.name, order)?;
+        reject_testnet_override(&self.name, order)?;
+        reject_leverage(&self.name, order)?;
+        reject_max_slippage(&self.name, order)?;
+        reject_quote_quantity(&self.name, order)?;
+        
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        Self::build_order_request(config, order)
+    }
+    
     async fn fetch_data(&self, symbol: &str) -> Result<MarketData, Box<dyn Error + Send + Sync>> {
         let config = self.config.read().await;
         let config = config.as_ref()
@@ -229,7 +380,7 @@ impl ExecutionPlugin for CCXTPlugin {
             timestamp: Option<i64>,
         }
         
-        let ticker: TickerResponse = response.json().await?;
+        let ticker: TickerResponse = super::parse_response("CCXT bridge", &response.text().await?)?;
         
         Ok(MarketData {
             symbol: ticker.symbol,
@@ -237,7 +388,7 @@ impl ExecutionPlugin for CCXTPlugin {
             ask: ticker.ask.unwrap_or(ticker.last * 1.0001),
             last: ticker.last,
             volume: ticker.volume.unwrap_or(0.0),
-            timestamp: ticker.timestamp.unwrap_or_else(|| Utc::now().timestamp_millis()),
+            timestamp: ticker.timestamp.unwrap_or_else(now_millis),
             extra: serde_json::json!({
                 "exchange": config.exchange,
                 "testnet": config.testnet
@@ -249,6 +400,15 @@ impl ExecutionPlugin for CCXTPlugin {
         &self.name
     }
     
+    fn is_paper(&self) -> bool {
+        self.testnet
+    }
+    
+    async fn effective_config(&self) -> Option<serde_json::Value> {
+        let config = self.config.read().await;
+        serde_json::to_value(config.as_ref()?).ok()
+    }
+    
     async fn health_check(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
         let config = self.config.read().await;
         let config = match config.as_ref() {
@@ -268,21 +428,42 @@ impl ExecutionPlugin for CCXTPlugin {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::plugins::OrderSide;
+    use crate::plugins::tests::spawn_mock_exchange;
+    use crate::plugins::{ExecutionError, OrderSide, QuantityType};
     
     #[test]
     fn test_signature_generation() {
-        let payload = r#"{"timestamp":1699113600,"symbol":"BTC/USDT","action":"buy"}"#;
+        let payload = r#"{"timestamp":1699113600000,"symbol":"BTC/USDT","action":"buy"}"#;
         let secret = "test-secret";
         
-        let sig1 = CCXTPlugin::generate_signature(payload, secret);
-        let sig2 = CCXTPlugin::generate_signature(payload, secret);
+        let sig1 = CCXTPlugin::generate_signature(payload, secret, SignatureAlgo::default());
+        let sig2 = CCXTPlugin::generate_signature(payload, secret, SignatureAlgo::default());
         
         // Same payload + secret should produce same signature
         assert_eq!(sig1, sig2);
         assert!(!sig1.is_empty());
     }
     
+    #[test]
+    fn test_signature_algorithms() {
+        let payload = r#"{"timestamp":1699113600000,"symbol":"BTC/USDT","action":"buy"}"#;
+        let sign = |algo| CCXTPlugin::generate_signature(payload, "test-secret", algo);
+        
+        assert_eq!(sign(SignatureAlgo::HmacSha256Hex), "625a873739de0b2e4ff39aa906223d45fdfe9722de5a7bc9e52e43820039199f");
+        assert_eq!(sign(SignatureAlgo::HmacSha256Base64), "YlqHNzneCy5P85qpBiI9Rf3+lyLeWnvJ5S5DggA5GZ8=");
+        assert_eq!(
+            sign(SignatureAlgo::HmacSha512Hex),
+            "8ff08e74f45f5173b86e81ff137457e0d28c5f03aea8d7761395d93de242fb73a4737d7701576f104444a6ec759721dd387a248514d215235ee660c6ec2b10d1"
+        );
+        
+        let config: CCXTConfig = serde_json::from_value(serde_json::json!({
+            "base_url": "http://localhost:8000",
+            "webhook_secret": "test-secret",
+            "signature_algo": "hmac_sha512_hex"
+        })).unwrap();
+        assert_eq!(config.signature_algo, SignatureAlgo::HmacSha512Hex);
+    }
+    
     #[tokio::test]
     async fn test_ccxt_plugin_not_initialized() {
         let plugin = CCXTPlugin::new("test-ccxt");
@@ -292,10 +473,8 @@ mod tests {
             side: OrderSide::Buy,
             order_type: OrderType::Market,
             quantity: 0.1,
-            price: None,
-            stop_loss: None,
-            take_profit: None,
             confidence: 0.75,
+            ..Default::default()
         };
         
         // Should fail - not initialized
@@ -306,7 +485,7 @@ mod tests {
     #[test]
     fn test_webhook_payload_serialization() {
         let payload = WebhookPayload {
-            timestamp: 1699113600,
+            timestamp: 1699113600000,
             symbol: "BTC/USDT".to_string(),
             action: "buy".to_string(),
             order_type: "market".to_string(),
@@ -322,4 +501,198 @@ mod tests {
         assert!(json.contains("buy"));
         assert!(json.contains("0.75"));
     }
+    
+    #[test]
+    fn test_webhook_payload_timestamp_is_millis() {
+        let order = Order {
+            symbol: "BTC/USDT".to_string(),
+            side: OrderSide::Sell,
+            order_type: OrderType::Limit,
+            quantity: 0.1,
+            price: Some(67500.0),
+            confidence: 0.75,
+            ..Default::default()
+        };
+        
+        let before = now_millis();
+        let payload = CCXTPlugin::build_payload(&order);
+        let after = now_millis();
+        
+        assert!(payload.timestamp >= before && payload.timestamp <= after);
+        // Seconds would be ~1.7e9; millis are ~1.7e12
+        assert!(payload.timestamp > 1_000_000_000_000);
+        assert_eq!(payload.action, "sell");
+        assert_eq!(payload.order_type, "limit");
+    }
+    
+    #[tokio::test]
+    async fn test_client_certificate_applied_to_plugin_client() {
+        let pki = crate::plugins::tls::tests::TestPki::new();
+        let base_url = pki.serve_mtls();
+        
+        let mut plugin = CCXTPlugin::new("test-ccxt");
+        plugin.init(serde_json::json!({
+            "base_url": base_url,
+            "webhook_secret": "secret",
+            "client_cert_path": pki.path("client.pem"),
+            "client_key_path": pki.path("client.key"),
+            "ca_cert_path": pki.path("ca.pem")
+        })).await.unwrap();
+        assert!(plugin.health_check().await.unwrap());
+        
+        // Trusting the server is not enough without the identity
+        let mut plugin = CCXTPlugin::new("test-ccxt");
+        plugin.init(serde_json::json!({
+            "base_url": base_url,
+            "webhook_secret": "secret",
+            "ca_cert_path": pki.path("ca.pem")
+        })).await.unwrap();
+        assert!(!plugin.health_check().await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_unreadable_client_certificate_fails_init() {
+        let mut plugin = CCXTPlugin::new("test-ccxt");
+        let err = plugin.init(serde_json::json!({
+            "base_url": "http://127.0.0.1:9",
+            "webhook_secret": "secret",
+            "client_cert_path": "/nonexistent/client.pem",
+            "client_key_path": "/nonexistent/client.key"
+        })).await.unwrap_err();
+        assert!(err.to_string().contains("client certificate"));
+    }
+    
+    /// Captured (content type, signature, body) of the last webhook
+    type Captured = Arc<std::sync::Mutex<Option<(String, String, String)>>>;
+    
+    async fn mock_bridge(path: &str) -> (String, Captured) {
+        use axum::{http::HeaderMap, routing::post, Json, Router};
+        
+        let captured: Captured = Arc::default();
+        let sink = captured.clone();
+        let app = Router::new().route(path, post(move |headers: HeaderMap, body: String| async move {
+            let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+            *sink.lock().unwrap() = Some((header("content-type"), header("x-webhook-signature"), body));
+            Json(serde_json::json!({ "status": "ok", "order_id": "bridge-1", "filled_quantity": 0.1, "average_price": 67500.0 }))
+        }));
+        
+        (spawn_mock_exchange(app).await, captured)
+    }
+    
+    fn bridge_order() -> Order {
+        Order {
+            symbol: "BTC/USDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity: 0.1,
+            price: Some(67500.0),
+            confidence: 0.75,
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn test_build_order_request_market_limit_stop() {
+        let config: CCXTConfig = serde_json::from_value(serde_json::json!({
+            "base_url": "http://bridge:8000/",
+            "webhook_secret": "secret"
+        })).unwrap();
+        
+        let mut order = bridge_order();
+        order.order_type = OrderType::Market;
+        order.price = None;
+        let market = CCXTPlugin::build_order_request(&config, &order).unwrap();
+        assert_eq!(market.path, "/webhook/tradingview");
+        assert_eq!(market.url, "http://bridge:8000/webhook/tradingview");
+        assert_eq!(market.body["order_type"], "market");
+        assert!(market.body["price"].is_null());
+        
+        let limit = CCXTPlugin::build_order_request(&config, &bridge_order()).unwrap();
+        assert_eq!(limit.body["order_type"], "limit");
+        assert_eq!(limit.body["action"], "buy");
+        assert_eq!(limit.body["price"], 67500.0);
+        
+        order.order_type = OrderType::Stop;
+        order.side = OrderSide::Sell;
+        order.stop_loss = Some(66000.0);
+        let stop = CCXTPlugin::build_order_request(&config, &order).unwrap();
+        assert_eq!(stop.body["order_type"], "stop");
+        assert_eq!(stop.body["action"], "sell");
+        assert_eq!(stop.body["stop_loss"], 66000.0);
+        
+        // Form bodies leave absent fields out
+        let form = WebhookContentType::Form.encode(&stop.body).unwrap();
+        assert!(form.contains("stop_loss=66000"));
+        assert!(!form.contains("price="));
+    }
+    
+    #[tokio::test]
+    async fn test_html_error_page_is_network_error() {
+        use axum::{http::StatusCode, routing::post, Router};
+        
+        // What a proxy in front of the bridge serves when the upstream is down
+        let page = "<html>\n<head><title>502 Bad Gateway</title></head>\n<body><center><h1>502 Bad Gateway</h1></center></body>\n</html>";
+        let app = Router::new().route("/webhook/tradingview", post(move || async move { (StatusCode::BAD_GATEWAY, page) }));
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let mut plugin = CCXTPlugin::new("test-ccxt");
+        plugin.init(serde_json::json!({ "base_url": base_url, "webhook_secret": "secret" })).await.unwrap();
+        
+        let err = plugin.execute_order(bridge_order()).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Network(_))));
+        assert_eq!(
+            err.to_string(),
+            "network: CCXT bridge returned HTTP 502 Bad Gateway with a non-JSON body: <html> <head><title>502 Bad Gateway</title></head> <body><center><h1>502 Bad Gateway</h1></center></body> </html>"
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_json_webhook_signed_as_sent() {
+        let (base_url, captured) = mock_bridge("/webhook/tradingview").await;
+        
+        let mut plugin = CCXTPlugin::new("test-ccxt");
+        plugin.init(serde_json::json!({ "base_url": base_url, "webhook_secret": "secret" })).await.unwrap();
+        let result = plugin.execute_order(bridge_order()).await.unwrap();
+        assert!(result.success);
+        
+        let (content_type, signature, body) = captured.lock().unwrap().clone().unwrap();
+        assert_eq!(content_type, "application/json");
+        assert_eq!(signature, CCXTPlugin::generate_signature(&body, "secret", SignatureAlgo::default()));
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["symbol"], "BTC/USDT");
+        assert_eq!(body["order_type"], "limit");
+    }
+    
+    #[tokio::test]
+    async fn test_form_webhook_on_custom_path_signed_as_sent() {
+        let (base_url, captured) = mock_bridge("/hooks/ccxt").await;
+        
+        let mut plugin = CCXTPlugin::new("test-ccxt");
+        plugin.init(serde_json::json!({
+            "base_url": base_url,
+            "webhook_secret": "secret",
+            "webhook_path": "hooks/ccxt",
+            "content_type": "form"
+        })).await.unwrap();
+        let result = plugin.execute_order(bridge_order()).await.unwrap();
+        assert!(result.success);
+        
+        let (content_type, signature, body) = captured.lock().unwrap().clone().unwrap();
+        assert_eq!(content_type, "application/x-www-form-urlencoded");
+        assert_eq!(signature, CCXTPlugin::generate_signature(&body, "secret", SignatureAlgo::default()));
+        assert!(body.contains("symbol=BTC%2FUSDT"));
+        assert!(body.contains("action=buy"));
+        assert!(body.contains("price=67500"));
+    }
+    
+    #[tokio::test]
+    async fn test_quote_quantity_rejected_as_unsupported() {
+        let plugin = CCXTPlugin::new("test-ccxt");
+        let order = Order { quantity_type: QuantityType::Quote, ..bridge_order() };
+        
+        let err = plugin.execute_order(order.clone()).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+        let err = plugin.preview_order(&order).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+    }
 }
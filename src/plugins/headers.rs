@@ -0,0 +1,75 @@
+//! Custom User-Agent and static headers for a plugin's HTTP client
+//!
+//! Some exchanges throttle or block reqwest's default user agent, and others
+//! ask integrators to identify their traffic. Plugins flatten
+//! [`ClientHeaders`] into their config and rebuild their HTTP client with it
+//! at init, so the headers go out on every request and a malformed header
+//! fails the plugin up front instead of on the first order.
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::ClientBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Optional `user_agent` and extra `headers` sent on every outbound request
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientHeaders {
+    /// Replaces reqwest's default `User-Agent`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    
+    /// Static headers added to every request; headers a request sets itself
+    /// (signatures, content type) take precedence
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+}
+
+impl ClientHeaders {
+    /// Whether anything is set (otherwise the default client is kept)
+    pub fn is_configured(&self) -> bool {
+        self.user_agent.is_some() || !self.headers.is_empty()
+    }
+    
+    /// Validate the headers and set them as the builder's defaults
+    pub fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder, Box<dyn Error + Send + Sync>> {
+        if let Some(user_agent) = &self.user_agent {
+            let value = HeaderValue::from_str(user_agent)
+                .map_err(|e| format!("Invalid user_agent '{}': {}", user_agent, e))?;
+            builder = builder.user_agent(value);
+        }
+        
+        if !self.headers.is_empty() {
+            let mut headers = HeaderMap::new();
+            for (name, value) in &self.headers {
+                let header_name = HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|e| format!("Invalid header name '{}': {}", name, e))?;
+                let header_value = HeaderValue::from_str(value)
+                    .map_err(|e| format!("Invalid value for header '{}': {}", name, e))?;
+                headers.insert(header_name, header_value);
+            }
+            builder = builder.default_headers(headers);
+        }
+        
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_invalid_header_rejected() {
+        let config: ClientHeaders = serde_json::from_value(serde_json::json!({
+            "headers": { "bad header": "x" }
+        })).unwrap();
+        let err = config.apply(reqwest::Client::builder()).unwrap_err();
+        assert!(err.to_string().contains("bad header"));
+        
+        let config = ClientHeaders { user_agent: Some("fks\nexecution".to_string()), ..Default::default() };
+        assert!(config.apply(reqwest::Client::builder()).is_err());
+        
+        assert!(!ClientHeaders::default().is_configured());
+    }
+}
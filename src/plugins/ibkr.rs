@@ -0,0 +1,766 @@
+//! Interactive Brokers Plugin via the Client Portal Web API
+//!
+//! Talks to a locally running IB gateway (`https://localhost:5000/v1/api`).
+//! The gateway holds a brokerage session that must be kept alive with
+//! periodic `/tickle` calls; the plugin spawns a keep-alive task on init.
+//! Orders are placed on `POST /iserver/account/{accountId}/orders`, with
+//! stop-loss/take-profit attached as bracket child orders.
+
+use super::headers::ClientHeaders;
+use super::{merge_extra_params, now_millis, reject_display_quantity, reject_leverage, reject_max_slippage, reject_post_only, reject_quote_quantity, reject_testnet_override, unique_id, ExecutionPlugin, ExecutionResult, MarketData, Order, OrderSide, OrderType, PreparedRequest};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+/// Configuration for IBKR plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IbkrConfig {
+    /// Client Portal gateway base URL
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    
+    /// Brokerage account id (e.g. "U1234567" or "DU1234567" for paper)
+    pub account_id: String,
+    
+    /// Verify the gateway TLS certificate (the bundled gateway is self-signed)
+    #[serde(default)]
+    pub verify_tls: bool,
+    
+    /// Seconds between session keep-alive `/tickle` calls (default: 60)
+    #[serde(default = "default_tickle_interval")]
+    pub tickle_interval_secs: u64,
+    
+    /// Static symbol -> contract id map, consulted before the secdef search
+    #[serde(default)]
+    pub conids: HashMap<String, i64>,
+    
+    /// Time in force for placed orders (default: "DAY")
+    #[serde(default = "default_tif")]
+    pub tif: String,
+    
+    /// `user_agent` / `headers` sent on every request
+    #[serde(flatten)]
+    pub client_headers: ClientHeaders,
+}
+
+fn default_base_url() -> String {
+    "https://localhost:5000/v1/api".to_string()
+}
+
+fn default_tickle_interval() -> u64 {
+    60
+}
+
+fn default_tif() -> String {
+    "DAY".to_string()
+}
+
+/// Market data snapshot field ids
+const FIELD_LAST: &str = "31";
+const FIELD_BID: &str = "84";
+const FIELD_ASK: &str = "86";
+const FIELD_VOLUME: &str = "87";
+
+/// Maximum order-reply confirmations before giving up
+const MAX_ORDER_REPLIES: usize = 5;
+
+/// Gateway authentication status
+#[derive(Debug, Deserialize)]
+struct AuthStatus {
+    #[serde(default)]
+    authenticated: bool,
+    #[serde(default)]
+    connected: bool,
+}
+
+/// Secdef search result entry
+#[derive(Debug, Deserialize)]
+struct SecdefResult {
+    conid: serde_json::Value,
+}
+
+/// IBKR Plugin implementation
+pub struct IbkrPlugin {
+    name: String,
+    config: Arc<RwLock<Option<IbkrConfig>>>,
+    client: Client,
+    conid_cache: RwLock<HashMap<String, i64>>,
+    keepalive: Option<JoinHandle<()>>,
+    /// Trading a paper account (`DU...`, or `DF...` for a paper advisor account)
+    paper: bool,
+}
+
+impl IbkrPlugin {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            config: Arc::new(RwLock::new(None)),
+            client: Self::build_client(false),
+            conid_cache: RwLock::new(HashMap::new()),
+            keepalive: None,
+            paper: false,
+        }
+    }
+    
+    fn build_client(verify_tls: bool) -> Client {
+        Self::client_builder(verify_tls)
+            .build()
+            .expect("Failed to create HTTP client")
+    }
+    
+    fn client_builder(verify_tls: bool) -> reqwest::ClientBuilder {
+        Client::builder()
+            .timeout(Duration::from_secs(30))
+            .danger_accept_invalid_certs(!verify_tls)
+    }
+    
+    /// Start the session keep-alive loop, replacing any previous one
+    fn spawn_keepalive(&mut self, base_url: String, interval_secs: u64) {
+        if let Some(handle) = self.keepalive.take() {
+            handle.abort();
+        }
+        
+        let client = self.client.clone();
+        let name = self.name.clone();
+        let interval = Duration::from_secs(interval_secs.max(1));
+        
+        self.keepalive = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let url = format!("{}/tickle", base_url);
+                match client.post(&url).send().await {
+                    Ok(resp) if resp.status().is_success() => {
+                        tracing::debug!(plugin = %name, "IB session tickled");
+                    }
+                    Ok(resp) => {
+                        tracing::warn!(plugin = %name, status = %resp.status(), "IB tickle returned non-success status");
+                    }
+                    Err(e) => {
+                        tracing::warn!(plugin = %name, error = %e, "IB tickle failed");
+                    }
+                }
+            }
+        }));
+    }
+    
+    /// Query the gateway brokerage session status
+    async fn auth_status(&self, base_url: &str) -> Result<AuthStatus, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/iserver/auth/status", base_url);
+        let response = self.client.post(&url).send().await?;
+        
+        if !response.status().is_success() {
+            return Err(format!("IB auth status error: {}", response.status()).into());
+        }
+        
+        Ok(response.json().await?)
+    }
+    
+    /// Resolve a symbol to an IB contract id
+    ///
+    /// Numeric symbols are treated as conids; otherwise the static map, the
+    /// cache, and finally `/iserver/secdef/search` are consulted.
+    async fn resolve_conid(&self, config: &IbkrConfig, symbol: &str) -> Result<i64, Box<dyn Error + Send + Sync>> {
+        if let Ok(conid) = symbol.parse::<i64>() {
+            return Ok(conid);
+        }
+        
+        if let Some(conid) = config.conids.get(symbol) {
+            return Ok(*conid);
+        }
+        
+        if let Some(conid) = self.conid_cache.read().await.get(symbol) {
+            return Ok(*conid);
+        }
+        
+        let url = format!("{}/iserver/secdef/search", config.base_url);
+        let response = self.client
+            .get(&url)
+            .query(&[("symbol", symbol)])
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if !status.is_success() {
+            return Err(format!("IB secdef search error ({}): {}", status, text).into());
+        }
+        
+        let results: Vec<SecdefResult> = serde_json::from_str(&text)?;
+        let conid = results
+            .first()
+            .and_then(|r| match &r.conid {
+                serde_json::Value::Number(n) => n.as_i64(),
+                serde_json::Value::String(s) => s.parse().ok(),
+                _ => None,
+            })
+            .ok_or_else(|| format!("No IB contract found for symbol: {}", symbol))?;
+        
+        self.conid_cache.write().await.insert(symbol.to_string(), conid);
+        Ok(conid)
+    }
+    
+    /// Build the `orders` payload, attaching SL/TP as bracket children
+    fn build_order_payload(config: &IbkrConfig, conid: i64, order: &Order, coid: &str) -> serde_json::Value {
+        let side = match order.side {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        };
+        let exit_side = match order.side {
+            OrderSide::Buy => "SELL",
+            OrderSide::Sell => "BUY",
+        };
+        
+        let order_type = match order.order_type {
+            OrderType::Market => "MKT",
+            OrderType::Limit | OrderType::TakeProfit => "LMT",
+            OrderType::Stop | OrderType::StopLoss => "STP",
+            OrderType::StopLimit => "STOP_LIMIT",
+        };
+        
+        let mut parent = serde_json::json!({
+            "acctId": config.account_id,
+            "conid": conid,
+            "cOID": coid,
+            "orderType": order_type,
+            "side": side,
+            "quantity": order.quantity,
+            "tif": config.tif,
+        });
+        
+        match order.order_type {
+            OrderType::Market => {}
+            OrderType::StopLimit => {
+                if let Some(price) = order.price {
+                    parent["price"] = serde_json::json!(price);
+                }
+                if let Some(stop) = order.trigger_price.or(order.stop_loss) {
+                    parent["auxPrice"] = serde_json::json!(stop);
+                }
+            }
+            _ => {
+                if let Some(price) = order.price {
+                    parent["price"] = serde_json::json!(price);
+                }
+            }
+        }
+        
+        // Extra params apply to the entry order, not its bracket children
+        merge_extra_params("ibkr", &mut parent, order);
+        let mut orders = vec![parent];
+        
+        // Stop-loss / take-profit on an entry order become bracket children
+        let is_entry = matches!(order.order_type, OrderType::Market | OrderType::Limit);
+        if is_entry {
+            if let Some(take_profit) = order.take_profit {
+                orders.push(serde_json::json!({
+                    "acctId": config.account_id,
+                    "conid": conid,
+                    "parentId": coid,
+                    "orderType": "LMT",
+                    "side": exit_side,
+                    "quantity": order.quantity,
+                    "price": take_profit,
+                    "tif": "GTC",
+                }));
+            }
+            if let Some(stop_loss) = order.stop_loss {
+                orders.push(serde_json::json!({
+                    "acctId": config.account_id,
+                    "conid": conid,
+                    "parentId": coid,
+                    "orderType": "STP",
+                    "side": exit_side,
+                    "quantity": order.quantity,
+                    "price": stop_loss,
+                    "tif": "GTC",
+                }));
+            }
+        }
+        
+        serde_json::json!({ "orders": orders })
+    }
+    
+    /// The `orders` request for an order on an already resolved contract
+    fn build_order_request(config: &IbkrConfig, conid: i64, order: &Order, coid: &str) -> PreparedRequest {
+        let path = format!("/iserver/account/{}/orders", config.account_id);
+        PreparedRequest {
+            method: "POST".to_string(),
+            url: format!("{}{}", config.base_url, path),
+            path,
+            body: Self::build_order_payload(config, conid, order, coid),
+        }
+    }
+    
+    /// Submit a prepared request, returning the status and raw body
+    ///
+    /// The gateway authenticates by session, so nothing is signed.
+    async fn send(&self, request: &PreparedRequest) -> Result<(reqwest::StatusCode, String), Box<dyn Error + Send + Sync>> {
+        let response = self.client
+            .post(&request.url)
+            .json(&request.body)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        Ok((status, text))
+    }
+    
+    /// Walk the order reply chain, confirming gateway warnings
+    ///
+    /// IB may answer an order with `[{"id": ..., "message": [...]}]`, which has
+    /// to be confirmed via `POST /iserver/reply/{id}` before the order is placed.
+    async fn confirm_replies(
+        &self,
+        base_url: &str,
+        mut reply: serde_json::Value,
+    ) -> Result<serde_json::Value, Box<dyn Error + Send + Sync>> {
+        for _ in 0..MAX_ORDER_REPLIES {
+            let first = reply.as_array().and_then(|a| a.first()).cloned();
+            let Some(first) = first else {
+                return Ok(reply);
+            };
+            
+            if first.get("order_id").is_some() || first.get("id").is_none() {
+                return Ok(reply);
+            }
+            
+            let reply_id = first["id"].as_str().unwrap_or_default().to_string();
+            tracing::info!(plugin = %self.name, reply_id = %reply_id, message = %first["message"], "Confirming IB order reply");
+            
+            let url = format!("{}/iserver/reply/{}", base_url, reply_id);
+            let response = self.client
+                .post(&url)
+                .json(&serde_json::json!({ "confirmed": true }))
+                .send()
+                .await?;
+            reply = response.json().await?;
+        }
+        
+        Err("IB order reply chain exceeded confirmation limit".into())
+    }
+}
+
+impl Drop for IbkrPlugin {
+    fn drop(&mut self) {
+        if let Some(handle) = self.keepalive.take() {
+            handle.abort();
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionPlugin for IbkrPlugin {
+    async fn init(&mut self, config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let ibkr_config: IbkrConfig = serde_json::from_value(config)?;
+        
+        tracing::info!(
+            plugin = %self.name,
+            base_url = %ibkr_config.base_url,
+            account_id = %ibkr_config.account_id,
+            "Initializing IBKR plugin"
+        );
+        
+        if ibkr_config.account_id.is_empty() {
+            return Err("IBKR account id must be provided".into());
+        }
+        
+        self.client = ibkr_config.client_headers.apply(Self::client_builder(ibkr_config.verify_tls))?.build()?;
+        
+        // Session check is non-blocking: the gateway may still need a browser login
+        match self.auth_status(&ibkr_config.base_url).await {
+            Ok(status) if status.authenticated && status.connected => {
+                tracing::info!(plugin = %self.name, "IB gateway session authenticated");
+            }
+            Ok(status) => {
+                tracing::warn!(
+                    plugin = %self.name,
+                    authenticated = status.authenticated,
+                    connected = status.connected,
+                    "IB gateway session not authenticated, log in via the gateway"
+                );
+            }
+            Err(e) => {
+                tracing::warn!(plugin = %self.name, error = %e, "IB gateway unreachable, will retry on first request");
+            }
+        }
+        
+        self.spawn_keepalive(ibkr_config.base_url.clone(), ibkr_config.tickle_interval_secs);
+        
+        self.paper = ibkr_config.account_id.starts_with("DU") || ibkr_config.account_id.starts_with("DF");
+        *self.config.write().await = Some(ibkr_config);
+        
+        tracing::info!(plugin = %self.name, "IBKR plugin initialized successfully");
+        Ok(())
+    }
+    
+    async fn execute_order(
+        &self,
+        order: Order,
+    ) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
+        reject_display_quantity(&self.name, &order)?;
+        reject_post_only(&self.name, &order)?;
+        reject_testnet_override(&self.name, &order)?;
+        reject_leverage(&self.name, &order)?;
+        reject_max_slippage(&self.name, &order)?;
+        reject_quote_quantity(&self.name, &order)?;
+        
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let conid = self.resolve_conid(config, &order.symbol).await?;
+        let coid = format!("fks-{}", unique_id());
+        let request = Self::build_order_request(config, conid, &order, &coid);
+        let (status, text) = self.send(&request).await?;
+        
+        if !status.is_success() {
+            return Ok(ExecutionResult {
+                success: false,
+                order_id: None,
+                filled_quantity: 0.0,
+                average_price: 0.0,
+                error: Some(format!("HTTP {}: {}", status, text)),
+                timestamp: now_millis(),
+            });
+        }
+        
+        let reply: serde_json::Value = serde_json::from_str(&text)?;
+        let reply = self.confirm_replies(&config.base_url, reply).await?;
+        
+        if let Some(error) = reply.get("error").and_then(|e| e.as_str()) {
+            return Ok(ExecutionResult {
+                success: false,
+                order_id: None,
+                filled_quantity: 0.0,
+                average_price: 0.0,
+                error: Some(format!("IB API error: {}", error)),
+                timestamp: now_millis(),
+            });
+        }
+        
+        let order_id = reply
+            .as_array()
+            .and_then(|a| a.first())
+            .and_then(|o| o.get("order_id"))
+            .and_then(|id| match id {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            });
+        
+        tracing::info!(
+            plugin = %self.name,
+            symbol = %order.symbol,
+            conid = conid,
+            order_id = ?order_id,
+            "Order placed successfully"
+        );
+        
+        // Fills are reported asynchronously by the gateway
+        Ok(ExecutionResult {
+            success: order_id.is_some(),
+            error: if order_id.is_none() { Some(format!("Unexpected IB order reply: {}", reply)) } else { None },
+            order_id,
+            filled_quantity: 0.0,
+            average_price: order.price.unwrap_or(0.0),
+            timestamp: now_millis(),
+        })
+    }
+    
+    /// Resolves the contract id (a gateway lookup) but places nothing
+    async fn preview_order(&self, order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        reject_display_quantity(&self.name, order)?;
+        reject_post_only(&self.name, order)?;
+        reject_testnet_override(&self.name, order)?;
+        reject_leverage(&self.name, order)?;
+        reject_max_slippage(&self.name, order)?;
+        reject_quote_quantity(&self.name, order)?;
+        
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let conid = self.resolve_conid(config, &order.symbol).await?;
+        Ok(Self::build_order_request(config, conid, order, &format!("fks-{}", unique_id())))
+    }
+    
+    async fn fetch_data(&self, symbol: &str) -> Result<MarketData, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let conid = self.resolve_conid(config, symbol).await?;
+        let fields = [FIELD_LAST, FIELD_BID, FIELD_ASK, FIELD_VOLUME].join(",");
+        
+        let url = format!("{}/iserver/marketdata/snapshot", config.base_url);
+        let response = self.client
+            .get(&url)
+            .query(&[("conids", conid.to_string()), ("fields", fields)])
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if !status.is_success() {
+            return Err(format!("IB API error ({}): {}", status, text).into());
+        }
+        
+        let snapshots: Vec<serde_json::Value> = serde_json::from_str(&text)?;
+        let snapshot = snapshots
+            .first()
+            .ok_or_else(|| format!("No market data found for symbol: {}", symbol))?;
+        
+        // Snapshot values are strings, sometimes prefixed ("C" closed, "H" halted)
+        let field = |id: &str| -> Option<f64> {
+            let value = snapshot.get(id)?;
+            match value {
+                serde_json::Value::Number(n) => n.as_f64(),
+                serde_json::Value::String(s) => s
+                    .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+                    .replace(',', "")
+                    .parse()
+                    .ok(),
+                _ => None,
+            }
+        };
+        
+        let last = field(FIELD_LAST).ok_or_else(|| format!("No last price in IB snapshot for {}", symbol))?;
+        
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            bid: field(FIELD_BID).unwrap_or(last),
+            ask: field(FIELD_ASK).unwrap_or(last),
+            last,
+            volume: field(FIELD_VOLUME).unwrap_or(0.0),
+            timestamp: now_millis(),
+            extra: serde_json::json!({ "conid": conid }),
+        })
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn is_paper(&self) -> bool {
+        self.paper
+    }
+    
+    async fn effective_config(&self) -> Option<serde_json::Value> {
+        let config = self.config.read().await;
+        serde_json::to_value(config.as_ref()?).ok()
+    }
+    
+    async fn health_check(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = match config.as_ref() {
+            Some(c) => c,
+            None => return Ok(false), // Not initialized
+        };
+        
+        match self.auth_status(&config.base_url).await {
+            Ok(status) => Ok(status.authenticated && status.connected),
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::spawn_mock_exchange;
+    use crate::plugins::{ExecutionError, QuantityType};
+    use axum::{extract::{Path, State}, routing::{get, post}, Json, Router};
+    use std::sync::Mutex;
+    
+    /// Captured order payloads posted to the mock gateway
+    type Captured = Arc<Mutex<Vec<serde_json::Value>>>;
+    
+    /// Start a mock Client Portal gateway on a random local port
+    async fn mock_gateway() -> (String, Captured) {
+        let captured: Captured = Arc::new(Mutex::new(Vec::new()));
+        
+        let app = Router::new()
+            .route("/v1/api/iserver/auth/status", post(|| async {
+                Json(serde_json::json!({ "authenticated": true, "connected": true }))
+            }))
+            .route("/v1/api/tickle", post(|| async { Json(serde_json::json!({ "session": "abc" })) }))
+            .route("/v1/api/iserver/secdef/search", get(|| async {
+                Json(serde_json::json!([{ "conid": "265598", "symbol": "AAPL" }]))
+            }))
+            .route("/v1/api/iserver/account/{account}/orders", post(
+                |State(captured): State<Captured>, Path(_account): Path<String>, Json(body): Json<serde_json::Value>| async move {
+                    captured.lock().unwrap().push(body);
+                    Json(serde_json::json!([{ "id": "reply-1", "message": ["Order price exceeds constraint"] }]))
+                }
+            ))
+            .route("/v1/api/iserver/reply/{id}", post(|| async {
+                Json(serde_json::json!([{ "order_id": "987654", "order_status": "Submitted" }]))
+            }))
+            .route("/v1/api/iserver/marketdata/snapshot", get(|| async {
+                Json(serde_json::json!([{ "conid": 265598, "31": "C189.50", "84": "189.45", "86": "189.55", "87": "1,234" }]))
+            }))
+            .with_state(captured.clone());
+        
+        (format!("{}/v1/api", spawn_mock_exchange(app).await), captured)
+    }
+    
+    async fn init_plugin(base_url: &str) -> IbkrPlugin {
+        let mut plugin = IbkrPlugin::new("test-ibkr");
+        plugin.init(serde_json::json!({
+            "base_url": base_url,
+            "account_id": "DU123456"
+        })).await.unwrap();
+        plugin
+    }
+    
+    #[tokio::test]
+    async fn test_order_payload_shape() {
+        let (base_url, captured) = mock_gateway().await;
+        let plugin = init_plugin(&base_url).await;
+        
+        let order = Order {
+            symbol: "AAPL".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity: 10.0,
+            price: Some(190.0),
+            stop_loss: Some(185.0),
+            take_profit: Some(200.0),
+            confidence: 0.8,
+            ..Default::default()
+        };
+        
+        let result = plugin.execute_order(order).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.order_id.as_deref(), Some("987654"));
+        
+        let captured = captured.lock().unwrap();
+        let orders = captured[0]["orders"].as_array().unwrap();
+        assert_eq!(orders.len(), 3);
+        
+        let parent = &orders[0];
+        assert_eq!(parent["acctId"], "DU123456");
+        assert_eq!(parent["conid"], 265598);
+        assert_eq!(parent["orderType"], "LMT");
+        assert_eq!(parent["side"], "BUY");
+        assert_eq!(parent["quantity"], 10.0);
+        assert_eq!(parent["price"], 190.0);
+        assert_eq!(parent["tif"], "DAY");
+        
+        let coid = parent["cOID"].as_str().unwrap();
+        assert!(coid.starts_with("fks-"));
+        
+        assert_eq!(orders[1]["parentId"], coid);
+        assert_eq!(orders[1]["side"], "SELL");
+        assert_eq!(orders[1]["price"], 200.0);
+        assert_eq!(orders[2]["orderType"], "STP");
+        assert_eq!(orders[2]["price"], 185.0);
+    }
+    
+    #[tokio::test]
+    async fn test_market_order_payload_has_no_price() {
+        let config: IbkrConfig = serde_json::from_value(serde_json::json!({ "account_id": "U1" })).unwrap();
+        let order = Order {
+            symbol: "ES".to_string(),
+            side: OrderSide::Sell,
+            order_type: OrderType::Market,
+            quantity: 1.0,
+            confidence: 0.7,
+            ..Default::default()
+        };
+        
+        let payload = IbkrPlugin::build_order_payload(&config, 495512551, &order, "fks-1");
+        let orders = payload["orders"].as_array().unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0]["orderType"], "MKT");
+        assert_eq!(orders[0]["side"], "SELL");
+        assert!(orders[0].get("price").is_none());
+    }
+    
+    #[test]
+    fn test_build_order_request_market_limit_stop() {
+        let config: IbkrConfig = serde_json::from_value(serde_json::json!({
+            "base_url": "https://localhost:5000/v1/api",
+            "account_id": "U1"
+        })).unwrap();
+        let order = |order_type, price, stop_loss| Order {
+            symbol: "AAPL".to_string(),
+            side: OrderSide::Buy,
+            order_type,
+            quantity: 5.0,
+            price,
+            stop_loss,
+            confidence: 0.7,
+            ..Default::default()
+        };
+        
+        let market = IbkrPlugin::build_order_request(&config, 265598, &order(OrderType::Market, None, None), "fks-1");
+        assert_eq!(market.path, "/iserver/account/U1/orders");
+        assert_eq!(market.url, "https://localhost:5000/v1/api/iserver/account/U1/orders");
+        assert_eq!(market.body["orders"][0]["orderType"], "MKT");
+        assert_eq!(market.body["orders"][0]["cOID"], "fks-1");
+        
+        let limit = IbkrPlugin::build_order_request(&config, 265598, &order(OrderType::Limit, Some(190.0), None), "fks-2");
+        assert_eq!(limit.body["orders"][0]["orderType"], "LMT");
+        assert_eq!(limit.body["orders"][0]["price"], 190.0);
+        
+        // A stop is an exit, so its stop-loss doesn't spawn a bracket child
+        let stop = IbkrPlugin::build_order_request(&config, 265598, &order(OrderType::Stop, Some(185.0), Some(180.0)), "fks-3");
+        let orders = stop.body["orders"].as_array().unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0]["orderType"], "STP");
+        assert_eq!(orders[0]["price"], 185.0);
+    }
+    
+    #[tokio::test]
+    async fn test_fetch_data_snapshot() {
+        let (base_url, _) = mock_gateway().await;
+        let plugin = init_plugin(&base_url).await;
+        
+        let data = plugin.fetch_data("AAPL").await.unwrap();
+        assert_eq!(data.last, 189.50);
+        assert_eq!(data.bid, 189.45);
+        assert_eq!(data.ask, 189.55);
+        assert_eq!(data.volume, 1234.0);
+        assert!(plugin.health_check().await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_paper_account_reported_as_paper() {
+        let (base_url, _) = mock_gateway().await;
+        let mut plugin = init_plugin(&base_url).await;
+        assert!(plugin.is_paper());
+        
+        plugin.init(serde_json::json!({
+            "base_url": base_url,
+            "account_id": "U1234567"
+        })).await.unwrap();
+        assert!(!plugin.is_paper());
+    }
+    
+    #[tokio::test]
+    async fn test_quote_quantity_rejected_as_unsupported() {
+        let plugin = IbkrPlugin::new("test-ibkr");
+        let order = Order {
+            symbol: "AAPL".to_string(),
+            quantity: 1000.0,
+            quantity_type: QuantityType::Quote,
+            ..Default::default()
+        };
+        
+        let err = plugin.execute_order(order.clone()).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+        let err = plugin.preview_order(&order).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+    }
+}
@@ -4,17 +4,21 @@
 //! Supports order placement, leverage management, and position queries.
 //! Canada-compliant exchange for live trading.
 
-use super::{ExecutionPlugin, ExecutionResult, MarketData, Order, OrderSide, OrderType};
+use super::leverage::LeverageLimit;
+use super::headers::ClientHeaders;
+use super::tls::ClientTlsConfig;
+use super::{apply_quote_alias, client_order_id, merge_extra_params, now_millis, round_to_step, CancelOutcome, ExecutionError, ExecutionPlugin, ExecutionResult, FundingRate, HealthStatus, LeverageChange, MaintenanceState, MarketData, Order, OrderPage, OrderRef, OrderSide, OrderStatus, OrderSummary, OrderType, PingResult, Position, PositionSide, PreparedRequest, QuantityType, RoundingMode, TimeSource, TimeSync, Trade, INVALID_CURSOR};
 use async_trait::async_trait;
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// Configuration for KuCoin plugin
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KuCoinConfig {
     /// KuCoin API key
     pub api_key: String,
@@ -36,6 +40,40 @@ pub struct KuCoinConfig {
     /// Default leverage (default: 10, for futures)
     #[serde(default = "default_leverage")]
     pub leverage: i32,
+    
+    /// Override the REST endpoint (e.g. a proxy); takes precedence over `testnet`
+    #[serde(default)]
+    pub base_url: Option<String>,
+    
+    /// Quote-currency aliases applied to incoming symbols (e.g. `USD` -> `USDT`)
+    #[serde(default)]
+    pub quote_aliases: HashMap<String, String>,
+    
+    /// Clock for signed timestamps: "local" (default) or "exchange", which
+    /// corrects local time by the measured offset to the server clock
+    #[serde(default)]
+    pub time_source: TimeSource,
+    
+    /// Seconds between server clock offset measurements (default: 300)
+    #[serde(default = "super::default_time_sync_secs")]
+    pub time_sync_secs: u64,
+    
+    /// Symbol whose ticker the health check fetches; pick one listed for the
+    /// configured trading type (default: BTC-USDT)
+    #[serde(default = "default_health_check_symbol")]
+    pub health_check_symbol: String,
+    
+    /// Client certificate / private CA for endpoints requiring mutual TLS
+    #[serde(flatten)]
+    pub tls: ClientTlsConfig,
+    
+    /// `user_agent` / `headers` sent on every request
+    #[serde(flatten)]
+    pub client_headers: ClientHeaders,
+    
+    /// `max_leverage` / `strict_leverage` cap on `leverage`, per-order leverage and set-leverage requests
+    #[serde(flatten)]
+    pub leverage_limit: LeverageLimit,
 }
 
 fn default_trading_type() -> String {
@@ -46,6 +84,24 @@ fn default_leverage() -> i32 {
     10
 }
 
+fn default_health_check_symbol() -> String {
+    "BTC-USDT".to_string()
+}
+
+/// KuCoin rejects a signed request whose `KC-API-TIMESTAMP` is further than
+/// this from its server time
+const TIMESTAMP_TOLERANCE_MS: u64 = 5000;
+
+/// Maximum length of a KuCoin `clientOid`
+const CLIENT_OID_MAX_LEN: usize = 40;
+
+/// Largest `pageSize` `/api/v1/fills` accepts
+const FILL_PAGE_LIMIT: usize = 500;
+
+/// KuCoin error codes for rejected credentials (missing headers, bad timestamp,
+/// unknown key, wrong passphrase, bad signature, IP not whitelisted, access denied)
+const AUTH_FAILED_CODES: &[&str] = &["400001", "400002", "400003", "400004", "400005", "400006", "400007"];
+
 /// KuCoin API response structure
 #[derive(Debug, Deserialize)]
 struct KuCoinResponse<T> {
@@ -78,6 +134,142 @@ impl KuCoinOrderResult {
     }
 }
 
+/// KuCoin paginated order list
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KuCoinOrderPage {
+    items: Option<Vec<KuCoinOrder>>,
+    #[serde(default)]
+    current_page: u32,
+    #[serde(default)]
+    total_page: u32,
+}
+
+/// KuCoin order as returned by `/api/v1/orders`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KuCoinOrder {
+    id: String,
+    client_oid: Option<String>,
+    symbol: String,
+    side: String,
+    #[serde(rename = "type")]
+    order_type: String,
+    price: Option<String>,
+    size: Option<String>,
+    /// `filledSize` on futures
+    #[serde(alias = "filledSize")]
+    deal_size: Option<String>,
+    /// Spot only: quote amount filled, so the average price is `dealFunds / dealSize`
+    deal_funds: Option<String>,
+    /// Futures only
+    avg_deal_price: Option<String>,
+    #[serde(default)]
+    is_active: bool,
+    #[serde(default)]
+    cancel_exist: bool,
+    created_at: Option<i64>,
+}
+
+impl KuCoinOrder {
+    fn into_summary(self) -> OrderSummary {
+        let quantity = self.size.and_then(|s| s.parse().ok()).unwrap_or(0.0);
+        let filled_quantity = self.deal_size.and_then(|s| s.parse().ok()).unwrap_or(0.0);
+        let deal_funds: Option<f64> = self.deal_funds.and_then(|s| s.parse().ok());
+        let average_price = self.avg_deal_price.and_then(|p| p.parse().ok())
+            .or(deal_funds.filter(|_| filled_quantity > 0.0).map(|funds| funds / filled_quantity))
+            .filter(|p: &f64| *p > 0.0);
+        
+        let status = if self.is_active {
+            if filled_quantity > 0.0 { OrderStatus::PartiallyFilled } else { OrderStatus::Open }
+        } else if self.cancel_exist {
+            OrderStatus::Cancelled
+        } else {
+            OrderStatus::Filled
+        };
+        
+        OrderSummary {
+            order_id: self.id,
+            client_order_id: self.client_oid.filter(|c| !c.is_empty()),
+            symbol: self.symbol,
+            side: if self.side == "sell" { OrderSide::Sell } else { OrderSide::Buy },
+            order_type: self.order_type,
+            quantity,
+            filled_quantity,
+            price: self.price.and_then(|p| p.parse().ok()).filter(|p: &f64| *p > 0.0),
+            average_price,
+            status,
+            timestamp: self.created_at.unwrap_or(0),
+        }
+    }
+}
+
+/// KuCoin futures current funding rate; `timePoint` starts the current interval
+/// of `granularity` millis, so the next settlement is their sum
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KuCoinFundingRate {
+    value: f64,
+    granularity: i64,
+    time_point: i64,
+}
+
+/// One entry of a futures `/api/v1/orders/multi-cancel` response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KuCoinCancelEntry {
+    order_id: Option<String>,
+    client_oid: Option<String>,
+    code: String,
+    #[serde(default)]
+    msg: String,
+}
+
+/// KuCoin paginated fill list
+#[derive(Debug, Deserialize)]
+struct KuCoinFillPage {
+    items: Option<Vec<KuCoinFill>>,
+}
+
+/// KuCoin fill as returned by `/api/v1/fills`
+///
+/// Spot sends numbers as strings; futures sends `size` (lots) as a number.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KuCoinFill {
+    symbol: String,
+    trade_id: String,
+    order_id: String,
+    side: String,
+    price: serde_json::Value,
+    size: serde_json::Value,
+    #[serde(default)]
+    fee: serde_json::Value,
+    fee_currency: Option<String>,
+    #[serde(default)]
+    created_at: i64,
+}
+
+impl KuCoinFill {
+    fn into_trade(self) -> Trade {
+        let number = |v: &serde_json::Value| match v {
+            serde_json::Value::String(s) => s.parse().unwrap_or(0.0),
+            v => v.as_f64().unwrap_or(0.0),
+        };
+        Trade {
+            price: number(&self.price),
+            quantity: number(&self.size),
+            fee: number(&self.fee),
+            trade_id: self.trade_id,
+            order_id: self.order_id,
+            symbol: self.symbol,
+            side: if self.side == "sell" { OrderSide::Sell } else { OrderSide::Buy },
+            fee_currency: self.fee_currency.filter(|c| !c.is_empty()),
+            timestamp: self.created_at,
+        }
+    }
+}
+
 /// KuCoin position result
 #[derive(Debug, Deserialize)]
 struct KuCoinPositionResult {
@@ -87,8 +279,10 @@ struct KuCoinPositionResult {
 
 /// KuCoin position
 #[derive(Debug, Clone, Deserialize)]
-struct KuCoinPosition {
+pub struct KuCoinPosition {
     symbol: String,
+    #[serde(default)]
+    #[allow(dead_code)] // deserialized for completeness, not read yet
     side: String,
     #[serde(rename = "currentQty")]
     current_qty: Option<String>,
@@ -101,15 +295,45 @@ struct KuCoinPosition {
     unrealized_pnl: Option<String>,
     leverage: Option<String>,
     #[serde(rename = "currentCost")]
+    #[allow(dead_code)] // deserialized for completeness, not read yet
     current_cost: Option<String>,
 }
 
+impl KuCoinPosition {
+    /// Normalize; the sign of `currentQty` gives the direction
+    fn into_position(self) -> Option<Position> {
+        let qty: f64 = self.current_qty.as_ref()
+            .or(self.size.as_ref())
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0.0);
+        if qty == 0.0 {
+            return None;
+        }
+        
+        let parse = |v: Option<String>| v.and_then(|s| s.parse().ok()).unwrap_or(0.0);
+        Some(Position {
+            symbol: self.symbol,
+            side: if qty > 0.0 { PositionSide::Long } else { PositionSide::Short },
+            size: qty.abs(),
+            entry_price: parse(self.avg_entry_price),
+            mark_price: parse(self.mark_price),
+            unrealized_pnl: parse(self.unrealized_pnl),
+            leverage: parse(self.leverage),
+        })
+    }
+}
+
 /// KuCoin Plugin implementation
 pub struct KuCoinPlugin {
     name: String,
     config: Arc<RwLock<Option<KuCoinConfig>>>,
     client: Client,
     base_url: String,
+    maintenance: MaintenanceState,
+    /// Offset to the exchange clock for signed timestamps
+    time_sync: TimeSync,
+    /// Log each request's prehash and signature (`DEBUG_SIGN=true`)
+    debug_sign: bool,
 }
 
 impl KuCoinPlugin {
@@ -122,11 +346,92 @@ impl KuCoinPlugin {
                 .build()
                 .expect("Failed to create HTTP client"),
             base_url: "https://api.kucoin.com".to_string(),
+            maintenance: MaintenanceState::default(),
+            time_sync: TimeSync::default(),
+            debug_sign: super::debug_sign_enabled(),
+        }
+    }
+    
+    /// Detect a maintenance response and open the maintenance window
+    ///
+    /// KuCoin answers HTTP 503 while the platform is under system maintenance.
+    fn check_maintenance(&self, status: reqwest::StatusCode, text: &str) -> Option<ExecutionError> {
+        if status != reqwest::StatusCode::SERVICE_UNAVAILABLE {
+            return None;
+        }
+        
+        self.maintenance.enter(MaintenanceState::DEFAULT_WINDOW);
+        tracing::warn!(plugin = %self.name, status = %status, body = %text, "KuCoin maintenance detected");
+        Some(MaintenanceState::error(&self.name, &format!("HTTP {}", status)))
+    }
+    
+    /// Map a rejected post-only order to [`ExecutionError::Exchange`] so callers can reprice
+    ///
+    /// KuCoin reports these under its generic order error codes, so the
+    /// message identifies them.
+    fn post_only_rejection(code: &str, msg: &str) -> Option<ExecutionError> {
+        if !super::is_post_only_message(msg) {
+            return None;
+        }
+        Some(ExecutionError::post_only_would_cross("KuCoin", &format!("{} - {}", code, msg)))
+    }
+    
+    /// Classify the response of an authenticated ping
+    fn classify_auth_response(status: reqwest::StatusCode, text: &str) -> HealthStatus {
+        let code = serde_json::from_str::<serde_json::Value>(text)
+            .ok()
+            .and_then(|v| v.get("code").and_then(|c| c.as_str()).map(str::to_string));
+        
+        if status == reqwest::StatusCode::UNAUTHORIZED
+            || code.as_deref().is_some_and(|c| AUTH_FAILED_CODES.contains(&c))
+        {
+            return HealthStatus::AuthFailed;
+        }
+        
+        match code.as_deref() {
+            Some(_) if status.is_success() => HealthStatus::Healthy,
+            _ => HealthStatus::Unreachable,
+        }
+    }
+    
+    /// Verify the API credentials against the accounts endpoint
+    async fn auth_ping(&self, config: &KuCoinConfig) -> HealthStatus {
+        let endpoint = "/api/v1/accounts";
+        let headers = match self.create_headers(
+            "GET",
+            endpoint,
+            "",
+            &config.api_key,
+            &config.api_secret,
+            &config.api_passphrase,
+        ).await {
+            Ok(h) => h,
+            // Credentials that can't even be encoded as headers are unusable
+            Err(_) => return HealthStatus::AuthFailed,
+        };
+        
+        let url = format!("{}{}", self.base_url, endpoint);
+        let response = match self.client.get(&url).headers(headers).send().await {
+            Ok(r) => r,
+            Err(_) => return HealthStatus::Unreachable,
+        };
+        
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        
+        if self.check_maintenance(status, &text).is_some() {
+            return HealthStatus::Unreachable;
+        }
+        
+        let health = Self::classify_auth_response(status, &text);
+        if health == HealthStatus::AuthFailed {
+            tracing::warn!(plugin = %self.name, status = %status, body = %text, "KuCoin rejected API credentials");
         }
+        health
     }
     
-    /// Get base URL (sandbox or mainnet)
-    fn get_base_url(&self, testnet: bool) -> &str {
+    /// Mainnet or sandbox host
+    fn network_url(testnet: bool) -> &'static str {
         if testnet {
             "https://openapi-sandbox.kucoin.com"
         } else {
@@ -134,6 +439,131 @@ impl KuCoinPlugin {
         }
     }
     
+    /// Base URL for one call: the configured endpoint, unless `testnet`
+    /// selects the other network for just this call
+    fn get_base_url(&self, config: &KuCoinConfig, testnet: Option<bool>) -> &str {
+        match testnet {
+            Some(testnet) if testnet != config.testnet => Self::network_url(testnet),
+            _ => &self.base_url,
+        }
+    }
+    
+    /// Apply the configured quote-currency aliases, then convert to KuCoin format
+    fn normalize_symbol(&self, config: &KuCoinConfig, symbol: &str) -> String {
+        Self::to_kucoin_symbol(&apply_quote_alias(&self.name, symbol, &config.quote_aliases))
+    }
+    
+    /// Convert symbol format (BTCUSDT -> BTC-USDT for KuCoin)
+    fn to_kucoin_symbol(symbol: &str) -> String {
+        if symbol.contains('-') {
+            return symbol.to_string();
+        }
+        
+        // Try to split USDT pairs
+        match symbol.strip_suffix("USDT") {
+            Some(base) => format!("{}-USDT", base),
+            None => symbol.to_string(),
+        }
+    }
+    
+    /// Parse a spot `/api/v2/symbols` or futures `/api/v1/contracts/active`
+    /// response body, keeping symbols open for trading
+    fn parse_symbol_list(text: &str) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct KuCoinSymbol {
+            symbol: String,
+            /// Spot only
+            #[serde(default)]
+            enable_trading: Option<bool>,
+            /// Futures only (`Open`, `Paused`, ...)
+            #[serde(default)]
+            status: Option<String>,
+        }
+        
+        let kucoin_resp: KuCoinResponse<Vec<KuCoinSymbol>> = super::parse_response("KuCoin", text)?;
+        
+        if !kucoin_resp.is_success() {
+            return Err(format!("KuCoin API error: {} - {}", kucoin_resp.code.as_deref().unwrap_or("unknown"), kucoin_resp.error_msg()).into());
+        }
+        
+        Ok(kucoin_resp.data
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| s.enable_trading.unwrap_or(true) && s.status.as_deref().is_none_or(|st| st == "Open"))
+            .map(|s| s.symbol)
+            .collect())
+    }
+    
+    /// Parse an `/api/v1/orders` response body
+    fn parse_order_page(text: &str) -> Result<OrderPage, Box<dyn Error + Send + Sync>> {
+        let kucoin_resp: KuCoinResponse<KuCoinOrderPage> = super::parse_response("KuCoin", text)?;
+        
+        if !kucoin_resp.is_success() {
+            return Err(format!("KuCoin API error: {} - {}", kucoin_resp.code.as_deref().unwrap_or("unknown"), kucoin_resp.error_msg()).into());
+        }
+        
+        let Some(page) = kucoin_resp.data else {
+            return Ok(OrderPage { orders: Vec::new(), next_cursor: None });
+        };
+        Ok(OrderPage {
+            orders: page.items
+                .unwrap_or_default()
+                .into_iter()
+                .map(KuCoinOrder::into_summary)
+                .collect(),
+            next_cursor: (page.current_page < page.total_page).then(|| (page.current_page + 1).to_string()),
+        })
+    }
+    
+    /// Parse an `/api/v1/fills` response body
+    fn parse_fill_list(text: &str) -> Result<Vec<Trade>, Box<dyn Error + Send + Sync>> {
+        let kucoin_resp: KuCoinResponse<KuCoinFillPage> = super::parse_response("KuCoin", text)?;
+        
+        if !kucoin_resp.is_success() {
+            return Err(format!("KuCoin API error: {} - {}", kucoin_resp.code.as_deref().unwrap_or("unknown"), kucoin_resp.error_msg()).into());
+        }
+        
+        Ok(kucoin_resp.data
+            .and_then(|d| d.items)
+            .unwrap_or_default()
+            .into_iter()
+            .map(KuCoinFill::into_trade)
+            .collect())
+    }
+    
+    /// Parse an `/api/v1/funding-rate/{symbol}/current` response body
+    fn parse_funding_rate(symbol: &str, text: &str) -> Result<FundingRate, Box<dyn Error + Send + Sync>> {
+        let kucoin_resp: KuCoinResponse<KuCoinFundingRate> = super::parse_response("KuCoin", text)?;
+        
+        if !kucoin_resp.is_success() {
+            return Err(format!("KuCoin API error: {} - {}", kucoin_resp.code.as_deref().unwrap_or("unknown"), kucoin_resp.error_msg()).into());
+        }
+        
+        let funding = kucoin_resp.data
+            .ok_or_else(|| format!("No funding rate for {}", symbol))?;
+        Ok(FundingRate {
+            symbol: symbol.to_string(),
+            rate: funding.value,
+            next_funding_time: funding.time_point + funding.granularity,
+            timestamp: now_millis(),
+        })
+    }
+    
+    /// Parse an `/api/v1/leverage` response; a `false` result leaves the leverage as it was
+    fn parse_set_leverage(text: &str) -> Result<LeverageChange, Box<dyn Error + Send + Sync>> {
+        let kucoin_resp: KuCoinResponse<serde_json::Value> = super::parse_response("KuCoin", text)?;
+        
+        if !kucoin_resp.is_success() {
+            return Err(format!("KuCoin API error: {} - {}", kucoin_resp.code.as_deref().unwrap_or("unknown"), kucoin_resp.error_msg()).into());
+        }
+        
+        match kucoin_resp.data {
+            Some(serde_json::Value::Bool(false)) => Ok(LeverageChange::Unchanged),
+            _ => Ok(LeverageChange::Changed),
+        }
+    }
+    
     /// Generate HMAC-SHA256 signature and base64 encode
     fn generate_signature(secret: &str, message: &str) -> String {
         use hmac::{Hmac, Mac};
@@ -164,6 +594,21 @@ impl KuCoinPlugin {
         general_purpose::STANDARD.encode(result.into_bytes())
     }
     
+    /// Timestamp for a signed request, re-syncing with the exchange clock when due
+    async fn signing_timestamp(&self) -> i64 {
+        if self.time_sync.start_refresh() {
+            match self.ping().await {
+                Ok(PingResult { clock_skew_ms: Some(skew), .. }) => {
+                    tracing::debug!(plugin = %self.name, offset_ms = skew, "KuCoin clock offset synced");
+                    self.time_sync.record(&self.name, skew);
+                }
+                Ok(_) => tracing::warn!(plugin = %self.name, "KuCoin server time missing, keeping clock offset"),
+                Err(e) => tracing::warn!(plugin = %self.name, error = %e, "KuCoin clock sync failed, keeping clock offset"),
+            }
+        }
+        self.time_sync.now_millis()
+    }
+    
     /// Create authenticated request headers for KuCoin API
     async fn create_headers(
         &self,
@@ -174,17 +619,16 @@ impl KuCoinPlugin {
         api_secret: &str,
         api_passphrase: &str,
     ) -> Result<reqwest::header::HeaderMap, Box<dyn Error + Send + Sync>> {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis()
-            .to_string();
+        let timestamp = self.signing_timestamp().await.to_string();
         
         // Prehash string: timestamp + method + endpoint + body
         let prehash_string = format!("{}{}{}{}", timestamp, method, endpoint, body);
         
         // Generate signature
         let signature = Self::generate_signature(api_secret, &prehash_string);
+        if self.debug_sign {
+            super::log_signed_request(&self.name, &timestamp, &prehash_string, &signature, api_secret);
+        }
         
         // Encrypt passphrase
         let encrypted_passphrase = Self::encrypt_passphrase(api_secret, api_passphrase);
@@ -200,61 +644,111 @@ impl KuCoinPlugin {
         Ok(headers)
     }
     
-    /// Set leverage for a symbol (KuCoin Futures-specific)
-    pub async fn set_leverage(
+    /// Send one futures `/api/v1/orders/multi-cancel` request
+    async fn multi_cancel(
         &self,
-        symbol: &str,
-        leverage: i32,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        config: &KuCoinConfig,
+        params: serde_json::Value,
+    ) -> Result<Vec<KuCoinCancelEntry>, Box<dyn Error + Send + Sync>> {
+        let endpoint = "/api/v1/orders/multi-cancel";
+        let body = serde_json::to_string(&params)?;
+        let headers = self.create_headers(
+            "DELETE",
+            endpoint,
+            &body,
+            &config.api_key,
+            &config.api_secret,
+            &config.api_passphrase,
+        ).await?;
+        
+        let url = format!("{}{}", self.base_url, endpoint);
+        let response = self.client
+            .delete(&url)
+            .headers(headers)
+            .body(body)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("KuCoin", status, &text));
+        }
+        
+        let kucoin_resp: KuCoinResponse<Vec<KuCoinCancelEntry>> = super::parse_response("KuCoin", &text)?;
+        if !kucoin_resp.is_success() {
+            return Err(format!("KuCoin API error: {}", kucoin_resp.error_msg()).into());
+        }
+        Ok(kucoin_resp.data.unwrap_or_default())
+    }
+    
+    /// One outcome per entry of `orders`, matched to the response by order or client order id
+    fn match_cancel_entries(orders: &[OrderRef], entries: Vec<KuCoinCancelEntry>) -> Vec<CancelOutcome> {
+        orders.iter()
+            .map(|order| {
+                let entry = entries.iter().find(|e| match order {
+                    OrderRef::OrderId(id) => e.order_id.as_deref() == Some(id.as_str()),
+                    OrderRef::ClientOrderId(id) => e.client_oid.as_deref() == Some(id.as_str()),
+                });
+                match entry {
+                    Some(entry) if entry.code == "200" => CancelOutcome::cancelled(order.clone()),
+                    Some(entry) => CancelOutcome::failed(order.clone(), format!("KuCoin API error: {} - {}", entry.code, entry.msg)),
+                    None => CancelOutcome::failed(order.clone(), "KuCoin returned no status for this order"),
+                }
+            })
+            .collect()
+    }
+    
+    /// All open futures positions in one request (`/api/v1/positions`)
+    async fn fetch_all_positions(&self) -> Result<Vec<KuCoinPosition>, Box<dyn Error + Send + Sync>> {
         let config = self.config.read().await;
         let config = config.as_ref()
             .ok_or("Plugin not initialized")?;
         
         if config.trading_type != "futures" {
-            return Err("Leverage setting only available for futures trading".into());
+            return Err(ExecutionError::Unsupported("KuCoin spot has no positions".to_string()).into());
         }
         
-        let base_url = self.get_base_url(config.testnet);
-        let endpoint = "/api/v1/leverage";
-        
-        let params = serde_json::json!({
-            "symbol": symbol,
-            "leverage": leverage.to_string(),
-        });
-        
-        let body = serde_json::to_string(&params)?;
+        let endpoint = "/api/v1/positions";
         let headers = self.create_headers(
-            "POST",
+            "GET",
             endpoint,
-            &body,
+            "",
             &config.api_key,
             &config.api_secret,
             &config.api_passphrase,
         ).await?;
         
-        let url = format!("{}{}", base_url, endpoint);
+        let url = format!("{}{}", self.base_url, endpoint);
         let response = self.client
-            .post(&url)
+            .get(&url)
             .headers(headers)
-            .body(body)
             .send()
             .await?;
         
         let status = response.status();
         let text = response.text().await?;
         
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
         if !status.is_success() {
-            return Err(format!("KuCoin API error ({}): {}", status, text).into());
+            return Err(super::http_error("KuCoin", status, &text));
         }
         
-        let kucoin_resp: KuCoinResponse<serde_json::Value> = serde_json::from_str(&text)?;
+        let kucoin_resp: KuCoinResponse<Vec<KuCoinPosition>> = super::parse_response("KuCoin", &text)?;
         
         if !kucoin_resp.is_success() {
             return Err(format!("KuCoin API error: {} - {}", kucoin_resp.code.as_deref().unwrap_or("unknown"), kucoin_resp.error_msg()).into());
         }
         
-        tracing::info!(plugin = %self.name, symbol = %symbol, leverage = %leverage, "Leverage set successfully");
-        Ok(())
+        Ok(kucoin_resp.data.unwrap_or_default())
     }
     
     /// Get positions for a symbol (KuCoin Futures)
@@ -267,10 +761,10 @@ impl KuCoinPlugin {
             .ok_or("Plugin not initialized")?;
         
         if config.trading_type != "futures" {
-            return Err("Position queries only available for futures trading".into());
+            return Err(ExecutionError::Unsupported("KuCoin spot has no positions".to_string()).into());
         }
         
-        let base_url = self.get_base_url(config.testnet);
+        let base_url = &self.base_url;
         let endpoint = format!("/api/v1/positions?symbol={}", symbol);
         
         let headers = self.create_headers(
@@ -292,11 +786,15 @@ impl KuCoinPlugin {
         let status = response.status();
         let text = response.text().await?;
         
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
         if !status.is_success() {
-            return Err(format!("KuCoin API error ({}): {}", status, text).into());
+            return Err(super::http_error("KuCoin", status, &text));
         }
         
-        let kucoin_resp: KuCoinResponse<KuCoinPositionResult> = serde_json::from_str(&text)?;
+        let kucoin_resp: KuCoinResponse<KuCoinPositionResult> = super::parse_response("KuCoin", &text)?;
         
         if !kucoin_resp.is_success() {
             return Err(format!("KuCoin API error: {} - {}", kucoin_resp.code.as_deref().unwrap_or("unknown"), kucoin_resp.error_msg()).into());
@@ -319,50 +817,21 @@ impl KuCoinPlugin {
         
         Ok(None)
     }
-}
-
-#[async_trait]
-impl ExecutionPlugin for KuCoinPlugin {
-    async fn init(&mut self, config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let kucoin_config: KuCoinConfig = serde_json::from_value(config)?;
-        
-        tracing::info!(
-            plugin = %self.name,
-            testnet = %kucoin_config.testnet,
-            trading_type = %kucoin_config.trading_type,
-            "Initializing KuCoin plugin"
-        );
-        
-        // Validate API credentials
-        if kucoin_config.api_key.is_empty() || kucoin_config.api_secret.is_empty() || kucoin_config.api_passphrase.is_empty() {
-            return Err("KuCoin API key, secret, and passphrase must be provided".into());
-        }
-        
-        // Update base URL
-        self.base_url = self.get_base_url(kucoin_config.testnet).to_string();
-        
-        *self.config.write().await = Some(kucoin_config);
-        
-        tracing::info!(plugin = %self.name, "KuCoin plugin initialized successfully");
-        Ok(())
-    }
     
-    async fn execute_order(
-        &self,
-        order: Order,
-    ) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
-        let config = self.config.read().await;
-        let config = config.as_ref()
-            .ok_or("Plugin not initialized")?;
-        
-        let base_url = self.get_base_url(config.testnet);
-        
-        // Choose endpoint based on trading type
-        let endpoint = if config.trading_type == "futures" {
-            "/api/v1/orders"
-        } else {
-            "/api/v1/orders"
-        };
+    /// The `/api/v1/orders` request for an order
+    ///
+    /// Spot and futures share the same order path; stop-loss, leverage and
+    /// reduce-only are only sent for futures. Spot market orders may be sized
+    /// in the quote currency, sent as `funds` instead of `size`. A display
+    /// quantity makes the order an iceberg showing `visibleSize`. Stop-limits
+    /// are limit orders with a `stopPrice`; spot sends them to the stop-order path.
+    /// Slippage-capped market orders, priced by [`Self::cap_slippage`], are
+    /// sent as IOC limits.
+    fn build_order_request(&self, config: &KuCoinConfig, order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        order.validate_stop_limit()?;
+        let futures = config.trading_type == "futures";
+        let stop_limit = order.order_type == OrderType::StopLimit;
+        let endpoint = if stop_limit && !futures { "/api/v1/stop-order" } else { "/api/v1/orders" };
         
         // Convert Order to KuCoin format
         let side = match order.side {
@@ -370,50 +839,79 @@ impl ExecutionPlugin for KuCoinPlugin {
             OrderSide::Sell => "sell",
         };
         
+        let slippage_capped = order.order_type == OrderType::Market && order.max_slippage_pct.is_some();
         let order_type = match order.order_type {
+            OrderType::Market if slippage_capped => "limit",
             OrderType::Market => "market",
-            OrderType::Limit => "limit",
+            OrderType::Limit | OrderType::StopLimit => "limit",
             OrderType::Stop => "stop",
-            OrderType::StopLimit => "stopLimit",
             OrderType::TakeProfit => "takeProfit",
             OrderType::StopLoss => "stopLoss",
         };
         
-        // Convert symbol format (BTCUSDT -> BTC-USDT for KuCoin)
-        let kucoin_symbol = if order.symbol.contains("-") {
-            order.symbol.clone()
-        } else {
-            // Try to split USDT pairs
-            if order.symbol.ends_with("USDT") {
-                let base = &order.symbol[..order.symbol.len() - 4];
-                format!("{}-USDT", base)
-            } else {
-                order.symbol.clone()
-            }
-        };
+        let kucoin_symbol = self.normalize_symbol(config, &order.symbol);
         
         // Build order parameters
         let mut params = serde_json::json!({
-            "clientOid": format!("fks-{}", SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis()),
+            "clientOid": client_order_id(order, CLIENT_OID_MAX_LEN),
             "side": side,
             "symbol": kucoin_symbol,
             "type": order_type,
         });
         
-        // Add size (KuCoin uses "size" for spot, "size" for futures too)
-        params["size"] = serde_json::json!(order.quantity.to_string());
+        // Base-asset "size" for spot and futures; quote "funds" only for spot market orders
+        let spot_market = config.trading_type != "futures" && matches!(order.order_type, OrderType::Market) && !slippage_capped;
+        match order.quantity_type {
+            QuantityType::Base => params["size"] = serde_json::json!(order.quantity.to_string()),
+            QuantityType::Quote if spot_market => params["funds"] = serde_json::json!(order.quantity.to_string()),
+            QuantityType::Quote => {
+                return Err(ExecutionError::Unsupported(format!("{} accepts quote-denominated quantities only on spot market orders", self.name)).into());
+            }
+        }
         
         // Add price for limit orders
         if let Some(price) = order.price {
             params["price"] = serde_json::json!(price.to_string());
         }
         
-        // Add stop-loss and take-profit if provided (futures only)
-        if config.trading_type == "futures" {
-            if let Some(stop_loss) = order.stop_loss {
-                params["stop"] = serde_json::json!("down");
-                params["stopPrice"] = serde_json::json!(stop_loss.to_string());
-            }
+        if let Some(display) = order.display_quantity {
+            params["iceberg"] = serde_json::json!(true);
+            params["visibleSize"] = serde_json::json!(display.to_string());
+        }
+        
+        if order.post_only {
+            params["postOnly"] = serde_json::json!(true);
+        }
+        
+        if slippage_capped {
+            if order.price.is_none() {
+                return Err("slippage-capped market orders need a limit price from the current quote".into());
+            }
+            params["timeInForce"] = serde_json::json!("IOC");
+        }
+        
+        // Buy stops trigger on a rise to the trigger price, sell stops on a fall
+        if let (true, Some(trigger)) = (stop_limit, order.trigger_price) {
+            let buy = order.side == OrderSide::Buy;
+            if futures {
+                params["stop"] = serde_json::json!(if buy { "up" } else { "down" });
+                params["stopPriceType"] = serde_json::json!("TP");
+            } else {
+                params["stop"] = serde_json::json!(if buy { "entry" } else { "loss" });
+            }
+            params["stopPrice"] = serde_json::json!(trigger.to_string());
+        }
+        
+        if !futures && order.leverage.is_some() {
+            return Err(ExecutionError::Unsupported(format!("{} spot orders carry no leverage", self.name)).into());
+        }
+        
+        // Add stop-loss and take-profit if provided (futures only)
+        if futures {
+            if let (false, Some(stop_loss)) = (stop_limit, order.stop_loss) {
+                params["stop"] = serde_json::json!("down");
+                params["stopPrice"] = serde_json::json!(stop_loss.to_string());
+            }
             
             if let Some(take_profit) = order.take_profit {
                 // KuCoin futures uses separate take profit orders
@@ -421,15 +919,71 @@ impl ExecutionPlugin for KuCoinPlugin {
                 tracing::debug!(take_profit = %take_profit, "Take profit specified (may need separate order)");
             }
             
-            // Add leverage if configured
-            params["leverage"] = serde_json::json!(config.leverage.to_string());
+            // The order's own leverage goes through the same cap as the configured one
+            let leverage = match order.leverage {
+                Some(leverage) => config.leverage_limit.apply(&self.name, leverage)?,
+                None => config.leverage,
+            };
+            params["leverage"] = serde_json::json!(leverage.to_string());
+            
+            if order.reduce_only {
+                params["reduceOnly"] = serde_json::json!(true);
+            }
         }
         
-        let body = serde_json::to_string(&params)?;
+        merge_extra_params(&self.name, &mut params, order);
+        Ok(PreparedRequest {
+            method: "POST".to_string(),
+            url: format!("{}{}", self.get_base_url(config, order.testnet), endpoint),
+            path: endpoint.to_string(),
+            body: params,
+        })
+    }
+    
+    /// Price a market order carrying `max_slippage_pct`
+    ///
+    /// KuCoin has no market-order slippage protection, so the order is given
+    /// the worst acceptable price from the current quote, rounded toward the
+    /// touch at the quote's precision, and goes out as an IOC limit: whatever
+    /// can't fill within the tolerance is cancelled instead of walking the book.
+    async fn cap_slippage(&self, mut order: Order) -> Result<Order, Box<dyn Error + Send + Sync>> {
+        if order.order_type != OrderType::Market || order.max_slippage_pct.is_none() {
+            return Ok(order);
+        }
+        let quote = self.fetch_data(&order.symbol).await?;
+        let (touch, mode) = match order.side {
+            OrderSide::Buy => (quote.ask, RoundingMode::Down),
+            OrderSide::Sell => (quote.bid, RoundingMode::Up),
+        };
+        if touch <= 0.0 {
+            return Err(format!("KuCoin has no quote for {} to cap slippage against", order.symbol).into());
+        }
+        let decimals = touch.to_string().split_once('.').map_or(0, |(_, frac)| frac.len()) as i32;
+        order.price = order.slippage_limit_price(&quote)
+            .map(|price| round_to_step(price, 10f64.powi(-decimals), mode));
+        tracing::debug!(plugin = %self.name, symbol = %order.symbol, touch, limit = ?order.price, "market_order_slippage_capped");
+        Ok(order)
+    }
+    
+    /// Look up an order by the `clientOid` it was placed with
+    ///
+    /// `None` when KuCoin has no such order.
+    async fn find_order_by_client_oid(
+        &self,
+        config: &KuCoinConfig,
+        base_url: &str,
+        client_oid: &str,
+    ) -> Result<Option<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        let endpoint = if config.trading_type == "futures" {
+            format!("/api/v1/orders/byClientOid?clientOid={}", client_oid)
+        } else {
+            format!("/api/v1/order/client-order/{}", client_oid)
+        };
+        
         let headers = self.create_headers(
-            "POST",
-            endpoint,
-            &body,
+            "GET",
+            &endpoint,
+            "",
             &config.api_key,
             &config.api_secret,
             &config.api_passphrase,
@@ -437,7 +991,76 @@ impl ExecutionPlugin for KuCoinPlugin {
         
         let url = format!("{}{}", base_url, endpoint);
         let response = self.client
-            .post(&url)
+            .get(&url)
+            .headers(headers)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("KuCoin", status, &text));
+        }
+        
+        let kucoin_resp: KuCoinResponse<KuCoinOrder> = super::parse_response("KuCoin", &text)?;
+        if !kucoin_resp.is_success() {
+            return Err(format!("KuCoin API error: {} - {}", kucoin_resp.code.as_deref().unwrap_or("unknown"), kucoin_resp.error_msg()).into());
+        }
+        Ok(kucoin_resp.data.map(KuCoinOrder::into_summary))
+    }
+    
+    /// Settle an order whose placement timed out
+    ///
+    /// The request may still have reached KuCoin, so the order is looked up by
+    /// the `clientOid` sent with it, on the host it was sent to, until the
+    /// timestamp tolerance has passed; an order that hasn't appeared by then is
+    /// reported as outcome unknown.
+    async fn reconcile_timeout(
+        &self,
+        config: &KuCoinConfig,
+        request: &PreparedRequest,
+        sent: Instant,
+        timeout: Box<dyn Error + Send + Sync>,
+    ) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
+        let Some(client_oid) = request.body["clientOid"].as_str() else {
+            return Err(timeout);
+        };
+        tracing::warn!(plugin = %self.name, client_oid = %client_oid, "order_create_timed_out_reconciling");
+        
+        let base_url = request.base_url().unwrap_or(&self.base_url);
+        let window = Duration::from_millis(TIMESTAMP_TOLERANCE_MS);
+        Ok(super::reconcile_by_client_id(&self.name, client_oid, sent, window, || {
+            self.find_order_by_client_oid(config, base_url, client_oid)
+        }).await?)
+    }
+    
+    /// Sign and submit a prepared request, returning the status and raw body
+    async fn send(
+        &self,
+        config: &KuCoinConfig,
+        request: &PreparedRequest,
+    ) -> Result<(reqwest::StatusCode, String), Box<dyn Error + Send + Sync>> {
+        let body = serde_json::to_string(&request.body)?;
+        let headers = self.create_headers(
+            &request.method,
+            &request.path,
+            &body,
+            &config.api_key,
+            &config.api_secret,
+            &config.api_passphrase,
+        ).await?;
+        
+        let response = self.client
+            .post(&request.url)
             .headers(headers)
             .body(body)
             .send()
@@ -445,6 +1068,81 @@ impl ExecutionPlugin for KuCoinPlugin {
         
         let status = response.status();
         let text = response.text().await?;
+        Ok((status, text))
+    }
+}
+
+#[async_trait]
+impl ExecutionPlugin for KuCoinPlugin {
+    async fn init(&mut self, config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut kucoin_config: KuCoinConfig = serde_json::from_value(config)?;
+        
+        // Client certificate / private CA and custom headers, validated here rather than on the first request
+        if kucoin_config.tls.is_configured() || kucoin_config.client_headers.is_configured() {
+            self.client = kucoin_config.tls.build_client(
+                kucoin_config.client_headers.apply(Client::builder().timeout(std::time::Duration::from_secs(30)))?
+            )?;
+        }
+        
+        tracing::info!(
+            plugin = %self.name,
+            testnet = %kucoin_config.testnet,
+            trading_type = %kucoin_config.trading_type,
+            "Initializing KuCoin plugin"
+        );
+        
+        // Validate API credentials
+        if kucoin_config.api_key.is_empty() || kucoin_config.api_secret.is_empty() || kucoin_config.api_passphrase.is_empty() {
+            return Err("KuCoin API key, secret, and passphrase must be provided".into());
+        }
+        
+        // Every futures order carries this leverage, so the cap applies here once
+        kucoin_config.leverage = kucoin_config.leverage_limit.apply(&self.name, kucoin_config.leverage)?;
+        
+        // Update base URL
+        self.base_url = match &kucoin_config.base_url {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => Self::network_url(kucoin_config.testnet).to_string(),
+        };
+        
+        self.time_sync = TimeSync::new(
+            kucoin_config.time_source,
+            std::time::Duration::from_secs(kucoin_config.time_sync_secs),
+        );
+        *self.config.write().await = Some(kucoin_config);
+        
+        tracing::info!(plugin = %self.name, "KuCoin plugin initialized successfully");
+        Ok(())
+    }
+    
+    async fn execute_order(
+        &self,
+        order: Order,
+    ) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
+        let order = self.cap_slippage(order).await?;
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        if self.maintenance.is_active() {
+            return Err(MaintenanceState::error(&self.name, "orders rejected until the window closes").into());
+        }
+        
+        let side = match order.side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        };
+        let request = self.build_order_request(config, &order)?;
+        let sent = Instant::now();
+        let (status, text) = match self.send(config, &request).await {
+            Ok(reply) => reply,
+            Err(e) if super::is_timeout(&*e) => return self.reconcile_timeout(config, &request, sent, e).await,
+            Err(e) => return Err(e),
+        };
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
         
         if !status.is_success() {
             return Ok(ExecutionResult {
@@ -452,27 +1150,26 @@ impl ExecutionPlugin for KuCoinPlugin {
                 order_id: None,
                 filled_quantity: 0.0,
                 average_price: 0.0,
-                error: Some(format!("HTTP {}: {}", status, text)),
-                timestamp: SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as i64,
+                error: Some(super::http_error("KuCoin", status, &text).to_string()),
+                timestamp: now_millis(),
             });
         }
         
-        let kucoin_resp: KuCoinResponse<KuCoinOrderResult> = serde_json::from_str(&text)?;
+        let kucoin_resp: KuCoinResponse<KuCoinOrderResult> = super::parse_response("KuCoin", &text)?;
         
         if !kucoin_resp.is_success() {
+            let code = kucoin_resp.code.as_deref().unwrap_or("unknown");
+            if let Some(err) = Self::post_only_rejection(code, &kucoin_resp.error_msg()).filter(|_| order.post_only) {
+                tracing::warn!(plugin = %self.name, symbol = %order.symbol, "post_only_would_cross");
+                return Err(err.into());
+            }
             return Ok(ExecutionResult {
                 success: false,
                 order_id: None,
                 filled_quantity: 0.0,
                 average_price: 0.0,
-                error: Some(format!("KuCoin API error: {} - {}", kucoin_resp.code.as_deref().unwrap_or("unknown"), kucoin_resp.error_msg())),
-                timestamp: SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as i64,
+                error: Some(format!("KuCoin API error: {} - {}", code, kucoin_resp.error_msg())),
+                timestamp: now_millis(),
             });
         }
         
@@ -514,29 +1211,27 @@ impl ExecutionPlugin for KuCoinPlugin {
             filled_quantity,
             average_price,
             error: None,
-            timestamp: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as i64,
+            timestamp: now_millis(),
         })
     }
     
+    async fn preview_order(&self, order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        let order = self.cap_slippage(order.clone()).await?;
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        self.build_order_request(config, &order)
+    }
+    
     async fn fetch_data(&self, symbol: &str) -> Result<MarketData, Box<dyn Error + Send + Sync>> {
         let config = self.config.read().await;
         let config = config.as_ref()
             .ok_or("Plugin not initialized")?;
         
-        let base_url = self.get_base_url(config.testnet);
+        let base_url = &self.base_url;
         
-        // Convert symbol format if needed
-        let kucoin_symbol = if symbol.contains("-") {
-            symbol.to_string()
-        } else if symbol.ends_with("USDT") {
-            let base = &symbol[..symbol.len() - 4];
-            format!("{}-USDT", base)
-        } else {
-            symbol.to_string()
-        };
+        let kucoin_symbol = self.normalize_symbol(config, symbol);
         
         // Use market data endpoint (public, no auth required)
         let endpoint = if config.trading_type == "futures" {
@@ -554,8 +1249,12 @@ impl ExecutionPlugin for KuCoinPlugin {
         let status = response.status();
         let text = response.text().await?;
         
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
         if !status.is_success() {
-            return Err(format!("KuCoin API error ({}): {}", status, text).into());
+            return Err(super::http_error("KuCoin", status, &text));
         }
         
         #[derive(Deserialize)]
@@ -568,18 +1267,15 @@ impl ExecutionPlugin for KuCoinPlugin {
             #[serde(rename = "last")]
             last_price: Option<String>,
             #[serde(rename = "bestBidSize")]
+            #[allow(dead_code)] // top-of-book depth isn't surfaced in MarketData
             best_bid_size: Option<String>,
             #[serde(rename = "bestAskSize")]
+            #[allow(dead_code)]
             best_ask_size: Option<String>,
             volume: Option<String>,
         }
         
-        #[derive(Deserialize)]
-        struct TickerResponse {
-            data: Option<TickerData>,
-        }
-        
-        let kucoin_resp: KuCoinResponse<TickerData> = serde_json::from_str(&text)?;
+        let kucoin_resp: KuCoinResponse<TickerData> = super::parse_response("KuCoin", &text)?;
         
         if !kucoin_resp.is_success() {
             return Err(format!("KuCoin API error: {} - {}", kucoin_resp.code.as_deref().unwrap_or("unknown"), kucoin_resp.error_msg()).into());
@@ -607,10 +1303,7 @@ impl ExecutionPlugin for KuCoinPlugin {
                 ask,
                 last,
                 volume,
-                timestamp: SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as i64,
+                timestamp: now_millis(),
                 extra: serde_json::json!({}),
             });
         }
@@ -623,17 +1316,1071 @@ impl ExecutionPlugin for KuCoinPlugin {
     }
     
     async fn health_check(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        Ok(self.health_status().await == HealthStatus::Healthy)
+    }
+    
+    async fn health_status(&self) -> HealthStatus {
         // Check if plugin is initialized
+        let config = match self.config.read().await.clone() {
+            Some(c) => c,
+            None => return HealthStatus::Unconfigured,
+        };
+        
+        if self.maintenance.is_active() {
+            return HealthStatus::Unreachable;
+        }
+        
+        // Public endpoint first, so network problems aren't reported as auth failures
+        if self.fetch_data(&config.health_check_symbol).await.is_err() {
+            return HealthStatus::Unreachable;
+        }
+        
+        // Public-only health when no credentials are configured
+        if config.api_key.is_empty() || config.api_secret.is_empty() || config.api_passphrase.is_empty() {
+            return HealthStatus::Healthy;
+        }
+        
+        self.auth_ping(&config).await
+    }
+    
+    async fn list_orders(&self, symbol: Option<&str>) -> Result<Vec<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        Ok(self.list_orders_page(symbol, None, 50).await?.orders)
+    }
+    
+    /// The cursor is KuCoin's page number; pages hold 10 to 500 orders
+    async fn list_orders_page(&self, symbol: Option<&str>, cursor: Option<&str>, limit: usize) -> Result<OrderPage, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        let page = match cursor {
+            Some(cursor) => cursor.parse::<u32>().ok().filter(|page| *page > 0).ok_or_else(|| ExecutionError::Exchange {
+                code: INVALID_CURSOR,
+                message: format!("Invalid cursor '{}'", cursor),
+            })?,
+            None => 1,
+        };
+        let base_url = &self.base_url;
+        
+        // Only live orders; the query string is part of the signed path
+        let mut endpoint = format!("/api/v1/orders?status=active&currentPage={}&pageSize={}", page, limit.clamp(10, 500));
+        if let Some(symbol) = symbol {
+            endpoint.push_str(&format!("&symbol={}", self.normalize_symbol(config, symbol)));
+        }
+        
+        let headers = self.create_headers(
+            "GET",
+            &endpoint,
+            "",
+            &config.api_key,
+            &config.api_secret,
+            &config.api_passphrase,
+        ).await?;
+        
+        let url = format!("{}{}", base_url, endpoint);
+        let response = self.client
+            .get(&url)
+            .headers(headers)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("KuCoin", status, &text));
+        }
+        
+        Self::parse_order_page(&text)
+    }
+    
+    /// Most recent fills only: the first page, newest first
+    /// `clientOid` lookups are account-wide, so `symbol` is not needed
+    async fn get_order_by_client_id(&self, _symbol: &str, client_order_id: &str) -> Result<Option<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        self.find_order_by_client_oid(config, &self.base_url, client_order_id).await
+    }
+    
+    async fn fetch_trades(&self, symbol: Option<&str>, limit: usize) -> Result<Vec<Trade>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        // The query string is part of the signed path
+        let page_size = limit.clamp(1, FILL_PAGE_LIMIT);
+        let endpoint = match symbol {
+            Some(symbol) => format!("/api/v1/fills?pageSize={}&symbol={}", page_size, self.normalize_symbol(config, symbol)),
+            None => format!("/api/v1/fills?pageSize={}", page_size),
+        };
+        
+        let headers = self.create_headers(
+            "GET",
+            &endpoint,
+            "",
+            &config.api_key,
+            &config.api_secret,
+            &config.api_passphrase,
+        ).await?;
+        
+        let url = format!("{}{}", self.base_url, endpoint);
+        let response = self.client
+            .get(&url)
+            .headers(headers)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("KuCoin", status, &text));
+        }
+        
+        let mut trades = Self::parse_fill_list(&text)?;
+        trades.truncate(limit);
+        Ok(trades)
+    }
+    
+    async fn fetch_funding_rate(&self, symbol: &str) -> Result<FundingRate, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        if config.trading_type != "futures" {
+            return Err(ExecutionError::Unsupported("KuCoin spot has no funding rate".to_string()).into());
+        }
+        
+        // Public endpoint, no authentication required
+        let url = format!("{}/api/v1/funding-rate/{}/current", self.base_url, self.normalize_symbol(config, symbol));
+        let response = self.client
+            .get(&url)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("KuCoin", status, &text));
+        }
+        
+        Self::parse_funding_rate(symbol, &text)
+    }
+    
+    async fn set_leverage(&self, symbol: &str, leverage: i32) -> Result<LeverageChange, Box<dyn Error + Send + Sync>> {
         let config = self.config.read().await;
-        if config.is_none() {
-            return Ok(false);
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        if config.trading_type != "futures" {
+            return Err(ExecutionError::Unsupported("KuCoin spot has no leverage".to_string()).into());
+        }
+        
+        let leverage = config.leverage_limit.apply(&self.name, leverage)?;
+        let base_url = &self.base_url;
+        let endpoint = "/api/v1/leverage";
+        
+        let params = serde_json::json!({
+            "symbol": symbol,
+            "leverage": leverage.to_string(),
+        });
+        
+        let body = serde_json::to_string(&params)?;
+        let headers = self.create_headers(
+            "POST",
+            endpoint,
+            &body,
+            &config.api_key,
+            &config.api_secret,
+            &config.api_passphrase,
+        ).await?;
+        
+        let url = format!("{}{}", base_url, endpoint);
+        let response = self.client
+            .post(&url)
+            .headers(headers)
+            .body(body)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("KuCoin", status, &text));
+        }
+        
+        let change = Self::parse_set_leverage(&text)?;
+        match change {
+            LeverageChange::Changed => tracing::info!(plugin = %self.name, symbol = %symbol, leverage = %leverage, "Leverage set successfully"),
+            LeverageChange::Unchanged => tracing::debug!(plugin = %self.name, symbol = %symbol, leverage = %leverage, "Leverage already set"),
+        }
+        Ok(change)
+    }
+    
+    /// Futures only, via `/api/v1/orders/multi-cancel`: one request for
+    /// order ids and one for client order ids
+    async fn cancel_batch(&self, symbol: &str, orders: &[OrderRef]) -> Result<Vec<CancelOutcome>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        if config.trading_type != "futures" {
+            return Err(ExecutionError::Unsupported("KuCoin spot has no batch cancellation by order id".to_string()).into());
+        }
+        
+        let symbol = self.normalize_symbol(config, symbol);
+        let order_ids: Vec<&str> = orders.iter()
+            .filter_map(|o| match o {
+                OrderRef::OrderId(id) => Some(id.as_str()),
+                OrderRef::ClientOrderId(_) => None,
+            })
+            .collect();
+        let client_oids: Vec<serde_json::Value> = orders.iter()
+            .filter_map(|o| match o {
+                OrderRef::ClientOrderId(id) => Some(serde_json::json!({ "symbol": symbol, "clientOid": id })),
+                OrderRef::OrderId(_) => None,
+            })
+            .collect();
+        
+        let mut entries = Vec::new();
+        if !order_ids.is_empty() {
+            entries.extend(self.multi_cancel(config, serde_json::json!({ "orderIdsList": order_ids })).await?);
+        }
+        if !client_oids.is_empty() {
+            entries.extend(self.multi_cancel(config, serde_json::json!({ "clientOidsList": client_oids })).await?);
+        }
+        Ok(Self::match_cancel_entries(orders, entries))
+    }
+    
+    async fn get_positions(&self, symbol: Option<&str>) -> Result<Vec<Position>, Box<dyn Error + Send + Sync>> {
+        let Some(symbol) = symbol else {
+            return self.get_all_positions().await;
+        };
+        Ok(self.get_position(symbol).await?
+            .and_then(KuCoinPosition::into_position)
+            .into_iter()
+            .collect())
+    }
+    
+    async fn get_all_positions(&self) -> Result<Vec<Position>, Box<dyn Error + Send + Sync>> {
+        Ok(self.fetch_all_positions().await?
+            .into_iter()
+            .filter_map(KuCoinPosition::into_position)
+            .collect())
+    }
+    
+    async fn list_symbols(&self) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let config = self.config.read().await;
+        let config = config.as_ref()
+            .ok_or("Plugin not initialized")?;
+        
+        // Public endpoints, no auth required
+        let endpoint = if config.trading_type == "futures" {
+            "/api/v1/contracts/active"
+        } else {
+            "/api/v2/symbols"
+        };
+        
+        let url = format!("{}{}", self.base_url, endpoint);
+        let response = self.client
+            .get(&url)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
+        }
+        
+        if !status.is_success() {
+            return Err(super::http_error("KuCoin", status, &text));
         }
         
-        // Try to fetch market data as a health check (public endpoint)
-        // This verifies connectivity to KuCoin API
-        match self.fetch_data("BTC-USDT").await {
-            Ok(_) => Ok(true),
-            Err(_) => Ok(false), // Return false but don't error
+        Self::parse_symbol_list(&text)
+    }
+    
+    async fn ping(&self) -> Result<PingResult, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/api/v1/timestamp", self.base_url);
+        
+        let sent_at = now_millis();
+        let start = std::time::Instant::now();
+        let response = self.client.get(&url).send().await?;
+        let elapsed = start.elapsed();
+        
+        let status = response.status();
+        let text = response.text().await?;
+        
+        if let Some(err) = self.check_maintenance(status, &text) {
+            return Err(err.into());
         }
+        
+        if !status.is_success() {
+            return Err(super::http_error("KuCoin", status, &text));
+        }
+        
+        let kucoin_resp: KuCoinResponse<i64> = super::parse_response("KuCoin", &text)?;
+        
+        Ok(PingResult::measure(sent_at, elapsed, kucoin_resp.data))
+    }
+    
+    fn in_maintenance(&self) -> bool {
+        self.maintenance.is_active()
+    }
+    
+    /// Paper only on the testnet host; a custom `base_url` can't be told apart from live
+    fn is_paper(&self) -> bool {
+        self.base_url == Self::network_url(true)
+    }
+    
+    async fn effective_config(&self) -> Option<serde_json::Value> {
+        let config = self.config.read().await;
+        serde_json::to_value(config.as_ref()?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::spawn_mock_exchange;
+    use crate::plugins::LEVERAGE_ABOVE_MAX;
+    
+    /// A plugin pointed at a mock exchange, with `extra` added to its config
+    async fn init_with_base_url(base_url: &str, extra: serde_json::Value) -> KuCoinPlugin {
+        let mut config = serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "api_passphrase": "test-pass",
+            "base_url": base_url
+        });
+        config.as_object_mut().unwrap().extend(extra.as_object().cloned().unwrap_or_default());
+        let mut plugin = KuCoinPlugin::new("test-kucoin");
+        plugin.init(config).await.unwrap();
+        plugin
+    }
+    
+    #[tokio::test]
+    async fn test_debug_sign_logs_prehash_without_secret() {
+        let logs = crate::plugins::tests::LogCapture::default();
+        let _guard = logs.install();
+        
+        let mut plugin = KuCoinPlugin::new("test-kucoin");
+        plugin.debug_sign = true;
+        let headers = plugin.create_headers("GET", "/api/v1/positions", "", "key123", "s3cr3t-value", "pass-phrase").await.unwrap();
+        
+        let timestamp = headers["KC-API-TIMESTAMP"].to_str().unwrap();
+        let output = logs.contents();
+        assert!(output.contains(&format!("prehash={}GET/api/v1/positions", timestamp)));
+        assert!(output.contains(headers["KC-API-SIGN"].to_str().unwrap()));
+        assert!(!output.contains("s3cr3t-value"));
+        assert!(!output.contains("pass-phrase"));
+    }
+    
+    #[test]
+    fn test_parse_symbol_list() {
+        let spot = r#"{"code":"200000","data":[
+            {"symbol":"BTC-USDT","enableTrading":true},
+            {"symbol":"OLD-USDT","enableTrading":false}
+        ]}"#;
+        assert_eq!(KuCoinPlugin::parse_symbol_list(spot).unwrap(), vec!["BTC-USDT"]);
+        
+        let futures = r#"{"code":"200000","data":[
+            {"symbol":"XBTUSDTM","status":"Open"},
+            {"symbol":"ETHUSDTM","status":"Paused"}
+        ]}"#;
+        assert_eq!(KuCoinPlugin::parse_symbol_list(futures).unwrap(), vec!["XBTUSDTM"]);
+    }
+    
+    #[test]
+    fn test_parse_order_list() {
+        let body = r#"{
+            "code": "200000",
+            "data": {
+                "currentPage": 1,
+                "pageSize": 2,
+                "totalNum": 3,
+                "totalPage": 2,
+                "items": [
+                    {
+                        "id": "5c35c02703aa673ceec2a168",
+                        "clientOid": "fks-1",
+                        "symbol": "BTC-USDT",
+                        "side": "buy",
+                        "type": "limit",
+                        "price": "10",
+                        "size": "2",
+                        "dealSize": "0",
+                        "isActive": true,
+                        "cancelExist": false,
+                        "createdAt": 1547026471000
+                    },
+                    {
+                        "id": "5c35c02703aa673ceec2a169",
+                        "clientOid": "",
+                        "symbol": "ETH-USDT",
+                        "side": "sell",
+                        "type": "limit",
+                        "price": "2000",
+                        "size": "1",
+                        "dealSize": "0.4",
+                        "isActive": true,
+                        "cancelExist": false,
+                        "createdAt": 1547026472000
+                    }
+                ]
+            }
+        }"#;
+        
+        let page = KuCoinPlugin::parse_order_page(body).unwrap();
+        assert_eq!(page.next_cursor.as_deref(), Some("2"));
+        let orders = page.orders;
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].status, OrderStatus::Open);
+        assert_eq!(orders[0].client_order_id.as_deref(), Some("fks-1"));
+        assert_eq!(orders[0].price, Some(10.0));
+        assert_eq!(orders[1].status, OrderStatus::PartiallyFilled);
+        assert_eq!(orders[1].side, OrderSide::Sell);
+        assert_eq!(orders[1].client_order_id, None);
+    }
+    
+    #[test]
+    fn test_multi_cancel_entries_matched_by_id() {
+        let orders = vec![
+            OrderRef::OrderId("5bd6e9286d99522a52e458de".to_string()),
+            OrderRef::OrderId("5bd6e9286d99522a52e458df".to_string()),
+            OrderRef::ClientOrderId("fks-exit-1".to_string()),
+        ];
+        let text = r#"{"code":"200000","data":[
+            {"orderId":"5bd6e9286d99522a52e458df","clientOid":null,"code":"100004","msg":"The order is already filled"},
+            {"orderId":"5bd6e9286d99522a52e458de","clientOid":null,"code":"200","msg":"success"}
+        ]}"#;
+        let entries = serde_json::from_str::<KuCoinResponse<Vec<KuCoinCancelEntry>>>(text).unwrap().data.unwrap();
+        
+        let outcomes = KuCoinPlugin::match_cancel_entries(&orders, entries);
+        assert!(outcomes[0].success);
+        assert_eq!(outcomes[1].error.as_deref(), Some("KuCoin API error: 100004 - The order is already filled"));
+        assert_eq!(outcomes[2].error.as_deref(), Some("KuCoin returned no status for this order"));
+    }
+    
+    #[test]
+    fn test_parse_set_leverage_outcome() {
+        let changed = r#"{"code":"200000","data":true}"#;
+        assert_eq!(KuCoinPlugin::parse_set_leverage(changed).unwrap(), LeverageChange::Changed);
+        
+        let unchanged = r#"{"code":"200000","data":false}"#;
+        assert_eq!(KuCoinPlugin::parse_set_leverage(unchanged).unwrap(), LeverageChange::Unchanged);
+        
+        let rejected = r#"{"code":"400100","msg":"Leverage out of range"}"#;
+        let err = KuCoinPlugin::parse_set_leverage(rejected).unwrap_err();
+        assert_eq!(err.to_string(), "KuCoin API error: 400100 - Leverage out of range");
+    }
+    
+    #[test]
+    fn test_parse_funding_rate() {
+        let body = r#"{"code":"200000","data":{
+            "symbol":".XBTUSDTMFPI8H","granularity":28800000,"timePoint":1718352000000,
+            "value":0.000153,"predictedValue":0.0001
+        }}"#;
+        let funding = KuCoinPlugin::parse_funding_rate("BTC/USDT", body).unwrap();
+        assert_eq!(funding.symbol, "BTC/USDT");
+        assert_eq!(funding.rate, 0.000153);
+        assert_eq!(funding.next_funding_time, 1718380800000);
+        
+        let missing = r#"{"code":"404000","msg":"Contract does not exist"}"#;
+        assert!(KuCoinPlugin::parse_funding_rate("FOO/USDT", missing).is_err());
+    }
+    
+    #[test]
+    fn test_parse_fill_list_spot_and_futures() {
+        let spot = r#"{
+            "code": "200000",
+            "data": {
+                "currentPage": 1,
+                "pageSize": 50,
+                "totalNum": 1,
+                "items": [{
+                    "symbol": "BTC-USDT",
+                    "tradeId": "5c35c02709e4f67d5266954e",
+                    "orderId": "5c35c02703aa673ceec2a168",
+                    "counterOrderId": "5c1ab46003aa676e487fa8e3",
+                    "side": "buy",
+                    "liquidity": "taker",
+                    "forceTaker": true,
+                    "price": "67500",
+                    "size": "0.01",
+                    "funds": "675",
+                    "fee": "0.675",
+                    "feeRate": "0.001",
+                    "feeCurrency": "USDT",
+                    "type": "limit",
+                    "createdAt": 1547026472000
+                }]
+            }
+        }"#;
+        let trades = KuCoinPlugin::parse_fill_list(spot).unwrap();
+        assert_eq!(trades, vec![Trade {
+            trade_id: "5c35c02709e4f67d5266954e".to_string(),
+            order_id: "5c35c02703aa673ceec2a168".to_string(),
+            symbol: "BTC-USDT".to_string(),
+            side: OrderSide::Buy,
+            price: 67500.0,
+            quantity: 0.01,
+            fee: 0.675,
+            fee_currency: Some("USDT".to_string()),
+            timestamp: 1547026472000,
+        }]);
+        
+        // Futures sizes are lot counts, sent as numbers
+        let futures = r#"{
+            "code": "200000",
+            "data": {
+                "items": [{
+                    "symbol": "XBTUSDTM",
+                    "tradeId": "5ce24c1f0c19fc3c58edc47c",
+                    "orderId": "5ce24c16b210233c36ee321d",
+                    "side": "sell",
+                    "price": "67400",
+                    "size": 3,
+                    "fee": "0.1213",
+                    "feeCurrency": "USDT",
+                    "createdAt": 1558334496000
+                }]
+            }
+        }"#;
+        let trades = KuCoinPlugin::parse_fill_list(futures).unwrap();
+        assert_eq!(trades[0].side, OrderSide::Sell);
+        assert_eq!(trades[0].quantity, 3.0);
+        assert_eq!(trades[0].fee, 0.1213);
+    }
+    
+    fn kucoin_order(order_type: OrderType, price: Option<f64>, stop_loss: Option<f64>) -> Order {
+        Order {
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type,
+            quantity: 0.5,
+            price,
+            stop_loss,
+            confidence: 0.8,
+            ..Default::default()
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_build_order_request_market_limit_stop() {
+        let plugin = init_with_base_url("http://127.0.0.1:9", serde_json::json!({})).await;
+        let mut config = plugin.config.read().await.clone().unwrap();
+        
+        let market = plugin.build_order_request(&config, &kucoin_order(OrderType::Market, None, None)).unwrap();
+        assert_eq!(market.path, "/api/v1/orders");
+        assert_eq!(market.url, "http://127.0.0.1:9/api/v1/orders");
+        assert_eq!(market.body["symbol"], "BTC-USDT");
+        assert_eq!(market.body["type"], "market");
+        assert_eq!(market.body["size"], "0.5");
+        assert_eq!(market.body["leverage"], "10");
+        assert!(market.body.get("price").is_none());
+        
+        let limit = plugin.build_order_request(&config, &kucoin_order(OrderType::Limit, Some(67000.0), None)).unwrap();
+        assert_eq!(limit.body["type"], "limit");
+        assert_eq!(limit.body["price"], "67000");
+        
+        let stop = plugin.build_order_request(&config, &kucoin_order(OrderType::Stop, None, Some(66000.0))).unwrap();
+        assert_eq!(stop.body["type"], "stop");
+        assert_eq!(stop.body["stop"], "down");
+        assert_eq!(stop.body["stopPrice"], "66000");
+        
+        // Spot orders carry no futures-only fields
+        config.trading_type = "spot".to_string();
+        let spot_stop = plugin.build_order_request(&config, &kucoin_order(OrderType::Stop, None, Some(66000.0))).unwrap();
+        assert!(spot_stop.body.get("stopPrice").is_none());
+        assert!(spot_stop.body.get("leverage").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_order_leverage_capped_and_futures_only() {
+        let plugin = init_with_base_url("http://127.0.0.1:9", serde_json::json!({
+            "max_leverage": 20,
+            "strict_leverage": true
+        })).await;
+        let mut config = plugin.config.read().await.clone().unwrap();
+        let mut order = kucoin_order(OrderType::Market, None, None);
+        
+        order.leverage = Some(5);
+        assert_eq!(plugin.build_order_request(&config, &order).unwrap().body["leverage"], "5");
+        
+        order.leverage = Some(50);
+        let err = plugin.build_order_request(&config, &order).unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Exchange { code: LEVERAGE_ABOVE_MAX, .. })));
+        
+        config.trading_type = "spot".to_string();
+        order.leverage = Some(5);
+        let err = plugin.build_order_request(&config, &order).unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_testnet_override_selects_sandbox_host() {
+        let plugin = init_with_base_url("http://127.0.0.1:9", serde_json::json!({})).await;
+        let config = plugin.config.read().await.clone().unwrap();
+        
+        let mut order = kucoin_order(OrderType::Market, None, None);
+        order.testnet = Some(true);
+        let request = plugin.build_order_request(&config, &order).unwrap();
+        assert_eq!(request.url, "https://openapi-sandbox.kucoin.com/api/v1/orders");
+        
+        order.testnet = Some(false);
+        let request = plugin.build_order_request(&config, &order).unwrap();
+        assert_eq!(request.url, "http://127.0.0.1:9/api/v1/orders");
+    }
+    
+    #[tokio::test]
+    async fn test_stop_limit_maps_trigger_and_limit_price() {
+        let mut plugin = KuCoinPlugin::new("test-kucoin");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "api_passphrase": "test-pass"
+        })).await.unwrap();
+        let mut config = plugin.config.read().await.clone().unwrap();
+        
+        let mut order = kucoin_order(OrderType::StopLimit, Some(68100.0), Some(60000.0));
+        order.trigger_price = Some(68000.0);
+        let futures = plugin.build_order_request(&config, &order).unwrap();
+        assert_eq!(futures.path, "/api/v1/orders");
+        assert_eq!(futures.body["type"], "limit");
+        assert_eq!(futures.body["price"], "68100");
+        assert_eq!(futures.body["stop"], "up");
+        assert_eq!(futures.body["stopPriceType"], "TP");
+        // The trigger, not the stop-loss
+        assert_eq!(futures.body["stopPrice"], "68000");
+        
+        config.trading_type = "spot".to_string();
+        order.side = OrderSide::Sell;
+        let spot = plugin.build_order_request(&config, &order).unwrap();
+        assert_eq!(spot.path, "/api/v1/stop-order");
+        assert_eq!(spot.body["stop"], "loss");
+        assert_eq!(spot.body["stopPrice"], "68000");
+        
+        order.trigger_price = None;
+        let err = plugin.build_order_request(&config, &order).unwrap_err();
+        assert_eq!(err.to_string(), "trigger_price is required for StopLimit orders");
+        
+        order.trigger_price = Some(68000.0);
+        order.price = None;
+        let err = plugin.build_order_request(&config, &order).unwrap_err();
+        assert_eq!(err.to_string(), "price (the limit) is required for StopLimit orders");
+    }
+    
+    #[tokio::test]
+    async fn test_display_quantity_sent_as_iceberg() {
+        let mut plugin = KuCoinPlugin::new("test-kucoin");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "api_passphrase": "test-pass"
+        })).await.unwrap();
+        let config = plugin.config.read().await.clone().unwrap();
+        
+        let mut order = kucoin_order(OrderType::Limit, Some(67000.0), None);
+        order.display_quantity = Some(0.1);
+        let request = plugin.build_order_request(&config, &order).unwrap();
+        assert_eq!(request.body["iceberg"], true);
+        assert_eq!(request.body["visibleSize"], "0.1");
+        
+        let plain = plugin.build_order_request(&config, &kucoin_order(OrderType::Limit, Some(67000.0), None)).unwrap();
+        assert!(plain.body.get("iceberg").is_none());
+        assert!(plain.body.get("visibleSize").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_post_only_flag_sent() {
+        let mut plugin = KuCoinPlugin::new("test-kucoin");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "api_passphrase": "test-pass"
+        })).await.unwrap();
+        let config = plugin.config.read().await.clone().unwrap();
+        
+        let mut order = kucoin_order(OrderType::Limit, Some(67000.0), None);
+        order.post_only = true;
+        assert_eq!(plugin.build_order_request(&config, &order).unwrap().body["postOnly"], true);
+        
+        let plain = plugin.build_order_request(&config, &kucoin_order(OrderType::Limit, Some(67000.0), None)).unwrap();
+        assert!(plain.body.get("postOnly").is_none());
+    }
+    
+    #[test]
+    fn test_post_only_reject_codes_mapped() {
+        let err = KuCoinPlugin::post_only_rejection("400100", "Post only order would take liquidity, rejected").unwrap();
+        assert!(matches!(err, ExecutionError::Exchange { code: crate::plugins::POST_ONLY_WOULD_CROSS, .. }));
+        assert!(err.to_string().contains("400100 - Post only order"));
+        
+        assert!(KuCoinPlugin::post_only_rejection("300000", "postOnly order cannot be matched as taker").is_some());
+        assert!(KuCoinPlugin::post_only_rejection("400100", "Parameter Error: size").is_none());
+        assert!(KuCoinPlugin::post_only_rejection("200004", "Balance insufficient!").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_spot_positions_unsupported() {
+        let mut plugin = KuCoinPlugin::new("test-kucoin");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "api_passphrase": "test-pass",
+            "trading_type": "spot"
+        })).await.unwrap();
+        
+        let err = plugin.get_all_positions().await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+        let err = plugin.get_positions(Some("BTC-USDT")).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_quote_spot_market_buy_sends_funds() {
+        let mut plugin = KuCoinPlugin::new("test-kucoin");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "api_passphrase": "test-pass",
+            "trading_type": "spot"
+        })).await.unwrap();
+        let mut config = plugin.config.read().await.clone().unwrap();
+        
+        let mut order = kucoin_order(OrderType::Market, None, None);
+        order.quantity = 250.0;
+        order.quantity_type = QuantityType::Quote;
+        let request = plugin.build_order_request(&config, &order).unwrap();
+        assert_eq!(request.body["funds"], "250");
+        assert!(request.body.get("size").is_none());
+        
+        // Quote sizing isn't available on limit or futures orders
+        order.order_type = OrderType::Limit;
+        order.price = Some(67000.0);
+        let err = plugin.build_order_request(&config, &order).unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+        
+        order.order_type = OrderType::Market;
+        order.price = None;
+        config.trading_type = "futures".to_string();
+        let err = plugin.build_order_request(&config, &order).unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_order_timeout_reconciled_by_client_oid() {
+        use axum::{extract::Query, routing::{get, post}, Json, Router};
+        
+        let app = Router::new()
+            .route("/api/v1/orders", post(|| async {
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                Json(serde_json::json!({ "code": "200000", "data": { "orderId": "late" } }))
+            }))
+            .route("/api/v1/orders/byClientOid", get(|Query(query): Query<HashMap<String, String>>| async move {
+                Json(serde_json::json!({
+                    "code": "200000",
+                    "data": {
+                        "id": "kucoin-7",
+                        "clientOid": query["clientOid"],
+                        "symbol": "XBTUSDTM",
+                        "side": "buy",
+                        "type": "limit",
+                        "price": "67000",
+                        "size": "1",
+                        "filledSize": "0",
+                        "isActive": true,
+                        "cancelExist": false,
+                        "createdAt": 1700000000000i64
+                    }
+                }))
+            }));
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let mut plugin = init_with_base_url(&base_url, serde_json::json!({})).await;
+        plugin.client = Client::builder()
+            .timeout(std::time::Duration::from_millis(200))
+            .build()
+            .unwrap();
+        
+        let result = plugin.execute_order(kucoin_order(OrderType::Limit, Some(67000.0), None)).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.order_id.as_deref(), Some("kucoin-7"));
+        assert_eq!(result.filled_quantity, 0.0);
+        // Nothing filled yet, so no fill price; the limit price isn't one
+        assert_eq!(result.average_price, 0.0);
+    }
+    
+    #[tokio::test]
+    async fn test_testnet_order_timeout_reconciled_on_sandbox_host() {
+        use axum::{extract::Query, routing::get, Json, Router};
+        
+        // Stands in for the sandbox; the configured host refuses connections
+        let app = Router::new().route("/api/v1/orders/byClientOid", get(|Query(query): Query<HashMap<String, String>>| async move {
+            Json(serde_json::json!({
+                "code": "200000",
+                "data": {
+                    "id": "sandbox-1",
+                    "clientOid": query["clientOid"],
+                    "symbol": "XBTUSDTM",
+                    "side": "buy",
+                    "type": "market",
+                    "size": "1",
+                    "filledSize": "1",
+                    "isActive": false,
+                    "cancelExist": false,
+                    "createdAt": 1700000000000i64
+                }
+            }))
+        }));
+        let sandbox_url = spawn_mock_exchange(app).await;
+        let plugin = init_with_base_url("http://127.0.0.1:9", serde_json::json!({})).await;
+        let config = plugin.config.read().await.clone().unwrap();
+        
+        let mut order = kucoin_order(OrderType::Market, None, None);
+        order.testnet = Some(true);
+        let mut request = plugin.build_order_request(&config, &order).unwrap();
+        assert_eq!(request.base_url(), Some("https://openapi-sandbox.kucoin.com"));
+        request.url = format!("{}{}", sandbox_url, request.path);
+        
+        let result = plugin.reconcile_timeout(&config, &request, Instant::now(), "timed out".into()).await.unwrap();
+        assert_eq!(result.order_id.as_deref(), Some("sandbox-1"));
+    }
+    
+    #[tokio::test]
+    async fn test_order_timeout_polls_until_order_appears() {
+        use axum::{http::StatusCode, routing::{get, post}, Json, Router};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        // The order only becomes visible on the third lookup
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let seen = lookups.clone();
+        let app = Router::new()
+            .route("/api/v1/orders", post(|| async {
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                Json(serde_json::json!({ "code": "200000", "data": { "orderId": "late" } }))
+            }))
+            .route("/api/v1/order/client-order/{oid}", get(move || async move {
+                if seen.fetch_add(1, Ordering::SeqCst) < 2 {
+                    return (StatusCode::NOT_FOUND, Json(serde_json::json!({ "code": "400100", "msg": "order not exist" })));
+                }
+                (StatusCode::OK, Json(serde_json::json!({
+                    "code": "200000",
+                    "data": {
+                        "id": "kucoin-8",
+                        "symbol": "BTC-USDT",
+                        "side": "buy",
+                        "type": "market",
+                        "size": "0.01",
+                        "dealSize": "0.01",
+                        "dealFunds": "675.1",
+                        "isActive": false,
+                        "cancelExist": false,
+                        "createdAt": 1700000000000i64
+                    }
+                })))
+            }));
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let mut plugin = init_with_base_url(&base_url, serde_json::json!({
+            "trading_type": "spot"
+        })).await;
+        plugin.client = Client::builder()
+            .timeout(std::time::Duration::from_millis(200))
+            .build()
+            .unwrap();
+        
+        let result = plugin.execute_order(kucoin_order(OrderType::Market, None, None)).await.unwrap();
+        assert!(result.success);
+        assert_eq!(lookups.load(Ordering::SeqCst), 3);
+        assert_eq!(result.order_id.as_deref(), Some("kucoin-8"));
+        assert_eq!(result.filled_quantity, 0.01);
+        assert!((result.average_price - 67510.0).abs() < 1e-6);
+    }
+    
+    #[tokio::test]
+    async fn test_quote_alias_applied_before_conversion() {
+        let mut plugin = KuCoinPlugin::new("test-kucoin");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "api_passphrase": "test-pass",
+            "quote_aliases": { "USD": "USDT" }
+        })).await.unwrap();
+        
+        let config = plugin.config.read().await.clone().unwrap();
+        assert_eq!(plugin.normalize_symbol(&config, "BTCUSD"), "BTC-USDT");
+        assert_eq!(plugin.normalize_symbol(&config, "ETHBTC"), "ETHBTC");
+    }
+    
+    #[test]
+    fn test_symbol_conversion() {
+        assert_eq!(KuCoinPlugin::to_kucoin_symbol("BTCUSDT"), "BTC-USDT");
+        assert_eq!(KuCoinPlugin::to_kucoin_symbol("ETH-BTC"), "ETH-BTC");
+        assert_eq!(KuCoinPlugin::to_kucoin_symbol("XBTUSDTM"), "XBTUSDTM");
+    }
+    
+    #[tokio::test]
+    async fn test_health_status_auth_failed() {
+        use axum::{http::StatusCode, routing::get, Json, Router};
+        
+        let app = Router::new()
+            .route("/api/v1/ticker", get(|| async {
+                Json(serde_json::json!({
+                    "code": "200000",
+                    "data": { "price": "67500.0", "bestBid": "67499.5", "bestAsk": "67500.5" }
+                }))
+            }))
+            .route("/api/v1/accounts", get(|| async {
+                (StatusCode::UNAUTHORIZED, Json(serde_json::json!({ "code": "400003", "msg": "KC-API-KEY not exists" })))
+            }));
+        
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "api_key": "bad-key",
+            "api_secret": "bad-secret",
+            "api_passphrase": "bad-pass"
+        })).await;
+        
+        assert_eq!(plugin.health_status().await, HealthStatus::AuthFailed);
+        assert!(!plugin.health_check().await.unwrap());
+        
+        assert_eq!(KuCoinPlugin::new("test-kucoin").health_status().await, HealthStatus::Unconfigured);
+    }
+    
+    #[tokio::test]
+    async fn test_slippage_capped_market_order_sent_as_ioc_limit() {
+        use axum::{routing::get, Json, Router};
+        
+        let app = Router::new()
+            .route("/api/v1/market/orderbook/level1", get(|| async {
+                Json(serde_json::json!({
+                    "code": "200000",
+                    "data": { "price": "3500.0", "bestBid": "3499.9", "bestAsk": "3500.1" }
+                }))
+            }));
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "trading_type": "spot"
+        })).await;
+        
+        // 1% above the ask, rounded down to the quote's precision
+        let mut order = kucoin_order(OrderType::Market, None, None);
+        order.symbol = "ETH/USDT".to_string();
+        order.max_slippage_pct = Some(1.0);
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.body["type"], "limit");
+        assert_eq!(request.body["timeInForce"], "IOC");
+        assert_eq!(request.body["price"], "3535.1");
+        assert_eq!(request.body["size"], "0.5");
+        
+        // 1% below the bid, rounded up
+        order.side = OrderSide::Sell;
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.body["price"], "3465");
+        
+        order.max_slippage_pct = None;
+        let request = plugin.preview_order(&order).await.unwrap();
+        assert_eq!(request.body["type"], "market");
+        assert!(request.body.get("timeInForce").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_health_check_queries_configured_symbol() {
+        use axum::{extract::Query, routing::get, Json, Router};
+        use std::sync::Mutex;
+        
+        let queried: Arc<Mutex<HashMap<String, usize>>> = Arc::default();
+        let counts = queried.clone();
+        let app = Router::new()
+            .route("/api/v1/market/orderbook/level1", get(move |Query(params): Query<HashMap<String, String>>| async move {
+                let symbol = params.get("symbol").cloned().unwrap_or_default();
+                *counts.lock().unwrap().entry(symbol).or_default() += 1;
+                Json(serde_json::json!({
+                    "code": "200000",
+                    "data": { "price": "3500.0", "bestBid": "3499.9", "bestAsk": "3500.1" }
+                }))
+            }))
+            .route("/api/v1/accounts", get(|| async {
+                Json(serde_json::json!({ "code": "200000", "data": [] }))
+            }));
+        
+        let base_url = spawn_mock_exchange(app).await;
+        
+        let plugin = init_with_base_url(&base_url, serde_json::json!({
+            "trading_type": "spot",
+            "health_check_symbol": "ETH-USDC"
+        })).await;
+        
+        assert_eq!(plugin.health_status().await, HealthStatus::Healthy);
+        let queried = queried.lock().unwrap().clone();
+        assert_eq!(queried, HashMap::from([("ETH-USDC".to_string(), 1)]));
+    }
+    
+    #[test]
+    fn test_classify_auth_response() {
+        use reqwest::StatusCode;
+        
+        let ok = r#"{"code":"200000","data":[]}"#;
+        assert_eq!(KuCoinPlugin::classify_auth_response(StatusCode::OK, ok), HealthStatus::Healthy);
+        
+        let bad_pass = r#"{"code":"400004","msg":"Invalid KC-API-PASSPHRASE"}"#;
+        assert_eq!(KuCoinPlugin::classify_auth_response(StatusCode::OK, bad_pass), HealthStatus::AuthFailed);
+        assert_eq!(KuCoinPlugin::classify_auth_response(StatusCode::UNAUTHORIZED, ""), HealthStatus::AuthFailed);
+        
+        assert_eq!(KuCoinPlugin::classify_auth_response(StatusCode::BAD_GATEWAY, "<html>"), HealthStatus::Unreachable);
+    }
+    
+    #[tokio::test]
+    async fn test_maintenance_response_marks_plugin_unavailable() {
+        let mut plugin = KuCoinPlugin::new("test-kucoin");
+        plugin.init(serde_json::json!({
+            "api_key": "test-key",
+            "api_secret": "test-secret",
+            "api_passphrase": "test-pass"
+        })).await.unwrap();
+        
+        assert!(plugin.check_maintenance(reqwest::StatusCode::OK, "{}").is_none());
+        assert!(!plugin.in_maintenance());
+        
+        let body = "<html>System maintenance</html>";
+        let err = plugin.check_maintenance(reqwest::StatusCode::SERVICE_UNAVAILABLE, body).unwrap();
+        assert!(matches!(err, ExecutionError::Unsupported(ref msg) if msg.contains("maintenance")));
+        assert!(plugin.in_maintenance());
+        assert!(!plugin.health_check().await.unwrap());
+        
+        let order = Order {
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Sell,
+            order_type: OrderType::Market,
+            quantity: 1.0,
+            confidence: 0.75,
+            ..Default::default()
+        };
+        let err = plugin.execute_order(order).await.unwrap_err();
+        assert!(err.downcast_ref::<ExecutionError>().is_some());
     }
 }
@@ -0,0 +1,171 @@
+//! Artificial latency for plugin calls in tests
+//!
+//! [`LatencyPlugin`] wraps any plugin and sleeps before delegating each call,
+//! so deadline and slow-venue behavior can be exercised deterministically
+//! without real network variability. Test builds only.
+
+use async_trait::async_trait;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{
+    Balance, CancelOutcome, ClosedPnl, ExecutionPlugin, ExecutionResult, FundingRate, HealthStatus, InstrumentInfo, LeverageChange,
+    MarketData, Order, OrderPage, OrderRef, OrderSummary, PingResult, Position, PreparedRequest, Trade,
+};
+
+/// A plugin whose every exchange call takes `latency` plus up to `jitter` longer
+pub struct LatencyPlugin {
+    inner: Arc<dyn ExecutionPlugin>,
+    latency: Duration,
+    jitter: Duration,
+}
+
+impl LatencyPlugin {
+    pub fn new(inner: Arc<dyn ExecutionPlugin>, latency: Duration, jitter: Duration) -> Self {
+        Self { inner, latency, jitter }
+    }
+    
+    /// Sleep for the base latency plus a random share of the jitter
+    async fn delay(&self) {
+        let jitter_ms = self.jitter.as_millis() as u64;
+        let extra = match jitter_ms {
+            0 => 0,
+            _ => (uuid::Uuid::new_v4().as_u128() as u64) % (jitter_ms + 1),
+        };
+        tokio::time::sleep(self.latency + Duration::from_millis(extra)).await;
+    }
+}
+
+#[async_trait]
+impl ExecutionPlugin for LatencyPlugin {
+    async fn init(&mut self, _config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Err("LatencyPlugin wraps an initialized plugin".into())
+    }
+    
+    async fn execute_order(&self, order: Order) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.execute_order(order).await
+    }
+    
+    async fn fetch_data(&self, symbol: &str) -> Result<MarketData, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.fetch_data(symbol).await
+    }
+    
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+    
+    async fn health_check(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.health_check().await
+    }
+    
+    async fn get_positions(&self, symbol: Option<&str>) -> Result<Vec<Position>, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.get_positions(symbol).await
+    }
+    
+    async fn get_balances(&self) -> Result<Vec<Balance>, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.get_balances().await
+    }
+    
+    async fn get_closed_pnl(&self, symbol: Option<&str>) -> Result<Vec<ClosedPnl>, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.get_closed_pnl(symbol).await
+    }
+    
+    async fn fetch_trades(&self, symbol: Option<&str>, limit: usize) -> Result<Vec<Trade>, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.fetch_trades(symbol, limit).await
+    }
+    
+    async fn fetch_funding_rate(&self, symbol: &str) -> Result<FundingRate, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.fetch_funding_rate(symbol).await
+    }
+    
+    async fn set_leverage(&self, symbol: &str, leverage: i32) -> Result<LeverageChange, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.set_leverage(symbol, leverage).await
+    }
+    
+    async fn get_all_positions(&self) -> Result<Vec<Position>, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.get_all_positions().await
+    }
+    
+    async fn instrument_info(&self, symbol: &str) -> Result<InstrumentInfo, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.instrument_info(symbol).await
+    }
+    
+    async fn ping(&self) -> Result<PingResult, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.ping().await
+    }
+    
+    async fn health_status(&self) -> HealthStatus {
+        self.delay().await;
+        self.inner.health_status().await
+    }
+    
+    async fn list_orders(&self, symbol: Option<&str>) -> Result<Vec<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.list_orders(symbol).await
+    }
+    
+    async fn list_orders_page(&self, symbol: Option<&str>, cursor: Option<&str>, limit: usize) -> Result<OrderPage, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.list_orders_page(symbol, cursor, limit).await
+    }
+    
+    // Previews are built locally and never reach the venue
+    async fn preview_order(&self, order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        self.inner.preview_order(order).await
+    }
+    
+    async fn list_symbols(&self) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.list_symbols().await
+    }
+    
+    async fn get_order(&self, symbol: &str, order_id: &str) -> Result<OrderSummary, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.get_order(symbol, order_id).await
+    }
+    
+    async fn get_order_by_client_id(&self, symbol: &str, client_order_id: &str) -> Result<Option<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.get_order_by_client_id(symbol, client_order_id).await
+    }
+    
+    async fn cancel_all_orders(&self, symbol: Option<&str>) -> Result<Vec<CancelOutcome>, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.cancel_all_orders(symbol).await
+    }
+    
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.cancel_order(symbol, order_id).await
+    }
+    
+    async fn cancel_batch(&self, symbol: &str, orders: &[OrderRef]) -> Result<Vec<CancelOutcome>, Box<dyn Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.cancel_batch(symbol, orders).await
+    }
+    
+    fn in_maintenance(&self) -> bool {
+        self.inner.in_maintenance()
+    }
+    
+    fn is_paper(&self) -> bool {
+        self.inner.is_paper()
+    }
+    
+    async fn effective_config(&self) -> Option<serde_json::Value> {
+        self.inner.effective_config().await
+    }
+}
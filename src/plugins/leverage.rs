@@ -0,0 +1,61 @@
+//! Operator cap on exchange leverage
+//!
+//! A misconfigured `leverage` or a fat-fingered set-leverage request or order
+//! can put an account at 100x. Plugins flatten [`LeverageLimit`] into their config and
+//! pass every leverage they are about to send through [`LeverageLimit::apply`]:
+//! above `max_leverage` it is clamped with a warning, or refused when
+//! `strict_leverage` is set.
+
+use super::{ExecutionError, LEVERAGE_ABOVE_MAX};
+use serde::{Deserialize, Serialize};
+
+/// Optional leverage ceiling for a plugin
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LeverageLimit {
+    /// Highest leverage the plugin may send (default: no cap)
+    #[serde(default)]
+    pub max_leverage: Option<i32>,
+    
+    /// Reject leverage above `max_leverage` instead of clamping it
+    #[serde(default)]
+    pub strict_leverage: bool,
+}
+
+impl LeverageLimit {
+    /// Leverage to send for `requested`, clamped to the cap or rejected when strict
+    pub fn apply(&self, plugin: &str, requested: i32) -> Result<i32, ExecutionError> {
+        let Some(max) = self.max_leverage.filter(|max| requested > *max) else {
+            return Ok(requested);
+        };
+        if self.strict_leverage {
+            return Err(ExecutionError::Exchange {
+                code: LEVERAGE_ABOVE_MAX,
+                message: format!("{} leverage {}x exceeds max_leverage {}x", plugin, requested, max),
+            });
+        }
+        tracing::warn!(plugin = %plugin, requested, max_leverage = max, "leverage_clamped_to_max");
+        Ok(max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_leverage_clamped_or_rejected_above_max() {
+        let uncapped = LeverageLimit::default();
+        assert_eq!(uncapped.apply("bybit", 100).unwrap(), 100);
+        
+        let capped = LeverageLimit { max_leverage: Some(20), strict_leverage: false };
+        assert_eq!(capped.apply("bybit", 100).unwrap(), 20);
+        assert_eq!(capped.apply("bybit", 20).unwrap(), 20);
+        assert_eq!(capped.apply("bybit", 5).unwrap(), 5);
+        
+        let strict = LeverageLimit { max_leverage: Some(20), strict_leverage: true };
+        let err = strict.apply("bybit", 100).unwrap_err();
+        assert!(matches!(err, ExecutionError::Exchange { code: LEVERAGE_ABOVE_MAX, .. }));
+        assert_eq!(err.to_string(), "leverage_above_max: bybit leverage 100x exceeds max_leverage 20x");
+        assert_eq!(strict.apply("bybit", 10).unwrap(), 10);
+    }
+}
@@ -1,16 +1,73 @@
 //! Mock Plugin for Testing
 //!
 //! Simulates order execution without real broker/exchange connections
+//!
+//! Market orders normally fill in full at a flat 0.01% slippage. Symbols
+//! given a synthetic order book in the `order_books` config instead fill by
+//! walking its levels, so large orders on a thin book fill partially and at
+//! a worse average price.
 
-use super::{ExecutionPlugin, ExecutionResult, MarketData, Order};
+use super::{now_millis, reject_quote_quantity, unique_id, Balance, CancelOutcome, ClosedPnl, ExecutionPlugin, ExecutionResult, FundingRate, InstrumentInfo, InstrumentRounding, LeverageChange, MarketData, Order, OrderRef, PreparedRequest, OrderSide, OrderStatus, OrderSummary, OrderType, PingResult, Position, PositionSide, Trade};
 use async_trait::async_trait;
-use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::error::Error;
+use std::sync::Mutex;
+
+/// Synthetic round-trip latency reported by `ping`
+pub const MOCK_PING_LATENCY_MS: u64 = 12;
 
 /// Mock plugin for testing and development
+/// Symbols the mock reports as tradable
+const MOCK_SYMBOLS: [&str; 3] = ["BTC/USDT", "ETH/USDT", "SOL/USDT"];
+
+/// Longest client order id the mock generates
+const MOCK_CLIENT_ID_MAX_LEN: usize = 36;
+
+/// Resting liquidity for one symbol as `[price, quantity]` levels
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SyntheticBook {
+    #[serde(default)]
+    bids: Vec<(f64, f64)>,
+    #[serde(default)]
+    asks: Vec<(f64, f64)>,
+}
+
+impl SyntheticBook {
+    /// Quantity a market order takes from the book, best level first, and its average price
+    fn fill(&self, side: &OrderSide, quantity: f64) -> (f64, f64) {
+        let mut levels = match side {
+            OrderSide::Buy => self.asks.clone(),
+            OrderSide::Sell => self.bids.clone(),
+        };
+        match side {
+            OrderSide::Buy => levels.sort_by(|a, b| a.0.total_cmp(&b.0)),
+            OrderSide::Sell => levels.sort_by(|a, b| b.0.total_cmp(&a.0)),
+        }
+        
+        let (mut filled, mut notional) = (0.0, 0.0);
+        for (price, size) in levels {
+            let take = size.min(quantity - filled);
+            if take <= 0.0 {
+                break;
+            }
+            filled += take;
+            notional += take * price;
+        }
+        let average = if filled > 0.0 { notional / filled } else { 0.0 };
+        (filled, average)
+    }
+}
+
 pub struct MockPlugin {
     name: String,
     is_initialized: bool,
+    /// Orders placed through this plugin; market orders fill immediately, others rest
+    placed: Mutex<HashMap<String, OrderSummary>>,
+    /// Last leverage set per symbol
+    leverage: Mutex<HashMap<String, i32>>,
+    /// Synthetic books market orders fill against, by symbol
+    books: HashMap<String, SyntheticBook>,
 }
 
 impl MockPlugin {
@@ -18,14 +75,32 @@ impl MockPlugin {
         Self {
             name: name.to_string(),
             is_initialized: false,
+            placed: Mutex::new(HashMap::new()),
+            leverage: Mutex::new(HashMap::new()),
+            books: HashMap::new(),
+        }
+    }
+    
+    /// Simulate an exchange-side status change (e.g. a resting order filling)
+    #[cfg(test)]
+    pub fn set_order_status(&self, order_id: &str, status: OrderStatus) {
+        if let Some(order) = self.placed.lock().unwrap().get_mut(order_id) {
+            if status == OrderStatus::Filled {
+                order.filled_quantity = order.quantity;
+            }
+            order.status = status;
         }
     }
 }
 
 #[async_trait]
 impl ExecutionPlugin for MockPlugin {
-    async fn init(&mut self, _config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
+    async fn init(&mut self, config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
         tracing::info!(plugin = %self.name, "Initializing mock plugin");
+        if let Some(books) = config.get("order_books") {
+            self.books = serde_json::from_value(books.clone())
+                .map_err(|e| format!("Invalid mock order_books: {}", e))?;
+        }
         self.is_initialized = true;
         Ok(())
     }
@@ -37,6 +112,7 @@ impl ExecutionPlugin for MockPlugin {
         if !self.is_initialized {
             return Err("Plugin not initialized".into());
         }
+        reject_quote_quantity(&self.name, &order)?;
         
         tracing::info!(
             plugin = %self.name,
@@ -46,24 +122,63 @@ impl ExecutionPlugin for MockPlugin {
             "Mock executing order"
         );
         
-        // Simulate execution with slight slippage
-        let base_price = order.price.unwrap_or(67500.0);
-        let slippage = base_price * 0.0001; // 0.01% slippage
-        let execution_price = match order.side {
-            super::OrderSide::Buy => base_price + slippage,
-            super::OrderSide::Sell => base_price - slippage,
+        let resting = !matches!(order.order_type, OrderType::Market);
+        let (filled, execution_price) = match self.books.get(&order.symbol).filter(|_| !resting) {
+            Some(book) => book.fill(&order.side, order.quantity),
+            None => {
+                // Simulate execution with slight slippage
+                let base_price = order.price.unwrap_or(67500.0);
+                let slippage = base_price * 0.0001; // 0.01% slippage
+                let execution_price = match order.side {
+                    super::OrderSide::Buy => base_price + slippage,
+                    super::OrderSide::Sell => base_price - slippage,
+                };
+                (order.quantity, execution_price)
+            }
         };
         
         // Simulate small delay
         tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
         
+        if !resting && filled <= 0.0 {
+            return Ok(ExecutionResult {
+                success: false,
+                order_id: None,
+                filled_quantity: 0.0,
+                average_price: 0.0,
+                error: Some(format!("No liquidity in the synthetic {} book", order.symbol)),
+                timestamp: now_millis(),
+            });
+        }
+        
+        // A market order's unfilled remainder is cancelled, as on an exchange
+        let status = match (resting, filled < order.quantity) {
+            (true, _) => OrderStatus::Open,
+            (false, false) => OrderStatus::Filled,
+            (false, true) => OrderStatus::Cancelled,
+        };
+        let order_id = format!("MOCK-{}", unique_id());
+        self.placed.lock().unwrap().insert(order_id.clone(), OrderSummary {
+            order_id: order_id.clone(),
+            client_order_id: Some(super::client_order_id(&order, MOCK_CLIENT_ID_MAX_LEN)),
+            symbol: order.symbol.clone(),
+            side: order.side.clone(),
+            order_type: format!("{:?}", order.order_type).to_lowercase(),
+            quantity: order.quantity,
+            filled_quantity: if resting { 0.0 } else { filled },
+            price: order.price,
+            average_price: (!resting && filled > 0.0).then_some(execution_price),
+            status,
+            timestamp: now_millis(),
+        });
+        
         Ok(ExecutionResult {
             success: true,
-            order_id: Some(format!("MOCK-{}", uuid::Uuid::new_v4())),
-            filled_quantity: order.quantity,
+            order_id: Some(order_id),
+            filled_quantity: filled,
             average_price: execution_price,
             error: None,
-            timestamp: Utc::now().timestamp_millis(),
+            timestamp: now_millis(),
         })
     }
     
@@ -89,24 +204,315 @@ impl ExecutionPlugin for MockPlugin {
             ask: base_price + spread / 2.0,
             last: base_price,
             volume: 1000000.0,
-            timestamp: Utc::now().timestamp_millis(),
+            timestamp: now_millis(),
             extra: serde_json::json!({"source": "mock"}),
         })
     }
     
+    async fn list_orders(&self, symbol: Option<&str>) -> Result<Vec<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        // Synthetic resting orders
+        let orders = vec![
+            OrderSummary {
+                order_id: "MOCK-OPEN-1".to_string(),
+                client_order_id: Some("fks-mock-1".to_string()),
+                symbol: "BTC/USDT".to_string(),
+                side: OrderSide::Buy,
+                order_type: "limit".to_string(),
+                quantity: 0.1,
+                filled_quantity: 0.0,
+                price: Some(65000.0),
+                average_price: None,
+                status: OrderStatus::Open,
+                timestamp: now_millis(),
+            },
+            OrderSummary {
+                order_id: "MOCK-OPEN-2".to_string(),
+                client_order_id: Some("fks-mock-2".to_string()),
+                symbol: "ETH/USDT".to_string(),
+                side: OrderSide::Sell,
+                order_type: "limit".to_string(),
+                quantity: 2.0,
+                filled_quantity: 0.5,
+                price: Some(3600.0),
+                average_price: Some(3600.0),
+                status: OrderStatus::PartiallyFilled,
+                timestamp: now_millis(),
+            },
+        ];
+        
+        Ok(orders
+            .into_iter()
+            .filter(|o| symbol.is_none_or(|s| o.symbol == s))
+            .collect())
+    }
+    
+    async fn preview_order(&self, order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        reject_quote_quantity(&self.name, order)?;
+        Ok(PreparedRequest {
+            method: "POST".to_string(),
+            url: "mock://orders".to_string(),
+            path: "/orders".to_string(),
+            body: serde_json::to_value(order)?,
+        })
+    }
+    
+    async fn list_symbols(&self) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        Ok(MOCK_SYMBOLS.iter().map(|s| s.to_string()).collect())
+    }
+    
+    async fn get_order(&self, _symbol: &str, order_id: &str) -> Result<OrderSummary, Box<dyn Error + Send + Sync>> {
+        self.placed.lock().unwrap()
+            .get(order_id)
+            .cloned()
+            .ok_or_else(|| format!("Mock order {} not found", order_id).into())
+    }
+    
+    async fn get_order_by_client_id(&self, _symbol: &str, client_order_id: &str) -> Result<Option<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        let synthetic = self.list_orders(None).await?;
+        let placed: Vec<OrderSummary> = self.placed.lock().unwrap().values().cloned().collect();
+        Ok(synthetic
+            .into_iter()
+            .chain(placed)
+            .find(|o| o.client_order_id.as_deref() == Some(client_order_id)))
+    }
+    
+    async fn cancel_order(&self, _symbol: &str, order_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut placed = self.placed.lock().unwrap();
+        let order = placed.get_mut(order_id)
+            .ok_or_else(|| format!("Mock order {} not found", order_id))?;
+        if !matches!(order.status, OrderStatus::Open | OrderStatus::PartiallyFilled) {
+            return Err(format!("Mock order {} is {:?} and cannot be cancelled", order_id, order.status).into());
+        }
+        order.status = OrderStatus::Cancelled;
+        Ok(())
+    }
+    
+    /// Every resting order placed through the mock is cancelled
+    async fn cancel_all_orders(&self, symbol: Option<&str>) -> Result<Vec<CancelOutcome>, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        let mut placed = self.placed.lock().unwrap();
+        let open = placed.values_mut()
+            .filter(|o| matches!(o.status, OrderStatus::Open | OrderStatus::PartiallyFilled))
+            .filter(|o| symbol.is_none_or(|s| o.symbol == s));
+        Ok(open
+            .map(|o| {
+                o.status = OrderStatus::Cancelled;
+                CancelOutcome::cancelled(OrderRef::OrderId(o.order_id.clone()))
+            })
+            .collect())
+    }
+    
+    /// Resting orders are cancelled; unknown or already closed ones fail individually
+    async fn cancel_batch(&self, symbol: &str, orders: &[OrderRef]) -> Result<Vec<CancelOutcome>, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        let mut outcomes = Vec::with_capacity(orders.len());
+        for order in orders {
+            let order_id = match order {
+                OrderRef::OrderId(id) => Some(id.clone()),
+                OrderRef::ClientOrderId(client_id) => self.placed.lock().unwrap()
+                    .values()
+                    .find(|o| o.client_order_id.as_deref() == Some(client_id.as_str()))
+                    .map(|o| o.order_id.clone()),
+            };
+            let result = match order_id {
+                Some(id) => self.cancel_order(symbol, &id).await,
+                None => Err("Mock order not found".into()),
+            };
+            outcomes.push(match result {
+                Ok(()) => CancelOutcome::cancelled(order.clone()),
+                Err(e) => CancelOutcome::failed(order.clone(), e.to_string()),
+            });
+        }
+        Ok(outcomes)
+    }
+    
+    async fn fetch_funding_rate(&self, symbol: &str) -> Result<FundingRate, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        // Synthetic 0.01% rate, settled on the next 8-hour boundary
+        const INTERVAL_MS: i64 = 8 * 60 * 60 * 1000;
+        let now = now_millis();
+        Ok(FundingRate {
+            symbol: symbol.to_string(),
+            rate: 0.0001,
+            next_funding_time: (now / INTERVAL_MS + 1) * INTERVAL_MS,
+            timestamp: now,
+        })
+    }
+    
+    async fn set_leverage(&self, symbol: &str, leverage: i32) -> Result<LeverageChange, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        match self.leverage.lock().unwrap().insert(symbol.to_string(), leverage) {
+            Some(previous) if previous == leverage => Ok(LeverageChange::Unchanged),
+            _ => Ok(LeverageChange::Changed),
+        }
+    }
+    
+    async fn get_positions(&self, symbol: Option<&str>) -> Result<Vec<Position>, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        // Synthetic open positions
+        let positions = vec![
+            Position {
+                symbol: "BTC/USDT".to_string(),
+                side: PositionSide::Long,
+                size: 0.5,
+                entry_price: 67000.0,
+                mark_price: 67500.0,
+                unrealized_pnl: 250.0,
+                leverage: 10.0,
+            },
+            Position {
+                symbol: "ETH/USDT".to_string(),
+                side: PositionSide::Short,
+                size: 2.0,
+                entry_price: 3550.0,
+                mark_price: 3500.0,
+                unrealized_pnl: 100.0,
+                leverage: 5.0,
+            },
+        ];
+        
+        Ok(positions
+            .into_iter()
+            .filter(|p| symbol.is_none_or(|s| p.symbol == s))
+            .collect())
+    }
+    
+    async fn get_balances(&self) -> Result<Vec<Balance>, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        // Synthetic wallet, including a tiny and an emptied asset
+        let balance = |asset: &str, free: f64, locked: f64| Balance {
+            asset: asset.to_string(),
+            free,
+            locked,
+            total: free + locked,
+        };
+        Ok(vec![
+            balance("BTC", 0.25, 0.0),
+            balance("ETH", 0.000004, 0.0),
+            balance("SOL", 0.0, 0.0),
+            balance("USDT", 9500.0, 500.0),
+        ])
+    }
+    
+    async fn get_closed_pnl(&self, symbol: Option<&str>) -> Result<Vec<ClosedPnl>, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        // Synthetic closes: one winner and one loser on BTC, one on ETH
+        let now = now_millis();
+        let closed = |symbol: &str, quantity: f64, realized_pnl: f64, age_ms: i64| ClosedPnl {
+            symbol: symbol.to_string(),
+            quantity,
+            realized_pnl,
+            closed_at: now - age_ms,
+        };
+        Ok(vec![
+            closed("BTC/USDT", 0.2, 180.0, 60_000),
+            closed("BTC/USDT", 0.1, -60.0, 3_600_000),
+            closed("ETH/USDT", 1.0, -25.5, 7_200_000),
+        ]
+            .into_iter()
+            .filter(|c| symbol.is_none_or(|s| c.symbol == s))
+            .collect())
+    }
+    
+    async fn fetch_trades(&self, symbol: Option<&str>, limit: usize) -> Result<Vec<Trade>, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        // Synthetic fills: one BTC order filled in two trades, one ETH fill
+        let now = now_millis();
+        let fill = |id: u32, order_id: &str, symbol: &str, side: OrderSide, price: f64, quantity: f64, age_ms: i64| Trade {
+            trade_id: format!("mock-trade-{}", id),
+            order_id: order_id.to_string(),
+            symbol: symbol.to_string(),
+            side,
+            price,
+            quantity,
+            fee: price * quantity * 0.001,
+            fee_currency: Some("USDT".to_string()),
+            timestamp: now - age_ms,
+        };
+        Ok(vec![
+            fill(3, "mock-order-2", "ETH/USDT", OrderSide::Sell, 3500.0, 1.0, 30_000),
+            fill(2, "mock-order-1", "BTC/USDT", OrderSide::Buy, 67510.0, 0.2, 60_000),
+            fill(1, "mock-order-1", "BTC/USDT", OrderSide::Buy, 67500.0, 0.3, 61_000),
+        ]
+            .into_iter()
+            .filter(|t| symbol.is_none_or(|s| t.symbol == s))
+            .take(limit)
+            .collect())
+    }
+    
+    async fn instrument_info(&self, symbol: &str) -> Result<InstrumentInfo, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        Ok(InstrumentInfo {
+            symbol: symbol.to_string(),
+            qty_step: 0.001,
+            min_qty: 0.001,
+            tick_size: 0.01,
+            rounding: InstrumentRounding::default(),
+        })
+    }
+    
     fn name(&self) -> &str {
         &self.name
     }
     
+    fn is_paper(&self) -> bool {
+        true
+    }
+    
     async fn health_check(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
         Ok(self.is_initialized)
     }
+    
+    async fn ping(&self) -> Result<PingResult, Box<dyn Error + Send + Sync>> {
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        let now = now_millis();
+        Ok(PingResult {
+            latency_ms: MOCK_PING_LATENCY_MS as f64,
+            server_time: Some(now),
+            clock_skew_ms: Some(0),
+            timestamp: now,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::plugins::{OrderSide, OrderType};
+    use crate::plugins::{ExecutionError, OrderSide, OrderType, QuantityType};
     
     #[tokio::test]
     async fn test_mock_plugin_init() {
@@ -129,9 +535,8 @@ mod tests {
             order_type: OrderType::Market,
             quantity: 0.1,
             price: Some(67500.0),
-            stop_loss: None,
-            take_profit: None,
             confidence: 0.75,
+            ..Default::default()
         };
         
         let result = plugin.execute_order(order).await.unwrap();
@@ -142,6 +547,43 @@ mod tests {
         assert!(result.average_price > 67500.0); // Buy has positive slippage
     }
     
+    #[tokio::test]
+    async fn test_large_market_order_partially_fills_thin_book() {
+        let mut plugin = MockPlugin::new("test-mock");
+        plugin.init(serde_json::json!({
+            "order_books": {
+                "BTC/USDT": { "bids": [[67490.0, 1.0]], "asks": [[67550.0, 0.3], [67510.0, 0.2]] }
+            }
+        })).await.unwrap();
+        
+        let order = Order {
+            symbol: "BTC/USDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: 1.0,
+            confidence: 0.75,
+            ..Default::default()
+        };
+        
+        // Only half the order finds liquidity, best ask first
+        let result = plugin.execute_order(order.clone()).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.filled_quantity, 0.5);
+        assert!((result.average_price - 67534.0).abs() < 1e-6);
+        let placed = plugin.get_order("BTC/USDT", result.order_id.as_deref().unwrap()).await.unwrap();
+        assert_eq!((placed.filled_quantity, placed.status), (0.5, OrderStatus::Cancelled));
+        
+        // A small order fills in full at the top of the book
+        let small = Order { quantity: 0.1, ..order.clone() };
+        let result = plugin.execute_order(small).await.unwrap();
+        assert_eq!(result.filled_quantity, 0.1);
+        assert_eq!(result.average_price, 67510.0);
+        
+        // Symbols without a book keep the flat slippage
+        let eth = Order { symbol: "ETH/USDT".to_string(), price: Some(3500.0), ..order };
+        assert_eq!(plugin.execute_order(eth).await.unwrap().filled_quantity, 1.0);
+    }
+    
     #[tokio::test]
     async fn test_mock_plugin_fetch_data() {
         let mut plugin = MockPlugin::new("test-mock");
@@ -168,4 +610,21 @@ mod tests {
         let health = plugin.health_check().await.unwrap();
         assert!(health);
     }
+    
+    #[tokio::test]
+    async fn test_quote_quantity_rejected_as_unsupported() {
+        let mut plugin = MockPlugin::new("test-mock");
+        plugin.init(serde_json::json!({})).await.unwrap();
+        let order = Order {
+            symbol: "BTC/USDT".to_string(),
+            quantity: 500.0,
+            quantity_type: QuantityType::Quote,
+            ..Default::default()
+        };
+        
+        let err = plugin.execute_order(order.clone()).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+        let err = plugin.preview_order(&order).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+    }
 }
@@ -4,14 +4,555 @@
 
 pub mod bybit;
 pub mod ccxt;
+pub mod headers;
+pub mod ibkr;
 pub mod kucoin;
+#[cfg(test)]
+pub mod latency;
+pub mod leverage;
 pub mod mock;
 pub mod openalgo;
 pub mod registry;
+pub mod tls;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::clock::{self, SharedClock};
+
+/// Current time as Unix milliseconds
+///
+/// All outward-facing timestamps (results, market data, webhook payloads,
+/// signals, health responses) use this unit.
+pub fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Last sequence value handed out by `unique_id`
+static LAST_ID_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Width of the base36-encoded sequence (covers timestamps until ~2085)
+const ID_SEQ_WIDTH: usize = 10;
+
+/// Generate a unique, time-sortable id for orders and idempotency keys
+///
+/// A process-wide monotonic sequence seeded from the clock (Unix millis x 1000,
+/// so bursts within one millisecond still get distinct values) encoded as
+/// fixed-width base36, followed by 4 random hex chars to keep concurrent
+/// processes apart.
+pub fn unique_id() -> String {
+    let now = now_millis().max(0) as u64 * 1000;
+    let previous = LAST_ID_SEQ
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(now.max(last + 1)))
+        .unwrap_or_else(|last| last);
+    let seq = now.max(previous + 1);
+    
+    let mut encoded = [b'0'; ID_SEQ_WIDTH];
+    let mut rest = seq;
+    for slot in encoded.iter_mut().rev() {
+        *slot = b"0123456789abcdefghijklmnopqrstuvwxyz"[(rest % 36) as usize];
+        rest /= 36;
+    }
+    
+    let random = uuid::Uuid::new_v4().simple().to_string();
+    format!("{}{}", String::from_utf8_lossy(&encoded), &random[..4])
+}
+
+/// Whether `DEBUG_SIGN=true`: log the prehash and signature of authenticated calls
+pub fn debug_sign_enabled() -> bool {
+    std::env::var("DEBUG_SIGN").is_ok_and(|v| v.eq_ignore_ascii_case("true") || v == "1")
+}
+
+/// Log a request signature at debug level to diagnose signature mismatches
+///
+/// Any occurrence of `secret` is redacted, so a prehash built from the wrong
+/// field can't leak it either.
+pub fn log_signed_request(plugin: &str, timestamp: &str, prehash: &str, signature: &str, secret: &str) {
+    let redact = |value: &str| if secret.is_empty() { value.to_string() } else { value.replace(secret, "[REDACTED]") };
+    tracing::debug!(
+        plugin = %plugin,
+        timestamp = %timestamp,
+        prehash = %redact(prehash),
+        signature = %redact(signature),
+        "signed_request"
+    );
+}
+
+/// Parse a quote-currency alias spec such as `USD=USDT,BUSD=USDT`
+pub fn parse_quote_aliases(spec: &str) -> HashMap<String, String> {
+    spec.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(from, to)| (from.trim().to_uppercase(), to.trim().to_uppercase()))
+        .filter(|(from, to)| !from.is_empty() && !to.is_empty())
+        .collect()
+}
+
+/// Remap the quote currency of `symbol` using a plugin's alias map
+///
+/// Handles `BTCUSD`, `BTC-USD` and `BTC/USD`; the longest matching alias wins.
+/// Symbols without a matching quote pass through unchanged.
+pub fn apply_quote_alias(plugin: &str, symbol: &str, aliases: &HashMap<String, String>) -> String {
+    let mut candidates: Vec<_> = aliases.iter().collect();
+    candidates.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+    
+    for (from, to) in candidates {
+        let Some(base) = symbol.strip_suffix(from.as_str()) else {
+            continue;
+        };
+        if base.trim_end_matches(['-', '/']).is_empty() {
+            continue;
+        }
+        
+        let remapped = format!("{}{}", base, to);
+        tracing::info!(plugin = %plugin, from = %symbol, to = %remapped, "Remapped quote currency");
+        return remapped;
+    }
+    
+    symbol.to_string()
+}
+
+/// Typed execution errors surfaced by plugins
+///
+/// Plugins still return `Box<dyn Error + Send + Sync>`; callers that need to
+/// branch on the failure kind can `downcast_ref::<ExecutionError>()`.
+#[derive(Debug, thiserror::Error)]
+pub enum ExecutionError {
+    /// The operation cannot be served by this plugin (e.g. exchange maintenance)
+    #[error("unsupported: {0}")]
+    Unsupported(String),
+    
+    /// The venue (or a proxy in front of it) answered with something other than its API
+    #[error("network: {0}")]
+    Network(String),
+    
+    /// The plugin is registered but disabled by an operator
+    #[error("disabled: {0}")]
+    Disabled(String),
+    
+    /// Too many orders are already being submitted; retrying later may succeed
+    #[error("overloaded: {0}")]
+    Overloaded(String),
+    
+    /// No plugin is registered under this name
+    #[error("Plugin '{0}' not found")]
+    PluginNotFound(String),
+    
+    /// The symbol is not in the exchange's instrument list; `suggestions`
+    /// are the closest listed symbols
+    #[error("Unknown symbol '{symbol}'{}", did_you_mean(suggestions))]
+    InvalidSymbol { symbol: String, suggestions: Vec<String> },
+    
+    /// The exchange rejected the order for a reason the caller can act on;
+    /// `code` is stable (e.g. [`POST_ONLY_WOULD_CROSS`]), `message` is the venue's
+    #[error("{code}: {message}")]
+    Exchange { code: &'static str, message: String },
+    
+    /// An order request timed out and the venue never confirmed or denied
+    /// the order; check the exchange before retrying
+    #[error("outcome unknown: {0}")]
+    OutcomeUnknown(String),
+}
+
+/// Most listed symbols suggested for an unknown one
+const MAX_SYMBOL_SUGGESTIONS: usize = 3;
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions.is_empty() {
+        true => String::new(),
+        false => format!("; did you mean {}?", suggestions.join(", ")),
+    }
+}
+
+/// Listed symbols within a few typos of `symbol`, closest first
+///
+/// Case and separators are ignored, so `btc/ustd` finds `BTCUSDT`.
+pub fn closest_symbols<'a>(symbol: &str, listed: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+    let key = |s: &str| s.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_uppercase()).collect::<Vec<_>>();
+    let wanted = key(symbol);
+    let max_distance = (wanted.len() / 4).max(2);
+    
+    let mut close: Vec<(usize, &String)> = listed.into_iter()
+        .map(|candidate| (edit_distance(&wanted, &key(candidate)), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    close.sort();
+    close.into_iter().take(MAX_SYMBOL_SUGGESTIONS).map(|(_, s)| s.clone()).collect()
+}
+
+/// Levenshtein distance, counting an adjacent swap (`USTD` for `USDT`) as one edit
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Error code for a post-only order rejected because it would take liquidity
+pub const POST_ONLY_WOULD_CROSS: &str = "post_only_would_cross";
+
+/// Error code for a fractional quantity on a market that only trades whole units
+pub const FRACTIONAL_QUANTITY: &str = "fractional_quantity";
+
+/// Error code for leverage above a strict `max_leverage` cap
+pub const LEVERAGE_ABOVE_MAX: &str = "leverage_above_max";
+
+impl ExecutionError {
+    /// Short snake_case name of the variant, e.g. for metric labels
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ExecutionError::Unsupported(_) => "unsupported",
+            ExecutionError::Network(_) => "network",
+            ExecutionError::Disabled(_) => "disabled",
+            ExecutionError::Overloaded(_) => "overloaded",
+            ExecutionError::PluginNotFound(_) => "plugin_not_found",
+            ExecutionError::InvalidSymbol { .. } => "invalid_symbol",
+            ExecutionError::Exchange { .. } => "exchange",
+            ExecutionError::OutcomeUnknown(_) => "outcome_unknown",
+        }
+    }
+    
+    /// `symbol` is not listed, with the closest of `listed` as suggestions
+    pub fn invalid_symbol<'a>(symbol: &str, listed: impl IntoIterator<Item = &'a String>) -> Self {
+        ExecutionError::InvalidSymbol {
+            symbol: symbol.to_string(),
+            suggestions: closest_symbols(symbol, listed),
+        }
+    }
+    
+    /// A post-only order the venue refused because it would match immediately
+    pub fn post_only_would_cross(venue: &str, detail: &str) -> Self {
+        ExecutionError::Exchange {
+            code: POST_ONLY_WOULD_CROSS,
+            message: format!("{} rejected the post-only order because it would cross the book ({})", venue, detail),
+        }
+    }
+}
+
+/// Whether an exchange error message describes a post-only (maker-only) rejection
+pub fn is_post_only_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    ["post only", "post-only", "postonly", "limit-maker", "limit_maker"]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
+
+/// Longest raw body excerpt quoted in an error
+const BODY_SNIPPET_CHARS: usize = 200;
+
+/// A single-line, truncated excerpt of a response body for error messages
+pub fn body_snippet(body: &str) -> String {
+    let collapsed = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return "<empty body>".to_string();
+    }
+    match collapsed.char_indices().nth(BODY_SNIPPET_CHARS) {
+        Some((cut, _)) => format!("{}...", &collapsed[..cut]),
+        None => collapsed,
+    }
+}
+
+/// Error for a non-success HTTP status
+///
+/// Non-JSON bodies (proxy error pages) map to [`ExecutionError::Network`];
+/// API error bodies are quoted, truncated, in a plain error.
+pub fn http_error(venue: &str, status: reqwest::StatusCode, body: &str) -> Box<dyn Error + Send + Sync> {
+    if serde_json::from_str::<serde::de::IgnoredAny>(body).is_err() {
+        return ExecutionError::Network(format!("{} returned HTTP {} with a non-JSON body: {}", venue, status, body_snippet(body))).into();
+    }
+    format!("{} API error ({}): {}", venue, status, body_snippet(body)).into()
+}
+
+/// Deserialize an exchange response body
+///
+/// A body that isn't JSON at all (an HTML error page from a proxy, an empty
+/// reply) becomes [`ExecutionError::Network`] quoting a snippet of the body;
+/// JSON that doesn't match `T` keeps the serde error.
+pub fn parse_response<T: serde::de::DeserializeOwned>(venue: &str, body: &str) -> Result<T, Box<dyn Error + Send + Sync>> {
+    serde_json::from_str(body).map_err(|e| {
+        if e.is_syntax() || e.is_eof() {
+            ExecutionError::Network(format!("{} returned a non-JSON body: {}", venue, body_snippet(body))).into()
+        } else {
+            e.into()
+        }
+    })
+}
+
+/// Whether a request failed by timing out, leaving its outcome unknown
+pub fn is_timeout(err: &(dyn Error + Send + Sync + 'static)) -> bool {
+    err.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_timeout())
+}
+
+/// First pause between lookups of a timed-out order; doubles after each miss
+const RECONCILE_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Settle an order whose placement timed out by looking it up by client id
+///
+/// `lookup` is retried with backoff until `window` (the venue's tolerance
+/// for request timestamps) has passed since the request was `sent`, since
+/// a delayed request can still be accepted until then. An order that never
+/// shows up, or lookups that keep failing, leave the outcome unknown.
+pub async fn reconcile_by_client_id<F, Fut>(
+    plugin: &str,
+    client_order_id: &str,
+    sent: Instant,
+    window: Duration,
+    mut lookup: F,
+) -> Result<ExecutionResult, ExecutionError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<OrderSummary>, Box<dyn Error + Send + Sync>>>,
+{
+    let deadline = sent + window;
+    let mut backoff = RECONCILE_BACKOFF;
+    loop {
+        let last_error = match lookup().await {
+            Ok(Some(found)) => return Ok(ExecutionResult::reconciled(client_order_id, found)),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!(plugin = %plugin, client_order_id = %client_order_id, error = %e, "order_reconcile_failed");
+                Some(e.to_string())
+            }
+        };
+        
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(ExecutionError::OutcomeUnknown(match last_error {
+                Some(e) => format!("{} order request timed out and looking up client id {} failed: {}", plugin, client_order_id, e),
+                None => format!("{} order request timed out and no order with client id {} has appeared yet", plugin, client_order_id),
+            }));
+        }
+        tokio::time::sleep(backoff.min(remaining)).await;
+        backoff *= 2;
+    }
+}
+
+/// Exchange maintenance window tracker
+///
+/// Set when a venue answers with one of its maintenance codes; while active the
+/// plugin short-circuits orders and reports itself unhealthy instead of retrying.
+#[derive(Debug)]
+pub struct MaintenanceState {
+    /// End of the window, in Unix millis
+    until: Mutex<Option<i64>>,
+    clock: SharedClock,
+}
+
+impl Default for MaintenanceState {
+    fn default() -> Self {
+        Self::with_clock(clock::system())
+    }
+}
+
+impl MaintenanceState {
+    /// How long a plugin stays unavailable after a maintenance response
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(300);
+    
+    /// Tracker that reads the current time from `clock`
+    pub fn with_clock(clock: SharedClock) -> Self {
+        Self {
+            until: Mutex::new(None),
+            clock,
+        }
+    }
+    
+    /// Mark the plugin unavailable for `window`
+    pub fn enter(&self, window: Duration) {
+        *self.until.lock().unwrap() = Some(self.clock.now_millis() + window.as_millis() as i64);
+    }
+    
+    /// Clear the maintenance flag
+    #[cfg(test)]
+    pub fn clear(&self) {
+        *self.until.lock().unwrap() = None;
+    }
+    
+    /// Whether the maintenance window is still open
+    pub fn is_active(&self) -> bool {
+        let mut until = self.until.lock().unwrap();
+        match *until {
+            Some(t) if self.clock.now_millis() < t => true,
+            Some(_) => {
+                *until = None;
+                false
+            }
+            None => false,
+        }
+    }
+    
+    /// Error returned to callers while the venue is in maintenance
+    pub fn error(plugin: &str, detail: &str) -> ExecutionError {
+        ExecutionError::Unsupported(format!("{} is in maintenance: {}", plugin, detail))
+    }
+}
+
+/// Clock used for signed request timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeSource {
+    /// Local system time
+    #[default]
+    Local,
+    /// Local time corrected by the offset to the exchange's server time
+    Exchange,
+}
+
+/// Default interval between exchange clock offset measurements
+pub fn default_time_sync_secs() -> u64 {
+    300
+}
+
+/// Maintained offset to an exchange's clock for signed timestamps
+///
+/// With [`TimeSource::Exchange`] the plugin re-measures the offset (via
+/// `ping`) whenever it is older than the refresh interval and signs with
+/// local time plus that offset; with [`TimeSource::Local`] it signs with local
+/// time unchanged.
+#[derive(Debug)]
+pub struct TimeSync {
+    /// `None` when signing with local time
+    refresh: Option<Duration>,
+    offset_ms: AtomicI64,
+    /// Last measurement attempt, in Unix millis (local clock)
+    attempted_at: Mutex<Option<i64>>,
+    clock: SharedClock,
+}
+
+impl Default for TimeSync {
+    fn default() -> Self {
+        Self::new(TimeSource::Local, Duration::ZERO)
+    }
+}
+
+impl TimeSync {
+    pub fn new(source: TimeSource, refresh: Duration) -> Self {
+        Self::with_clock(source, refresh, clock::system())
+    }
+    
+    /// Tracker that reads local time from `clock`
+    pub fn with_clock(source: TimeSource, refresh: Duration, clock: SharedClock) -> Self {
+        Self {
+            refresh: (source == TimeSource::Exchange).then_some(refresh),
+            offset_ms: AtomicI64::new(0),
+            attempted_at: Mutex::new(None),
+            clock,
+        }
+    }
+    
+    /// Whether the offset should be (re-)measured before signing
+    ///
+    /// Claims the measurement, so concurrent signers don't all ping at once.
+    pub fn start_refresh(&self) -> bool {
+        let Some(refresh) = self.refresh else {
+            return false;
+        };
+        let now = self.clock.now_millis();
+        let mut attempted_at = self.attempted_at.lock().unwrap();
+        if attempted_at.is_some_and(|t| now - t < refresh.as_millis() as i64) {
+            return false;
+        }
+        *attempted_at = Some(now);
+        true
+    }
+    
+    /// Adopt a measured skew (server time minus local time)
+    pub fn record(&self, plugin: &str, skew_ms: i64) {
+        self.offset_ms.store(skew_ms, Ordering::Relaxed);
+        crate::metrics::CLOCK_OFFSET_MS.with_label_values(&[plugin]).set(skew_ms);
+    }
+    
+    /// Current offset applied to signed timestamps (milliseconds)
+    pub fn offset_ms(&self) -> i64 {
+        self.offset_ms.load(Ordering::Relaxed)
+    }
+    
+    /// Timestamp to sign with, in Unix millis
+    pub fn now_millis(&self) -> i64 {
+        self.clock.now_millis() + self.offset_ms()
+    }
+}
+
+/// Retry policy for plugin init, so slow-starting dependencies are tolerated
+///
+/// An attempt succeeds once `init` returns Ok and the health probe doesn't
+/// report the exchange unreachable. Rejected credentials aren't retried:
+/// readiness reports them. The wait doubles after every failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InitRetry {
+    pub attempts: u32,
+    pub backoff: Duration,
+}
+
+impl Default for InitRetry {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl InitRetry {
+    /// Build from `PLUGIN_INIT_ATTEMPTS` and `PLUGIN_INIT_BACKOFF_MS` (default: 3 attempts, 500ms)
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            attempts: std::env::var("PLUGIN_INIT_ATTEMPTS").ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(default.attempts)
+                .max(1),
+            backoff: std::env::var("PLUGIN_INIT_BACKOFF_MS").ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(default.backoff),
+        }
+    }
+    
+    /// Initialize `plugin`, retrying failed attempts with backoff
+    pub async fn init<P: ExecutionPlugin + ?Sized>(&self, plugin: &mut P, config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut delay = self.backoff;
+        let mut attempt = 1;
+        loop {
+            let error = match plugin.init(config.clone()).await {
+                Ok(()) => match plugin.health_status().await {
+                    HealthStatus::Unreachable => "exchange unreachable".to_string(),
+                    _ => return Ok(()),
+                },
+                Err(e) => e.to_string(),
+            };
+            
+            if attempt >= self.attempts {
+                return Err(format!("{} init failed after {} attempt(s): {}", plugin.name(), attempt, error).into());
+            }
+            tracing::warn!(
+                plugin = %plugin.name(),
+                attempt,
+                error = %error,
+                retry_in_ms = delay.as_millis() as u64,
+                "plugin_init_retry"
+            );
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+}
 
 /// Order side (buy or sell)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -60,10 +601,335 @@ pub struct Order {
     /// Confidence score (0-1) from agent system
     #[serde(default = "default_confidence")]
     pub confidence: f64,
+    
+    /// Only reduce an existing position, never open or flip one
+    #[serde(default)]
+    pub reduce_only: bool,
+    
+    /// Strategy tag of the originating signal, embedded in the client order id
+    #[serde(default)]
+    pub strategy_tag: Option<String>,
+    
+    /// Unit of `quantity`: base asset (default) or quote currency
+    #[serde(default)]
+    pub quantity_type: QuantityType,
+    
+    /// Iceberg orders: size shown on the book, the rest stays hidden (limit orders only)
+    #[serde(default)]
+    pub display_quantity: Option<f64>,
+    
+    /// Stop orders: price that activates the order (at its limit `price` for
+    /// stop-limits); `stop_loss` is the protective level, not a trigger
+    #[serde(default)]
+    pub trigger_price: Option<f64>,
+    
+    /// Advanced/unsafe: venue-specific fields (a JSON object) added to the
+    /// exchange request as-is, e.g. Bybit `smpType` or KuCoin `remark`.
+    /// Never replaces a field the plugin already set; not validated.
+    #[serde(default)]
+    pub extra_params: Option<serde_json::Value>,
+    
+    /// Limit orders: only add liquidity; the exchange rejects the order
+    /// instead of letting it match on arrival
+    #[serde(default)]
+    pub post_only: bool,
+    
+    /// Send this one order to testnet (`true`) or mainnet (`false`) instead
+    /// of the plugin's configured network; the API keys must be valid there
+    #[serde(default)]
+    pub testnet: Option<bool>,
+    
+    /// Market orders: worst acceptable fill, in percent away from the current
+    /// price. Bybit enforces it natively; KuCoin sends an IOC limit at that price.
+    #[serde(default)]
+    pub max_slippage_pct: Option<f64>,
+    
+    /// Derivatives: leverage for this order instead of the plugin's configured
+    /// one, subject to the same `max_leverage` cap (Bybit and KuCoin futures only)
+    #[serde(default)]
+    pub leverage: Option<i32>,
+}
+
+/// Unit an order quantity is denominated in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuantityType {
+    /// Base asset, e.g. BTC for BTC/USDT
+    #[default]
+    Base,
+    
+    /// Quote currency, e.g. USDT for BTC/USDT; only market orders on some venues
+    Quote,
+}
+
+/// Confidence for orders that don't carry one, unless `DEFAULT_CONFIDENCE` is set
+pub const DEFAULT_CONFIDENCE: f64 = 0.7;
+
+/// Parse a `DEFAULT_CONFIDENCE` value, falling back to [`DEFAULT_CONFIDENCE`]
+/// when unset or outside `[0, 1]`
+pub fn parse_default_confidence(value: Option<&str>) -> f64 {
+    let Some(value) = value else {
+        return DEFAULT_CONFIDENCE;
+    };
+    value.trim().parse::<f64>().ok()
+        .filter(|c| (0.0..=1.0).contains(c))
+        .unwrap_or_else(|| {
+            tracing::warn!(value = %value, default = DEFAULT_CONFIDENCE, "default_confidence_invalid_using_default");
+            DEFAULT_CONFIDENCE
+        })
+}
+
+/// Confidence for orders that don't carry one, read from `DEFAULT_CONFIDENCE` once
+pub fn default_confidence() -> f64 {
+    static CONFIGURED: OnceLock<f64> = OnceLock::new();
+    *CONFIGURED.get_or_init(|| parse_default_confidence(std::env::var("DEFAULT_CONFIDENCE").ok().as_deref()))
+}
+
+/// A base-quantity market buy with no protection or venue overrides; callers
+/// fill in symbol, side and quantity
+impl Default for Order {
+    fn default() -> Self {
+        Self {
+            symbol: String::new(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: 0.0,
+            price: None,
+            stop_loss: None,
+            take_profit: None,
+            confidence: default_confidence(),
+            reduce_only: false,
+            strategy_tag: None,
+            quantity_type: QuantityType::Base,
+            display_quantity: None,
+            trigger_price: None,
+            extra_params: None,
+            post_only: false,
+            testnet: None,
+            max_slippage_pct: None,
+            leverage: None,
+        }
+    }
+}
+
+impl Order {
+    /// Whether the order carries a stop-loss or take-profit
+    pub fn has_protection(&self) -> bool {
+        self.stop_loss.is_some() || self.take_profit.is_some()
+    }
+    
+    /// Check stop-loss and take-profit sit on the correct side of the entry
+    ///
+    /// The entry is the limit price, or `market_price` for orders without one.
+    /// Without either, only the stop-loss/take-profit ordering is checked.
+    pub fn validate(&self, market_price: Option<f64>) -> Result<(), String> {
+        // A long's stop sits below the entry and its target above; a short's the reverse
+        let long = self.side == OrderSide::Buy;
+        let (sl_side, tp_side) = if long { ("below", "above") } else { ("above", "below") };
+        let stop_ok = |sl: f64, reference: f64| if long { sl < reference } else { sl > reference };
+        
+        if let (Some(sl), Some(tp)) = (self.stop_loss, self.take_profit) {
+            if !stop_ok(sl, tp) {
+                return Err(format!(
+                    "stop_loss {} must be {} take_profit {} for a {:?} order",
+                    sl, sl_side, tp, self.side
+                ));
+            }
+        }
+        
+        let Some(entry) = self.price.or(market_price) else {
+            return Ok(());
+        };
+        if let Some(sl) = self.stop_loss {
+            if !stop_ok(sl, entry) {
+                return Err(format!("stop_loss {} must be {} entry {} for a {:?} order", sl, sl_side, entry, self.side));
+            }
+        }
+        if let Some(tp) = self.take_profit {
+            // The target must be strictly on the profitable side
+            if tp == entry || stop_ok(tp, entry) {
+                return Err(format!("take_profit {} must be {} entry {} for a {:?} order", tp, tp_side, entry, self.side));
+            }
+        }
+        Ok(())
+    }
+    
+    /// Price that doesn't fit the order type: set on a market order, or
+    /// missing from a limit or stop-limit order
+    ///
+    /// Plugins handle both inconsistently, so callers warn or reject.
+    pub fn price_issue(&self) -> Option<String> {
+        match (&self.order_type, self.price) {
+            (OrderType::Market, Some(price)) => Some(format!("price {} is ignored for market orders", price)),
+            (OrderType::Limit | OrderType::StopLimit, None) => Some(format!("price is required for {:?} orders", self.order_type)),
+            _ => None,
+        }
+    }
+    
+    /// Check a stop-limit order has both its trigger and its limit price
+    pub fn validate_stop_limit(&self) -> Result<(), String> {
+        if self.order_type != OrderType::StopLimit {
+            return Ok(());
+        }
+        if self.trigger_price.is_none() {
+            return Err("trigger_price is required for StopLimit orders".to_string());
+        }
+        if self.price.is_none() {
+            return Err("price (the limit) is required for StopLimit orders".to_string());
+        }
+        Ok(())
+    }
+    
+    /// Check `post_only` is only set on limit orders
+    pub fn validate_post_only(&self) -> Result<(), String> {
+        if self.post_only && self.order_type != OrderType::Limit {
+            return Err(format!("post_only only applies to limit orders, not {:?}", self.order_type));
+        }
+        Ok(())
+    }
+    
+    /// Check `max_slippage_pct` is a sane percentage on a market order
+    pub fn validate_max_slippage(&self) -> Result<(), String> {
+        let Some(pct) = self.max_slippage_pct else {
+            return Ok(());
+        };
+        if self.order_type != OrderType::Market {
+            return Err(format!("max_slippage_pct only applies to market orders, not {:?}", self.order_type));
+        }
+        if !(pct > 0.0 && pct < 100.0) {
+            return Err(format!("max_slippage_pct must be between 0 and 100, got {}", pct));
+        }
+        Ok(())
+    }
+    
+    /// Worst acceptable price for a slippage-capped market order: `max_slippage_pct`
+    /// above the ask for a buy, below the bid for a sell
+    pub fn slippage_limit_price(&self, quote: &MarketData) -> Option<f64> {
+        let pct = self.max_slippage_pct?;
+        match self.side {
+            OrderSide::Buy => Some(quote.ask * (1.0 + pct / 100.0)),
+            OrderSide::Sell => Some(quote.bid * (1.0 - pct / 100.0)),
+        }
+    }
+    
+    /// Check `display_quantity` is positive, at most `quantity`, and on a limit order
+    pub fn validate_display_quantity(&self) -> Result<(), String> {
+        let Some(display) = self.display_quantity else {
+            return Ok(());
+        };
+        if self.order_type != OrderType::Limit {
+            return Err(format!("display_quantity only applies to limit orders, not {:?}", self.order_type));
+        }
+        if display <= 0.0 {
+            return Err(format!("display_quantity must be positive, got {}", display));
+        }
+        if display > self.quantity {
+            return Err(format!("display_quantity {} exceeds quantity {}", display, self.quantity));
+        }
+        Ok(())
+    }
+}
+
+/// Add an order's `extra_params` to an exchange request body
+///
+/// Fields the plugin already set (symbol, side, quantity, ...) are kept and
+/// the conflicting extra param is dropped with a warning.
+pub fn merge_extra_params(plugin: &str, body: &mut serde_json::Value, order: &Order) {
+    let (Some(serde_json::Value::Object(extra)), Some(body)) = (&order.extra_params, body.as_object_mut()) else {
+        return;
+    };
+    for (key, value) in extra {
+        if body.contains_key(key) {
+            tracing::warn!(plugin = %plugin, param = %key, "extra_param_ignored_would_override");
+            continue;
+        }
+        body.insert(key.clone(), value.clone());
+    }
+}
+
+/// Refuse iceberg orders on venues without a visible-size parameter
+///
+/// Sending the order without it would show the full size on the book.
+pub fn reject_display_quantity(plugin: &str, order: &Order) -> Result<(), ExecutionError> {
+    match order.display_quantity {
+        Some(_) => Err(ExecutionError::Unsupported(format!("{} does not support display_quantity (iceberg) orders", plugin))),
+        None => Ok(()),
+    }
+}
+
+/// Refuse post-only orders on venues without a maker-only flag
+///
+/// Sending the order without it could fill as a taker.
+pub fn reject_post_only(plugin: &str, order: &Order) -> Result<(), ExecutionError> {
+    match order.post_only {
+        true => Err(ExecutionError::Unsupported(format!("{} does not support post_only orders", plugin))),
+        false => Ok(()),
+    }
+}
+
+/// Refuse per-order network overrides on plugins bound to one host
+///
+/// Ignoring the override would send a testnet order to the live venue.
+pub fn reject_testnet_override(plugin: &str, order: &Order) -> Result<(), ExecutionError> {
+    match order.testnet {
+        Some(_) => Err(ExecutionError::Unsupported(format!("{} does not support per-order testnet selection", plugin))),
+        None => Ok(()),
+    }
+}
+
+/// Refuse per-order leverage on venues without a leverage parameter
+///
+/// Sending the order anyway would trade at the account's current leverage.
+pub fn reject_leverage(plugin: &str, order: &Order) -> Result<(), ExecutionError> {
+    match order.leverage {
+        Some(_) => Err(ExecutionError::Unsupported(format!("{} does not support per-order leverage", plugin))),
+        None => Ok(()),
+    }
+}
+
+/// Refuse slippage-capped market orders on venues with no way to enforce the cap
+///
+/// Sending a plain market order instead could fill anywhere on the book.
+pub fn reject_max_slippage(plugin: &str, order: &Order) -> Result<(), ExecutionError> {
+    match order.max_slippage_pct {
+        Some(_) => Err(ExecutionError::Unsupported(format!("{} does not support max_slippage_pct", plugin))),
+        None => Ok(()),
+    }
 }
 
-fn default_confidence() -> f64 {
-    0.6
+/// Refuse quote-denominated quantities on venues that only size in the base asset
+///
+/// Sending the amount as-is would trade it as a base quantity.
+pub fn reject_quote_quantity(plugin: &str, order: &Order) -> Result<(), ExecutionError> {
+    match order.quantity_type {
+        QuantityType::Quote => Err(ExecutionError::Unsupported(format!("{} does not support quote-denominated quantities", plugin))),
+        QuantityType::Base => Ok(()),
+    }
+}
+
+/// Build a traceable client order id: `fks-{tag}-{conf}-{unique_id}`
+///
+/// Confidence is encoded as a 0-100 percentage. The tag is reduced to
+/// `[A-Za-z0-9_]` and truncated so the whole id fits the exchange's `max_len`.
+pub fn client_order_id(order: &Order, max_len: usize) -> String {
+    let confidence = (order.confidence.clamp(0.0, 1.0) * 100.0).round() as u8;
+    let suffix = format!("{}-{}", confidence, unique_id());
+    
+    let tag: String = order.strategy_tag
+        .as_deref()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+    let budget = max_len.saturating_sub("fks--".len() + suffix.len());
+    let tag: String = tag.chars().take(budget).collect();
+    
+    let id = if tag.is_empty() {
+        format!("fks-{}", suffix)
+    } else {
+        format!("fks-{}-{}", tag, suffix)
+    };
+    id.chars().take(max_len).collect()
 }
 
 /// Execution result from a plugin
@@ -84,34 +950,455 @@ pub struct ExecutionResult {
     /// Error message if execution failed
     pub error: Option<String>,
     
-    /// Execution timestamp (Unix millis)
-    pub timestamp: i64,
+    /// Execution timestamp (Unix millis)
+    pub timestamp: i64,
+}
+
+impl ExecutionResult {
+    /// Result for an order whose placement timed out, from the order the
+    /// venue reports for `client_order_id`
+    pub fn reconciled(client_order_id: &str, found: OrderSummary) -> Self {
+        let closed = matches!(found.status, OrderStatus::Cancelled | OrderStatus::Rejected);
+        Self {
+            success: !closed,
+            error: closed.then(|| format!("Order {} is {:?} on the exchange", client_order_id, found.status)),
+            order_id: Some(found.order_id),
+            filled_quantity: found.filled_quantity,
+            average_price: found.average_price.unwrap_or(0.0),
+            timestamp: now_millis(),
+        }
+    }
+}
+
+/// Direction of an open position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PositionSide {
+    Long,
+    Short,
+}
+
+impl PositionSide {
+    /// Order side that reduces a position on this side
+    pub fn closing_side(self) -> OrderSide {
+        match self {
+            PositionSide::Long => OrderSide::Sell,
+            PositionSide::Short => OrderSide::Buy,
+        }
+    }
+}
+
+/// Open position as reported by the exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Position {
+    pub symbol: String,
+    pub side: PositionSide,
+    
+    /// Absolute position size in base units
+    pub size: f64,
+    pub entry_price: f64,
+    pub mark_price: f64,
+    pub unrealized_pnl: f64,
+    pub leverage: f64,
+}
+
+/// Realized profit or loss from closing (part of) a position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClosedPnl {
+    pub symbol: String,
+    
+    /// Size closed, in base units
+    pub quantity: f64,
+    
+    /// Realized PnL in the settlement currency, net of fees
+    pub realized_pnl: f64,
+    
+    /// Unix millis when the close completed
+    pub closed_at: i64,
+}
+
+/// One fill of an order; an order may fill in several trades
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trade {
+    /// Exchange trade (execution) ID
+    pub trade_id: String,
+    
+    /// Exchange ID of the order that filled
+    pub order_id: String,
+    
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: f64,
+    
+    /// Size filled, in the venue's order units
+    pub quantity: f64,
+    
+    /// Fee charged for the fill (negative for rebates)
+    pub fee: f64,
+    pub fee_currency: Option<String>,
+    
+    /// Unix millis when the fill happened
+    pub timestamp: i64,
+}
+
+/// Current funding rate of a perpetual contract
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingRate {
+    pub symbol: String,
+    
+    /// Rate for the current interval as a fraction (0.0001 = 0.01%);
+    /// positive means longs pay shorts
+    pub rate: f64,
+    
+    /// Unix millis of the next funding settlement
+    pub next_funding_time: i64,
+    
+    /// Unix millis when the rate was read
+    pub timestamp: i64,
+}
+
+/// Account balance of one asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Balance {
+    /// Asset code as the venue names it (e.g. "USDT")
+    pub asset: String,
+    
+    /// Available to trade or withdraw
+    pub free: f64,
+    
+    /// Held by open orders or margin
+    pub locked: f64,
+    
+    pub total: f64,
+}
+
+/// Trading rules for a symbol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstrumentInfo {
+    pub symbol: String,
+    
+    /// Quantity increment (lot step)
+    pub qty_step: f64,
+    
+    /// Minimum order quantity
+    pub min_qty: f64,
+    
+    /// Price increment
+    pub tick_size: f64,
+    
+    /// Direction quantities and prices are rounded to `qty_step` / `tick_size`
+    #[serde(default)]
+    pub rounding: InstrumentRounding,
+}
+
+impl InstrumentInfo {
+    /// `quantity` on the lot step, in the plugin's quantity rounding mode
+    pub fn round_qty(&self, quantity: f64) -> f64 {
+        round_to_step(quantity, self.qty_step, self.rounding.qty_rounding)
+    }
+    
+    /// `price` on the tick size, in the plugin's price rounding mode
+    pub fn round_price(&self, price: f64) -> f64 {
+        round_to_step(price, self.tick_size, self.rounding.price_rounding)
+    }
+}
+
+/// Direction a value is moved onto a step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// Toward zero: never more than asked for
+    Down,
+    
+    /// Closest step, halves rounded up
+    Nearest,
+    
+    /// Away from zero
+    Up,
+}
+
+/// Per-plugin rounding modes, flattened into plugin configs as
+/// `qty_rounding` / `price_rounding`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstrumentRounding {
+    /// Quantities (default: down, so a rounded order stays within the balance)
+    #[serde(default = "default_qty_rounding")]
+    pub qty_rounding: RoundingMode,
+    
+    /// Prices (default: nearest)
+    #[serde(default = "default_price_rounding")]
+    pub price_rounding: RoundingMode,
+}
+
+impl Default for InstrumentRounding {
+    fn default() -> Self {
+        Self {
+            qty_rounding: default_qty_rounding(),
+            price_rounding: default_price_rounding(),
+        }
+    }
+}
+
+fn default_qty_rounding() -> RoundingMode {
+    RoundingMode::Down
+}
+
+fn default_price_rounding() -> RoundingMode {
+    RoundingMode::Nearest
+}
+
+/// Round `value` to a multiple of `step` in the given direction, trimming
+/// float noise to the step's precision
+pub fn round_to_step(value: f64, step: f64, mode: RoundingMode) -> f64 {
+    if step <= 0.0 {
+        return value;
+    }
+    
+    let decimals = step.to_string().split_once('.').map_or(0, |(_, frac)| frac.len()) as i32;
+    let scale = 10f64.powi(decimals);
+    // Epsilon keeps exact multiples (e.g. 0.3 / 0.1) from landing one step off
+    let steps = match mode {
+        RoundingMode::Down => (value / step + 1e-9).floor(),
+        RoundingMode::Nearest => (value / step + 1e-9).round(),
+        RoundingMode::Up => (value / step - 1e-9).ceil(),
+    };
+    (steps * step * scale).round() / scale
+}
+
+/// Round-trip measurement against an exchange's time endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingResult {
+    /// Round-trip time in milliseconds
+    pub latency_ms: f64,
+    
+    /// Exchange server time (Unix millis), if reported
+    pub server_time: Option<i64>,
+    
+    /// Server time minus local time at the midpoint of the round trip (milliseconds)
+    pub clock_skew_ms: Option<i64>,
+    
+    /// Measurement timestamp (Unix millis)
+    pub timestamp: i64,
+}
+
+impl PingResult {
+    /// Build from a round trip sent at `sent_at` (Unix millis) that took `elapsed`
+    pub fn measure(sent_at: i64, elapsed: Duration, server_time: Option<i64>) -> Self {
+        let latency_ms = elapsed.as_secs_f64() * 1000.0;
+        let midpoint = sent_at + (latency_ms / 2.0).round() as i64;
+        Self {
+            latency_ms,
+            server_time,
+            clock_skew_ms: server_time.map(|t| t - midpoint),
+            timestamp: now_millis(),
+        }
+    }
+}
+
+/// Market data snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketData {
+    /// Trading symbol
+    pub symbol: String,
+    
+    /// Bid price
+    pub bid: f64,
+    
+    /// Ask price
+    pub ask: f64,
+    
+    /// Last traded price
+    pub last: f64,
+    
+    /// 24h volume
+    pub volume: f64,
+    
+    /// Timestamp (Unix millis)
+    pub timestamp: i64,
+    
+    /// Optional additional fields (exchange-specific)
+    #[serde(default)]
+    pub extra: serde_json::Value,
+}
+
+/// Normalized order status across exchanges
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+    Open,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+    Rejected,
+}
+
+impl OrderStatus {
+    /// Parse a status filter; "open" also matches partially filled orders
+    pub fn parse_filter(filter: &str) -> Option<Vec<OrderStatus>> {
+        match filter.to_lowercase().as_str() {
+            "open" | "active" => Some(vec![OrderStatus::Open, OrderStatus::PartiallyFilled]),
+            "partially_filled" => Some(vec![OrderStatus::PartiallyFilled]),
+            "filled" => Some(vec![OrderStatus::Filled]),
+            "cancelled" | "canceled" => Some(vec![OrderStatus::Cancelled]),
+            "rejected" => Some(vec![OrderStatus::Rejected]),
+            _ => None,
+        }
+    }
+}
+
+/// An exchange order request, built without any I/O
+///
+/// Plugins split order placement into a pure build step, which produces this,
+/// and a send step that signs and submits it. Previews return the build step's
+/// output unsent.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreparedRequest {
+    pub method: String,
+    pub url: String,
+    
+    /// Path (with query) the venue signs, relative to its base URL
+    pub path: String,
+    
+    /// Request body exactly as it would be signed and sent
+    pub body: serde_json::Value,
+}
+
+impl PreparedRequest {
+    /// Base URL the request goes to, `url` without its `path`
+    ///
+    /// A per-order `testnet` override can point this away from the plugin's
+    /// configured host, so lookups about the same order should use it.
+    pub fn base_url(&self) -> Option<&str> {
+        self.url.strip_suffix(&self.path)
+    }
+}
+
+/// Health of a plugin's exchange connection, as reported by `/ready`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// Plugin has not been initialized
+    Unconfigured,
+    
+    /// Exchange could not be reached (network error, outage, maintenance)
+    Unreachable,
+    
+    /// Exchange is reachable but rejected the API credentials
+    AuthFailed,
+    
+    /// Exchange is reachable and the credentials (if any) are accepted
+    Healthy,
+    
+    /// Plugin is registered but routing to it is switched off
+    Disabled,
+}
+
+/// Outcome of a set-leverage request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeverageChange {
+    /// The exchange applied the new leverage
+    Changed,
+    
+    /// The symbol was already at the requested leverage
+    Unchanged,
+}
+
+/// An order to cancel, by exchange or client order id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderRef {
+    OrderId(String),
+    ClientOrderId(String),
+}
+
+/// Result of cancelling one order of a batch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CancelOutcome {
+    #[serde(flatten)]
+    pub order: OrderRef,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl CancelOutcome {
+    pub fn cancelled(order: OrderRef) -> Self {
+        Self { order, success: true, error: None }
+    }
+    
+    pub fn failed(order: OrderRef, error: impl Into<String>) -> Self {
+        Self { order, success: false, error: Some(error.into()) }
+    }
 }
 
-/// Market data snapshot
+/// Normalized view of an order living on an exchange
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MarketData {
-    /// Trading symbol
+pub struct OrderSummary {
+    /// Exchange order ID
+    pub order_id: String,
+    
+    /// Client order ID, if one was sent
+    pub client_order_id: Option<String>,
+    
+    /// Trading symbol (exchange format)
     pub symbol: String,
     
-    /// Bid price
-    pub bid: f64,
+    /// Order side
+    pub side: OrderSide,
     
-    /// Ask price
-    pub ask: f64,
+    /// Exchange order type (e.g. "limit", "market")
+    pub order_type: String,
     
-    /// Last traded price
-    pub last: f64,
+    /// Order quantity
+    pub quantity: f64,
     
-    /// 24h volume
-    pub volume: f64,
+    /// Quantity filled so far
+    pub filled_quantity: f64,
     
-    /// Timestamp (Unix millis)
-    pub timestamp: i64,
+    /// Limit price, if any
+    pub price: Option<f64>,
     
-    /// Optional additional fields (exchange-specific)
+    /// Average fill price, once anything has filled
     #[serde(default)]
-    pub extra: serde_json::Value,
+    pub average_price: Option<f64>,
+    
+    /// Normalized status
+    pub status: OrderStatus,
+    
+    /// Creation timestamp (Unix millis)
+    pub timestamp: i64,
+}
+
+/// Error code for a pagination cursor that wasn't issued by the plugin
+pub const INVALID_CURSOR: &str = "invalid_cursor";
+
+/// One page of live orders
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderPage {
+    pub orders: Vec<OrderSummary>,
+    
+    /// Opaque token for the next page, `None` on the last one
+    pub next_cursor: Option<String>,
+}
+
+impl OrderPage {
+    /// Page a complete list locally; the cursor is an offset into it
+    pub fn from_list(mut orders: Vec<OrderSummary>, cursor: Option<&str>, limit: usize) -> Result<Self, ExecutionError> {
+        let start = match cursor {
+            Some(cursor) => cursor.parse::<usize>().map_err(|_| ExecutionError::Exchange {
+                code: INVALID_CURSOR,
+                message: format!("Invalid cursor '{}'", cursor),
+            })?,
+            None => 0,
+        };
+        let end = start.saturating_add(limit.max(1)).min(orders.len());
+        let next_cursor = (end < orders.len()).then(|| end.to_string());
+        let orders = match start < orders.len() {
+            true => orders.drain(start..end).collect(),
+            false => Vec::new(),
+        };
+        Ok(Self { orders, next_cursor })
+    }
 }
 
 /// ExecutionPlugin trait - implemented by all execution backends
@@ -162,11 +1449,386 @@ pub trait ExecutionPlugin: Send + Sync {
     /// # Returns
     /// * `true` if plugin is healthy, `false` otherwise
     async fn health_check(&self) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    
+    /// Open positions, optionally for a single symbol
+    async fn get_positions(&self, _symbol: Option<&str>) -> Result<Vec<Position>, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support position queries", self.name())).into())
+    }
+    
+    /// Account balances, one entry per asset
+    async fn get_balances(&self) -> Result<Vec<Balance>, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support balance queries", self.name())).into())
+    }
+    
+    /// Recently closed positions with their realized PnL, newest first
+    async fn get_closed_pnl(&self, _symbol: Option<&str>) -> Result<Vec<ClosedPnl>, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support closed PnL queries", self.name())).into())
+    }
+    
+    /// Recent fills, newest first, at most `limit`
+    async fn fetch_trades(&self, _symbol: Option<&str>, _limit: usize) -> Result<Vec<Trade>, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support trade history", self.name())).into())
+    }
+    
+    /// Current funding rate and next settlement time of a perpetual
+    async fn fetch_funding_rate(&self, _symbol: &str) -> Result<FundingRate, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support funding rates", self.name())).into())
+    }
+    
+    /// Set a symbol's leverage; an exchange "not modified" answer is `Unchanged`, not an error
+    async fn set_leverage(&self, _symbol: &str, _leverage: i32) -> Result<LeverageChange, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support leverage changes", self.name())).into())
+    }
+    
+    /// Every open position, in as few requests as the exchange allows
+    ///
+    /// Defaults to an unfiltered `get_positions`; plugins whose position query
+    /// needs a symbol override this with their list-all endpoint.
+    async fn get_all_positions(&self) -> Result<Vec<Position>, Box<dyn Error + Send + Sync>> {
+        self.get_positions(None).await
+    }
+    
+    /// Trading rules (lot step, minimum quantity, tick size) for a symbol
+    async fn instrument_info(&self, _symbol: &str) -> Result<InstrumentInfo, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support instrument queries", self.name())).into())
+    }
+    
+    /// Measure round-trip latency and clock skew against the exchange
+    async fn ping(&self) -> Result<PingResult, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support ping", self.name())).into())
+    }
+    
+    /// Detailed health, distinguishing rejected credentials from connectivity
+    ///
+    /// Defaults to mapping `health_check` onto Healthy/Unreachable.
+    async fn health_status(&self) -> HealthStatus {
+        match self.health_check().await {
+            Ok(true) => HealthStatus::Healthy,
+            _ => HealthStatus::Unreachable,
+        }
+    }
+    
+    /// List live orders on the exchange, optionally for a single symbol
+    ///
+    /// Plugins without order listing return `ExecutionError::Unsupported`.
+    async fn list_orders(&self, _symbol: Option<&str>) -> Result<Vec<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support listing orders", self.name())).into())
+    }
+    
+    /// One page of live orders, from `cursor` (`None`: the first page)
+    ///
+    /// Venues with native pagination pass their own token through as the
+    /// cursor and may cap `limit` to their page size; by default the full
+    /// [`Self::list_orders`] result is paged locally.
+    async fn list_orders_page(&self, symbol: Option<&str>, cursor: Option<&str>, limit: usize) -> Result<OrderPage, Box<dyn Error + Send + Sync>> {
+        Ok(OrderPage::from_list(self.list_orders(symbol).await?, cursor, limit)?)
+    }
+    
+    /// Build the exchange request for `order` without sending it
+    async fn preview_order(&self, _order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support order previews", self.name())).into())
+    }
+    
+    /// Symbols the venue currently trades, in its own naming
+    async fn list_symbols(&self) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support symbol listing", self.name())).into())
+    }
+    
+    /// Current state of a single order
+    async fn get_order(&self, _symbol: &str, _order_id: &str) -> Result<OrderSummary, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support order queries", self.name())).into())
+    }
+    
+    /// Look up an order by the client order id it was placed with
+    ///
+    /// `None` when the venue has no order with that id.
+    async fn get_order_by_client_id(&self, _symbol: &str, _client_order_id: &str) -> Result<Option<OrderSummary>, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support client order id lookups", self.name())).into())
+    }
+    
+    /// Cancel a resting order
+    async fn cancel_order(&self, _symbol: &str, _order_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support order cancellation", self.name())).into())
+    }
+    
+    /// Cancel every open order, optionally only those of one symbol
+    ///
+    /// Defaults to listing open orders and cancelling them one by one; each
+    /// order gets its own outcome. An `Err` means the orders couldn't be listed.
+    async fn cancel_all_orders(&self, symbol: Option<&str>) -> Result<Vec<CancelOutcome>, Box<dyn Error + Send + Sync>> {
+        let open = self.list_orders(symbol).await?
+            .into_iter()
+            .filter(|o| matches!(o.status, OrderStatus::Open | OrderStatus::PartiallyFilled));
+        let mut outcomes = Vec::new();
+        for order in open {
+            let order_ref = OrderRef::OrderId(order.order_id.clone());
+            outcomes.push(match self.cancel_order(&order.symbol, &order.order_id).await {
+                Ok(()) => CancelOutcome::cancelled(order_ref),
+                Err(e) => CancelOutcome::failed(order_ref, e.to_string()),
+            });
+        }
+        Ok(outcomes)
+    }
+    
+    /// Cancel several orders of one symbol in as few requests as the venue allows
+    ///
+    /// Returns one outcome per entry of `orders`, in order; an `Err` means the
+    /// batch as a whole could not be sent.
+    async fn cancel_batch(&self, _symbol: &str, _orders: &[OrderRef]) -> Result<Vec<CancelOutcome>, Box<dyn Error + Send + Sync>> {
+        Err(ExecutionError::Unsupported(format!("{} does not support batch cancellation", self.name())).into())
+    }
+    
+    /// Whether the venue reported a maintenance window that is still open
+    fn in_maintenance(&self) -> bool {
+        false
+    }
+    
+    /// Whether orders go to a paper, sandbox or testnet account instead of a live one
+    ///
+    /// Plugins that can't tell report live, so a UI errs on the side of warning.
+    fn is_paper(&self) -> bool {
+        false
+    }
+    
+    /// Configuration in effect, defaults applied, for diagnostics
+    ///
+    /// Credentials are included as configured; callers redact them before
+    /// exposing the config. `None` for plugins without settings to report.
+    async fn effective_config(&self) -> Option<serde_json::Value> {
+        None
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use std::sync::Arc;
+    
+    /// Collects formatted log lines while installed as the thread's default subscriber
+    #[derive(Clone, Default)]
+    pub(crate) struct LogCapture(Arc<Mutex<Vec<u8>>>);
+    
+    impl std::io::Write for LogCapture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for LogCapture {
+        type Writer = Self;
+        
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+    
+    impl LogCapture {
+        /// Capture debug-level logs on this thread until the guard drops
+        pub(crate) fn install(&self) -> tracing::subscriber::DefaultGuard {
+            let subscriber = tracing_subscriber::fmt()
+                .with_max_level(tracing::Level::DEBUG)
+                .with_ansi(false)
+                .with_writer(self.clone())
+                .finish();
+            tracing::subscriber::set_default(subscriber)
+        }
+        
+        pub(crate) fn contents(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+        }
+    }
+    
+    /// Configurable venue for tests that need specific plugin behaviour
+    ///
+    /// Serves only what a test sets up; other calls keep the trait's
+    /// `Unsupported` defaults. A plugin that is `down` fails every call with a
+    /// network error and reports unhealthy.
+    #[derive(Default)]
+    pub(crate) struct StubPlugin {
+        name: String,
+        /// Bid, ask and quote age in milliseconds
+        quote: Option<(f64, f64, i64)>,
+        /// Symbols quoted; others fail as unknown (default: any symbol)
+        listed: Option<Vec<String>>,
+        positions: Option<Vec<Position>>,
+        balances: Option<Vec<Balance>>,
+        down: bool,
+        init_failures: u32,
+        pub(crate) inits: u32,
+        /// Symbol list requests served; each returns `SYM<n>`
+        pub(crate) symbol_lookups: AtomicU64,
+    }
+    
+    impl StubPlugin {
+        pub(crate) fn new(name: &str) -> Self {
+            Self { name: name.to_string(), ..Self::default() }
+        }
+        
+        /// Quote a fixed bid/ask, `age_ms` old
+        pub(crate) fn with_quote(self, bid: f64, ask: f64, age_ms: i64) -> Self {
+            Self { quote: Some((bid, ask, age_ms)), ..self }
+        }
+        
+        /// Quote only `symbols`, rejecting others as [`ExecutionError::InvalidSymbol`]
+        pub(crate) fn listing(self, symbols: &[&str]) -> Self {
+            Self { listed: Some(symbols.iter().map(|s| s.to_string()).collect()), ..self }
+        }
+        
+        pub(crate) fn with_positions(self, positions: Vec<Position>) -> Self {
+            Self { positions: Some(positions), ..self }
+        }
+        
+        pub(crate) fn with_balances(self, balances: Vec<Balance>) -> Self {
+            Self { balances: Some(balances), ..self }
+        }
+        
+        pub(crate) fn down(self) -> Self {
+            Self { down: true, ..self }
+        }
+        
+        /// Fail the first `failures` inits, as a sidecar still starting up would
+        pub(crate) fn failing_inits(self, failures: u32) -> Self {
+            Self { init_failures: failures, ..self }
+        }
+        
+        pub(crate) fn arc(self) -> Arc<dyn ExecutionPlugin> {
+            Arc::new(self)
+        }
+        
+        fn check_up(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+            match self.down {
+                true => Err(ExecutionError::Network("connection refused".to_string()).into()),
+                false => Ok(()),
+            }
+        }
+        
+        fn unsupported<T>(&self, what: &str) -> Result<T, Box<dyn Error + Send + Sync>> {
+            Err(ExecutionError::Unsupported(format!("{} does not support {}", self.name, what)).into())
+        }
+    }
+    
+    #[async_trait]
+    impl ExecutionPlugin for StubPlugin {
+        async fn init(&mut self, _config: serde_json::Value) -> Result<(), Box<dyn Error + Send + Sync>> {
+            self.inits += 1;
+            if self.inits <= self.init_failures {
+                return Err("connection refused".into());
+            }
+            Ok(())
+        }
+        
+        async fn execute_order(&self, _order: Order) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
+            Err("not used".into())
+        }
+        
+        async fn fetch_data(&self, symbol: &str) -> Result<MarketData, Box<dyn Error + Send + Sync>> {
+            self.check_up()?;
+            if let Some(listed) = self.listed.as_ref().filter(|listed| !listed.iter().any(|s| s == symbol)) {
+                return Err(ExecutionError::invalid_symbol(symbol, listed).into());
+            }
+            let Some((bid, ask, age_ms)) = self.quote else {
+                return self.unsupported("market data");
+            };
+            Ok(MarketData {
+                symbol: symbol.to_string(),
+                bid,
+                ask,
+                last: (bid + ask) / 2.0,
+                volume: 0.0,
+                timestamp: now_millis() - age_ms,
+                extra: serde_json::json!({}),
+            })
+        }
+        
+        async fn health_check(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+            Ok(!self.down && self.inits >= self.init_failures)
+        }
+        
+        fn name(&self) -> &str {
+            &self.name
+        }
+        
+        async fn get_positions(&self, _symbol: Option<&str>) -> Result<Vec<Position>, Box<dyn Error + Send + Sync>> {
+            self.check_up()?;
+            match &self.positions {
+                Some(positions) => Ok(positions.clone()),
+                None => self.unsupported("position queries"),
+            }
+        }
+        
+        async fn get_balances(&self) -> Result<Vec<Balance>, Box<dyn Error + Send + Sync>> {
+            self.check_up()?;
+            match &self.balances {
+                Some(balances) => Ok(balances.clone()),
+                None => self.unsupported("balance queries"),
+            }
+        }
+        
+        async fn list_symbols(&self) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+            let lookup = self.symbol_lookups.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(vec![format!("SYM{}", lookup)])
+        }
+    }
+    
+    /// Serve `app` as a stand-in exchange on a free local port, returning its base URL
+    pub(crate) async fn spawn_mock_exchange(app: axum::Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+    
+    #[test]
+    fn test_closest_symbols_ignore_case_and_separators() {
+        let listed: Vec<String> = ["BTCUSDT", "BTCUSDC", "ETHUSDT", "ETHBTC", "XRPUSDT"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(closest_symbols("btc/ustd", &listed), vec!["BTCUSDT", "BTCUSDC"]);
+        assert_eq!(closest_symbols("ETHUSD", &listed), vec!["ETHUSDT"]);
+        assert!(closest_symbols("DOGEJPY", &listed).is_empty());
+        
+        let err = ExecutionError::invalid_symbol("ETHUSD", &listed);
+        assert_eq!(err.to_string(), "Unknown symbol 'ETHUSD'; did you mean ETHUSDT?");
+    }
+    
+    #[test]
+    fn test_signed_request_log_redacts_secret() {
+        let logs = LogCapture::default();
+        let _guard = logs.install();
+        log_signed_request("bybit", "1700000000000", "1700000000000key5000topsecret", "abc123", "topsecret");
+        
+        let output = logs.contents();
+        assert!(output.contains("prehash=1700000000000key5000[REDACTED]"));
+        assert!(!output.contains("topsecret"));
+    }
+    
+    #[test]
+    fn test_non_json_body_is_network_error() {
+        let err = parse_response::<serde_json::Value>("Bybit", "<html><body>502 Bad Gateway</body></html>").unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Network(_))));
+        assert!(err.to_string().contains("Bybit returned a non-JSON body: <html><body>502 Bad Gateway"));
+        
+        let err = parse_response::<serde_json::Value>("KuCoin", "").unwrap_err();
+        assert_eq!(err.to_string(), "network: KuCoin returned a non-JSON body: <empty body>");
+        
+        // Valid JSON of the wrong shape is still a deserialization error
+        let err = parse_response::<Vec<i64>>("Bybit", r#"{"retCode":0}"#).unwrap_err();
+        assert!(err.downcast_ref::<ExecutionError>().is_none());
+        
+        let err = http_error("Bybit", reqwest::StatusCode::BAD_GATEWAY, &"<p>oops</p>".repeat(100));
+        let message = err.to_string();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Network(_))));
+        assert!(message.ends_with("..."));
+        assert!(message.len() < 300);
+        
+        let err = http_error("Bybit", reqwest::StatusCode::BAD_REQUEST, r#"{"retCode":10001}"#);
+        assert_eq!(err.to_string(), r#"Bybit API error (400 Bad Request): {"retCode":10001}"#);
+    }
     
     #[test]
     fn test_order_serialization() {
@@ -179,6 +1841,7 @@ mod tests {
             stop_loss: Some(67000.0),
             take_profit: Some(69000.0),
             confidence: 0.75,
+            ..Default::default()
         };
         
         let json = serde_json::to_string(&order).unwrap();
@@ -199,7 +1862,7 @@ mod tests {
         }"#;
         
         let order: Order = serde_json::from_str(json).unwrap();
-        assert_eq!(order.confidence, 0.6); // Default
+        assert_eq!(order.confidence, DEFAULT_CONFIDENCE);
     }
     
     #[test]
@@ -216,4 +1879,353 @@ mod tests {
         assert!(result.success);
         assert!(result.error.is_none());
     }
+    
+    #[test]
+    fn test_client_order_id_format() {
+        let mut order: Order = serde_json::from_str(r#"{
+            "symbol": "BTCUSDT",
+            "side": "buy",
+            "order_type": "market",
+            "quantity": 1.0,
+            "confidence": 0.756,
+            "strategy_tag": "breakout-v2"
+        }"#).unwrap();
+        
+        let id = client_order_id(&order, 36);
+        let parts: Vec<&str> = id.split('-').collect();
+        assert_eq!(parts[0], "fks");
+        assert_eq!(parts[1], "breakoutv2");
+        assert_eq!(parts[2], "76");
+        assert_eq!(parts[3].len(), ID_SEQ_WIDTH + 4);
+        
+        order.strategy_tag = None;
+        let id = client_order_id(&order, 36);
+        assert!(id.starts_with("fks-76-"));
+    }
+    
+    #[test]
+    fn test_unique_id_concurrent_generation() {
+        let handles: Vec<_> = (0..8)
+            .map(|_| std::thread::spawn(|| (0..2000).map(|_| unique_id()).collect::<Vec<_>>()))
+            .collect();
+        
+        let mut all = std::collections::HashSet::new();
+        for handle in handles {
+            let ids = handle.join().unwrap();
+            // Ids from one thread are strictly increasing in their sequence part
+            assert!(ids.windows(2).all(|w| w[0][..ID_SEQ_WIDTH] < w[1][..ID_SEQ_WIDTH]));
+            all.extend(ids);
+        }
+        assert_eq!(all.len(), 8 * 2000);
+    }
+    
+    #[test]
+    fn test_client_order_id_truncation() {
+        let order: Order = serde_json::from_str(r#"{
+            "symbol": "BTCUSDT",
+            "side": "sell",
+            "order_type": "limit",
+            "quantity": 1.0,
+            "confidence": 1.0,
+            "strategy_tag": "a_very_long_strategy_name_that_overflows"
+        }"#).unwrap();
+        
+        let id = client_order_id(&order, 36);
+        assert_eq!(id.len(), 36);
+        assert!(id.starts_with("fks-a_very_long_"));
+        assert!(id.contains("-100-"));
+        
+        let id = client_order_id(&order, 40);
+        assert_eq!(id.len(), 40);
+    }
+    
+    #[test]
+    fn test_quote_alias_remaps_symbol() {
+        let aliases = parse_quote_aliases("USD=USDT, busd = usdt");
+        assert_eq!(aliases.len(), 2);
+        
+        assert_eq!(apply_quote_alias("test", "BTCUSD", &aliases), "BTCUSDT");
+        assert_eq!(apply_quote_alias("test", "BTC-USD", &aliases), "BTC-USDT");
+        assert_eq!(apply_quote_alias("test", "ETH/BUSD", &aliases), "ETH/USDT");
+    }
+    
+    #[test]
+    fn test_quote_alias_passes_unmapped_through() {
+        let aliases = parse_quote_aliases("USD=USDT");
+        
+        assert_eq!(apply_quote_alias("test", "BTCUSDT", &aliases), "BTCUSDT");
+        assert_eq!(apply_quote_alias("test", "ETHBTC", &aliases), "ETHBTC");
+        assert_eq!(apply_quote_alias("test", "USD", &aliases), "USD");
+        assert_eq!(apply_quote_alias("test", "BTCUSD", &HashMap::new()), "BTCUSD");
+    }
+    
+    #[test]
+    fn test_round_to_step() {
+        use RoundingMode::{Down, Nearest, Up};
+        
+        // Exact multiples are kept in every mode
+        for mode in [Down, Nearest, Up] {
+            assert_eq!(round_to_step(0.25, 0.001, mode), 0.25);
+            assert_eq!(round_to_step(0.3, 0.1, mode), 0.3);
+            assert_eq!(round_to_step(0.1234, 0.0, mode), 0.1234);
+        }
+        
+        assert_eq!(round_to_step(0.2567, 0.001, Down), 0.256);
+        assert_eq!(round_to_step(0.2567, 0.001, Nearest), 0.257);
+        assert_eq!(round_to_step(0.2567, 0.001, Up), 0.257);
+        
+        // Halfway between steps
+        assert_eq!(round_to_step(7.5, 1.0, Down), 7.0);
+        assert_eq!(round_to_step(7.5, 1.0, Nearest), 8.0);
+        assert_eq!(round_to_step(7.5, 1.0, Up), 8.0);
+        
+        // Just past a step
+        assert_eq!(round_to_step(0.3001, 0.1, Down), 0.3);
+        assert_eq!(round_to_step(0.3001, 0.1, Nearest), 0.3);
+        assert_eq!(round_to_step(0.3001, 0.1, Up), 0.4);
+        
+        // Below the first step
+        assert_eq!(round_to_step(0.0004, 0.001, Down), 0.0);
+        assert_eq!(round_to_step(0.0004, 0.001, Nearest), 0.0);
+        assert_eq!(round_to_step(0.0004, 0.001, Up), 0.001);
+    }
+    
+    #[test]
+    fn test_instrument_rounding_defaults_and_config() {
+        let rounding: InstrumentRounding = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(rounding, InstrumentRounding::default());
+        
+        let mut info = InstrumentInfo {
+            symbol: "BTCUSDT".to_string(),
+            qty_step: 0.001,
+            min_qty: 0.001,
+            tick_size: 0.5,
+            rounding,
+        };
+        assert_eq!(info.round_qty(0.0129), 0.012);
+        assert_eq!(info.round_price(67000.3), 67000.5);
+        
+        info.rounding = serde_json::from_value(serde_json::json!({ "qty_rounding": "up", "price_rounding": "down" })).unwrap();
+        assert_eq!(info.round_qty(0.0121), 0.013);
+        assert_eq!(info.round_price(67000.3), 67000.0);
+    }
+    
+    #[test]
+    fn test_ping_result_clock_skew() {
+        let ping = PingResult::measure(1_000_000, Duration::from_millis(40), Some(1_000_520));
+        assert_eq!(ping.latency_ms, 40.0);
+        // Midpoint of the round trip is 1_000_020
+        assert_eq!(ping.clock_skew_ms, Some(500));
+        
+        let ping = PingResult::measure(1_000_000, Duration::from_millis(40), None);
+        assert_eq!(ping.clock_skew_ms, None);
+    }
+    
+    fn bracket_order(side: OrderSide, price: Option<f64>, stop_loss: Option<f64>, take_profit: Option<f64>) -> Order {
+        Order {
+            symbol: "BTC/USDT".to_string(),
+            side,
+            order_type: if price.is_some() { OrderType::Limit } else { OrderType::Market },
+            quantity: 0.1,
+            price,
+            stop_loss,
+            take_profit,
+            confidence: 0.75,
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn test_buy_stop_loss_above_price_rejected() {
+        let order = bracket_order(OrderSide::Buy, Some(67500.0), Some(68000.0), None);
+        let err = order.validate(None).unwrap_err();
+        assert!(err.contains("stop_loss 68000 must be below entry 67500"));
+    }
+    
+    #[test]
+    fn test_buy_stop_loss_below_price_accepted() {
+        let order = bracket_order(OrderSide::Buy, Some(67500.0), Some(67000.0), Some(69000.0));
+        assert!(order.validate(None).is_ok());
+    }
+    
+    #[test]
+    fn test_sell_brackets_inverted() {
+        assert!(bracket_order(OrderSide::Sell, Some(67500.0), Some(68000.0), Some(66000.0)).validate(None).is_ok());
+        
+        let err = bracket_order(OrderSide::Sell, Some(67500.0), None, Some(68000.0)).validate(None).unwrap_err();
+        assert!(err.contains("take_profit 68000 must be below entry 67500"));
+    }
+    
+    #[test]
+    fn test_market_order_validated_against_market_price() {
+        let order = bracket_order(OrderSide::Buy, None, Some(3600.0), None);
+        assert!(order.validate(Some(3500.0)).is_err());
+        assert!(order.validate(Some(3700.0)).is_ok());
+        
+        // No reference price: only the stop/target ordering is checked
+        assert!(order.validate(None).is_ok());
+        let crossed = bracket_order(OrderSide::Buy, None, Some(3600.0), Some(3500.0));
+        assert!(crossed.validate(None).unwrap_err().contains("must be below take_profit"));
+    }
+    
+    #[test]
+    fn test_price_issue_by_order_type() {
+        let mut order = bracket_order(OrderSide::Buy, None, None, None);
+        assert_eq!(order.price_issue(), None);
+        order.price = Some(67500.0);
+        assert_eq!(order.price_issue().unwrap(), "price 67500 is ignored for market orders");
+        
+        for order_type in [OrderType::Limit, OrderType::StopLimit] {
+            order.order_type = order_type;
+            order.price = Some(67500.0);
+            assert_eq!(order.price_issue(), None);
+            order.price = None;
+            assert!(order.price_issue().unwrap().starts_with("price is required"));
+        }
+        
+        // Stops trigger off stop_loss; a price is optional either way
+        order.order_type = OrderType::Stop;
+        assert_eq!(order.price_issue(), None);
+        order.price = Some(66000.0);
+        assert_eq!(order.price_issue(), None);
+    }
+    
+    #[test]
+    fn test_stop_limit_requires_trigger_and_limit_price() {
+        let mut order = bracket_order(OrderSide::Buy, Some(68100.0), None, None);
+        order.order_type = OrderType::StopLimit;
+        assert_eq!(order.validate_stop_limit().unwrap_err(), "trigger_price is required for StopLimit orders");
+        
+        order.trigger_price = Some(68000.0);
+        assert!(order.validate_stop_limit().is_ok());
+        
+        order.price = None;
+        assert!(order.validate_stop_limit().unwrap_err().starts_with("price (the limit) is required"));
+        
+        // Other order types don't need a trigger
+        order.order_type = OrderType::Limit;
+        order.trigger_price = None;
+        assert!(order.validate_stop_limit().is_ok());
+    }
+    
+    #[test]
+    fn test_display_quantity_validation() {
+        let mut order = bracket_order(OrderSide::Buy, Some(67000.0), None, None);
+        order.order_type = OrderType::Limit;
+        order.quantity = 1.0;
+        assert!(order.validate_display_quantity().is_ok());
+        
+        order.display_quantity = Some(0.1);
+        assert!(order.validate_display_quantity().is_ok());
+        order.display_quantity = Some(1.0);
+        assert!(order.validate_display_quantity().is_ok());
+        
+        order.display_quantity = Some(1.5);
+        assert_eq!(order.validate_display_quantity().unwrap_err(), "display_quantity 1.5 exceeds quantity 1");
+        order.display_quantity = Some(0.0);
+        assert!(order.validate_display_quantity().unwrap_err().contains("must be positive"));
+        
+        order.display_quantity = Some(0.1);
+        order.order_type = OrderType::Market;
+        assert_eq!(order.validate_display_quantity().unwrap_err(), "display_quantity only applies to limit orders, not Market");
+        
+        assert!(reject_display_quantity("ccxt", &order).is_err());
+        order.display_quantity = None;
+        assert!(reject_display_quantity("ccxt", &order).is_ok());
+    }
+    
+    #[test]
+    fn test_testnet_override_rejected_on_single_host_plugins() {
+        let mut order = bracket_order(OrderSide::Buy, Some(67000.0), None, None);
+        assert!(reject_testnet_override("ibkr", &order).is_ok());
+        
+        order.testnet = Some(false);
+        let err = reject_testnet_override("ibkr", &order).unwrap_err();
+        assert_eq!(err.to_string(), "unsupported: ibkr does not support per-order testnet selection");
+    }
+    
+    #[test]
+    fn test_order_leverage_rejected_without_leverage_parameter() {
+        let mut order = bracket_order(OrderSide::Buy, Some(67000.0), None, None);
+        assert!(reject_leverage("ccxt", &order).is_ok());
+        
+        order.leverage = Some(5);
+        let err = reject_leverage("ccxt", &order).unwrap_err();
+        assert_eq!(err.to_string(), "unsupported: ccxt does not support per-order leverage");
+    }
+    
+    #[test]
+    fn test_order_status_filter() {
+        let open = OrderStatus::parse_filter("open").unwrap();
+        assert!(open.contains(&OrderStatus::Open));
+        assert!(open.contains(&OrderStatus::PartiallyFilled));
+        assert!(!open.contains(&OrderStatus::Filled));
+        
+        assert_eq!(OrderStatus::parse_filter("Canceled"), Some(vec![OrderStatus::Cancelled]));
+        assert_eq!(OrderStatus::parse_filter("bogus"), None);
+    }
+    
+    #[test]
+    fn test_maintenance_state_window() {
+        let state = MaintenanceState::default();
+        assert!(!state.is_active());
+        
+        state.enter(Duration::from_secs(60));
+        assert!(state.is_active());
+        
+        state.enter(Duration::ZERO);
+        assert!(!state.is_active());
+        
+        state.enter(Duration::from_secs(60));
+        state.clear();
+        assert!(!state.is_active());
+    }
+    
+    #[test]
+    fn test_maintenance_window_expires_on_clock() {
+        let clock = crate::clock::TestClock::new(1_700_000_000_000);
+        let state = MaintenanceState::with_clock(clock.clone());
+        
+        state.enter(MaintenanceState::DEFAULT_WINDOW);
+        clock.advance(Duration::from_secs(299));
+        assert!(state.is_active());
+        
+        clock.advance(Duration::from_secs(1));
+        assert!(!state.is_active());
+    }
+    
+    #[test]
+    fn test_time_sync_applies_offset_and_refreshes_on_interval() {
+        let clock = crate::clock::TestClock::new(1_700_000_000_000);
+        
+        let local = TimeSync::with_clock(TimeSource::Local, Duration::from_secs(60), clock.clone());
+        assert!(!local.start_refresh());
+        assert_eq!(local.now_millis(), 1_700_000_000_000);
+        
+        let synced = TimeSync::with_clock(TimeSource::Exchange, Duration::from_secs(60), clock.clone());
+        assert!(synced.start_refresh());
+        assert!(!synced.start_refresh(), "a measurement is already in progress");
+        synced.record("time-sync-test", -1500);
+        assert_eq!(synced.now_millis(), 1_699_999_998_500);
+        assert_eq!(crate::metrics::CLOCK_OFFSET_MS.with_label_values(&["time-sync-test"]).get(), -1500);
+        
+        clock.advance(Duration::from_secs(59));
+        assert!(!synced.start_refresh());
+        clock.advance(Duration::from_secs(1));
+        assert!(synced.start_refresh());
+    }
+    
+    #[tokio::test]
+    async fn test_init_retries_until_plugin_comes_up() {
+        let retry = InitRetry { attempts: 3, backoff: Duration::from_millis(1) };
+        
+        let mut plugin = StubPlugin::new("flaky").failing_inits(2);
+        retry.init(&mut plugin, serde_json::json!({})).await.unwrap();
+        assert_eq!(plugin.inits, 3);
+        
+        let mut plugin = StubPlugin::new("flaky").failing_inits(3);
+        let err = retry.init(&mut plugin, serde_json::json!({})).await.unwrap_err();
+        assert_eq!(err.to_string(), "flaky init failed after 3 attempt(s): connection refused");
+        assert_eq!(plugin.inits, 3);
+    }
 }
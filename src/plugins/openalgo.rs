@@ -10,11 +10,12 @@
 //! - Real-time order status tracking
 //! - Position and balance management
 
-use super::{ExecutionPlugin, ExecutionResult, MarketData, Order, OrderSide, OrderType};
+use super::headers::ClientHeaders;
+use super::{merge_extra_params, now_millis, reject_display_quantity, reject_leverage, reject_max_slippage, reject_post_only, reject_quote_quantity, reject_testnet_override, ExecutionError, ExecutionPlugin, ExecutionResult, MarketData, Order, OrderSide, OrderType, PreparedRequest, FRACTIONAL_QUANTITY};
 use async_trait::async_trait;
-use chrono::Utc;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
 use std::time::Duration;
 
@@ -35,6 +36,40 @@ pub struct OpenAlgoConfig {
     
     /// Request timeout in seconds
     pub timeout_secs: u64,
+    
+    /// The broker accepts fractional quantities; otherwise only whole units
+    /// are sent and a fractional quantity is rejected rather than truncated
+    #[serde(default)]
+    pub fractional_quantity: bool,
+    
+    /// Product type (CNC, MIS or NRML) by symbol or `*` pattern, e.g.
+    /// `{"RELIANCE": "CNC", "*FUT": "NRML"}`; an exact symbol beats a
+    /// pattern and a longer pattern beats a shorter one. Unmatched symbols
+    /// fall back to NRML for futures/options and MIS otherwise
+    #[serde(default)]
+    pub product_types: HashMap<String, String>,
+    
+    /// Order submissions tried while the sidecar refuses connections (default: 3)
+    #[serde(default = "default_connect_attempts")]
+    pub connect_attempts: u32,
+    
+    /// Wait before the first connection retry, doubled after each (default: 250ms)
+    #[serde(default = "default_connect_backoff_ms")]
+    pub connect_backoff_ms: u64,
+    
+    /// `user_agent` / `headers` sent on every request
+    #[serde(flatten)]
+    pub client_headers: ClientHeaders,
+}
+
+fn default_connect_attempts() -> u32 {
+    std::env::var("OPENALGO_CONNECT_ATTEMPTS").ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(3)
+}
+
+fn default_connect_backoff_ms() -> u64 {
+    250
 }
 
 impl Default for OpenAlgoConfig {
@@ -47,6 +82,13 @@ impl Default for OpenAlgoConfig {
                 .unwrap_or(true), // Default to sandbox for safety
             broker: std::env::var("OPENALGO_BROKER").unwrap_or_else(|_| "paper".to_string()),
             timeout_secs: 30,
+            fractional_quantity: std::env::var("OPENALGO_FRACTIONAL_QUANTITY")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            product_types: HashMap::new(),
+            connect_attempts: default_connect_attempts(),
+            connect_backoff_ms: default_connect_backoff_ms(),
+            client_headers: ClientHeaders::default(),
         }
     }
 }
@@ -57,13 +99,21 @@ struct OpenAlgoOrderRequest {
     symbol: String,
     exchange: String,
     action: String,      // BUY or SELL
-    quantity: i32,
+    quantity: OpenAlgoQuantity,
     order_type: String,  // MARKET, LIMIT, SL, SL-M
     product: String,     // CNC (delivery), MIS (intraday), NRML (F&O)
     price: Option<f64>,
     trigger_price: Option<f64>,
 }
 
+/// Order quantity, a JSON integer unless the broker trades fractions
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(untagged)]
+enum OpenAlgoQuantity {
+    Whole(i64),
+    Fractional(f64),
+}
+
 /// OpenAlgo order response
 #[derive(Debug, Deserialize)]
 struct OpenAlgoOrderResponse {
@@ -74,6 +124,7 @@ struct OpenAlgoOrderResponse {
 
 /// OpenAlgo position response
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)] // positions endpoint not implemented yet
 struct OpenAlgoPosition {
     symbol: String,
     quantity: i32,
@@ -129,12 +180,35 @@ impl OpenAlgoPlugin {
         }
     }
     
+    /// Product type configured for a symbol, exact match first, then the most specific pattern
+    fn configured_product_type(&self, symbol: &str) -> Option<&str> {
+        let symbol = symbol.to_uppercase();
+        let mut best: Option<(usize, &str)> = None;
+        for (key, product) in &self.config.product_types {
+            let key = key.trim().to_uppercase();
+            if key == symbol {
+                return Some(product);
+            }
+            let Some((prefix, suffix)) = key.split_once('*') else { continue };
+            let matched = symbol.len() >= prefix.len() + suffix.len()
+                && symbol.starts_with(prefix)
+                && symbol.ends_with(suffix);
+            let literal = prefix.len() + suffix.len();
+            if matched && best.is_none_or(|(len, _)| literal > len) {
+                best = Some((literal, product));
+            }
+        }
+        best.map(|(_, product)| product)
+    }
+    
     /// Determine product type based on order context
     fn get_product_type(&self, symbol: &str) -> String {
-        // TODO: Make this configurable
         // CNC = Cash and Carry (delivery)
         // MIS = Margin Intraday Square-off
         // NRML = Normal (F&O)
+        if let Some(product) = self.configured_product_type(symbol) {
+            return product.trim().to_uppercase();
+        }
         
         if symbol.contains("FUT") || symbol.contains("OPT") {
             "NRML".to_string()
@@ -142,6 +216,123 @@ impl OpenAlgoPlugin {
             "MIS".to_string() // Default to intraday for safety
         }
     }
+    
+    /// The quantity to send, refusing to silently drop a fraction on whole-unit markets
+    fn order_quantity(&self, order: &Order) -> Result<OpenAlgoQuantity, ExecutionError> {
+        let whole = order.quantity.round();
+        if (order.quantity - whole).abs() < 1e-9 {
+            return Ok(OpenAlgoQuantity::Whole(whole as i64));
+        }
+        match self.config.fractional_quantity {
+            true => Ok(OpenAlgoQuantity::Fractional(order.quantity)),
+            false => Err(ExecutionError::Exchange {
+                code: FRACTIONAL_QUANTITY,
+                message: format!(
+                    "{} trades whole units only, got quantity {} for {}",
+                    self.name, order.quantity, order.symbol
+                ),
+            }),
+        }
+    }
+    
+    /// Trigger and limit prices for an order
+    ///
+    /// `trigger_price` activates stop orders, with `stop_loss` standing in as
+    /// the trigger of a protective stop; `take_profit` is the limit of a
+    /// take-profit order. OpenAlgo has no bracket legs, so a stop-loss or
+    /// take-profit attached to any other order is refused rather than dropped.
+    fn order_prices(&self, order: &Order) -> Result<(Option<f64>, Option<f64>), ExecutionError> {
+        let protective_stop = matches!(order.order_type, OrderType::Stop | OrderType::StopLoss);
+        let take_profit = order.order_type == OrderType::TakeProfit;
+        let attached: Vec<&str> = [
+            (order.stop_loss.is_some() && !protective_stop, "stop_loss"),
+            (order.take_profit.is_some() && !take_profit, "take_profit"),
+        ]
+            .into_iter()
+            .filter_map(|(set, field)| set.then_some(field))
+            .collect();
+        if !attached.is_empty() {
+            return Err(ExecutionError::Unsupported(format!(
+                "{} cannot attach {} to a {:?} order; place it as a separate order",
+                self.name, attached.join(" and "), order.order_type
+            )));
+        }
+        
+        let trigger_price = match order.order_type {
+            OrderType::Stop | OrderType::StopLoss => order.trigger_price.or(order.stop_loss),
+            OrderType::StopLimit => order.trigger_price,
+            _ => None,
+        };
+        let price = match take_profit {
+            true => order.price.or(order.take_profit),
+            false => order.price,
+        };
+        Ok((price, trigger_price))
+    }
+    
+    /// The `/api/v1/orders` request for an order
+    fn build_order_request(&self, order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        order.validate_stop_limit()?;
+        let (price, trigger_price) = self.order_prices(order)?;
+        let (symbol, exchange) = self.convert_symbol(&order.symbol);
+        let product = self.get_product_type(&symbol);
+        let openalgo_order = OpenAlgoOrderRequest {
+            symbol,
+            exchange,
+            action: match order.side {
+                OrderSide::Buy => "BUY".to_string(),
+                OrderSide::Sell => "SELL".to_string(),
+            },
+            quantity: self.order_quantity(order)?,
+            order_type: self.convert_order_type(&order.order_type),
+            product,
+            price,
+            trigger_price,
+        };
+        
+        let mut body = serde_json::to_value(openalgo_order)?;
+        merge_extra_params(&self.name, &mut body, order);
+        
+        let path = "/api/v1/orders".to_string();
+        Ok(PreparedRequest {
+            method: "POST".to_string(),
+            url: format!("{}{}", self.config.base_url, path),
+            path,
+            body,
+        })
+    }
+    
+    /// Submit a prepared request, returning the status and raw body
+    ///
+    /// The sidecar may still be starting, so a refused connection is retried
+    /// with backoff. Only connection failures are: the request never reached
+    /// OpenAlgo, so a retry can't place the order twice.
+    async fn send(&self, request: &PreparedRequest) -> Result<(reqwest::StatusCode, String), Box<dyn Error + Send + Sync>> {
+        let client = self.client.as_ref().ok_or("HTTP client not available")?;
+        let mut delay = Duration::from_millis(self.config.connect_backoff_ms);
+        let mut attempt = 1;
+        let response = loop {
+            match client.post(&request.url).json(&request.body).send().await {
+                Err(e) if e.is_connect() && attempt < self.config.connect_attempts => {
+                    tracing::warn!(
+                        plugin = %self.name,
+                        attempt,
+                        error = %e,
+                        retry_in_ms = delay.as_millis() as u64,
+                        "openalgo_connect_retry"
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                result => break result?,
+            }
+        };
+        
+        let status = response.status();
+        let text = response.text().await?;
+        Ok((status, text))
+    }
 }
 
 #[async_trait]
@@ -161,10 +352,16 @@ impl ExecutionPlugin for OpenAlgoPlugin {
             }
         }
         
+        if let Some((symbol, product)) = self.config.product_types.iter()
+            .find(|(_, product)| !matches!(product.trim().to_uppercase().as_str(), "CNC" | "MIS" | "NRML"))
+        {
+            return Err(format!("Invalid product type '{}' for '{}': expected CNC, MIS or NRML", product, symbol).into());
+        }
+        
         // Create HTTP client
         self.client = Some(
-            Client::builder()
-                .timeout(Duration::from_secs(self.config.timeout_secs))
+            self.config.client_headers
+                .apply(Client::builder().timeout(Duration::from_secs(self.config.timeout_secs)))?
                 .build()?
         );
         
@@ -203,11 +400,17 @@ impl ExecutionPlugin for OpenAlgoPlugin {
         &self,
         order: Order,
     ) -> Result<ExecutionResult, Box<dyn Error + Send + Sync>> {
+        reject_display_quantity(&self.name, &order)?;
+        reject_post_only(&self.name, &order)?;
+        reject_testnet_override(&self.name, &order)?;
+        reject_leverage(&self.name, &order)?;
+        reject_max_slippage(&self.name, &order)?;
+        reject_quote_quantity(&self.name, &order)?;
+        
         if !self.is_initialized {
             return Err("Plugin not initialized".into());
         }
         
-        let client = self.client.as_ref().ok_or("HTTP client not available")?;
         let (symbol, exchange) = self.convert_symbol(&order.symbol);
         
         tracing::info!(
@@ -220,32 +423,11 @@ impl ExecutionPlugin for OpenAlgoPlugin {
             "Executing order via OpenAlgo"
         );
         
-        // Build OpenAlgo order request
-        let openalgo_order = OpenAlgoOrderRequest {
-            symbol,
-            exchange,
-            action: match order.side {
-                OrderSide::Buy => "BUY".to_string(),
-                OrderSide::Sell => "SELL".to_string(),
-            },
-            quantity: order.quantity as i32,
-            order_type: self.convert_order_type(&order.order_type),
-            product: self.get_product_type(&order.symbol),
-            price: order.price,
-            trigger_price: order.stop_loss,
-        };
-        
-        // Execute order
-        let order_url = format!("{}/api/v1/orders", self.config.base_url);
+        let request = self.build_order_request(&order)?;
+        let (status, text) = self.send(&request).await?;
         
-        let response = client
-            .post(&order_url)
-            .json(&openalgo_order)
-            .send()
-            .await?;
-        
-        if response.status().is_success() {
-            let result: OpenAlgoOrderResponse = response.json().await?;
+        if status.is_success() {
+            let result: OpenAlgoOrderResponse = serde_json::from_str(&text)?;
             
             if result.status == "success" || result.status == "ok" {
                 tracing::info!(
@@ -260,7 +442,7 @@ impl ExecutionPlugin for OpenAlgoPlugin {
                     filled_quantity: order.quantity,
                     average_price: order.price.unwrap_or(0.0),
                     error: None,
-                    timestamp: Utc::now().timestamp_millis(),
+                    timestamp: now_millis(),
                 })
             } else {
                 tracing::warn!(
@@ -275,17 +457,14 @@ impl ExecutionPlugin for OpenAlgoPlugin {
                     filled_quantity: 0.0,
                     average_price: 0.0,
                     error: result.message,
-                    timestamp: Utc::now().timestamp_millis(),
+                    timestamp: now_millis(),
                 })
             }
         } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            
             tracing::error!(
                 plugin = %self.name,
                 status = %status,
-                error = %error_text,
+                error = %text,
                 "OpenAlgo API error"
             );
             
@@ -294,12 +473,27 @@ impl ExecutionPlugin for OpenAlgoPlugin {
                 order_id: None,
                 filled_quantity: 0.0,
                 average_price: 0.0,
-                error: Some(format!("API error {}: {}", status, error_text)),
-                timestamp: Utc::now().timestamp_millis(),
+                error: Some(format!("API error {}: {}", status, text)),
+                timestamp: now_millis(),
             })
         }
     }
     
+    async fn preview_order(&self, order: &Order) -> Result<PreparedRequest, Box<dyn Error + Send + Sync>> {
+        reject_display_quantity(&self.name, order)?;
+        reject_post_only(&self.name, order)?;
+        reject_testnet_override(&self.name, order)?;
+        reject_leverage(&self.name, order)?;
+        reject_max_slippage(&self.name, order)?;
+        reject_quote_quantity(&self.name, order)?;
+        
+        if !self.is_initialized {
+            return Err("Plugin not initialized".into());
+        }
+        
+        self.build_order_request(order)
+    }
+    
     async fn fetch_data(&self, symbol: &str) -> Result<MarketData, Box<dyn Error + Send + Sync>> {
         if !self.is_initialized {
             return Err("Plugin not initialized".into());
@@ -330,7 +524,7 @@ impl ExecutionPlugin for OpenAlgoPlugin {
                 ask,
                 last,
                 volume,
-                timestamp: Utc::now().timestamp_millis(),
+                timestamp: now_millis(),
                 extra: serde_json::json!({
                     "exchange": exchange,
                     "source": "openalgo",
@@ -351,7 +545,7 @@ impl ExecutionPlugin for OpenAlgoPlugin {
                 ask: 0.0,
                 last: 0.0,
                 volume: 0.0,
-                timestamp: Utc::now().timestamp_millis(),
+                timestamp: now_millis(),
                 extra: serde_json::json!({
                     "source": "openalgo",
                     "error": "quote_failed"
@@ -364,6 +558,14 @@ impl ExecutionPlugin for OpenAlgoPlugin {
         &self.name
     }
     
+    fn is_paper(&self) -> bool {
+        self.config.sandbox_mode
+    }
+    
+    async fn effective_config(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(&self.config).ok()
+    }
+    
     async fn health_check(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
         if !self.is_initialized {
             return Ok(false);
@@ -385,6 +587,7 @@ impl ExecutionPlugin for OpenAlgoPlugin {
 
 impl OpenAlgoPlugin {
     /// Get list of supported markets/exchanges
+    #[allow(dead_code)] // public plugin API, not routed over HTTP
     pub fn supported_markets(&self) -> Vec<String> {
         vec![
             "NSE".to_string(),  // National Stock Exchange
@@ -423,10 +626,213 @@ mod tests {
         assert_eq!(plugin.convert_order_type(&OrderType::StopLimit), "SL");
     }
     
+    #[test]
+    fn test_build_order_request_market_limit_stop() {
+        let mut plugin = OpenAlgoPlugin::new("test");
+        plugin.config.base_url = "http://openalgo:5000".to_string();
+        let order = |order_type, price, stop_loss| Order {
+            symbol: "RELIANCE".to_string(),
+            side: OrderSide::Sell,
+            order_type,
+            quantity: 10.0,
+            price,
+            stop_loss,
+            confidence: 0.7,
+            ..Default::default()
+        };
+        
+        let market = plugin.build_order_request(&order(OrderType::Market, None, None)).unwrap();
+        assert_eq!(market.url, "http://openalgo:5000/api/v1/orders");
+        assert_eq!(market.body["order_type"], "MARKET");
+        assert_eq!(market.body["action"], "SELL");
+        assert_eq!(market.body["exchange"], "NSE");
+        assert_eq!(market.body["quantity"], 10);
+        
+        let limit = plugin.build_order_request(&order(OrderType::Limit, Some(2900.0), None)).unwrap();
+        assert_eq!(limit.body["order_type"], "LIMIT");
+        assert_eq!(limit.body["price"], 2900.0);
+        
+        let stop = plugin.build_order_request(&order(OrderType::Stop, None, Some(2850.0))).unwrap();
+        assert_eq!(stop.body["order_type"], "SL-M");
+        assert_eq!(stop.body["trigger_price"], 2850.0);
+    }
+    
+    #[test]
+    fn test_fractional_quantity_rejected_on_whole_unit_market() {
+        let mut plugin = OpenAlgoPlugin::new("test");
+        let order = |quantity| Order {
+            symbol: "RELIANCE".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity,
+            confidence: 0.7,
+            ..Default::default()
+        };
+        
+        // 0.5 used to be truncated to a zero-quantity order
+        let err = plugin.build_order_request(&order(0.5)).unwrap_err();
+        let err = err.downcast_ref::<ExecutionError>().unwrap();
+        assert!(matches!(err, ExecutionError::Exchange { code: FRACTIONAL_QUANTITY, .. }));
+        assert!(err.to_string().contains("whole units only, got quantity 0.5 for RELIANCE"));
+        assert_eq!(plugin.build_order_request(&order(3.0)).unwrap().body["quantity"], 3);
+        
+        plugin.config.fractional_quantity = true;
+        assert_eq!(plugin.build_order_request(&order(0.5)).unwrap().body["quantity"], 0.5);
+        assert!(plugin.build_order_request(&order(3.0)).unwrap().body["quantity"].is_i64());
+    }
+    
+    #[test]
+    fn test_configured_product_type_overrides_heuristic() {
+        let mut plugin = OpenAlgoPlugin::new("test");
+        
+        // Without configuration: F&O is NRML, everything else intraday
+        assert_eq!(plugin.get_product_type("RELIANCE"), "MIS");
+        assert_eq!(plugin.get_product_type("NIFTY24JANFUT"), "NRML");
+        
+        plugin.config.product_types = HashMap::from([
+            ("reliance".to_string(), "cnc".to_string()),
+            ("NIFTY*".to_string(), "MIS".to_string()),
+            ("NIFTY*FUT".to_string(), "NRML".to_string()),
+            ("*".to_string(), "CNC".to_string()),
+        ]);
+        assert_eq!(plugin.get_product_type("RELIANCE"), "CNC");
+        assert_eq!(plugin.get_product_type("NIFTY24JANFUT"), "NRML");
+        assert_eq!(plugin.get_product_type("NIFTY24JAN22000CE"), "MIS");
+        assert_eq!(plugin.get_product_type("INFY"), "CNC");
+        
+        plugin.config.product_types = HashMap::from([("RELIANCE".to_string(), "CNC".to_string())]);
+        let order = Order {
+            symbol: "RELIANCE-BSE".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: 1.0,
+            confidence: 0.7,
+            ..Default::default()
+        };
+        assert_eq!(plugin.build_order_request(&order).unwrap().body["product"], "CNC");
+        assert_eq!(plugin.get_product_type("INFY"), "MIS");
+    }
+    
+    #[tokio::test]
+    async fn test_invalid_product_type_fails_init() {
+        let mut plugin = OpenAlgoPlugin::new("test");
+        let mut config = serde_json::to_value(OpenAlgoConfig::default()).unwrap();
+        config["product_types"] = serde_json::json!({ "RELIANCE": "DELIVERY" });
+        let err = plugin.init(config).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid product type 'DELIVERY' for 'RELIANCE'"));
+    }
+    
+    #[test]
+    fn test_stop_trigger_and_take_profit_mapped_distinctly() {
+        let plugin = OpenAlgoPlugin::new("test");
+        let order = |order_type| Order {
+            symbol: "RELIANCE".to_string(),
+            side: OrderSide::Sell,
+            order_type,
+            quantity: 10.0,
+            confidence: 0.7,
+            ..Default::default()
+        };
+        
+        // A stop-limit triggers at trigger_price, not at the stop-loss
+        let mut stop_limit = order(OrderType::StopLimit);
+        stop_limit.price = Some(2840.0);
+        stop_limit.trigger_price = Some(2850.0);
+        let body = plugin.build_order_request(&stop_limit).unwrap().body;
+        assert_eq!((body["order_type"].clone(), body["price"].clone(), body["trigger_price"].clone()), ("SL".into(), 2840.0.into(), 2850.0.into()));
+        
+        stop_limit.trigger_price = None;
+        stop_limit.stop_loss = Some(2850.0);
+        assert!(plugin.build_order_request(&stop_limit).is_err());
+        
+        // An explicit trigger wins over the stop-loss level
+        let mut stop = order(OrderType::Stop);
+        stop.stop_loss = Some(2800.0);
+        stop.trigger_price = Some(2850.0);
+        assert_eq!(plugin.build_order_request(&stop).unwrap().body["trigger_price"], 2850.0);
+        
+        let mut take_profit = order(OrderType::TakeProfit);
+        take_profit.take_profit = Some(3000.0);
+        let body = plugin.build_order_request(&take_profit).unwrap().body;
+        assert_eq!((body["order_type"].clone(), body["price"].clone(), body["trigger_price"].clone()), ("LIMIT".into(), 3000.0.into(), serde_json::Value::Null));
+        
+        // Brackets on an entry order used to be sent as a trigger on a market order, or dropped
+        let mut entry = order(OrderType::Market);
+        entry.stop_loss = Some(2800.0);
+        entry.take_profit = Some(3000.0);
+        let err = plugin.build_order_request(&entry).unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+        assert!(err.to_string().contains("cannot attach stop_loss and take_profit to a Market order"));
+    }
+    
+    #[tokio::test]
+    async fn test_order_retried_until_sidecar_accepts_connections() {
+        use axum::{routing::post, Json, Router};
+        
+        // Reserve a port, then free it so connections are refused until the server comes up
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let mut plugin = OpenAlgoPlugin::new("test");
+        let mut config = serde_json::to_value(OpenAlgoConfig::default()).unwrap();
+        config["base_url"] = serde_json::json!(format!("http://{}", addr));
+        config["connect_attempts"] = serde_json::json!(1);
+        config["connect_backoff_ms"] = serde_json::json!(100);
+        plugin.init(config.clone()).await.unwrap();
+        
+        let order = Order {
+            symbol: "RELIANCE".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: 1.0,
+            confidence: 0.7,
+            ..Default::default()
+        };
+        
+        // A single attempt gives up on the refused connection
+        assert!(plugin.execute_order(order.clone()).await.is_err());
+        
+        // Attempts at 0, 100 and 300ms are refused; the one at 700ms reaches the server started at 500ms
+        config["connect_attempts"] = serde_json::json!(5);
+        plugin.init(config).await.unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            let app = Router::new().route("/api/v1/orders", post(|| async {
+                Json(serde_json::json!({ "status": "success", "order_id": "OA-1" }))
+            }));
+            let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+            axum::serve(listener, app).await.unwrap();
+        });
+        let started = std::time::Instant::now();
+        let result = plugin.execute_order(order).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.order_id.as_deref(), Some("OA-1"));
+        assert!(started.elapsed() >= Duration::from_millis(500));
+    }
+    
     #[test]
     fn test_default_config() {
         let config = OpenAlgoConfig::default();
         assert!(config.sandbox_mode); // Should default to sandbox for safety
         assert_eq!(config.broker, "paper");
+        
+        let mut plugin = OpenAlgoPlugin::new("test");
+        assert!(plugin.is_paper());
+        plugin.config.sandbox_mode = false;
+        assert!(!plugin.is_paper());
+    }
+    
+    #[tokio::test]
+    async fn test_quote_quantity_rejected_as_unsupported() {
+        let plugin = OpenAlgoPlugin::new("test");
+        let order = Order {
+            symbol: "RELIANCE".to_string(),
+            quantity: 10000.0,
+            quantity_type: crate::plugins::QuantityType::Quote,
+            ..Default::default()
+        };
+        
+        let err = plugin.execute_order(order.clone()).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
+        let err = plugin.preview_order(&order).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))));
     }
 }
@@ -2,15 +2,109 @@
 //!
 //! Manages multiple execution plugins and routes orders to the appropriate backend
 
-use super::{ExecutionPlugin, ExecutionResult, MarketData, Order};
-use std::collections::HashMap;
+use super::{now_millis, CancelOutcome, ExecutionError, ExecutionPlugin, ExecutionResult, HealthStatus, MarketData, Order, OrderRef, OrderSide};
+use crate::in_flight::InFlightOrders;
+use crate::order_cap::DailyOrderCap;
+use crate::publisher::{ExecutionEvent, NoopPublisher, ResultPublisher};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
+
+/// Plugin name that routes an order to whichever enabled plugin quotes the best price
+pub const BEST_PRICE_ROUTE: &str = "best";
+
+/// Quotes older than this are ignored by best-price routing
+const QUOTE_MAX_AGE_MS: i64 = 5_000;
+
+/// Default distance from the median, in percent, beyond which a redundant quote is an outlier
+pub const DEFAULT_OUTLIER_PCT: f64 = 1.0;
+
+/// One venue's price in a [`RedundantPrice`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceSource {
+    pub plugin: String,
+    /// Bid/ask midpoint, or the last price when the book is empty
+    pub price: f64,
+    pub timestamp: i64,
+    /// Further than the outlier threshold from the median
+    pub outlier: bool,
+}
+
+/// A symbol's price cross-checked across every venue quoting it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RedundantPrice {
+    pub symbol: String,
+    /// Median over all fresh quotes
+    pub median: f64,
+    /// The most recent quote that is not an outlier
+    pub freshest: PriceSource,
+    /// Every fresh quote, outliers flagged
+    pub sources: Vec<PriceSource>,
+}
+
+/// How orders are routed, as reported by `GET /api/v1/diag`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoutingConfig {
+    /// Plugin used when an order names no exchange
+    pub default_plugin: Option<String>,
+    /// Best-price routing priority, highest first
+    pub priority: Vec<String>,
+    /// Registered plugins that are not routed to
+    pub disabled: Vec<String>,
+    /// Deadline for a market-data call, in milliseconds
+    pub call_timeout_ms: Option<u64>,
+    /// Orders submitted at once across all plugins
+    pub max_concurrent_orders: Option<usize>,
+    /// Redundant price outlier threshold, in percent
+    pub outlier_pct: f64,
+    /// Kill switch set
+    pub halted: bool,
+}
+
+/// Parse `ROUTING_PRIORITY`: comma-separated plugin names, highest priority first
+pub fn parse_priority(spec: &str) -> Vec<String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Cap on orders being submitted at once, across all plugins
+struct OrderSlots {
+    permits: Arc<Semaphore>,
+    limit: usize,
+    /// How long an order may queue for a slot before it is refused
+    max_wait: Duration,
+}
 
 /// Plugin registry for managing multiple execution backends
 pub struct PluginRegistry {
     plugins: Arc<RwLock<HashMap<String, Arc<dyn ExecutionPlugin>>>>,
     default_plugin: Arc<RwLock<Option<String>>>,
+    /// Registered plugins that are kept but not routed to
+    disabled: Arc<RwLock<HashSet<String>>>,
+    publisher: Arc<RwLock<Arc<dyn ResultPublisher>>>,
+    /// Best-price routing tiebreak and fallback order, highest priority first
+    priority: Arc<RwLock<Vec<String>>>,
+    /// Deadline for a single market-data call (tickers, best-price quotes)
+    call_timeout: Arc<RwLock<Option<Duration>>>,
+    /// Set once startup has tried to initialize every configured plugin
+    init_attempted: Arc<AtomicBool>,
+    /// Optional limit on concurrent `execute_order` calls
+    order_slots: Arc<RwLock<Option<OrderSlots>>>,
+    /// How far (percent) a redundant quote may sit from the median before it's an outlier
+    outlier_pct: Arc<RwLock<f64>>,
+    /// Kill switch: while set, every order is refused
+    halted: Arc<AtomicBool>,
+    /// Orders being submitted, for the shutdown drain
+    in_flight: Arc<InFlightOrders>,
+    /// `MAX_DAILY_ORDERS` counters, checked for every order but reduce-only ones
+    order_cap: Arc<RwLock<Arc<DailyOrderCap>>>,
 }
 
 impl PluginRegistry {
@@ -19,6 +113,104 @@ impl PluginRegistry {
         Self {
             plugins: Arc::new(RwLock::new(HashMap::new())),
             default_plugin: Arc::new(RwLock::new(None)),
+            disabled: Arc::new(RwLock::new(HashSet::new())),
+            publisher: Arc::new(RwLock::new(Arc::new(NoopPublisher))),
+            priority: Arc::new(RwLock::new(Vec::new())),
+            call_timeout: Arc::new(RwLock::new(None)),
+            init_attempted: Arc::new(AtomicBool::new(false)),
+            order_slots: Arc::new(RwLock::new(None)),
+            outlier_pct: Arc::new(RwLock::new(DEFAULT_OUTLIER_PCT)),
+            halted: Arc::new(AtomicBool::new(false)),
+            in_flight: Arc::new(InFlightOrders::default()),
+            order_cap: Arc::new(RwLock::new(Arc::new(DailyOrderCap::default()))),
+        }
+    }
+    
+    /// Record that startup has tried to initialize every configured plugin,
+    /// whether or not any succeeded
+    pub fn mark_init_attempted(&self) {
+        self.init_attempted.store(true, Ordering::SeqCst);
+    }
+    
+    /// Whether startup plugin initialization has finished
+    pub fn init_attempted(&self) -> bool {
+        self.init_attempted.load(Ordering::SeqCst)
+    }
+    
+    /// Refuse all orders until [`Self::resume`]
+    pub fn halt(&self) {
+        self.halted.store(true, Ordering::SeqCst);
+    }
+    
+    /// Accept orders again, returning whether trading was halted
+    pub fn resume(&self) -> bool {
+        self.halted.swap(false, Ordering::SeqCst)
+    }
+    
+    /// Whether the kill switch is set
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+    
+    /// Orders currently being submitted; paths that call a plugin's
+    /// `execute_order` directly take a guard here so shutdown waits for them
+    pub fn in_flight(&self) -> &Arc<InFlightOrders> {
+        &self.in_flight
+    }
+    
+    /// Set where execution results are published after each order
+    pub async fn set_publisher(&self, publisher: Arc<dyn ResultPublisher>) {
+        *self.publisher.write().await = publisher;
+    }
+    
+    /// Set the daily order cap applied in [`Self::execute_order`]
+    pub async fn set_order_cap(&self, cap: DailyOrderCap) {
+        *self.order_cap.write().await = Arc::new(cap);
+    }
+    
+    /// Set the plugin priority used by best-price routing, highest first
+    pub async fn set_priority(&self, priority: Vec<String>) {
+        *self.priority.write().await = priority;
+    }
+    
+    /// Set how long a market-data call may take before it fails (`None`: no deadline)
+    ///
+    /// Orders are never cut off: an abandoned submission could still be placed.
+    pub async fn set_call_timeout(&self, timeout: Option<Duration>) {
+        *self.call_timeout.write().await = timeout;
+    }
+    
+    /// Set how far (percent) from the median a quote may be in [`Self::fetch_data_redundant`]
+    pub async fn set_outlier_threshold(&self, pct: f64) {
+        *self.outlier_pct.write().await = pct;
+    }
+    
+    /// Limit how many orders are submitted at once (`None`: no limit)
+    ///
+    /// Orders past the limit wait up to `max_wait` for a slot, then fail with
+    /// [`ExecutionError::Overloaded`].
+    pub async fn set_order_concurrency(&self, limit: Option<usize>, max_wait: Duration) {
+        *self.order_slots.write().await = limit.map(|limit| OrderSlots {
+            permits: Arc::new(Semaphore::new(limit)),
+            limit,
+            max_wait,
+        });
+    }
+    
+    /// Wait for an order slot, if concurrency is limited
+    async fn order_permit(&self) -> Result<Option<OwnedSemaphorePermit>, Box<dyn std::error::Error + Send + Sync>> {
+        let (permits, limit, max_wait) = match self.order_slots.read().await.as_ref() {
+            Some(slots) => (slots.permits.clone(), slots.limit, slots.max_wait),
+            None => return Ok(None),
+        };
+        match tokio::time::timeout(max_wait, permits.acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(Some(permit)),
+            _ => {
+                tracing::warn!(limit, max_wait_ms = max_wait.as_millis() as u64, "order_concurrency_limit_reached");
+                Err(ExecutionError::Overloaded(format!(
+                    "{} orders already in flight, none finished within {}ms", limit, max_wait.as_millis()
+                )).into())
+            }
         }
     }
     
@@ -38,6 +230,25 @@ impl PluginRegistry {
         }
     }
     
+    /// Remove a plugin, returning whether it was registered
+    ///
+    /// If the removed plugin was the default, the remaining plugin with the
+    /// lowest name becomes the default (or the default is cleared if none remain).
+    pub async fn deregister(&self, name: &str) -> bool {
+        let mut plugins = self.plugins.write().await;
+        if plugins.remove(name).is_none() {
+            return false;
+        }
+        self.disabled.write().await.remove(name);
+        
+        let mut default = self.default_plugin.write().await;
+        if default.as_deref() == Some(name) {
+            *default = plugins.keys().min().cloned();
+            tracing::info!(removed = %name, default = ?*default, "Default plugin reassigned");
+        }
+        true
+    }
+    
     /// Set the default plugin
     pub async fn set_default(&self, name: String) -> Result<(), String> {
         let plugins = self.plugins.read().await;
@@ -50,6 +261,46 @@ impl PluginRegistry {
         Ok(())
     }
     
+    /// Enable or disable routing to a registered plugin
+    ///
+    /// A disabled plugin keeps its config and instance (re-registering it under
+    /// the same name leaves it disabled) but rejects orders and market data
+    /// requests and is not health-checked. Returns whether the state changed.
+    pub async fn set_enabled(&self, name: &str, enabled: bool) -> Result<bool, String> {
+        if !self.plugins.read().await.contains_key(name) {
+            return Err(format!("Plugin '{}' not found", name));
+        }
+        
+        let mut disabled = self.disabled.write().await;
+        let changed = if enabled {
+            disabled.remove(name)
+        } else {
+            disabled.insert(name.to_string())
+        };
+        Ok(changed)
+    }
+    
+    /// Whether a plugin is routed to (unknown plugins count as enabled)
+    pub async fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.read().await.contains(name)
+    }
+    
+    /// Resolve a plugin for routing, rejecting missing and disabled plugins
+    async fn route(&self, plugin_name: Option<&str>) -> Result<Arc<dyn ExecutionPlugin>, Box<dyn std::error::Error + Send + Sync>> {
+        let plugin = if let Some(name) = plugin_name {
+            self.get(name).await
+                .ok_or_else(|| ExecutionError::PluginNotFound(name.to_string()))?
+        } else {
+            self.get_default().await
+                .ok_or("No default plugin configured")?
+        };
+        
+        if !self.is_enabled(plugin.name()).await {
+            return Err(ExecutionError::Disabled(format!("Plugin '{}' is disabled", plugin.name())).into());
+        }
+        Ok(plugin)
+    }
+    
     /// Get a plugin by name
     pub async fn get(&self, name: &str) -> Option<Arc<dyn ExecutionPlugin>> {
         let plugins = self.plugins.read().await;
@@ -66,21 +317,139 @@ impl PluginRegistry {
         self.get(&default_name).await
     }
     
+    /// Resolve the named plugin, or the default when no name is given
+    ///
+    /// For lookups and reads: disabled plugins are returned too and nothing
+    /// here checks the halt, so orders must go through [`Self::execute_order`].
+    pub async fn resolve(&self, plugin_name: Option<&str>) -> Option<Arc<dyn ExecutionPlugin>> {
+        match plugin_name {
+            Some(name) => self.get(name).await,
+            None => self.get_default().await,
+        }
+    }
+    
+    /// Pick the enabled plugin quoting the best price for `order`
+    ///
+    /// Buys go to the lowest ask and sells to the highest bid, ignoring quotes
+    /// older than five seconds. Equal prices are resolved by the routing
+    /// priority (unlisted plugins last, then by name). Without any fresh quote
+    /// the top-priority enabled plugin is used, or the default if none is listed.
+    pub async fn route_best(&self, order: &Order) -> Result<Arc<dyn ExecutionPlugin>, Box<dyn std::error::Error + Send + Sync>> {
+        let candidates: Vec<(String, Arc<dyn ExecutionPlugin>)> = {
+            let plugins = self.plugins.read().await;
+            let disabled = self.disabled.read().await;
+            plugins.iter()
+                .filter(|(name, _)| !disabled.contains(*name))
+                .map(|(name, plugin)| (name.clone(), plugin.clone()))
+                .collect()
+        };
+        let priority = self.priority.read().await.clone();
+        let rank = |name: &str| priority.iter().position(|p| p == name).unwrap_or(priority.len());
+        
+        let call_timeout = *self.call_timeout.read().await;
+        let mut quotes = tokio::task::JoinSet::new();
+        for (name, plugin) in candidates.iter().cloned() {
+            let symbol = order.symbol.clone();
+            quotes.spawn(async move {
+                let quote = with_deadline(call_timeout, plugin.name(), plugin.fetch_data(&symbol)).await;
+                (name, plugin, quote)
+            });
+        }
+        
+        let now = now_millis();
+        // (price, lower is better; priority rank; registered name; plugin)
+        let mut ranked: Vec<(f64, usize, String, Arc<dyn ExecutionPlugin>)> = Vec::new();
+        while let Some(joined) = quotes.join_next().await {
+            let Ok((name, plugin, quote)) = joined else { continue };
+            let quote = match quote {
+                Ok(quote) => quote,
+                Err(e) => {
+                    crate::metrics::record_plugin_error(plugin.name(), e.as_ref());
+                    continue;
+                }
+            };
+            let price = match order.side {
+                OrderSide::Buy => quote.ask,
+                OrderSide::Sell => quote.bid,
+            };
+            if price <= 0.0 || now - quote.timestamp > QUOTE_MAX_AGE_MS {
+                tracing::debug!(plugin = %plugin.name(), symbol = %order.symbol, "best_price_quote_unusable");
+                continue;
+            }
+            let score = if order.side == OrderSide::Buy { price } else { -price };
+            ranked.push((score, rank(&name), name, plugin));
+        }
+        
+        let best = ranked.into_iter().min_by(|a, b| {
+            a.0.total_cmp(&b.0)
+                .then(a.1.cmp(&b.1))
+                .then_with(|| a.2.cmp(&b.2))
+        });
+        if let Some((_, _, _, plugin)) = best {
+            return Ok(plugin);
+        }
+        
+        let fallback = priority.iter()
+            .find_map(|name| candidates.iter().find(|(candidate, _)| candidate == name))
+            .map(|(_, plugin)| plugin.clone());
+        tracing::warn!(symbol = %order.symbol, fallback = ?fallback.as_ref().map(|p| p.name().to_string()), "best_price_no_fresh_quotes");
+        match fallback {
+            Some(plugin) => Ok(plugin),
+            None => self.route(None).await,
+        }
+    }
+    
     /// Execute order using specified plugin or default
+    ///
+    /// [`BEST_PRICE_ROUTE`] as the plugin name routes via [`Self::route_best`].
+    /// Every order but a reduce-only one counts against the daily order cap,
+    /// and is refused with an [`OrderCapError`](crate::order_cap::OrderCapError)
+    /// once the routed plugin's cap is reached; closing out is never capped.
     pub async fn execute_order(
         &self,
         order: Order,
         plugin_name: Option<&str>,
     ) -> Result<ExecutionResult, Box<dyn std::error::Error + Send + Sync>> {
-        let plugin = if let Some(name) = plugin_name {
-            self.get(name).await
-                .ok_or_else(|| format!("Plugin '{}' not found", name))?
-        } else {
-            self.get_default().await
-                .ok_or("No default plugin configured")?
+        if self.is_halted() {
+            return Err(ExecutionError::Disabled("Trading is halted".to_string()).into());
+        }
+        let _in_flight = self.in_flight.begin();
+        let plugin = match plugin_name {
+            Some(BEST_PRICE_ROUTE) => self.route_best(&order).await?,
+            _ => self.route(plugin_name).await?,
         };
+        if plugin_name.is_none() {
+            tracing::debug!(plugin = %plugin.name(), symbol = %order.symbol, "order_routed_to_default_plugin");
+            crate::metrics::DEFAULT_ROUTED_TOTAL.with_label_values(&[plugin.name()]).inc();
+        }
+        if !order.reduce_only {
+            let order_cap = self.order_cap.read().await.clone();
+            order_cap.try_count(plugin.name()).inspect_err(|e| {
+                tracing::warn!(plugin = %plugin.name(), symbol = %order.symbol, error = %e, "order_rejected_daily_cap");
+            })?;
+        }
+        
+        let permit = self.order_permit().await?;
+        let result = plugin.execute_order(order.clone()).await
+            .inspect_err(|e| crate::metrics::record_plugin_error(plugin.name(), e.as_ref()));
+        drop(permit);
         
-        plugin.execute_order(order).await
+        let event = match &result {
+            Ok(result) => ExecutionEvent::new(plugin.name(), plugin_name.is_none(), order, result.clone()),
+            Err(e) => ExecutionEvent::failed(plugin.name(), plugin_name.is_none(), order, &e.to_string()),
+        };
+        self.publish(event).await;
+        result
+    }
+    
+    /// Publish in the background so a slow backend never delays the order response
+    async fn publish(&self, event: ExecutionEvent) {
+        let publisher = self.publisher.read().await.clone();
+        tokio::spawn(async move {
+            if let Err(e) = publisher.publish(&event).await {
+                tracing::warn!(plugin = %event.plugin, error = %e, "execution_result_publish_failed");
+            }
+        });
     }
     
     /// Fetch market data using specified plugin or default
@@ -89,15 +458,119 @@ impl PluginRegistry {
         symbol: &str,
         plugin_name: Option<&str>,
     ) -> Result<MarketData, Box<dyn std::error::Error + Send + Sync>> {
-        let plugin = if let Some(name) = plugin_name {
-            self.get(name).await
-                .ok_or_else(|| format!("Plugin '{}' not found", name))?
-        } else {
-            self.get_default().await
-                .ok_or("No default plugin configured")?
+        let plugin = self.route(plugin_name).await?;
+        let call_timeout = *self.call_timeout.read().await;
+        with_deadline(call_timeout, plugin.name(), plugin.fetch_data(symbol)).await
+            .inspect_err(|e| crate::metrics::record_plugin_error(plugin.name(), e.as_ref()))
+    }
+    
+    /// Price `symbol` on every enabled plugin and cross-check the answers
+    ///
+    /// Plugins that fail, miss the call deadline or answer with a quote older
+    /// than five seconds are left out. Quotes further than the outlier threshold
+    /// from the median are flagged, and the freshest remaining one is picked,
+    /// so one venue with a stale or broken feed can't skew order sizing. Fails
+    /// when no venue quotes the symbol, or when every quote is an outlier, with
+    /// an [`ExecutionError`]; when every venue fails, the first venue's error is
+    /// returned so e.g. an unknown symbol still reads as one.
+    pub async fn fetch_data_redundant(&self, symbol: &str) -> Result<RedundantPrice, Box<dyn std::error::Error + Send + Sync>> {
+        let candidates: Vec<Arc<dyn ExecutionPlugin>> = {
+            let plugins = self.plugins.read().await;
+            let disabled = self.disabled.read().await;
+            plugins.iter()
+                .filter(|(name, _)| !disabled.contains(*name))
+                .map(|(_, plugin)| plugin.clone())
+                .collect()
+        };
+        if candidates.is_empty() {
+            return Err(ExecutionError::Disabled(format!("No enabled plugin to price {}", symbol)).into());
+        }
+        
+        let call_timeout = *self.call_timeout.read().await;
+        let mut quotes = tokio::task::JoinSet::new();
+        for plugin in candidates {
+            let symbol = symbol.to_string();
+            quotes.spawn(async move {
+                let quote = with_deadline(call_timeout, plugin.name(), plugin.fetch_data(&symbol)).await;
+                (plugin, quote)
+            });
+        }
+        
+        let now = now_millis();
+        let mut sources = Vec::new();
+        let mut answered = false;
+        let mut first_error = None;
+        while let Some(joined) = quotes.join_next().await {
+            let Ok((plugin, quote)) = joined else { continue };
+            let quote = match quote {
+                Ok(quote) => quote,
+                Err(e) => {
+                    crate::metrics::record_plugin_error(plugin.name(), e.as_ref());
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+            };
+            answered = true;
+            let price = match quote.bid > 0.0 && quote.ask > 0.0 {
+                true => (quote.bid + quote.ask) / 2.0,
+                false => quote.last,
+            };
+            if price <= 0.0 || now - quote.timestamp > QUOTE_MAX_AGE_MS {
+                tracing::debug!(plugin = %plugin.name(), symbol = %symbol, "redundant_quote_unusable");
+                continue;
+            }
+            sources.push(PriceSource { plugin: plugin.name().to_string(), price, timestamp: quote.timestamp, outlier: false });
+        }
+        if sources.is_empty() {
+            return match first_error {
+                Some(e) if !answered => Err(e),
+                _ => Err(ExecutionError::Network(format!("No fresh quote for {} from any plugin", symbol)).into()),
+            };
+        }
+        
+        let mut prices: Vec<f64> = sources.iter().map(|s| s.price).collect();
+        prices.sort_by(f64::total_cmp);
+        let mid = prices.len() / 2;
+        let median = match prices.len() % 2 {
+            0 => (prices[mid - 1] + prices[mid]) / 2.0,
+            _ => prices[mid],
         };
         
-        plugin.fetch_data(symbol).await
+        let outlier_pct = *self.outlier_pct.read().await;
+        sources.sort_by(|a, b| a.plugin.cmp(&b.plugin));
+        for source in &mut sources {
+            source.outlier = (source.price - median).abs() / median * 100.0 > outlier_pct;
+            if source.outlier {
+                tracing::warn!(plugin = %source.plugin, symbol = %symbol, price = source.price, median, "price_outlier");
+            }
+        }
+        
+        let freshest = sources.iter()
+            .filter(|s| !s.outlier)
+            .max_by_key(|s| s.timestamp)
+            .cloned()
+            .ok_or_else(|| ExecutionError::Network(format!("Quotes for {} disagree by more than {}% from their median {}", symbol, outlier_pct, median)))?;
+        Ok(RedundantPrice { symbol: symbol.to_string(), median, freshest, sources })
+    }
+    
+    /// Cancel several orders of `symbol` on the specified plugin or default
+    pub async fn cancel_batch(
+        &self,
+        symbol: &str,
+        orders: &[OrderRef],
+        plugin_name: Option<&str>,
+    ) -> Result<Vec<CancelOutcome>, Box<dyn std::error::Error + Send + Sync>> {
+        let plugin = self.route(plugin_name).await?;
+        let outcomes = plugin.cancel_batch(symbol, orders).await
+            .inspect_err(|e| crate::metrics::record_plugin_error(plugin.name(), e.as_ref()))?;
+        tracing::info!(
+            plugin = %plugin.name(),
+            symbol = %symbol,
+            requested = orders.len(),
+            cancelled = outcomes.iter().filter(|o| o.success).count(),
+            "orders_batch_cancelled"
+        );
+        Ok(outcomes)
     }
     
     /// List all registered plugins
@@ -106,18 +579,69 @@ impl PluginRegistry {
         plugins.keys().cloned().collect()
     }
     
-    /// Health check all plugins
-    pub async fn health_check_all(&self) -> HashMap<String, bool> {
-        let plugins = self.plugins.read().await;
-        let mut results = HashMap::new();
+    /// Detailed health of all plugins (auth failures vs connectivity)
+    ///
+    /// Disabled plugins are reported as [`HealthStatus::Disabled`] without a check.
+    pub async fn health_status_all(&self) -> HashMap<String, HealthStatus> {
+        // Don't hold the lock across exchange round-trips
+        let plugins: Vec<_> = {
+            let plugins = self.plugins.read().await;
+            plugins.iter().map(|(name, plugin)| (name.clone(), plugin.clone())).collect()
+        };
         
-        for (name, plugin) in plugins.iter() {
-            let health = plugin.health_check().await.unwrap_or(false);
-            results.insert(name.clone(), health);
+        let mut results = HashMap::new();
+        for (name, plugin) in plugins {
+            let status = if self.is_enabled(&name).await {
+                plugin.health_status().await
+            } else {
+                HealthStatus::Disabled
+            };
+            results.insert(name, status);
         }
-        
         results
     }
+    
+    /// Current routing settings
+    pub async fn routing_config(&self) -> RoutingConfig {
+        let mut disabled: Vec<String> = self.disabled.read().await.iter().cloned().collect();
+        disabled.sort();
+        RoutingConfig {
+            default_plugin: self.default_plugin.read().await.clone(),
+            priority: self.priority.read().await.clone(),
+            disabled,
+            call_timeout_ms: self.call_timeout.read().await.map(|t| t.as_millis() as u64),
+            max_concurrent_orders: self.order_slots.read().await.as_ref().map(|slots| slots.limit),
+            outlier_pct: *self.outlier_pct.read().await,
+            halted: self.is_halted(),
+        }
+    }
+    
+    /// Maintenance state of all plugins
+    pub async fn maintenance_status_all(&self) -> HashMap<String, bool> {
+        let plugins = self.plugins.read().await;
+        plugins
+            .iter()
+            .map(|(name, plugin)| (name.clone(), plugin.in_maintenance()))
+            .collect()
+    }
+}
+
+/// Await a plugin call, failing with [`ExecutionError::Network`] past `timeout`
+async fn with_deadline<T>(
+    timeout: Option<Duration>,
+    plugin: &str,
+    call: impl Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(limit) = timeout else {
+        return call.await;
+    };
+    match tokio::time::timeout(limit, call).await {
+        Ok(result) => result,
+        Err(_) => {
+            tracing::warn!(plugin = %plugin, timeout_ms = limit.as_millis() as u64, "plugin_call_deadline_exceeded");
+            Err(ExecutionError::Network(format!("{} did not answer within {}ms", plugin, limit.as_millis())).into())
+        }
+    }
 }
 
 impl Default for PluginRegistry {
@@ -130,6 +654,7 @@ impl Default for PluginRegistry {
 mod tests {
     use super::*;
     use crate::plugins::mock::MockPlugin;
+    use crate::plugins::tests::StubPlugin;
     use crate::plugins::{OrderSide, OrderType};
     
     #[tokio::test]
@@ -183,9 +708,8 @@ mod tests {
             order_type: OrderType::Market,
             quantity: 0.1,
             price: Some(67500.0),
-            stop_loss: None,
-            take_profit: None,
             confidence: 0.75,
+            ..Default::default()
         };
         
         // Execute with default plugin
@@ -197,6 +721,104 @@ mod tests {
         assert!(result.success);
     }
     
+    #[tokio::test]
+    async fn test_daily_order_cap_spares_reduce_only_orders() {
+        let registry = PluginRegistry::new();
+        let mut mock_plugin = MockPlugin::new("mock1");
+        mock_plugin.init(serde_json::json!({})).await.unwrap();
+        registry.register("mock1".to_string(), Arc::new(mock_plugin)).await;
+        registry.set_order_cap(DailyOrderCap::new(Some(1), HashMap::new())).await;
+        
+        let order = Order { symbol: "BTC/USDT".to_string(), quantity: 0.1, ..Default::default() };
+        assert!(registry.execute_order(order.clone(), Some("mock1")).await.unwrap().success);
+        let err = registry.execute_order(order.clone(), Some("mock1")).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<crate::order_cap::OrderCapError>().map(|e| e.limit), Some(1));
+        
+        // Closing out still works, and doesn't use up the cap
+        let close = Order { side: OrderSide::Sell, reduce_only: true, ..order };
+        for _ in 0..2 {
+            assert!(registry.execute_order(close.clone(), Some("mock1")).await.unwrap().success);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_default_routing_counted() {
+        let registry = PluginRegistry::new();
+        
+        let mut mock = MockPlugin::new("default-routed-mock");
+        mock.init(serde_json::json!({})).await.unwrap();
+        registry.register("default-routed-mock".to_string(), Arc::new(mock)).await;
+        
+        let order = Order {
+            symbol: "BTC/USDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: 0.1,
+            confidence: 0.75,
+            ..Default::default()
+        };
+        let counter = crate::metrics::DEFAULT_ROUTED_TOTAL.with_label_values(&["default-routed-mock"]);
+        let before = counter.get();
+        
+        registry.execute_order(order.clone(), None).await.unwrap();
+        assert_eq!(counter.get(), before + 1);
+        
+        // Explicitly named routing is not counted
+        registry.execute_order(order, Some("default-routed-mock")).await.unwrap();
+        assert_eq!(counter.get(), before + 1);
+    }
+    
+    /// Forwards every event to a channel
+    struct CapturingPublisher(tokio::sync::mpsc::UnboundedSender<ExecutionEvent>);
+    
+    #[async_trait::async_trait]
+    impl ResultPublisher for CapturingPublisher {
+        async fn publish(&self, event: &ExecutionEvent) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.0.send(event.clone())?;
+            Ok(())
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_execution_results_published() {
+        let registry = PluginRegistry::new();
+        let mut mock = MockPlugin::new("mock1");
+        mock.init(serde_json::json!({})).await.unwrap();
+        registry.register("mock1".to_string(), Arc::new(mock)).await;
+        
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        registry.set_publisher(Arc::new(CapturingPublisher(tx))).await;
+        
+        let order = Order {
+            symbol: "ETH/USDT".to_string(),
+            side: OrderSide::Sell,
+            order_type: OrderType::Market,
+            quantity: 2.0,
+            confidence: 0.75,
+            strategy_tag: Some("mean-revert".to_string()),
+            ..Default::default()
+        };
+        let result = registry.execute_order(order.clone(), None).await.unwrap();
+        
+        let event = tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(event.plugin, "mock1");
+        assert!(event.routed_to_default);
+        assert_eq!(event.order.symbol, "ETH/USDT");
+        assert_eq!(event.order.strategy_tag.as_deref(), Some("mean-revert"));
+        assert_eq!(event.result.order_id, result.order_id);
+        
+        registry.execute_order(order.clone(), Some("mock1")).await.unwrap();
+        let event = tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv()).await.unwrap().unwrap();
+        assert!(!event.routed_to_default);
+        
+        // Orders the plugin fails are published too
+        let quote_sized = Order { quantity_type: crate::plugins::QuantityType::Quote, ..order };
+        let err = registry.execute_order(quote_sized, Some("mock1")).await.unwrap_err();
+        let event = tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv()).await.unwrap().unwrap();
+        assert!(!event.result.success);
+        assert_eq!(event.result.error, Some(err.to_string()));
+    }
+    
     #[tokio::test]
     async fn test_registry_list_plugins() {
         let registry = PluginRegistry::new();
@@ -217,22 +839,236 @@ mod tests {
     }
     
     #[tokio::test]
-    async fn test_registry_health_check_all() {
+    async fn test_registry_deregister() {
+        let registry = PluginRegistry::new();
+        
+        for name in ["mock1", "mock2", "mock3"] {
+            let mut mock_plugin = MockPlugin::new(name);
+            mock_plugin.init(serde_json::json!({})).await.unwrap();
+            registry.register(name.to_string(), Arc::new(mock_plugin)).await;
+        }
+        assert_eq!(registry.get_default().await.unwrap().name(), "mock1");
+        
+        // Removing a non-default plugin leaves the default alone
+        assert!(registry.deregister("mock3").await);
+        assert!(registry.get("mock3").await.is_none());
+        assert_eq!(registry.get_default().await.unwrap().name(), "mock1");
+        
+        // Removing the default promotes a remaining plugin
+        assert!(registry.deregister("mock1").await);
+        assert_eq!(registry.get_default().await.unwrap().name(), "mock2");
+        
+        // Removing the last plugin clears the default
+        assert!(registry.deregister("mock2").await);
+        assert!(registry.get_default().await.is_none());
+        
+        // Removing a missing plugin is a no-op
+        assert!(!registry.deregister("mock1").await);
+    }
+    
+    #[tokio::test]
+    async fn test_registry_health_status_all() {
+        let registry = PluginRegistry::new();
+        
+        let mut mock_plugin = MockPlugin::new("mock1");
+        mock_plugin.init(serde_json::json!({})).await.unwrap();
+        registry.register("mock1".to_string(), Arc::new(mock_plugin)).await;
+        
+        let health = registry.health_status_all().await;
+        assert_eq!(health.get("mock1"), Some(&HealthStatus::Healthy));
+    }
+    
+    #[tokio::test]
+    async fn test_registry_maintenance_status_all() {
         let registry = PluginRegistry::new();
         
         let mut mock1 = MockPlugin::new("mock1");
         mock1.init(serde_json::json!({})).await.unwrap();
+        registry.register("mock1".to_string(), Arc::new(mock1)).await;
         
-        let mut mock2 = MockPlugin::new("mock2");
-        mock2.init(serde_json::json!({})).await.unwrap();
+        let status = registry.maintenance_status_all().await;
+        assert_eq!(status.get("mock1"), Some(&false));
+    }
+    
+    fn routing_order(side: OrderSide) -> Order {
+        Order {
+            symbol: "BTCUSDT".to_string(),
+            side,
+            order_type: OrderType::Market,
+            quantity: 0.1,
+            confidence: 0.75,
+            ..Default::default()
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_route_best_tie_resolved_by_priority() {
+        let registry = PluginRegistry::new();
+        registry.register("alpha".to_string(), StubPlugin::new("alpha").with_quote(67490.0, 67500.0, 0).arc()).await;
+        registry.register("bravo".to_string(), StubPlugin::new("bravo").with_quote(67490.0, 67500.0, 0).arc()).await;
+        registry.register("charlie".to_string(), StubPlugin::new("charlie").with_quote(67480.0, 67510.0, 0).arc()).await;
         
-        registry.register("mock1".to_string(), Arc::new(mock1)).await;
-        registry.register("mock2".to_string(), Arc::new(mock2)).await;
+        // Without a priority, ties fall back to name order
+        let plugin = registry.route_best(&routing_order(OrderSide::Buy)).await.unwrap();
+        assert_eq!(plugin.name(), "alpha");
+        
+        registry.set_priority(parse_priority("bravo, alpha")).await;
+        let plugin = registry.route_best(&routing_order(OrderSide::Buy)).await.unwrap();
+        assert_eq!(plugin.name(), "bravo");
+        let plugin = registry.route_best(&routing_order(OrderSide::Sell)).await.unwrap();
+        assert_eq!(plugin.name(), "bravo");
+        
+        // A strictly better price beats priority
+        registry.register("delta".to_string(), StubPlugin::new("delta").with_quote(67495.0, 67499.0, 0).arc()).await;
+        let plugin = registry.route_best(&routing_order(OrderSide::Buy)).await.unwrap();
+        assert_eq!(plugin.name(), "delta");
+    }
+    
+    #[tokio::test]
+    async fn test_injected_latency_trips_call_deadline() {
+        use crate::plugins::latency::LatencyPlugin;
+        
+        let mut mock = MockPlugin::new("slow");
+        mock.init(serde_json::json!({})).await.unwrap();
+        let slow = LatencyPlugin::new(Arc::new(mock), Duration::from_millis(300), Duration::from_millis(50));
+        let registry = PluginRegistry::new();
+        registry.register("slow".to_string(), Arc::new(slow)).await;
+        
+        // No deadline: the slow call completes
+        assert!(registry.fetch_data("BTC/USDT", Some("slow")).await.is_ok());
+        
+        registry.set_call_timeout(Some(Duration::from_millis(100))).await;
+        let started = std::time::Instant::now();
+        let err = registry.fetch_data("BTC/USDT", Some("slow")).await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_millis(300));
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Network(_))));
+        assert_eq!(err.to_string(), "network: slow did not answer within 100ms");
+        
+        // Best-price routing skips the venue that missed the deadline
+        registry.register("fast".to_string(), StubPlugin::new("fast").with_quote(67490.0, 67510.0, 0).arc()).await;
+        let plugin = registry.route_best(&routing_order(OrderSide::Buy)).await.unwrap();
+        assert_eq!(plugin.name(), "fast");
+    }
+    
+    #[tokio::test]
+    async fn test_orders_past_concurrency_limit_queue_then_fail() {
+        use crate::plugins::latency::LatencyPlugin;
+        
+        let mut mock = MockPlugin::new("slow");
+        mock.init(serde_json::json!({})).await.unwrap();
+        let slow = LatencyPlugin::new(Arc::new(mock), Duration::from_millis(200), Duration::ZERO);
+        let registry = PluginRegistry::new();
+        registry.register("slow".to_string(), Arc::new(slow)).await;
+        registry.set_order_concurrency(Some(1), Duration::from_millis(300)).await;
+        
+        // The second order queues behind the first and still goes through
+        let started = std::time::Instant::now();
+        let (first, second) = tokio::join!(
+            registry.execute_order(routing_order(OrderSide::Buy), None),
+            registry.execute_order(routing_order(OrderSide::Buy), None),
+        );
+        assert!(first.unwrap().success && second.unwrap().success);
+        assert!(started.elapsed() >= Duration::from_millis(400));
+        
+        // A third waiting longer than the queue timeout is refused
+        let (first, second, third) = tokio::join!(
+            registry.execute_order(routing_order(OrderSide::Buy), None),
+            registry.execute_order(routing_order(OrderSide::Buy), None),
+            registry.execute_order(routing_order(OrderSide::Buy), None),
+        );
+        let results = [first, second, third];
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 2);
+        let err = results.into_iter().find_map(Result::err).unwrap();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Overloaded(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_shutdown_drain_waits_for_registry_orders() {
+        use crate::plugins::latency::LatencyPlugin;
+        
+        let mut mock = MockPlugin::new("slow");
+        mock.init(serde_json::json!({})).await.unwrap();
+        let slow = LatencyPlugin::new(Arc::new(mock), Duration::from_millis(200), Duration::ZERO);
+        let registry = Arc::new(PluginRegistry::new());
+        registry.register("slow".to_string(), Arc::new(slow)).await;
+        
+        // Any caller (TWAP, reduce, self-test) is tracked, not just the order endpoints
+        let submitting = registry.clone();
+        let order = tokio::spawn(async move { submitting.execute_order(routing_order(OrderSide::Buy), None).await.unwrap() });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(registry.in_flight().pending(), 1);
+        
+        let report = registry.in_flight().drain(Duration::from_secs(2)).await;
+        assert_eq!((report.drained, report.abandoned), (1, 0));
+        assert!(order.await.unwrap().success);
+    }
+    
+    #[tokio::test]
+    async fn test_route_best_stale_quotes_fall_back_to_priority() {
+        let registry = PluginRegistry::new();
+        registry.register("alpha".to_string(), StubPlugin::new("alpha").with_quote(67490.0, 67500.0, 60_000).arc()).await;
+        registry.register("bravo".to_string(), StubPlugin::new("bravo").with_quote(67000.0, 67010.0, 60_000).arc()).await;
+        registry.register("charlie".to_string(), StubPlugin::new("charlie").with_quote(67490.0, 67500.0, 60_000).arc()).await;
+        registry.set_priority(parse_priority("missing,charlie,alpha")).await;
+        
+        // bravo quotes best, but its quote is stale like everyone else's
+        let plugin = registry.route_best(&routing_order(OrderSide::Buy)).await.unwrap();
+        assert_eq!(plugin.name(), "charlie");
+        
+        // Disabled plugins are skipped in the fallback too
+        registry.set_enabled("charlie", false).await.unwrap();
+        let plugin = registry.route_best(&routing_order(OrderSide::Buy)).await.unwrap();
+        assert_eq!(plugin.name(), "alpha");
+        
+        // No priority configured: the default plugin
+        registry.set_priority(Vec::new()).await;
+        let plugin = registry.route_best(&routing_order(OrderSide::Buy)).await.unwrap();
+        assert_eq!(plugin.name(), "alpha");
+    }
+    
+    #[tokio::test]
+    async fn test_redundant_price_median_flags_outlier() {
+        let registry = PluginRegistry::new();
+        registry.register("alpha".to_string(), StubPlugin::new("alpha").with_quote(67490.0, 67510.0, 2_000).arc()).await;
+        registry.register("bravo".to_string(), StubPlugin::new("bravo").with_quote(69990.0, 70010.0, 0).arc()).await;
+        
+        // Two venues 3.7% apart: the median sits between them and both are too far from it
+        let err = registry.fetch_data_redundant("BTCUSDT").await.unwrap_err();
+        assert!(err.to_string().contains("disagree by more than 1%"), "{}", err);
+        
+        // A looser threshold accepts both, and the newer quote is the freshest
+        registry.set_outlier_threshold(5.0).await;
+        let price = registry.fetch_data_redundant("BTCUSDT").await.unwrap();
+        assert_eq!(price.median, 68750.0);
+        assert_eq!(price.freshest.plugin, "bravo");
+        assert!(price.sources.iter().all(|s| !s.outlier));
+        
+        // A third venue agreeing with alpha moves the median and leaves bravo the outlier
+        registry.set_outlier_threshold(1.0).await;
+        registry.register("charlie".to_string(), StubPlugin::new("charlie").with_quote(67495.0, 67515.0, 1_000).arc()).await;
+        registry.register("stale".to_string(), StubPlugin::new("stale").with_quote(10.0, 11.0, 60_000).arc()).await;
+        let price = registry.fetch_data_redundant("BTCUSDT").await.unwrap();
+        assert_eq!(price.median, 67505.0);
+        assert_eq!(price.freshest.plugin, "charlie");
+        let outliers: Vec<(&str, bool)> = price.sources.iter().map(|s| (s.plugin.as_str(), s.outlier)).collect();
+        assert_eq!(outliers, vec![("alpha", false), ("bravo", true), ("charlie", false)]);
+    }
+    
+    #[tokio::test]
+    async fn test_redundant_price_failures_are_typed() {
+        let registry = PluginRegistry::new();
+        let err = registry.fetch_data_redundant("BTCUSDT").await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Disabled(_))), "{}", err);
         
-        let health = registry.health_check_all().await;
+        // Every venue rejecting the symbol keeps the venue's own error
+        registry.register("alpha".to_string(), StubPlugin::new("alpha").with_quote(67490.0, 67510.0, 0).listing(&["BTCUSDT"]).arc()).await;
+        registry.register("bravo".to_string(), StubPlugin::new("bravo").with_quote(67490.0, 67510.0, 0).listing(&["BTCUSDT"]).arc()).await;
+        let err = registry.fetch_data_redundant("BTCUSTD").await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::InvalidSymbol { .. })), "{}", err);
         
-        assert_eq!(health.len(), 2);
-        assert_eq!(health.get("mock1"), Some(&true));
-        assert_eq!(health.get("mock2"), Some(&true));
+        // A venue that answered, but too late, makes it a network failure
+        registry.register("stale".to_string(), StubPlugin::new("stale").with_quote(67490.0, 67510.0, 60_000).arc()).await;
+        let err = registry.fetch_data_redundant("BTCUSTD").await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ExecutionError>(), Some(ExecutionError::Network(_))), "{}", err);
     }
 }
@@ -0,0 +1,243 @@
+//! Client TLS settings for exchanges requiring mutual TLS
+//!
+//! Institutional endpoints may only accept connections presenting a client
+//! certificate, sometimes issued by a private CA. Plugins flatten
+//! [`ClientTlsConfig`] into their config and rebuild their HTTP client with it
+//! at init, so a bad certificate fails the plugin up front instead of on the
+//! first order.
+
+use reqwest::{Certificate, ClientBuilder, Identity};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// Optional client certificate and extra trusted root for a plugin's HTTP client
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClientTlsConfig {
+    /// PEM client certificate (optionally followed by its chain)
+    #[serde(default)]
+    pub client_cert_path: Option<String>,
+    
+    /// PEM PKCS#8 private key for `client_cert_path`
+    #[serde(default)]
+    pub client_key_path: Option<String>,
+    
+    /// PEM root certificate to trust in addition to the system roots
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+}
+
+fn read_pem(kind: &str, path: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    std::fs::read(path).map_err(|e| format!("Failed to read {} '{}': {}", kind, path, e).into())
+}
+
+impl ClientTlsConfig {
+    /// Whether any TLS setting is present (otherwise the default client is kept)
+    pub fn is_configured(&self) -> bool {
+        self.client_cert_path.is_some() || self.client_key_path.is_some() || self.ca_cert_path.is_some()
+    }
+    
+    /// Load the client identity; the certificate and key must be set together
+    pub fn identity(&self) -> Result<Option<Identity>, Box<dyn Error + Send + Sync>> {
+        match (&self.client_cert_path, &self.client_key_path) {
+            (None, None) => Ok(None),
+            (Some(cert_path), Some(key_path)) => {
+                let cert = read_pem("client certificate", cert_path)?;
+                let key = read_pem("client key", key_path)?;
+                let identity = Identity::from_pkcs8_pem(&cert, &key)
+                    .map_err(|e| format!("Invalid client certificate '{}' or key '{}': {}", cert_path, key_path, e))?;
+                Ok(Some(identity))
+            }
+            _ => Err("client_cert_path and client_key_path must be set together".into()),
+        }
+    }
+    
+    /// Apply the identity and extra root certificate to a client builder
+    pub fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder, Box<dyn Error + Send + Sync>> {
+        if let Some(identity) = self.identity()? {
+            builder = builder.identity(identity);
+        }
+        
+        if let Some(ca_path) = &self.ca_cert_path {
+            let ca = Certificate::from_pem(&read_pem("CA certificate", ca_path)?)
+                .map_err(|e| format!("Invalid CA certificate '{}': {}", ca_path, e))?;
+            builder = builder.add_root_certificate(ca);
+        }
+        
+        Ok(builder)
+    }
+    
+    /// Build a client with these settings, surfacing certificate errors
+    pub fn build_client(&self, builder: ClientBuilder) -> Result<reqwest::Client, Box<dyn Error + Send + Sync>> {
+        self.apply(builder)?
+            .build()
+            .map_err(|e| format!("Failed to build TLS client: {}", e).into())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use openssl::asn1::Asn1Time;
+    use openssl::bn::BigNum;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::{PKey, Private};
+    use openssl::ssl::{SslAcceptor, SslMethod, SslVerifyMode};
+    use openssl::x509::extension::{BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName};
+    use openssl::x509::{X509Builder, X509NameBuilder, X509};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    
+    /// Issue a P-256 certificate, self-signed when `issuer` is `None`
+    fn issue(common_name: &str, serial: u32, issuer: Option<(&X509, &PKey<Private>)>) -> (X509, PKey<Private>) {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", common_name).unwrap();
+        let name = name.build();
+        
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_serial_number(&BigNum::from_u32(serial).unwrap().to_asn1_integer().unwrap()).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(issuer.map_or(&*name, |(cert, _)| cert.subject_name())).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        builder.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+        
+        match issuer {
+            None => {
+                builder.append_extension(BasicConstraints::new().critical().ca().build().unwrap()).unwrap();
+                builder.append_extension(KeyUsage::new().critical().key_cert_sign().crl_sign().build().unwrap()).unwrap();
+            }
+            Some((issuer_cert, _)) => {
+                let san = SubjectAlternativeName::new()
+                    .ip("127.0.0.1")
+                    .dns("localhost")
+                    .build(&builder.x509v3_context(Some(issuer_cert), None))
+                    .unwrap();
+                builder.append_extension(san).unwrap();
+                builder.append_extension(BasicConstraints::new().build().unwrap()).unwrap();
+                builder.append_extension(ExtendedKeyUsage::new().server_auth().client_auth().build().unwrap()).unwrap();
+            }
+        }
+        
+        let signing_key = issuer.map_or(&key, |(_, k)| k);
+        builder.sign(signing_key, MessageDigest::sha256()).unwrap();
+        (builder.build(), key)
+    }
+    
+    /// PEM files for a test CA, plus a client certificate it issued
+    pub(crate) struct TestPki {
+        pub dir: PathBuf,
+        pub ca: X509,
+        ca_key: PKey<Private>,
+    }
+    
+    impl TestPki {
+        pub(crate) fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("fks-tls-{}", crate::plugins::unique_id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            
+            let (ca, ca_key) = issue("fks test ca", 1, None);
+            std::fs::write(dir.join("ca.pem"), ca.to_pem().unwrap()).unwrap();
+            
+            let (client, client_key) = issue("fks test client", 2, Some((&ca, &ca_key)));
+            std::fs::write(dir.join("client.pem"), client.to_pem().unwrap()).unwrap();
+            std::fs::write(dir.join("client.key"), client_key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+            
+            Self { dir, ca, ca_key }
+        }
+        
+        pub(crate) fn path(&self, file: &str) -> String {
+            self.dir.join(file).to_string_lossy().into_owned()
+        }
+        
+        pub(crate) fn config(&self, with_identity: bool) -> ClientTlsConfig {
+            ClientTlsConfig {
+                client_cert_path: with_identity.then(|| self.path("client.pem")),
+                client_key_path: with_identity.then(|| self.path("client.key")),
+                ca_cert_path: Some(self.path("ca.pem")),
+            }
+        }
+        
+        /// HTTPS server on 127.0.0.1 answering `200 ok` only to clients
+        /// presenting a certificate issued by this CA
+        pub(crate) fn serve_mtls(&self) -> String {
+            let (cert, key) = issue("localhost", 3, Some((&self.ca, &self.ca_key)));
+            
+            let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+            acceptor.set_private_key(&key).unwrap();
+            acceptor.set_certificate(&cert).unwrap();
+            acceptor.cert_store_mut().add_cert(self.ca.clone()).unwrap();
+            acceptor.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+            let acceptor = acceptor.build();
+            
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let Ok(mut tls) = acceptor.accept(stream) else { continue };
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match tls.read(&mut buf) {
+                            Ok(0) | Err(_) => break,
+                            Ok(n) => request.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let _ = tls.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+                    let _ = tls.shutdown();
+                }
+            });
+            format!("https://{}", addr)
+        }
+    }
+    
+    impl Drop for TestPki {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_client_identity_presented_to_mtls_server() {
+        let pki = TestPki::new();
+        let url = pki.serve_mtls();
+        
+        let client = pki.config(true).build_client(reqwest::Client::builder()).unwrap();
+        let response = client.get(format!("{}/health", url)).send().await.unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(response.text().await.unwrap(), "ok");
+        
+        // Same trust, no identity: the server refuses the handshake
+        let client = pki.config(false).build_client(reqwest::Client::builder()).unwrap();
+        assert!(client.get(format!("{}/health", url)).send().await.is_err());
+    }
+    
+    #[test]
+    fn test_invalid_identity_rejected() {
+        let pki = TestPki::new();
+        
+        let mut config = pki.config(true);
+        config.client_key_path = None;
+        let err = config.identity().unwrap_err();
+        assert!(err.to_string().contains("must be set together"));
+        
+        let mut config = pki.config(true);
+        config.client_cert_path = Some(pki.path("missing.pem"));
+        let err = config.identity().unwrap_err();
+        assert!(err.to_string().contains("missing.pem"));
+        
+        // A certificate where the key belongs
+        let mut config = pki.config(true);
+        config.client_key_path = Some(pki.path("ca.pem"));
+        assert!(config.build_client(reqwest::Client::builder()).is_err());
+        
+        assert!(!ClientTlsConfig::default().is_configured());
+        assert!(ClientTlsConfig::default().identity().unwrap().is_none());
+    }
+}
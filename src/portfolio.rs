@@ -0,0 +1,194 @@
+//! Consolidated portfolio across every exchange for `GET /api/v1/portfolio`
+//!
+//! Positions and balances are fetched from all enabled plugins concurrently.
+//! A venue that fails (or is in a maintenance window) is reported in `errors`
+//! and left out of the totals rather than failing the whole request; calls a
+//! plugin doesn't support simply contribute nothing.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::plugins::registry::PluginRegistry;
+use crate::plugins::{Balance, ExecutionError, Position};
+
+/// Positions and balances held on one exchange
+#[derive(Debug, Serialize)]
+pub struct ExchangePortfolio {
+    pub positions: Vec<Position>,
+    pub balances: Vec<Balance>,
+    pub unrealized_pnl: f64,
+}
+
+/// Sums across every exchange that answered
+#[derive(Debug, Serialize)]
+pub struct PortfolioTotals {
+    pub unrealized_pnl: f64,
+    pub positions: usize,
+    
+    /// Balances of the same asset added together, in asset order
+    pub balances: Vec<Balance>,
+}
+
+/// All exchanges by registered name, their totals, and the venues that failed
+#[derive(Debug, Serialize)]
+pub struct Portfolio {
+    pub exchanges: BTreeMap<String, ExchangePortfolio>,
+    pub total: PortfolioTotals,
+    pub errors: BTreeMap<String, String>,
+}
+
+/// Query every enabled plugin and combine their positions and balances
+pub async fn collect(registry: &PluginRegistry) -> Portfolio {
+    let mut calls = tokio::task::JoinSet::new();
+    let mut errors = BTreeMap::new();
+    for name in registry.list_plugins().await {
+        let Some(plugin) = registry.get(&name).await else { continue };
+        if !registry.is_enabled(&name).await {
+            continue;
+        }
+        if plugin.in_maintenance() {
+            errors.insert(name, "in an exchange maintenance window".to_string());
+            continue;
+        }
+        calls.spawn(async move {
+            let (positions, balances) = tokio::join!(plugin.get_all_positions(), plugin.get_balances());
+            (name, plugin, positions, balances)
+        });
+    }
+    
+    let mut exchanges = BTreeMap::new();
+    while let Some(joined) = calls.join_next().await {
+        let Ok((name, plugin, positions, balances)) = joined else { continue };
+        let mut failures = Vec::new();
+        let positions = supported(positions).unwrap_or_else(|e| {
+            crate::metrics::record_plugin_error(plugin.name(), e.as_ref());
+            failures.push(format!("positions: {}", e));
+            Vec::new()
+        });
+        let balances = supported(balances).unwrap_or_else(|e| {
+            crate::metrics::record_plugin_error(plugin.name(), e.as_ref());
+            failures.push(format!("balances: {}", e));
+            Vec::new()
+        });
+        
+        if !failures.is_empty() {
+            tracing::warn!(plugin = %name, errors = ?failures, "portfolio_exchange_failed");
+            errors.insert(name, failures.join("; "));
+            continue;
+        }
+        exchanges.insert(name, ExchangePortfolio {
+            unrealized_pnl: positions.iter().map(|p| p.unrealized_pnl).sum(),
+            positions,
+            balances,
+        });
+    }
+    
+    Portfolio {
+        total: totals(&exchanges),
+        exchanges,
+        errors,
+    }
+}
+
+/// Treat an unsupported call as an empty result
+fn supported<T>(result: Result<Vec<T>, Box<dyn Error + Send + Sync>>) -> Result<Vec<T>, Box<dyn Error + Send + Sync>> {
+    match result {
+        Err(e) if matches!(e.downcast_ref::<ExecutionError>(), Some(ExecutionError::Unsupported(_))) => Ok(Vec::new()),
+        other => other,
+    }
+}
+
+fn totals(exchanges: &BTreeMap<String, ExchangePortfolio>) -> PortfolioTotals {
+    let mut balances: BTreeMap<&str, Balance> = BTreeMap::new();
+    for balance in exchanges.values().flat_map(|e| &e.balances) {
+        let sum = balances.entry(&balance.asset).or_insert_with(|| Balance {
+            asset: balance.asset.clone(),
+            free: 0.0,
+            locked: 0.0,
+            total: 0.0,
+        });
+        sum.free += balance.free;
+        sum.locked += balance.locked;
+        sum.total += balance.total;
+    }
+    
+    PortfolioTotals {
+        unrealized_pnl: exchanges.values().map(|e| e.unrealized_pnl).sum(),
+        positions: exchanges.values().map(|e| e.positions.len()).sum(),
+        balances: balances.into_values().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::ccxt::CCXTPlugin;
+    use crate::plugins::mock::MockPlugin;
+    use crate::plugins::tests::StubPlugin;
+    use crate::plugins::{ExecutionPlugin, PositionSide};
+    use std::sync::Arc;
+    
+    fn position(symbol: &str, size: f64, unrealized_pnl: f64) -> Position {
+        Position {
+            symbol: symbol.to_string(),
+            side: PositionSide::Long,
+            size,
+            entry_price: 100.0,
+            mark_price: 101.0,
+            unrealized_pnl,
+            leverage: 1.0,
+        }
+    }
+    
+    async fn register(registry: &PluginRegistry, name: &str, positions: Vec<Position>, usdt: f64, down: bool) {
+        let plugin = StubPlugin::new(name)
+            .with_positions(positions)
+            .with_balances(vec![Balance { asset: "USDT".to_string(), free: usdt, locked: 0.0, total: usdt }]);
+        let plugin = if down { plugin.down() } else { plugin };
+        registry.register(name.to_string(), plugin.arc()).await;
+    }
+    
+    #[tokio::test]
+    async fn test_portfolio_combines_exchanges_and_reports_failures() {
+        let registry = PluginRegistry::new();
+        register(&registry, "alpha", vec![position("BTC/USDT", 0.5, 250.0)], 1000.0, false).await;
+        register(&registry, "beta", vec![position("ETH/USDT", 2.0, -40.0), position("SOL/USDT", 10.0, 15.0)], 500.0, false).await;
+        register(&registry, "gamma", Vec::new(), 0.0, true).await;
+        
+        let portfolio = collect(&registry).await;
+        
+        assert_eq!(portfolio.exchanges.keys().collect::<Vec<_>>(), vec!["alpha", "beta"]);
+        assert_eq!(portfolio.exchanges["alpha"].positions[0].symbol, "BTC/USDT");
+        assert_eq!(portfolio.exchanges["beta"].positions.len(), 2);
+        assert_eq!(portfolio.exchanges["beta"].unrealized_pnl, -25.0);
+        
+        assert_eq!(portfolio.total.positions, 3);
+        assert_eq!(portfolio.total.unrealized_pnl, 225.0);
+        assert_eq!(portfolio.total.balances.len(), 1);
+        assert_eq!(portfolio.total.balances[0].total, 1500.0);
+        
+        assert_eq!(
+            portfolio.errors["gamma"],
+            "positions: network: connection refused; balances: network: connection refused"
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_portfolio_skips_disabled_and_unsupported() {
+        let registry = PluginRegistry::new();
+        let mut mock = MockPlugin::new("mock");
+        mock.init(serde_json::json!({})).await.unwrap();
+        registry.register("mock".to_string(), Arc::new(mock)).await;
+        register(&registry, "off", vec![position("BTC/USDT", 1.0, 5.0)], 100.0, false).await;
+        registry.set_enabled("off", false).await.unwrap();
+        // No position or balance support: listed empty, not as an error
+        registry.register("ccxt".to_string(), Arc::new(CCXTPlugin::new("ccxt"))).await;
+        
+        let portfolio = collect(&registry).await;
+        assert_eq!(portfolio.exchanges.keys().collect::<Vec<_>>(), vec!["ccxt", "mock"]);
+        assert!(portfolio.exchanges["ccxt"].positions.is_empty());
+        assert_eq!(portfolio.exchanges["mock"].positions.len(), 2);
+        assert!(portfolio.errors.is_empty());
+    }
+}
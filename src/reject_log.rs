@@ -0,0 +1,182 @@
+//! Sampled logging of rejected orders
+//!
+//! A strategy firing orders that all fail the same way (below min notional,
+//! insufficient margin) would otherwise write one log line per attempt. The
+//! first rejection of each (exchange, symbol, reason) is logged as usual;
+//! identical ones within the window are only counted and reported as one
+//! summary line when the window closes.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::clock::{self, SharedClock};
+
+/// Default length of a sampling window (seconds)
+const DEFAULT_WINDOW_SECS: i64 = 60;
+
+/// Repeated rejections collapsed into one line
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectSummary {
+    pub exchange: String,
+    pub symbol: String,
+    pub reason: String,
+    /// Rejections not logged individually
+    pub count: u64,
+    pub window_secs: i64,
+}
+
+impl std::fmt::Display for RejectSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} rejections for {} on {} in last {}s", self.count, self.reason, self.symbol, self.window_secs)
+    }
+}
+
+struct Window {
+    started_at: i64,
+    suppressed: u64,
+}
+
+/// Per-(exchange, symbol, reason) rejection counters
+pub struct RejectLog {
+    window_secs: i64,
+    windows: Mutex<HashMap<(String, String, String), Window>>,
+    clock: SharedClock,
+}
+
+impl RejectLog {
+    pub fn new(window_secs: i64) -> Self {
+        Self::with_clock(window_secs, clock::system())
+    }
+    
+    /// Log that reads the current time from `clock`
+    pub fn with_clock(window_secs: i64, clock: SharedClock) -> Self {
+        Self {
+            window_secs,
+            windows: Mutex::new(HashMap::new()),
+            clock,
+        }
+    }
+    
+    /// Build from `REJECT_LOG_WINDOW_SECS` (default: 60; 0 logs every rejection)
+    pub fn from_env() -> Self {
+        let window_secs = std::env::var("REJECT_LOG_WINDOW_SECS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(DEFAULT_WINDOW_SECS);
+        Self::new(window_secs)
+    }
+    
+    /// Count a rejection; true when the caller should log it itself
+    ///
+    /// A rejection arriving after its window closed summarizes the old window
+    /// and opens a new one.
+    pub fn record(&self, exchange: &str, symbol: &str, reason: &str) -> bool {
+        if self.window_secs <= 0 {
+            return true;
+        }
+        let now = self.clock.now_millis();
+        let key = (exchange.to_string(), symbol.to_string(), reason.to_string());
+        
+        let mut windows = self.windows.lock().unwrap();
+        match windows.get_mut(&key) {
+            Some(window) if now - window.started_at < self.window_secs * 1000 => {
+                window.suppressed += 1;
+                false
+            }
+            previous => {
+                if let Some(window) = previous {
+                    self.summarize(&key, window.suppressed);
+                }
+                windows.insert(key, Window { started_at: now, suppressed: 0 });
+                true
+            }
+        }
+    }
+    
+    /// Close every expired window, logging and returning a summary for each one with repeats
+    pub fn flush(&self) -> Vec<RejectSummary> {
+        let now = self.clock.now_millis();
+        let mut summaries = Vec::new();
+        self.windows.lock().unwrap().retain(|key, window| {
+            if now - window.started_at < self.window_secs * 1000 {
+                return true;
+            }
+            summaries.extend(self.summarize(key, window.suppressed));
+            false
+        });
+        summaries
+    }
+    
+    fn summarize(&self, (exchange, symbol, reason): &(String, String, String), suppressed: u64) -> Option<RejectSummary> {
+        if suppressed == 0 {
+            return None;
+        }
+        let summary = RejectSummary {
+            exchange: exchange.clone(),
+            symbol: symbol.clone(),
+            reason: reason.clone(),
+            count: suppressed,
+            window_secs: self.window_secs,
+        };
+        tracing::warn!(exchange = %exchange, symbol = %symbol, count = suppressed, "{}", summary);
+        Some(summary)
+    }
+    
+    /// Flush expired windows once per window in the background
+    pub fn spawn(self: Arc<Self>) -> Option<tokio::task::JoinHandle<()>> {
+        if self.window_secs <= 0 {
+            return None;
+        }
+        let interval = Duration::from_secs(self.window_secs as u64);
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.flush();
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TestClock;
+    
+    #[test]
+    fn test_repeated_rejections_collapse_into_one_summary() {
+        let clock = TestClock::new(1_700_000_000_000);
+        let log = RejectLog::with_clock(60, clock.clone());
+        
+        // Only the first of 128 identical rejections is logged on its own
+        assert!(log.record("bybit", "BTCUSDT", "min-notional"));
+        for _ in 0..127 {
+            assert!(!log.record("bybit", "BTCUSDT", "min-notional"));
+        }
+        // A different reason or symbol has its own window
+        assert!(log.record("bybit", "BTCUSDT", "insufficient balance"));
+        assert!(log.record("bybit", "ETHUSDT", "min-notional"));
+        
+        clock.advance(Duration::from_secs(59));
+        assert!(log.flush().is_empty());
+        
+        clock.advance(Duration::from_secs(1));
+        let summaries = log.flush();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].to_string(), "127 rejections for min-notional on BTCUSDT in last 60s");
+        
+        // The next rejection starts a fresh window and is logged again
+        assert!(log.record("bybit", "BTCUSDT", "min-notional"));
+        assert!(log.flush().is_empty());
+    }
+    
+    #[test]
+    fn test_zero_window_logs_every_rejection() {
+        let log = RejectLog::new(0);
+        assert!(log.record("bybit", "BTCUSDT", "min-notional"));
+        assert!(log.record("bybit", "BTCUSDT", "min-notional"));
+        assert!(log.flush().is_empty());
+    }
+}
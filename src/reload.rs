@@ -0,0 +1,252 @@
+//! Plugin config file with live reload on SIGHUP
+//!
+//! `PLUGINS_CONFIG` points at a JSON file listing plugins to register:
+//!
+//! ```json
+//! { "plugins": [ { "name": "bybit-main", "kind": "bybit", "config": { ... }, "default": true } ] }
+//! ```
+//!
+//! On SIGHUP the file is re-read and diffed against what was last applied.
+//! Changed plugins are rebuilt and swapped into the registry, new ones are
+//! registered and dropped ones deregistered; unchanged plugins are left alone.
+//! Orders already in flight keep the plugin instance they started with.
+//! Plugins built from environment variables at startup are not tracked here
+//! and keep their config until the process restarts.
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::admin::create_plugin;
+use crate::plugins::registry::PluginRegistry;
+
+/// One plugin entry in the config file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PluginSpec {
+    pub name: String,
+    pub kind: String,
+    #[serde(default)]
+    pub config: Value,
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Deserialize)]
+struct PluginFile {
+    plugins: Vec<PluginSpec>,
+}
+
+/// What a reload changed
+#[derive(Debug, Default, PartialEq)]
+pub struct ReloadSummary {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// Plugins whose new config failed to initialize (the previous instance keeps running)
+    pub failed: Vec<String>,
+}
+
+/// Applies the plugin config file to the registry, remembering what it applied
+pub struct ConfigReloader {
+    registry: Arc<PluginRegistry>,
+    path: PathBuf,
+    applied: HashMap<String, PluginSpec>,
+}
+
+impl ConfigReloader {
+    pub fn new(registry: Arc<PluginRegistry>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            registry,
+            path: path.into(),
+            applied: HashMap::new(),
+        }
+    }
+    
+    /// Build from `PLUGINS_CONFIG`, if set
+    pub fn from_env(registry: Arc<PluginRegistry>) -> Option<Self> {
+        std::env::var("PLUGINS_CONFIG")
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(|path| Self::new(registry, path))
+    }
+    
+    fn load(&self) -> Result<Vec<PluginSpec>, Box<dyn Error + Send + Sync>> {
+        let text = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read plugin config '{}': {}", self.path.display(), e))?;
+        let file: PluginFile = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid plugin config '{}': {}", self.path.display(), e))?;
+        Ok(file.plugins)
+    }
+    
+    /// Re-read the file and apply the differences
+    ///
+    /// A file that can't be read or parsed leaves every plugin untouched.
+    pub async fn reload(&mut self) -> Result<ReloadSummary, Box<dyn Error + Send + Sync>> {
+        let specs = self.load()?;
+        Ok(self.apply(specs).await)
+    }
+    
+    async fn apply(&mut self, specs: Vec<PluginSpec>) -> ReloadSummary {
+        let mut summary = ReloadSummary::default();
+        let mut seen = HashMap::new();
+        
+        for spec in specs {
+            let previous = self.applied.get(&spec.name);
+            if previous == Some(&spec) {
+                summary.unchanged.push(spec.name.clone());
+                seen.insert(spec.name.clone(), spec);
+                continue;
+            }
+            
+            match create_plugin(&spec.kind, &spec.name, spec.config.clone()).await {
+                Ok(plugin) => {
+                    // Registering under the same name swaps the instance in place
+                    self.registry.register(spec.name.clone(), plugin).await;
+                    if spec.default {
+                        if let Err(e) = self.registry.set_default(spec.name.clone()).await {
+                            tracing::warn!(plugin = %spec.name, error = %e, "reload_set_default_failed");
+                        }
+                    }
+                    if previous.is_some() {
+                        summary.updated.push(spec.name.clone());
+                    } else {
+                        summary.added.push(spec.name.clone());
+                    }
+                    seen.insert(spec.name.clone(), spec);
+                }
+                Err(e) => {
+                    tracing::warn!(plugin = %spec.name, kind = %spec.kind, error = %e, "reload_plugin_init_failed");
+                    summary.failed.push(spec.name.clone());
+                    // Keep tracking the old config so the next reload retries
+                    if let Some(previous) = previous {
+                        seen.insert(spec.name.clone(), previous.clone());
+                    }
+                }
+            }
+        }
+        
+        for name in self.applied.keys() {
+            if !seen.contains_key(name) && !summary.failed.contains(name) {
+                self.registry.deregister(name).await;
+                summary.removed.push(name.clone());
+            }
+        }
+        
+        self.applied = seen;
+        summary.removed.sort();
+        
+        tracing::info!(
+            added = ?summary.added,
+            updated = ?summary.updated,
+            removed = ?summary.removed,
+            unchanged = ?summary.unchanged,
+            failed = ?summary.failed,
+            "plugin_config_applied"
+        );
+        summary
+    }
+    
+    /// Reload on every SIGHUP for the life of the process
+    #[cfg(unix)]
+    pub fn spawn_sighup(mut self) -> std::io::Result<tokio::task::JoinHandle<()>> {
+        use tokio::signal::unix::{signal, SignalKind};
+        
+        let mut hangups = signal(SignalKind::hangup())?;
+        Ok(tokio::spawn(async move {
+            while hangups.recv().await.is_some() {
+                tracing::info!(path = %self.path.display(), "sighup_received_reloading_plugins");
+                if let Err(e) = self.reload().await {
+                    tracing::error!(error = %e, "plugin_config_reload_failed");
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    struct TempConfig(PathBuf);
+    
+    impl TempConfig {
+        fn new() -> Self {
+            Self(std::env::temp_dir().join(format!("fks-plugins-{}.json", crate::plugins::unique_id())))
+        }
+        
+        fn write(&self, plugins: Value) {
+            std::fs::write(&self.0, serde_json::json!({ "plugins": plugins }).to_string()).unwrap();
+        }
+    }
+    
+    impl Drop for TempConfig {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_reload_swaps_only_changed_plugins() {
+        let registry = Arc::new(PluginRegistry::new());
+        let file = TempConfig::new();
+        let mut reloader = ConfigReloader::new(registry.clone(), &file.0);
+        
+        file.write(serde_json::json!([
+            { "name": "mock-a", "kind": "mock", "config": { "version": 1 } },
+            { "name": "mock-b", "kind": "mock", "default": true }
+        ]));
+        let summary = reloader.reload().await.unwrap();
+        assert_eq!(summary.added, vec!["mock-a", "mock-b"]);
+        assert_eq!(registry.get_default().await.unwrap().name(), "mock-b");
+        
+        let a_before = registry.get("mock-a").await.unwrap();
+        let b_before = registry.get("mock-b").await.unwrap();
+        
+        // SIGHUP after editing only mock-a's config
+        file.write(serde_json::json!([
+            { "name": "mock-a", "kind": "mock", "config": { "version": 2 } },
+            { "name": "mock-b", "kind": "mock", "default": true }
+        ]));
+        let summary = reloader.reload().await.unwrap();
+        assert_eq!(summary.updated, vec!["mock-a"]);
+        assert_eq!(summary.unchanged, vec!["mock-b"]);
+        
+        assert!(!Arc::ptr_eq(&a_before, &registry.get("mock-a").await.unwrap()));
+        assert!(Arc::ptr_eq(&b_before, &registry.get("mock-b").await.unwrap()));
+        
+        // Dropping a plugin from the file deregisters it
+        file.write(serde_json::json!([
+            { "name": "mock-b", "kind": "mock", "default": true }
+        ]));
+        let summary = reloader.reload().await.unwrap();
+        assert_eq!(summary.removed, vec!["mock-a"]);
+        assert!(registry.get("mock-a").await.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_failed_reload_keeps_running_plugins() {
+        let registry = Arc::new(PluginRegistry::new());
+        let file = TempConfig::new();
+        let mut reloader = ConfigReloader::new(registry.clone(), &file.0);
+        
+        file.write(serde_json::json!([{ "name": "mock-a", "kind": "mock" }]));
+        reloader.reload().await.unwrap();
+        let before = registry.get("mock-a").await.unwrap();
+        
+        // New config fails to initialize: the old instance stays registered
+        file.write(serde_json::json!([{ "name": "mock-a", "kind": "nope" }]));
+        let summary = reloader.reload().await.unwrap();
+        assert_eq!(summary.failed, vec!["mock-a"]);
+        assert!(summary.removed.is_empty());
+        assert!(Arc::ptr_eq(&before, &registry.get("mock-a").await.unwrap()));
+        
+        // Unparseable file: nothing changes
+        std::fs::write(&file.0, "{ not json").unwrap();
+        assert!(reloader.reload().await.is_err());
+        assert!(registry.get("mock-a").await.is_some());
+    }
+}
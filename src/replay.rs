@@ -0,0 +1,167 @@
+//! Replay protection for the inbound TradingView webhook
+//!
+//! Rejects payloads whose timestamp falls outside the accepted window and
+//! payloads whose (timestamp, symbol, nonce) has already been seen.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::clock::{self, SharedClock};
+
+/// Default accepted clock distance between sender and service (seconds)
+const DEFAULT_WINDOW_SECS: i64 = 300;
+
+/// Why a webhook payload was rejected
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ReplayError {
+    #[error("timestamp {timestamp}ms is outside the accepted window of {window_secs}s")]
+    Stale { timestamp: i64, window_secs: i64 },
+    
+    #[error("payload has already been processed")]
+    Replayed,
+}
+
+/// Recently seen webhook nonces, kept until they age out of the window
+pub struct NonceStore {
+    window_secs: i64,
+    seen: Mutex<HashMap<String, i64>>,
+    clock: SharedClock,
+}
+
+impl NonceStore {
+    pub fn new(window_secs: i64) -> Self {
+        Self::with_clock(window_secs, clock::system())
+    }
+    
+    /// Store that reads the current time from `clock`
+    pub fn with_clock(window_secs: i64, clock: SharedClock) -> Self {
+        Self {
+            window_secs,
+            seen: Mutex::new(HashMap::new()),
+            clock,
+        }
+    }
+    
+    /// Build from `WEBHOOK_REPLAY_WINDOW_SECS` (default: 300)
+    pub fn from_env() -> Self {
+        let window_secs = std::env::var("WEBHOOK_REPLAY_WINDOW_SECS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(DEFAULT_WINDOW_SECS);
+        Self::new(window_secs)
+    }
+    
+    /// Check and record a payload against the store's clock
+    pub fn check(&self, timestamp: i64, symbol: &str, nonce: Option<&str>) -> Result<(), ReplayError> {
+        self.check_at(timestamp, symbol, nonce, self.clock.now_millis())
+    }
+    
+    /// Check and record a payload at `now` (timestamps in Unix millis)
+    pub fn check_at(
+        &self,
+        timestamp: i64,
+        symbol: &str,
+        nonce: Option<&str>,
+        now: i64,
+    ) -> Result<(), ReplayError> {
+        let window_ms = self.window_secs * 1000;
+        if (now - timestamp).abs() > window_ms {
+            return Err(ReplayError::Stale { timestamp, window_secs: self.window_secs });
+        }
+        
+        let mut seen = self.seen.lock().unwrap();
+        
+        // Anything older than the window would be rejected as stale anyway
+        let cutoff = now - window_ms;
+        seen.retain(|_, ts| *ts >= cutoff);
+        
+        let key = format!("{}|{}|{}", timestamp, symbol, nonce.unwrap_or(""));
+        if seen.contains_key(&key) {
+            return Err(ReplayError::Replayed);
+        }
+        seen.insert(key, timestamp);
+        Ok(())
+    }
+    
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.seen.lock().unwrap().len()
+    }
+}
+
+impl Default for NonceStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW_SECS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, TestClock};
+    use std::time::Duration;
+    
+    #[test]
+    fn test_stale_timestamp_rejected() {
+        let store = NonceStore::new(300);
+        let now = 1_700_000_000_000;
+        
+        let result = store.check_at(now - 301_000, "BTC/USDT", Some("a"), now);
+        assert!(matches!(result, Err(ReplayError::Stale { .. })));
+        
+        // Too far in the future is rejected as well
+        let result = store.check_at(now + 301_000, "BTC/USDT", Some("a"), now);
+        assert!(matches!(result, Err(ReplayError::Stale { .. })));
+        
+        assert!(store.check_at(now - 299_000, "BTC/USDT", Some("a"), now).is_ok());
+        
+        // A seconds-resolution timestamp is far outside the window
+        assert!(matches!(store.check_at(now / 1000, "BTC/USDT", Some("b"), now), Err(ReplayError::Stale { .. })));
+    }
+    
+    #[test]
+    fn test_replay_rejected() {
+        let store = NonceStore::new(300);
+        let now = 1_700_000_000_000;
+        
+        assert!(store.check_at(now, "BTC/USDT", Some("n1"), now).is_ok());
+        assert_eq!(store.check_at(now, "BTC/USDT", Some("n1"), now + 1000), Err(ReplayError::Replayed));
+        
+        // A different nonce or symbol is a distinct payload
+        assert!(store.check_at(now, "BTC/USDT", Some("n2"), now).is_ok());
+        assert!(store.check_at(now, "ETH/USDT", Some("n1"), now).is_ok());
+    }
+    
+    #[test]
+    fn test_expired_nonces_evicted() {
+        let store = NonceStore::new(60);
+        let now = 1_700_000_000_000;
+        
+        store.check_at(now, "BTC/USDT", None, now).unwrap();
+        assert_eq!(store.len(), 1);
+        
+        store.check_at(now + 120_000, "BTC/USDT", None, now + 120_000).unwrap();
+        assert_eq!(store.len(), 1);
+    }
+    
+    #[test]
+    fn test_clock_driven_eviction() {
+        let clock = TestClock::new(1_700_000_000_000);
+        let store = NonceStore::with_clock(60, clock.clone());
+        let sent = clock.now_millis();
+        
+        store.check(sent, "BTC/USDT", Some("n1")).unwrap();
+        store.check(sent, "ETH/USDT", Some("n1")).unwrap();
+        assert_eq!(store.len(), 2);
+        
+        // Still inside the window: remembered and rejected as a replay
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(store.check(sent, "BTC/USDT", Some("n1")), Err(ReplayError::Replayed));
+        
+        // Past the window both entries are evicted on the next check
+        clock.advance(Duration::from_secs(2));
+        store.check(clock.now_millis(), "SOL/USDT", None).unwrap();
+        assert_eq!(store.len(), 1);
+        assert!(matches!(store.check(sent, "BTC/USDT", Some("n1")), Err(ReplayError::Stale { .. })));
+    }
+}
@@ -0,0 +1,155 @@
+//! Access logging for audit and debugging
+//!
+//! Every request is logged once it has been answered, with its method, path,
+//! status, latency and headers, at `REQUEST_LOG_LEVEL` (default: info; `off`
+//! disables it). Header values that carry credentials (authorization, API
+//! keys, signatures, admin tokens) are replaced with `[REDACTED]`. Query
+//! strings and bodies are never logged, since webhook payloads may embed
+//! their shared secret; only the declared body length is.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+use tracing::Level;
+
+/// Replacement for a sensitive header value
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Headers that are always sensitive
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie"];
+
+/// Header name fragments marking a credential (e.g. `x-api-key`, `x-bapi-sign`, `x-admin-token`)
+const SENSITIVE_FRAGMENTS: &[&str] = &["key", "secret", "token", "sign", "passphrase", "password"];
+
+/// Whether a header's value must not be logged
+pub fn is_sensitive(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_HEADERS.contains(&name.as_str()) || SENSITIVE_FRAGMENTS.iter().any(|f| name.contains(f))
+}
+
+/// `name: value` pairs with sensitive values redacted
+pub fn redacted_headers(headers: &HeaderMap) -> String {
+    headers.iter()
+        .map(|(name, value)| {
+            let value = match is_sensitive(name.as_str()) {
+                true => REDACTED,
+                false => value.to_str().unwrap_or("<binary>"),
+            };
+            format!("{}: {}", name, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Level requests are logged at, `None` when request logging is off
+#[derive(Debug, Clone, Copy)]
+pub struct RequestLog {
+    level: Option<Level>,
+}
+
+impl RequestLog {
+    pub fn new(level: Option<Level>) -> Self {
+        Self { level }
+    }
+    
+    /// Build from `REQUEST_LOG_LEVEL`: trace, debug, info (default), warn, error or off
+    pub fn from_env() -> Self {
+        let level = match std::env::var("REQUEST_LOG_LEVEL") {
+            Ok(value) if value.trim().eq_ignore_ascii_case("off") => None,
+            Ok(value) => Some(value.trim().parse().unwrap_or_else(|_| {
+                tracing::warn!(value = %value, "request_log_level_invalid_using_info");
+                Level::INFO
+            })),
+            Err(_) => Some(Level::INFO),
+        };
+        Self::new(level)
+    }
+}
+
+/// Middleware logging each request after the response is produced
+pub async fn middleware(State(log): State<RequestLog>, request: Request, next: Next) -> Response {
+    let Some(level) = log.level else {
+        return next.run(request).await;
+    };
+    
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let headers = redacted_headers(request.headers());
+    let body_bytes = request.headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let status = response.status().as_u16();
+    
+    // Event levels must be constants, hence one call per level
+    macro_rules! log_at {
+        ($event:ident) => {
+            tracing::$event!(method = %method, path = %path, status, latency_ms, body_bytes, headers = %headers, "http_request")
+        };
+    }
+    match level {
+        Level::ERROR => log_at!(error),
+        Level::WARN => log_at!(warn),
+        Level::INFO => log_at!(info),
+        Level::DEBUG => log_at!(debug),
+        Level::TRACE => log_at!(trace),
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::post, Router};
+    use tower::ServiceExt;
+    
+    fn app(log: RequestLog) -> Router {
+        Router::new()
+            .route("/webhook/tradingview", post(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(log, middleware))
+    }
+    
+    #[tokio::test]
+    async fn test_sensitive_headers_and_body_redacted() {
+        let logs = crate::plugins::tests::LogCapture::default();
+        let _guard = logs.install();
+        
+        let request = Request::post("/webhook/tradingview?token=query-secret")
+            .header(header::AUTHORIZATION, "Bearer admin-s3cret")
+            .header("X-BAPI-SIGN", "deadbeef")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"symbol":"BTCUSDT","secret":"body-s3cret"}"#))
+            .unwrap();
+        let response = app(RequestLog::new(Some(Level::INFO))).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), 200);
+        
+        let output = logs.contents();
+        assert!(output.contains("http_request"));
+        assert!(output.contains("method=POST path=/webhook/tradingview status=200"));
+        assert!(output.contains("authorization: [REDACTED]"));
+        assert!(output.contains("x-bapi-sign: [REDACTED]"));
+        assert!(output.contains("content-type: application/json"));
+        for secret in ["admin-s3cret", "deadbeef", "query-secret", "body-s3cret"] {
+            assert!(!output.contains(secret), "{} leaked into {}", secret, output);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_off_logs_nothing() {
+        let logs = crate::plugins::tests::LogCapture::default();
+        let _guard = logs.install();
+        
+        let request = Request::post("/webhook/tradingview").body(Body::empty()).unwrap();
+        app(RequestLog::new(None)).oneshot(request).await.unwrap();
+        assert!(!logs.contents().contains("http_request"));
+    }
+}
@@ -0,0 +1,47 @@
+//! Credentials from files as well as the environment
+//!
+//! Secrets in env vars show up in process listings and `docker inspect`.
+//! Each credential `NAME` can instead be given as `NAME_FILE`, a path to a
+//! file holding it, which is how Docker and Kubernetes mount secrets.
+
+/// Read `NAME_FILE`'s file if set, otherwise `NAME`
+///
+/// Trailing whitespace (the newline most secret files end with) is dropped.
+/// An unreadable file is logged and treated as no secret rather than
+/// falling back to `NAME`, so a broken mount isn't silently ignored.
+pub fn read_secret(name: &str) -> Option<String> {
+    let file_var = format!("{}_FILE", name);
+    match std::env::var(&file_var) {
+        Ok(path) => match std::fs::read_to_string(&path) {
+            Ok(secret) => Some(secret.trim_end().to_string()),
+            Err(e) => {
+                tracing::error!(var = %file_var, path = %path, error = %e, "secret_file_unreadable");
+                None
+            }
+        },
+        Err(_) => std::env::var(name).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_file_takes_precedence_over_env() {
+        let path = std::env::temp_dir().join(format!("fks-secret-{}", std::process::id()));
+        std::fs::write(&path, "from-file\n").unwrap();
+        std::env::set_var("FKS_TEST_SECRET", "from-env");
+        assert_eq!(read_secret("FKS_TEST_SECRET").as_deref(), Some("from-env"));
+        
+        std::env::set_var("FKS_TEST_SECRET_FILE", &path);
+        assert_eq!(read_secret("FKS_TEST_SECRET").as_deref(), Some("from-file"));
+        
+        // A missing file is an error, not a fallback to the env var
+        std::env::set_var("FKS_TEST_SECRET_FILE", path.with_extension("missing"));
+        assert_eq!(read_secret("FKS_TEST_SECRET"), None);
+        
+        assert_eq!(read_secret("FKS_TEST_SECRET_UNSET"), None);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+//! Combine several signals for one symbol into a single decision
+//!
+//! Each signal is a directional action with a confidence in `[0, 1]`. The
+//! scheme turns buy and sell support into a net score in `[-1, 1]`; a net
+//! score inside the hold band resolves to [`Action::Hold`]. Hold signals are
+//! abstentions: they never push the decision either way, though under
+//! [`Scheme::Mean`] they dilute it.
+
+use serde::{Deserialize, Serialize};
+
+/// Net score magnitude below which the decision is hold
+pub const DEFAULT_HOLD_THRESHOLD: f64 = 0.1;
+
+/// Direction of a signal or decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", try_from = "String")]
+pub enum Action {
+    Buy,
+    Sell,
+    Hold,
+}
+
+impl TryFrom<String> for Action {
+    type Error = String;
+    
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.trim().to_lowercase().as_str() {
+            "buy" => Ok(Action::Buy),
+            "sell" => Ok(Action::Sell),
+            "hold" => Ok(Action::Hold),
+            other => Err(format!("expected \"buy\", \"sell\" or \"hold\", got \"{}\"", other)),
+        }
+    }
+}
+
+/// One agent's view
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct SignalInput {
+    pub action: Action,
+    pub confidence: f64,
+}
+
+/// How signals are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scheme {
+    /// Agreeing signals compound (`1 - Π(1 - c)` per side), opposing sides cancel
+    #[default]
+    WeightedVote,
+    /// Strongest buy against strongest sell
+    Max,
+    /// Average signed confidence over all signals, holds counting as zero
+    Mean,
+}
+
+/// Aggregated decision
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Decision {
+    pub action: Action,
+    
+    /// Conviction behind `action`: `|net|` when directional, `1 - |net|` for hold
+    pub confidence: f64,
+    
+    /// Signed score, positive for buy
+    pub net: f64,
+    
+    pub scheme: Scheme,
+    pub signals: usize,
+}
+
+/// Reject empty input and confidences outside `[0, 1]`
+pub fn validate(signals: &[SignalInput]) -> Result<(), String> {
+    if signals.is_empty() {
+        return Err("signals: at least one signal is required".to_string());
+    }
+    for (i, signal) in signals.iter().enumerate() {
+        if !(0.0..=1.0).contains(&signal.confidence) {
+            return Err(format!("signals[{}].confidence: expected a value in [0, 1], got {}", i, signal.confidence));
+        }
+    }
+    Ok(())
+}
+
+fn confidences(signals: &[SignalInput], action: Action) -> impl Iterator<Item = f64> + '_ {
+    signals.iter().filter(move |s| s.action == action).map(|s| s.confidence)
+}
+
+/// Probability that at least one of independent signals is right
+fn compound(confidences: impl Iterator<Item = f64>) -> f64 {
+    1.0 - confidences.map(|c| 1.0 - c).product::<f64>()
+}
+
+/// Signed score in `[-1, 1]` for `signals` under `scheme`
+pub fn net_score(signals: &[SignalInput], scheme: Scheme) -> f64 {
+    match scheme {
+        Scheme::WeightedVote => compound(confidences(signals, Action::Buy)) - compound(confidences(signals, Action::Sell)),
+        Scheme::Max => {
+            let strongest = |action| confidences(signals, action).fold(0.0, f64::max);
+            strongest(Action::Buy) - strongest(Action::Sell)
+        }
+        Scheme::Mean if signals.is_empty() => 0.0,
+        Scheme::Mean => {
+            let sum: f64 = confidences(signals, Action::Buy).sum::<f64>() - confidences(signals, Action::Sell).sum::<f64>();
+            sum / signals.len() as f64
+        }
+    }
+}
+
+/// Combine `signals` into one decision; `|net| < hold_threshold` resolves to hold
+pub fn aggregate(signals: &[SignalInput], scheme: Scheme, hold_threshold: f64) -> Decision {
+    let net = net_score(signals, scheme);
+    let (action, confidence) = if net.abs() < hold_threshold {
+        (Action::Hold, 1.0 - net.abs())
+    } else if net > 0.0 {
+        (Action::Buy, net)
+    } else {
+        (Action::Sell, -net)
+    };
+    
+    Decision {
+        action,
+        confidence,
+        net,
+        scheme,
+        signals: signals.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn signal(action: Action, confidence: f64) -> SignalInput {
+        SignalInput { action, confidence }
+    }
+    
+    #[test]
+    fn test_conflicting_signals_resolve_to_hold() {
+        let signals = [signal(Action::Buy, 0.8), signal(Action::Sell, 0.8)];
+        for scheme in [Scheme::WeightedVote, Scheme::Max, Scheme::Mean] {
+            let decision = aggregate(&signals, scheme, DEFAULT_HOLD_THRESHOLD);
+            assert_eq!(decision.action, Action::Hold, "{:?}", scheme);
+            assert!(decision.net.abs() < 1e-9);
+        }
+        
+        // Nearly balanced is still a hold
+        let signals = [signal(Action::Buy, 0.7), signal(Action::Sell, 0.65)];
+        assert_eq!(aggregate(&signals, Scheme::WeightedVote, DEFAULT_HOLD_THRESHOLD).action, Action::Hold);
+    }
+    
+    #[test]
+    fn test_agreeing_signals_compound_confidence() {
+        let signals = [signal(Action::Buy, 0.6), signal(Action::Buy, 0.6), signal(Action::Hold, 0.9)];
+        
+        let decision = aggregate(&signals, Scheme::WeightedVote, DEFAULT_HOLD_THRESHOLD);
+        assert_eq!(decision.action, Action::Buy);
+        assert!((decision.confidence - 0.84).abs() < 1e-9);
+        
+        // Max doesn't compound; mean is diluted by the hold
+        assert!((aggregate(&signals, Scheme::Max, DEFAULT_HOLD_THRESHOLD).confidence - 0.6).abs() < 1e-9);
+        assert!((aggregate(&signals, Scheme::Mean, DEFAULT_HOLD_THRESHOLD).confidence - 0.4).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_stronger_side_wins() {
+        let signals = [signal(Action::Sell, 0.9), signal(Action::Buy, 0.3)];
+        let decision = aggregate(&signals, Scheme::WeightedVote, DEFAULT_HOLD_THRESHOLD);
+        assert_eq!(decision.action, Action::Sell);
+        assert!((decision.confidence - 0.6).abs() < 1e-9);
+        assert!(decision.net < 0.0);
+    }
+    
+    #[test]
+    fn test_validate_and_parse() {
+        assert!(validate(&[]).is_err());
+        let err = validate(&[signal(Action::Buy, 0.5), signal(Action::Sell, 1.5)]).unwrap_err();
+        assert!(err.starts_with("signals[1].confidence"));
+        
+        let parsed: SignalInput = serde_json::from_str(r#"{"action": "SELL", "confidence": 0.4}"#).unwrap();
+        assert_eq!(parsed.action, Action::Sell);
+        assert!(serde_json::from_str::<SignalInput>(r#"{"action": "short", "confidence": 0.4}"#).is_err());
+    }
+}
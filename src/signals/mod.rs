@@ -0,0 +1,3 @@
+//! Trading signal processing
+
+pub mod aggregate;
@@ -0,0 +1,100 @@
+//! Cached per-plugin symbol lists for `GET /api/v1/symbols`
+//!
+//! Instrument lists change rarely and the exchange endpoints are heavy, so each
+//! plugin's list is kept for `SYMBOLS_CACHE_SECS` (default one hour). Failed
+//! lookups are not cached.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::clock::{self, SharedClock};
+use crate::plugins::ExecutionPlugin;
+
+/// Default time a symbol list is served from cache
+const DEFAULT_TTL: Duration = Duration::from_secs(3600);
+
+struct CachedSymbols {
+    fetched_at: i64,
+    symbols: Arc<Vec<String>>,
+}
+
+/// TTL cache of `ExecutionPlugin::list_symbols`, keyed by plugin name
+pub struct SymbolCache {
+    ttl: Duration,
+    clock: SharedClock,
+    entries: RwLock<HashMap<String, CachedSymbols>>,
+}
+
+impl SymbolCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_clock(ttl, clock::system())
+    }
+    
+    /// Cache that reads the current time from `clock`
+    pub fn with_clock(ttl: Duration, clock: SharedClock) -> Self {
+        Self {
+            ttl,
+            clock,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Build from `SYMBOLS_CACHE_SECS`
+    pub fn from_env() -> Self {
+        let ttl = std::env::var("SYMBOLS_CACHE_SECS").ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .map_or(DEFAULT_TTL, Duration::from_secs);
+        Self::new(ttl)
+    }
+    
+    /// The plugin's symbols, fetched when missing or older than the TTL
+    pub async fn get(&self, plugin: &dyn ExecutionPlugin) -> Result<Arc<Vec<String>>, Box<dyn Error + Send + Sync>> {
+        let now = self.clock.now_millis();
+        if let Some(entry) = self.entries.read().await.get(plugin.name()) {
+            if now - entry.fetched_at < self.ttl.as_millis() as i64 {
+                return Ok(entry.symbols.clone());
+            }
+        }
+        
+        let symbols = Arc::new(plugin.list_symbols().await?);
+        tracing::debug!(plugin = %plugin.name(), count = symbols.len(), "symbol_list_refreshed");
+        self.entries.write().await.insert(plugin.name().to_string(), CachedSymbols {
+            fetched_at: now,
+            symbols: symbols.clone(),
+        });
+        Ok(symbols)
+    }
+}
+
+impl Default for SymbolCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TestClock;
+    use crate::plugins::tests::StubPlugin;
+    use std::sync::atomic::Ordering;
+    
+    #[tokio::test]
+    async fn test_symbols_cached_until_ttl() {
+        let clock = TestClock::new(1_700_000_000_000);
+        let cache = SymbolCache::with_clock(Duration::from_secs(60), clock.clone());
+        let plugin = StubPlugin::new("counting");
+        
+        assert_eq!(*cache.get(&plugin).await.unwrap(), vec!["SYM1"]);
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(*cache.get(&plugin).await.unwrap(), vec!["SYM1"]);
+        assert_eq!(plugin.symbol_lookups.load(Ordering::SeqCst), 1);
+        
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(*cache.get(&plugin).await.unwrap(), vec!["SYM2"]);
+        assert_eq!(plugin.symbol_lookups.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,315 @@
+//! TWAP execution: split a large order into equal market slices over time
+//!
+//! A job submits `slices` child market orders of `quantity / slices` through
+//! the plugin registry, the first immediately and the rest every
+//! `duration / slices`. Progress is kept in memory for polling. Cancelling a
+//! job stops the slices not yet submitted; a slice already on its way to the
+//! exchange is left alone. Each slice counts against the daily order cap, and
+//! slices past it fail like any other refused order.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+
+use crate::plugins::registry::PluginRegistry;
+use crate::plugins::{now_millis, unique_id, Order, OrderSide, OrderType};
+
+/// Most slices one job may be split into
+pub const MAX_SLICES: u32 = 1000;
+
+/// What to execute: `quantity` of `symbol`, in `slices` orders spread over `duration`
+#[derive(Debug, Clone)]
+pub struct TwapSpec {
+    pub exchange: Option<String>,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub quantity: f64,
+    pub duration: Duration,
+    pub slices: u32,
+    pub strategy_tag: Option<String>,
+    /// Confidence given to each slice
+    pub confidence: f64,
+}
+
+/// Lifecycle of a TWAP job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TwapStatus {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+/// Snapshot of a job, as returned by the TWAP endpoints
+#[derive(Debug, Clone, Serialize)]
+pub struct TwapProgress {
+    pub id: String,
+    pub exchange: Option<String>,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub total_quantity: f64,
+    pub slices: u32,
+    pub slices_submitted: u32,
+    pub slices_failed: u32,
+    pub filled_quantity: f64,
+    pub order_ids: Vec<String>,
+    pub last_error: Option<String>,
+    pub status: TwapStatus,
+    pub started_at: i64,
+}
+
+struct TwapJob {
+    progress: TwapProgress,
+    /// Wakes the job from its wait between slices when it is cancelled
+    wake: Arc<Notify>,
+}
+
+/// Runs TWAP jobs in the background and keeps their progress
+pub struct TwapScheduler {
+    registry: Arc<PluginRegistry>,
+    jobs: Mutex<HashMap<String, TwapJob>>,
+}
+
+impl TwapScheduler {
+    pub fn new(registry: Arc<PluginRegistry>) -> Self {
+        Self {
+            registry,
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    /// Validate `spec` and start submitting its slices
+    pub async fn start(self: &Arc<Self>, spec: TwapSpec) -> Result<TwapProgress, String> {
+        if !spec.quantity.is_finite() || spec.quantity <= 0.0 {
+            return Err(format!("quantity must be positive, got {}", spec.quantity));
+        }
+        if !(1..=MAX_SLICES).contains(&spec.slices) {
+            return Err(format!("slices must be between 1 and {}, got {}", MAX_SLICES, spec.slices));
+        }
+        
+        let progress = TwapProgress {
+            id: format!("twap-{}", unique_id()),
+            exchange: spec.exchange.clone(),
+            symbol: spec.symbol.clone(),
+            side: spec.side.clone(),
+            total_quantity: spec.quantity,
+            slices: spec.slices,
+            slices_submitted: 0,
+            slices_failed: 0,
+            filled_quantity: 0.0,
+            order_ids: Vec::new(),
+            last_error: None,
+            status: TwapStatus::Running,
+            started_at: now_millis(),
+        };
+        let wake = Arc::new(Notify::new());
+        self.jobs.lock().await.insert(progress.id.clone(), TwapJob {
+            progress: progress.clone(),
+            wake: wake.clone(),
+        });
+        
+        tracing::info!(
+            twap = %progress.id,
+            symbol = %spec.symbol,
+            quantity = spec.quantity,
+            slices = spec.slices,
+            duration_secs = spec.duration.as_secs(),
+            "twap_started"
+        );
+        let scheduler = self.clone();
+        let id = progress.id.clone();
+        tokio::spawn(async move { scheduler.run(&id, spec, wake).await });
+        Ok(progress)
+    }
+    
+    /// Current progress of a job
+    pub async fn progress(&self, id: &str) -> Option<TwapProgress> {
+        self.jobs.lock().await.get(id).map(|job| job.progress.clone())
+    }
+    
+    /// Stop a running job's remaining slices; finished jobs are returned as they are
+    pub async fn cancel(&self, id: &str) -> Option<TwapProgress> {
+        let mut jobs = self.jobs.lock().await;
+        let job = jobs.get_mut(id)?;
+        if job.progress.status == TwapStatus::Running {
+            job.progress.status = TwapStatus::Cancelled;
+            job.wake.notify_one();
+            tracing::info!(twap = %id, submitted = job.progress.slices_submitted, "twap_cancelled");
+        }
+        Some(job.progress.clone())
+    }
+    
+    async fn run(&self, id: &str, spec: TwapSpec, wake: Arc<Notify>) {
+        let interval = spec.duration / spec.slices;
+        let slice = spec.quantity / spec.slices as f64;
+        
+        for i in 0..spec.slices {
+            if i > 0 {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = wake.notified() => {}
+                }
+            }
+            if self.progress(id).await.is_none_or(|p| p.status != TwapStatus::Running) {
+                return;
+            }
+            
+            // The last slice takes the rounding remainder so the slices sum to the total
+            let quantity = match i + 1 == spec.slices {
+                true => spec.quantity - slice * (spec.slices - 1) as f64,
+                false => slice,
+            };
+            let result = self.registry.execute_order(child_order(&spec, quantity), spec.exchange.as_deref()).await;
+            
+            let mut jobs = self.jobs.lock().await;
+            let Some(job) = jobs.get_mut(id) else { return };
+            let progress = &mut job.progress;
+            match result {
+                Ok(result) if result.success => {
+                    progress.slices_submitted += 1;
+                    progress.filled_quantity += result.filled_quantity;
+                    progress.order_ids.extend(result.order_id);
+                }
+                Ok(result) => {
+                    progress.slices_failed += 1;
+                    progress.last_error = result.error;
+                }
+                Err(e) => {
+                    tracing::warn!(twap = %id, slice = i + 1, error = %e, "twap_slice_failed");
+                    progress.slices_failed += 1;
+                    progress.last_error = Some(e.to_string());
+                }
+            }
+        }
+        
+        if let Some(job) = self.jobs.lock().await.get_mut(id) {
+            if job.progress.status == TwapStatus::Running {
+                job.progress.status = TwapStatus::Completed;
+                tracing::info!(twap = %id, submitted = job.progress.slices_submitted, failed = job.progress.slices_failed, "twap_completed");
+            }
+        }
+    }
+}
+
+/// One market slice of a TWAP job
+fn child_order(spec: &TwapSpec, quantity: f64) -> Order {
+    Order {
+        symbol: spec.symbol.clone(),
+        side: spec.side.clone(),
+        order_type: OrderType::Market,
+        quantity,
+        confidence: spec.confidence,
+        strategy_tag: Some(spec.strategy_tag.clone().unwrap_or_else(|| "twap".to_string())),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::mock::MockPlugin;
+    use crate::plugins::ExecutionPlugin;
+    
+    async fn setup() -> (Arc<MockPlugin>, Arc<TwapScheduler>) {
+        let mut mock = MockPlugin::new("mock");
+        mock.init(serde_json::json!({})).await.unwrap();
+        let mock = Arc::new(mock);
+        let registry = Arc::new(PluginRegistry::new());
+        registry.register("mock".to_string(), mock.clone()).await;
+        (mock, Arc::new(TwapScheduler::new(registry)))
+    }
+    
+    fn spec(quantity: f64, duration: Duration, slices: u32) -> TwapSpec {
+        TwapSpec {
+            exchange: Some("mock".to_string()),
+            symbol: "BTC/USDT".to_string(),
+            side: OrderSide::Buy,
+            quantity,
+            duration,
+            slices,
+            strategy_tag: None,
+            confidence: 0.7,
+        }
+    }
+    
+    async fn wait_finished(scheduler: &TwapScheduler, id: &str) -> TwapProgress {
+        for _ in 0..200 {
+            let progress = scheduler.progress(id).await.unwrap();
+            if progress.status != TwapStatus::Running {
+                return progress;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("TWAP job {} still running", id);
+    }
+    
+    #[tokio::test]
+    async fn test_twap_submits_equal_slices() {
+        let (mock, scheduler) = setup().await;
+        
+        let started = scheduler.start(spec(1.0, Duration::from_millis(40), 4)).await.unwrap();
+        assert_eq!(started.status, TwapStatus::Running);
+        
+        let done = wait_finished(&scheduler, &started.id).await;
+        assert_eq!(done.status, TwapStatus::Completed);
+        assert_eq!(done.slices_submitted, 4);
+        assert_eq!(done.slices_failed, 0);
+        assert_eq!(done.order_ids.len(), 4);
+        assert!((done.filled_quantity - 1.0).abs() < 1e-12);
+        
+        for order_id in &done.order_ids {
+            let order = mock.get_order("BTC/USDT", order_id).await.unwrap();
+            assert_eq!(order.quantity, 0.25);
+            assert_eq!(order.order_type, "market");
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_twap_cancel_stops_remaining_slices() {
+        let (_mock, scheduler) = setup().await;
+        
+        let started = scheduler.start(spec(3.0, Duration::from_secs(30), 3)).await.unwrap();
+        // The first slice goes out immediately, the next only after 10s
+        for _ in 0..200 {
+            if scheduler.progress(&started.id).await.unwrap().slices_submitted == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        
+        let cancelled = scheduler.cancel(&started.id).await.unwrap();
+        assert_eq!(cancelled.status, TwapStatus::Cancelled);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let progress = scheduler.progress(&started.id).await.unwrap();
+        assert_eq!(progress.slices_submitted, 1);
+        assert_eq!(progress.status, TwapStatus::Cancelled);
+        
+        assert!(scheduler.cancel("twap-missing").await.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_twap_rejects_invalid_spec() {
+        let (_mock, scheduler) = setup().await;
+        
+        let err = scheduler.start(spec(0.0, Duration::from_secs(60), 4)).await.unwrap_err();
+        assert_eq!(err, "quantity must be positive, got 0");
+        let err = scheduler.start(spec(1.0, Duration::from_secs(60), 0)).await.unwrap_err();
+        assert_eq!(err, "slices must be between 1 and 1000, got 0");
+    }
+    
+    #[tokio::test]
+    async fn test_twap_slices_past_daily_cap_fail() {
+        let (_mock, scheduler) = setup().await;
+        let cap = crate::order_cap::DailyOrderCap::new(None, HashMap::from([("mock".to_string(), 2)]));
+        scheduler.registry.set_order_cap(cap).await;
+        
+        let started = scheduler.start(spec(1.5, Duration::from_millis(30), 3)).await.unwrap();
+        let done = wait_finished(&scheduler, &started.id).await;
+        assert_eq!(done.status, TwapStatus::Completed);
+        assert_eq!(done.slices_submitted, 2);
+        assert_eq!(done.slices_failed, 1);
+        assert_eq!(done.last_error.as_deref(), Some("daily order cap of 2 reached on mock, resets at 00:00 UTC"));
+    }
+}
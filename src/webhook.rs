@@ -0,0 +1,457 @@
+//! TradingView webhook payload parsing and validation
+//!
+//! Alerts are rendered from user-written templates, so payloads are parsed
+//! leniently (numbers may arrive as strings, actions in any case) and every
+//! problem is reported at once with the offending field named.
+//!
+//! Strategies also disagree on action names ("long", "exit", "BUY"), so the
+//! action is looked up in [`ActionAliases`]: `buy` and `sell` are always
+//! accepted, and `WEBHOOK_ACTION_ALIASES` adds names for them and for the
+//! reduce-only close actions.
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::plugins::{Order, OrderSide, OrderType, Position};
+
+/// Unknown fields that are almost certainly a misnamed required field
+const MISNAMED_FIELDS: &[(&str, &str)] = &[
+    ("qty", "quantity"),
+    ("amount", "quantity"),
+    ("size", "quantity"),
+    ("side", "action"),
+    ("ticker", "symbol"),
+    ("type", "order_type"),
+];
+
+/// Number that a TradingView template may render as a JSON string
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Numeric {
+    Number(f64),
+    Text(String),
+}
+
+impl Numeric {
+    pub fn value(&self) -> Option<f64> {
+        match self {
+            Numeric::Number(n) => Some(*n),
+            Numeric::Text(s) => s.trim().parse().ok().filter(|n: &f64| n.is_finite()),
+        }
+    }
+}
+
+/// `deserialize_with` for an optional number also accepted as a numeric
+/// string (`"0.01"`), as clients that avoid float serialization send it
+pub fn lenient_f64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    let Some(numeric) = Option::<Numeric>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    match &numeric {
+        Numeric::Number(n) => Ok(Some(*n)),
+        Numeric::Text(s) => numeric.value()
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom(format!("expected a number or numeric string, got {:?}", s))),
+    }
+}
+
+/// What a webhook action asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookAction {
+    /// Open or add to a position on this side
+    Enter(OrderSide),
+    /// Reduce-only order on this side (`close_long` sells, `close_short` buys)
+    Exit(OrderSide),
+    /// Reduce-only order against whichever position is open
+    Close,
+}
+
+impl WebhookAction {
+    /// Parse an alias target: buy, sell, close, close_long or close_short
+    fn parse(target: &str) -> Option<Self> {
+        match target.trim().to_lowercase().as_str() {
+            "buy" => Some(WebhookAction::Enter(OrderSide::Buy)),
+            "sell" => Some(WebhookAction::Enter(OrderSide::Sell)),
+            "close" => Some(WebhookAction::Close),
+            "close_long" => Some(WebhookAction::Exit(OrderSide::Sell)),
+            "close_short" => Some(WebhookAction::Exit(OrderSide::Buy)),
+            _ => None,
+        }
+    }
+}
+
+/// Action names accepted from alerts, matched case-insensitively
+///
+/// Configured with `WEBHOOK_ACTION_ALIASES`, comma-separated `alias=target`
+/// rules where the target is one of buy, sell, close, close_long or
+/// close_short, e.g. `enter_long=buy,flat=close`. Rules are added to the
+/// defaults (long, short, close, exit, close_long, close_short, exit_long,
+/// exit_short); `buy` and `sell` can't be remapped.
+#[derive(Debug, Clone)]
+pub struct ActionAliases {
+    aliases: HashMap<String, WebhookAction>,
+}
+
+impl Default for ActionAliases {
+    fn default() -> Self {
+        let aliases = [
+            ("buy", "buy"),
+            ("sell", "sell"),
+            ("long", "buy"),
+            ("short", "sell"),
+            ("close", "close"),
+            ("exit", "close"),
+            ("close_long", "close_long"),
+            ("close_short", "close_short"),
+            ("exit_long", "close_long"),
+            ("exit_short", "close_short"),
+        ];
+        Self {
+            aliases: aliases.into_iter()
+                .filter_map(|(alias, target)| Some((alias.to_string(), WebhookAction::parse(target)?)))
+                .collect(),
+        }
+    }
+}
+
+impl ActionAliases {
+    /// Parse a `WEBHOOK_ACTION_ALIASES` spec, skipping (and logging) malformed rules
+    pub fn parse(spec: &str) -> Self {
+        let mut actions = Self::default();
+        for rule in spec.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+            let parsed = rule.split_once('=')
+                .and_then(|(alias, target)| Some((alias.trim().to_lowercase(), WebhookAction::parse(target)?)));
+            match parsed {
+                Some((alias, _)) if alias == "buy" || alias == "sell" => {
+                    tracing::warn!(rule = %rule, "webhook_action_alias_builtin_ignored");
+                }
+                Some((alias, action)) if !alias.is_empty() => {
+                    actions.aliases.insert(alias, action);
+                }
+                _ => tracing::warn!(rule = %rule, "webhook_action_alias_invalid"),
+            }
+        }
+        actions
+    }
+    
+    /// Build from `WEBHOOK_ACTION_ALIASES` (default: the built-in aliases only)
+    pub fn from_env() -> Self {
+        Self::parse(&std::env::var("WEBHOOK_ACTION_ALIASES").unwrap_or_default())
+    }
+    
+    /// Action for an alert's `action` string, `None` if it isn't known
+    pub fn resolve(&self, action: &str) -> Option<WebhookAction> {
+        self.aliases.get(&action.trim().to_lowercase()).cloned()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TradingViewWebhook {
+    #[serde(default)]
+    pub symbol: String,
+    #[serde(default)]
+    pub action: String, // "buy", "sell" or an alias, any case
+    pub order_type: Option<String>, // "market", "limit", "stop", "stop_limit"
+    pub quantity: Option<Numeric>,
+    pub price: Option<Numeric>,
+    pub stop_loss: Option<Numeric>,
+    pub take_profit: Option<Numeric>,
+    pub confidence: Option<Numeric>,
+    pub timestamp: Option<i64>, // Unix millis, required for replay protection
+    pub nonce: Option<String>,
+    pub strategy_tag: Option<String>,
+    
+    /// Fields not part of the schema (checked for misnamed required fields)
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+    
+    /// `action` looked up in the aliases the payload was parsed with
+    #[serde(skip)]
+    resolved: Option<WebhookAction>,
+}
+
+impl TradingViewWebhook {
+    /// Parse and validate a raw request body, resolving its action with `aliases`
+    pub fn parse(body: &[u8], aliases: &ActionAliases) -> Result<Self, Vec<String>> {
+        let mut webhook: Self = serde_json::from_slice(body)
+            .map_err(|e| vec![format!("invalid JSON payload: {}", e)])?;
+        webhook.resolved = aliases.resolve(&webhook.action);
+        
+        let problems = webhook.validate();
+        if problems.is_empty() {
+            Ok(webhook)
+        } else {
+            Err(problems)
+        }
+    }
+    
+    /// Every problem with the payload, each prefixed with the field name
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        
+        if self.symbol.trim().is_empty() {
+            problems.push("symbol: required".to_string());
+        }
+        
+        if self.action.trim().is_empty() {
+            problems.push("action: required".to_string());
+        } else if self.resolved.is_none() {
+            problems.push(format!("action: expected \"buy\", \"sell\" or a configured alias, got \"{}\"", self.action));
+        }
+        
+        if let Some(order_type) = &self.order_type {
+            if self.order_type().is_none() {
+                problems.push(format!(
+                    "order_type: expected one of market, limit, stop, stop_limit, got \"{}\"",
+                    order_type
+                ));
+            }
+        }
+        
+        // A missing quantity may still be filled in from DEFAULT_QUANTITY
+        if let Some(q) = &self.quantity {
+            match q.value() {
+                None => problems.push(format!("quantity: expected a number, got {:?}", q)),
+                Some(v) if v <= 0.0 => problems.push(format!("quantity: must be positive, got {}", v)),
+                Some(_) => {}
+            }
+        }
+        
+        for (field, value) in [
+            ("price", &self.price),
+            ("stop_loss", &self.stop_loss),
+            ("take_profit", &self.take_profit),
+            ("confidence", &self.confidence),
+        ] {
+            if let Some(v) = value {
+                if v.value().is_none() {
+                    problems.push(format!("{}: expected a number, got {:?}", field, v));
+                }
+            }
+        }
+        
+        if let Some(confidence) = self.confidence.as_ref().and_then(Numeric::value) {
+            if !(0.0..=1.0).contains(&confidence) {
+                problems.push(format!("confidence: must be between 0 and 1, got {}", confidence));
+            }
+        }
+        
+        if self.timestamp.is_none() {
+            problems.push("timestamp: required (Unix millis) for replay protection".to_string());
+        }
+        
+        for (unknown, expected) in MISNAMED_FIELDS {
+            if self.extra.contains_key(*unknown) {
+                problems.push(format!("{}: unknown field, did you mean \"{}\"?", unknown, expected));
+            }
+        }
+        
+        problems
+    }
+    
+    /// Whether the order is sized and directed by the open position
+    pub fn closes_position(&self) -> bool {
+        self.resolved == Some(WebhookAction::Close)
+    }
+    
+    pub fn order_type(&self) -> Option<OrderType> {
+        match self.order_type.as_deref().unwrap_or("market").trim().to_lowercase().as_str() {
+            "market" => Some(OrderType::Market),
+            "limit" => Some(OrderType::Limit),
+            "stop" => Some(OrderType::Stop),
+            "stop_limit" => Some(OrderType::StopLimit),
+            _ => None,
+        }
+    }
+    
+    /// Build the order for a validated payload, using `default_quantity` and
+    /// `default_confidence` for fields the payload omits
+    ///
+    /// A `close` action needs the symbol's open `position`: the order takes
+    /// the closing side and, without a quantity, the whole position size.
+    pub fn to_order(&self, position: Option<&Position>, default_quantity: Option<f64>, default_confidence: f64) -> Result<Order, String> {
+        let number = |v: &Option<Numeric>| v.as_ref().and_then(Numeric::value);
+        let (side, reduce_only, default_quantity) = match &self.resolved {
+            Some(WebhookAction::Close) => {
+                let position = position.filter(|p| p.size > 0.0).ok_or_else(|| {
+                    format!("action: no open position for {} to close", self.symbol.trim())
+                })?;
+                (position.side.closing_side(), true, Some(position.size))
+            }
+            Some(WebhookAction::Exit(side)) => (side.clone(), true, default_quantity),
+            Some(WebhookAction::Enter(side)) => (side.clone(), false, default_quantity),
+            None => (OrderSide::Buy, false, default_quantity),
+        };
+        let quantity = number(&self.quantity).or(default_quantity).ok_or_else(|| {
+            format!("quantity: required, no default quantity configured for {}", self.symbol.trim())
+        })?;
+        
+        Ok(Order {
+            symbol: self.symbol.trim().to_string(),
+            side,
+            order_type: self.order_type().unwrap_or(OrderType::Market),
+            quantity,
+            price: number(&self.price),
+            stop_loss: number(&self.stop_loss),
+            take_profit: number(&self.take_profit),
+            confidence: number(&self.confidence).unwrap_or(default_confidence),
+            reduce_only,
+            strategy_tag: self.strategy_tag.clone(),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::{parse_default_confidence, DEFAULT_CONFIDENCE};
+    
+    fn parse(value: serde_json::Value) -> Result<TradingViewWebhook, Vec<String>> {
+        TradingViewWebhook::parse(value.to_string().as_bytes(), &ActionAliases::default())
+    }
+    
+    #[test]
+    fn test_string_quantity_accepted() {
+        let webhook = parse(serde_json::json!({
+            "symbol": "BTC/USDT",
+            "action": "buy",
+            "quantity": "0.25",
+            "price": "67000.5",
+            "timestamp": 1_700_000_000_000i64
+        })).unwrap();
+        
+        let order = webhook.to_order(None, Some(1.0), DEFAULT_CONFIDENCE).unwrap();
+        assert_eq!(order.quantity, 0.25);
+        assert_eq!(order.price, Some(67000.5));
+        assert_eq!(order.confidence, DEFAULT_CONFIDENCE);
+    }
+    
+    #[test]
+    fn test_configured_default_confidence_used_when_absent() {
+        let webhook = parse(serde_json::json!({
+            "symbol": "BTC/USDT",
+            "action": "buy",
+            "quantity": 1,
+            "timestamp": 1_700_000_000_000i64
+        })).unwrap();
+        
+        let configured = parse_default_confidence(Some("0.55"));
+        assert_eq!(webhook.to_order(None, None, configured).unwrap().confidence, 0.55);
+        
+        // Out of range falls back to the built-in default
+        assert_eq!(parse_default_confidence(Some("1.5")), DEFAULT_CONFIDENCE);
+    }
+    
+    #[test]
+    fn test_uppercase_action_accepted() {
+        let webhook = parse(serde_json::json!({
+            "symbol": "BTC/USDT",
+            "action": "SELL",
+            "order_type": "Limit",
+            "quantity": 1,
+            "timestamp": 1_700_000_000_000i64
+        })).unwrap();
+        
+        let order = webhook.to_order(None, None, DEFAULT_CONFIDENCE).unwrap();
+        assert_eq!(order.side, OrderSide::Sell);
+        assert_eq!(order.order_type, OrderType::Limit);
+    }
+    
+    #[test]
+    fn test_missing_quantity_uses_default_or_is_reported() {
+        let webhook = parse(serde_json::json!({
+            "symbol": "BTC/USDT",
+            "action": "buy",
+            "timestamp": 1_700_000_000_000i64
+        })).unwrap();
+        
+        assert_eq!(webhook.to_order(None, Some(0.01), DEFAULT_CONFIDENCE).unwrap().quantity, 0.01);
+        assert_eq!(
+            webhook.to_order(None, None, DEFAULT_CONFIDENCE).unwrap_err(),
+            "quantity: required, no default quantity configured for BTC/USDT"
+        );
+    }
+    
+    #[test]
+    fn test_all_problems_reported_together() {
+        let problems = parse(serde_json::json!({
+            "action": "hold",
+            "qty": 1,
+            "quantity": "lots",
+            "confidence": 3
+        })).unwrap_err();
+        
+        let fields: Vec<&str> = problems.iter().map(|p| p.split(':').next().unwrap()).collect();
+        assert_eq!(fields, vec!["symbol", "action", "quantity", "confidence", "timestamp", "qty"]);
+        assert_eq!(problems[2], "quantity: expected a number, got Text(\"lots\")");
+        assert!(problems.last().unwrap().contains("did you mean \"quantity\""));
+    }
+    
+    #[test]
+    fn test_action_aliases_resolved() {
+        let aliases = ActionAliases::parse("Enter_Long=buy, flat=close, buy=sell, broken");
+        let parse = |action: &str| TradingViewWebhook::parse(serde_json::json!({
+            "symbol": "BTC/USDT",
+            "action": action,
+            "quantity": 0.1,
+            "timestamp": 1_700_000_000_000i64
+        }).to_string().as_bytes(), &aliases);
+        
+        for (action, side, reduce_only) in [
+            ("BUY", OrderSide::Buy, false),
+            ("Long", OrderSide::Buy, false),
+            ("short", OrderSide::Sell, false),
+            ("enter_long", OrderSide::Buy, false),
+            ("exit_long", OrderSide::Sell, true),
+            ("CLOSE_SHORT", OrderSide::Buy, true),
+        ] {
+            let order = parse(action).unwrap().to_order(None, None, DEFAULT_CONFIDENCE).unwrap();
+            assert_eq!((order.side, order.reduce_only), (side, reduce_only), "{}", action);
+        }
+        
+        // buy and sell can't be remapped
+        let order = parse("buy").unwrap().to_order(None, None, DEFAULT_CONFIDENCE).unwrap();
+        assert_eq!(order.side, OrderSide::Buy);
+        let problems = parse("hold").unwrap_err();
+        assert_eq!(problems, vec!["action: expected \"buy\", \"sell\" or a configured alias, got \"hold\""]);
+    }
+    
+    #[test]
+    fn test_close_is_reduce_only_against_open_position() {
+        let aliases = ActionAliases::parse("flat=close");
+        let short = Position {
+            symbol: "ETH/USDT".to_string(),
+            side: crate::plugins::PositionSide::Short,
+            size: 2.0,
+            entry_price: 3550.0,
+            mark_price: 3500.0,
+            unrealized_pnl: 100.0,
+            leverage: 5.0,
+        };
+        
+        for action in ["close", "Exit", "flat"] {
+            let webhook = TradingViewWebhook::parse(serde_json::json!({
+                "symbol": "ETH/USDT",
+                "action": action,
+                "timestamp": 1_700_000_000_000i64
+            }).to_string().as_bytes(), &aliases).unwrap();
+            assert!(webhook.closes_position());
+            
+            let order = webhook.to_order(Some(&short), Some(0.01), DEFAULT_CONFIDENCE).unwrap();
+            assert_eq!((order.side, order.quantity, order.reduce_only), (OrderSide::Buy, 2.0, true));
+            assert_eq!(
+                webhook.to_order(None, Some(0.01), DEFAULT_CONFIDENCE).unwrap_err(),
+                "action: no open position for ETH/USDT to close"
+            );
+        }
+    }
+    
+    #[test]
+    fn test_malformed_json_reported() {
+        let problems = TradingViewWebhook::parse(b"{\"symbol\": \"BTC/USDT\",", &ActionAliases::default()).unwrap_err();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("invalid JSON payload"));
+    }
+}